@@ -64,6 +64,7 @@ export interface RawComponent {
   canBeUpgraded: boolean;
   fromBaseChangeSet: boolean;
   viewData?: ViewGeometry;
+  tags: Record<string, string>;
 }
 
 export type EdgeId = string;
@@ -32,6 +32,7 @@ export interface ViewDescription {
   id: ViewId;
   name: string;
   isDefault: boolean;
+  tags: Record<string, string>;
 }
 export interface StringGeometry {
   x: string;
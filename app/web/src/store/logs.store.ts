@@ -28,6 +28,8 @@ interface AuditLogCommon {
   title: string;
   userId?: UserId;
   userEmail?: string;
+  /** Whether the user acted through an automation token */
+  automation: boolean;
   kind: string;
   entityName: string;
   entityType: string;
@@ -125,6 +127,7 @@ export const useLogsStore = (forceChangeSetId?: ChangeSetId) => {
                       userName: log.userName ?? "System",
                       userId: log.userId,
                       userEmail: log.userEmail,
+                      automation: log.automation,
                       kind: log.kind,
                       entityType: log.entityType,
                       entityName: log.entityName,
@@ -0,0 +1,4 @@
+-- AlterEnum
+ALTER TYPE "RoleType" ADD VALUE 'VIEWER';
+ALTER TYPE "RoleType" ADD VALUE 'OPERATOR';
+ALTER TYPE "RoleType" ADD VALUE 'FUNC_AUTHOR';
@@ -205,7 +205,7 @@ router.post("/workspace/:workspaceId/membership", async (ctx) => {
     ctx.request.body,
     z.object({
       userId: z.string(),
-      role: z.string(),
+      role: z.nativeEnum(RoleType),
     }),
   );
 
@@ -173,6 +173,9 @@ const roleTypeMap: { [key: string]: RoleType } = {
   OWNER: RoleType.OWNER,
   APPROVER: RoleType.APPROVER,
   EDITOR: RoleType.EDITOR,
+  VIEWER: RoleType.VIEWER,
+  OPERATOR: RoleType.OPERATOR,
+  FUNC_AUTHOR: RoleType.FUNC_AUTHOR,
 };
 
 export async function inviteMember(
@@ -1,7 +1,7 @@
 use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
 use clap::{ArgAction, Parser};
-use cyclone_server::{Config, ConfigError, IncomingStream};
+use cyclone_server::{Config, ConfigError, FuncRuntime, IncomingStream};
 
 const NAME: &str = "cyclone";
 
@@ -119,6 +119,10 @@ pub(crate) struct Args {
     #[arg(long, env = "SI_LANG_SERVER", hide_env = true)]
     pub(crate) lang_server: PathBuf,
 
+    /// Path to the lang server program for shell functions.
+    #[arg(long, env = "SI_LANG_SHELL_SERVER", hide_env = true)]
+    pub(crate) lang_shell_server: Option<PathBuf>,
+
     /// Overrides the default function timeout of the lang server program.
     #[arg(long)]
     pub(crate) lang_server_function_timeout: Option<usize>,
@@ -174,6 +178,9 @@ impl TryFrom<Args> for Config {
         }
 
         builder.try_lang_server_path(args.lang_server)?;
+        if let Some(lang_shell_server) = args.lang_shell_server {
+            builder.try_runtime_lang_server_path(FuncRuntime::Shell, lang_shell_server)?;
+        }
         builder.lang_server_function_timeout(args.lang_server_function_timeout);
 
         if args.enable_watch {
@@ -80,6 +80,14 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) data_warehouse_stream_name: Option<String>,
 
+    /// Writes data warehouse records to rotating newline-delimited JSON files in this directory
+    #[arg(long, conflicts_with_all = ["data_warehouse_stream_name", "data_warehouse_http_url"])]
+    pub(crate) data_warehouse_file_directory: Option<PathBuf>,
+
+    /// Posts data warehouse records to this HTTP endpoint
+    #[arg(long, conflicts_with = "data_warehouse_stream_name")]
+    pub(crate) data_warehouse_http_url: Option<String>,
+
     /// Enables the audit logs app
     #[arg(long)]
     pub(crate) enable_audit_logs_app: Option<bool>,
@@ -109,6 +117,17 @@ impl TryFrom<Args> for Config {
             if let Some(data_warehouse_stream_name) = args.data_warehouse_stream_name {
                 config_map.set("data_warehouse_stream_name", data_warehouse_stream_name);
             }
+            if let Some(directory) = args.data_warehouse_file_directory {
+                config_map.set("data_warehouse_stream.kind", "file");
+                config_map.set(
+                    "data_warehouse_stream.directory",
+                    directory.display().to_string(),
+                );
+            }
+            if let Some(url) = args.data_warehouse_http_url {
+                config_map.set("data_warehouse_stream.kind", "http");
+                config_map.set("data_warehouse_stream.url", url);
+            }
             if let Some(enable_audit_logs_app) = args.enable_audit_logs_app {
                 config_map.set("enable_audit_logs_app", enable_audit_logs_app);
             }
@@ -59,6 +59,8 @@ export interface ManagementOperations {
       remove?: string[],
     }
   };
+  delete?: string[];
+  erase?: string[];
 }
 
 export interface ManagementFuncResultSuccess extends ResultSuccess {
@@ -0,0 +1,50 @@
+use std::{io, time::Duration};
+
+use clap::Parser;
+use color_eyre::Result;
+use cyclone_core::lang_server::{self, LangServerCommand};
+
+mod shell;
+
+const NAME: &str = "lang-shell";
+
+/// A lang server which executes shell script functions.
+///
+/// Cyclone runs lang-shell for functions with the shell runtime, sending the request on standard
+/// input and reading outputs and the result from standard output.
+#[derive(Debug, Parser)]
+#[command(name = NAME, max_term_width = 100)]
+struct Args {
+    /// The kind of function execution to perform.
+    command: LangServerCommand,
+
+    /// Kills the function if it runs for longer than this many seconds.
+    #[arg(long)]
+    timeout: Option<u64>,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+
+    let mut lang_server = shell::ShellLangServer::new(args.timeout.map(Duration::from_secs));
+    lang_server::serve(
+        &mut lang_server,
+        args.command,
+        io::stdin().lock(),
+        io::stdout().lock(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_command() {
+        use clap::CommandFactory;
+        Args::command().debug_assert()
+    }
+}
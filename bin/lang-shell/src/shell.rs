@@ -0,0 +1,523 @@
+//! Executes functions written as shell scripts.
+//!
+//! A function's code is a POSIX shell script defining a shell function named by the request's
+//! handler. lang-shell runs the script with `sh`, calling the handler with the function's input as
+//! JSON on stdin, and reads the function's return value as JSON from its stdout. Every line the
+//! function writes to stderr is streamed back to cyclone as output.
+//!
+//! Resolver functions receive the component (`{"data": ..., "parents": [...]}`) and may return
+//! any JSON value, or print nothing to leave the value unset. Qualifications must return an
+//! object with a `result` of `"success"`, `"warning"` or `"failure"`. Action functions receive
+//! the action's arguments and must return an object with a `status` of `"ok"`, `"warning"` or
+//! `"error"`, optionally along with a `payload`, `message` and `resourceId`.
+//!
+//! A function which runs past the timeout is killed, along with anything it started in the
+//! background.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    os::unix::process::CommandExt,
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose, Engine};
+use cyclone_core::{
+    lang_server::{
+        LangServer, LangServerActionRunResultSuccess, LangServerError, LangServerFailure,
+        LangServerOutputWriter, LangServerResolverFunctionResultSuccess, LangServerResult,
+    },
+    ActionRunRequest, BeforeFunction, FunctionResultFailureErrorKind, ResolverFunctionRequest,
+    ResolverFunctionResponseType, ResourceStatus,
+};
+use nix::{
+    sys::signal::{killpg, Signal},
+    unistd::Pid,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// How often to check whether a function which closed its stderr has exited.
+const WAIT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// What a shell function printed on stdout, if anything, or why it failed.
+type Returned = Result<Option<Value>, LangServerFailure>;
+
+/// The object an action function returns.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShellActionResult {
+    status: ResourceStatus,
+    #[serde(default)]
+    payload: Option<Value>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    resource_id: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ShellLangServer {
+    timeout: Option<Duration>,
+}
+
+impl ShellLangServer {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout }
+    }
+
+    fn execute(
+        &self,
+        execution_id: &str,
+        handler: &str,
+        code_base64: &str,
+        before: &[BeforeFunction],
+        input: Vec<u8>,
+        output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<Returned, LangServerError> {
+        let failure = |kind: FunctionResultFailureErrorKind, message: String| {
+            Result::<Returned, LangServerError>::Ok(Err(LangServerFailure::new(
+                execution_id,
+                kind,
+                message,
+            )))
+        };
+
+        if !before.is_empty() {
+            return failure(
+                FunctionResultFailureErrorKind::VeritechServer,
+                "before functions are not supported by lang-shell".to_owned(),
+            );
+        }
+        if !is_shell_name(handler) {
+            return failure(
+                FunctionResultFailureErrorKind::UserCodeException("InvalidHandler".to_owned()),
+                format!("handler is not a valid shell function name: {handler}"),
+            );
+        }
+        let code = match general_purpose::STANDARD
+            .decode(code_base64)
+            .map_err(|err| err.to_string())
+            .and_then(|code| String::from_utf8(code).map_err(|err| err.to_string()))
+        {
+            Ok(code) => code,
+            Err(err) => {
+                return failure(
+                    FunctionResultFailureErrorKind::VeritechServer,
+                    format!("failed to decode function code: {err}"),
+                )
+            }
+        };
+
+        // The handler is the script's last command, so its exit status is the script's. The script
+        // leads its own process group so anything it starts in the background can be killed with it
+        let mut child = match Command::new("sh")
+            .arg("-c")
+            .arg(format!("{code}\n{handler}\n"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => {
+                return failure(
+                    FunctionResultFailureErrorKind::VeritechServer,
+                    format!("failed to run sh: {err}"),
+                )
+            }
+        };
+        let timed_out = |child: &mut Child| {
+            kill_process_group(child);
+            failure(
+                FunctionResultFailureErrorKind::UserCodeException("TimeoutError".to_owned()),
+                format!(
+                    "function timed out after {:?}",
+                    self.timeout.unwrap_or_default()
+                ),
+            )
+        };
+
+        // Each pipe gets its own thread so a function which ignores its input or writes a lot of
+        // output can't block on a full pipe
+        if let Some(mut stdin) = child.stdin.take() {
+            thread::spawn(move || stdin.write_all(&input));
+        }
+        let (stdout_tx, stdout_rx) = mpsc::channel();
+        if let Some(mut stdout) = child.stdout.take() {
+            thread::spawn(move || {
+                let mut returned = Vec::new();
+                let _ = stdout_tx.send(stdout.read_to_end(&mut returned).map(|_| returned));
+            });
+        }
+        let (line_tx, line_rx) = mpsc::channel();
+        if let Some(stderr) = child.stderr.take() {
+            thread::spawn(move || {
+                let mut stderr = BufReader::new(stderr);
+                let mut line = Vec::new();
+                while matches!(stderr.read_until(b'\n', &mut line), Ok(read) if read > 0) {
+                    let text = String::from_utf8_lossy(&line).trim_end().to_owned();
+                    if line_tx.send(text).is_err() {
+                        break;
+                    }
+                    line.clear();
+                }
+            });
+        }
+
+        // Stream stderr until the function closes it, which is usually when it exits
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match recv_before(&line_rx, deadline) {
+                Ok(line) => output.output("output", "info", line)?,
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => return timed_out(&mut child),
+            }
+        }
+
+        // A function may close stderr and keep running, so the deadline still applies
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                    return timed_out(&mut child)
+                }
+                Ok(None) => thread::sleep(WAIT_INTERVAL),
+                Err(err) => {
+                    kill_process_group(&mut child);
+                    return failure(
+                        FunctionResultFailureErrorKind::VeritechServer,
+                        format!("failed to wait for sh: {err}"),
+                    );
+                }
+            }
+        };
+        if !status.success() {
+            return failure(
+                FunctionResultFailureErrorKind::UserCodeException("ExitStatus".to_owned()),
+                format!("handler {handler} failed with {status}"),
+            );
+        }
+
+        // Commands left running in the background keep stdout open, and are killed at the deadline
+        let returned = match recv_before(&stdout_rx, deadline) {
+            Ok(Ok(returned)) => returned,
+            Err(RecvTimeoutError::Timeout) => return timed_out(&mut child),
+            Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => {
+                return failure(
+                    FunctionResultFailureErrorKind::VeritechServer,
+                    "failed to read the function's stdout".to_owned(),
+                )
+            }
+        };
+        if returned.trim_ascii().is_empty() {
+            return Ok(Ok(None));
+        }
+        match serde_json::from_slice(&returned) {
+            Ok(value) => Ok(Ok(Some(value))),
+            Err(err) => failure(
+                FunctionResultFailureErrorKind::InvalidReturnType,
+                format!("function did not print its return value as json: {err}"),
+            ),
+        }
+    }
+}
+
+impl LangServer for ShellLangServer {
+    fn action_run(
+        &mut self,
+        request: ActionRunRequest,
+        output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<LangServerActionRunResultSuccess>, LangServerError> {
+        let input = serde_json::to_vec(&request.args).map_err(LangServerError::MessageSerialize)?;
+        let returned = match self.execute(
+            &request.execution_id,
+            &request.handler,
+            &request.code_base64,
+            &request.before,
+            input,
+            output,
+        )? {
+            Ok(returned) => returned,
+            Err(failure) => return Ok(LangServerResult::Failure(failure)),
+        };
+
+        match returned.map(serde_json::from_value::<ShellActionResult>) {
+            Some(Ok(result)) => Ok(LangServerResult::Success(
+                LangServerActionRunResultSuccess {
+                    execution_id: request.execution_id,
+                    resource_id: result.resource_id,
+                    payload: result.payload,
+                    health: result.status,
+                    message: result.message,
+                    error: None,
+                },
+            )),
+            _ => Ok(LangServerResult::Failure(LangServerFailure::new(
+                request.execution_id,
+                FunctionResultFailureErrorKind::InvalidReturnType,
+                "actions must return an object with a status of ok, warning or error",
+            ))),
+        }
+    }
+
+    fn resolver_function(
+        &mut self,
+        request: ResolverFunctionRequest,
+        output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<LangServerResolverFunctionResultSuccess>, LangServerError> {
+        let input =
+            serde_json::to_vec(&request.component).map_err(LangServerError::MessageSerialize)?;
+        let returned = match self.execute(
+            &request.execution_id,
+            &request.handler,
+            &request.code_base64,
+            &request.before,
+            input,
+            output,
+        )? {
+            Ok(returned) => returned,
+            Err(failure) => return Ok(LangServerResult::Failure(failure)),
+        };
+
+        if request.response_type == ResolverFunctionResponseType::Qualification
+            && !is_qualification(returned.as_ref())
+        {
+            return Ok(LangServerResult::Failure(LangServerFailure::new(
+                request.execution_id,
+                FunctionResultFailureErrorKind::InvalidReturnType,
+                "qualifications must return an object with a result of success, warning or failure",
+            )));
+        }
+
+        Ok(LangServerResult::Success(
+            LangServerResolverFunctionResultSuccess {
+                execution_id: request.execution_id,
+                unset: returned.is_none(),
+                data: returned.unwrap_or(Value::Null),
+            },
+        ))
+    }
+}
+
+/// Receives from the channel, giving up at the deadline if there is one.
+fn recv_before<T>(rx: &Receiver<T>, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+    match deadline {
+        Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
+
+/// Kills the function's process group, taking any commands it left running in the background with
+/// it, and reaps the script.
+fn kill_process_group(child: &mut Child) {
+    // The script leads its group, so the group's id is the script's pid
+    if let Ok(pid) = i32::try_from(child.id()) {
+        let _ = killpg(Pid::from_raw(pid), Signal::SIGKILL);
+    }
+    let _ = child.wait();
+}
+
+fn is_shell_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_qualification(returned: Option<&Value>) -> bool {
+    matches!(
+        returned
+            .and_then(|value| value.get("result"))
+            .and_then(Value::as_str),
+        Some("success" | "warning" | "failure")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use cyclone_core::ResolverFunctionComponent;
+    use serde_json::json;
+
+    use super::*;
+
+    fn resolver_request(
+        code: &str,
+        response_type: ResolverFunctionResponseType,
+    ) -> ResolverFunctionRequest {
+        ResolverFunctionRequest {
+            execution_id: "1234".to_owned(),
+            handler: "main".to_owned(),
+            component: ResolverFunctionComponent::default(),
+            response_type,
+            code_base64: general_purpose::STANDARD.encode(code),
+            before: vec![],
+        }
+    }
+
+    fn resolve(
+        lang_server: &mut ShellLangServer,
+        request: ResolverFunctionRequest,
+    ) -> (
+        LangServerResult<LangServerResolverFunctionResultSuccess>,
+        String,
+    ) {
+        let mut output = Vec::new();
+        let result = {
+            let mut writer = LangServerOutputWriter::new("1234", &mut output);
+            lang_server
+                .resolver_function(request, &mut writer)
+                .expect("failed to execute function")
+        };
+        (
+            result,
+            String::from_utf8(output).expect("output is not utf8"),
+        )
+    }
+
+    #[test]
+    fn resolver_returns_json_and_streams_stderr() {
+        let code = r#"
+            main() {
+                input=$(cat)
+                echo "resolving" >&2
+                printf '{"input": %s}' "$input"
+            }
+        "#;
+        let (result, output) = resolve(
+            &mut ShellLangServer::new(None),
+            resolver_request(code, ResolverFunctionResponseType::Object),
+        );
+
+        assert_eq!(
+            LangServerResult::Success(LangServerResolverFunctionResultSuccess {
+                execution_id: "1234".to_owned(),
+                data: json!({
+                    "input": { "data": { "kind": "standard", "properties": {} }, "parents": [] },
+                }),
+                unset: false,
+            }),
+            result
+        );
+        assert!(output.contains(r#""message":"resolving""#));
+    }
+
+    #[test]
+    fn resolver_without_output_is_unset() {
+        let (result, _) = resolve(
+            &mut ShellLangServer::new(None),
+            resolver_request("main() { :; }", ResolverFunctionResponseType::String),
+        );
+
+        assert!(matches!(
+            result,
+            LangServerResult::Success(LangServerResolverFunctionResultSuccess { unset: true, .. })
+        ));
+    }
+
+    #[test]
+    fn qualification_must_return_a_result() {
+        let mut lang_server = ShellLangServer::new(None);
+
+        let (result, _) = resolve(
+            &mut lang_server,
+            resolver_request(
+                r#"main() { echo '{"result": "warning", "message": "hmm"}'; }"#,
+                ResolverFunctionResponseType::Qualification,
+            ),
+        );
+        assert!(matches!(result, LangServerResult::Success(_)));
+
+        let (result, _) = resolve(
+            &mut lang_server,
+            resolver_request(
+                r#"main() { echo '"success"'; }"#,
+                ResolverFunctionResponseType::Qualification,
+            ),
+        );
+        assert!(matches!(
+            result,
+            LangServerResult::Failure(LangServerFailure {
+                error: cyclone_core::lang_server::LangServerFailureError {
+                    kind: FunctionResultFailureErrorKind::InvalidReturnType,
+                    ..
+                },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn action_returns_status_and_payload() {
+        let code = r#"
+            main() {
+                name=$(cat | sed 's/.*"name":"\([^"]*\)".*/\1/')
+                printf '{"status": "ok", "payload": {"name": "%s"}, "resourceId": "r-1"}' "$name"
+            }
+        "#;
+        let request = ActionRunRequest {
+            execution_id: "1234".to_owned(),
+            handler: "main".to_owned(),
+            code_base64: general_purpose::STANDARD.encode(code),
+            args: json!({ "name": "poop" }),
+            before: vec![],
+        };
+
+        let mut output = Vec::new();
+        let mut writer = LangServerOutputWriter::new("1234", &mut output);
+        let result = ShellLangServer::new(None)
+            .action_run(request, &mut writer)
+            .expect("failed to execute function");
+
+        assert_eq!(
+            LangServerResult::Success(LangServerActionRunResultSuccess {
+                execution_id: "1234".to_owned(),
+                resource_id: Some("r-1".to_owned()),
+                payload: Some(json!({ "name": "poop" })),
+                health: ResourceStatus::Ok,
+                message: None,
+                error: None,
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn failing_and_slow_functions_fail() {
+        let (result, _) = resolve(
+            &mut ShellLangServer::new(None),
+            resolver_request("main() { exit 3; }", ResolverFunctionResponseType::String),
+        );
+        assert!(matches!(result, LangServerResult::Failure(_)));
+
+        let (result, _) = resolve(
+            &mut ShellLangServer::new(Some(Duration::from_millis(200))),
+            resolver_request("main() { sleep 5; }", ResolverFunctionResponseType::String),
+        );
+        assert!(matches!(result, LangServerResult::Failure(_)));
+    }
+
+    #[test]
+    fn timed_out_functions_are_killed_with_their_background_commands() {
+        let marker = std::env::temp_dir().join(format!("lang-shell-marker-{}", std::process::id()));
+        let code = format!(
+            "main() {{ exec 2>&-; (sleep 1; touch '{}') & sleep 5; }}",
+            marker.display()
+        );
+        let start = Instant::now();
+        let (result, _) = resolve(
+            &mut ShellLangServer::new(Some(Duration::from_millis(200))),
+            resolver_request(&code, ResolverFunctionResponseType::String),
+        );
+        assert!(matches!(result, LangServerResult::Failure(_)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // The background command would have touched the marker by now had it survived
+        thread::sleep(Duration::from_millis(1500));
+        assert!(!marker.exists());
+    }
+}
@@ -115,6 +115,14 @@ pub(crate) struct Args {
     #[arg(long, env)]
     pub(crate) s3_path_prefix: Option<String>,
 
+    /// Where modules are stored: in the s3 bucket or on the local filesystem [default: s3]
+    #[arg(long, env, value_parser = ["s3", "local"])]
+    pub(crate) storage_backend: Option<String>,
+
+    /// The directory modules are stored in when using the local storage backend
+    #[arg(long, env)]
+    pub(crate) storage_local_path: Option<PathBuf>,
+
     /// The path to the JWT public signing key
     #[arg(long, env)]
     pub(crate) jwt_public_key: Option<String>,
@@ -178,6 +186,15 @@ impl TryFrom<Args> for Config {
             if let Some(s3_path_prefix) = args.s3_path_prefix {
                 config_map.set("s3.path_prefix", s3_path_prefix);
             }
+            if let Some(storage_backend) = args.storage_backend {
+                config_map.set("storage.backend", storage_backend);
+            }
+            if let Some(storage_local_path) = args.storage_local_path {
+                config_map.set(
+                    "storage.local_path",
+                    storage_local_path.display().to_string(),
+                );
+            }
             if let Some(jwt_public_key) = args.jwt_public_key {
                 config_map.set("jwt_signing_public_key_path", jwt_public_key.to_string());
             }
@@ -0,0 +1,62 @@
+use clap::Parser;
+use color_eyre::Result;
+use si_data_nats::{NatsClient, NatsConfig};
+
+const NAME: &str = "nats-dlq-replay";
+
+/// Moves dead lettered messages back onto the subjects they were originally published on.
+#[derive(Parser, Debug)]
+#[command(name = NAME, max_term_width = 100)]
+pub(crate) struct Args {
+    /// The stream whose dead lettered messages should be replayed.
+    #[arg(index = 1)]
+    pub stream: String,
+
+    /// Only replay messages dead lettered from this consumer.
+    #[arg(long, short = 'c')]
+    pub consumer: Option<String>,
+
+    /// The maximum number of messages to replay.
+    #[arg(long, short = 'l')]
+    pub limit: Option<usize>,
+
+    /// NATS connection URL [example: demo.nats.io]
+    #[arg(long, env = "SI_NATS_URL", default_value = "localhost")]
+    pub nats_url: String,
+
+    /// NATS credentials file
+    #[arg(long, env = "SI_NATS_CREDS_FILE")]
+    pub nats_creds_file: Option<String>,
+
+    /// NATS subject prefix
+    #[arg(long, env = "SI_NATS_SUBJECT_PREFIX")]
+    pub nats_subject_prefix: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+
+    let client = NatsClient::new(&NatsConfig {
+        connection_name: Some(NAME.to_string()),
+        creds_file: args.nats_creds_file,
+        subject_prefix: args.nats_subject_prefix,
+        url: args.nats_url,
+        ..Default::default()
+    })
+    .await?;
+    let context = si_data_nats::jetstream::new(client);
+
+    let replayed = nats_dead_letter_queue::replay(
+        &context,
+        &args.stream,
+        args.consumer.as_deref(),
+        args.limit,
+    )
+    .await?;
+
+    println!("replayed {replayed} message(s) from the dead letter queue");
+
+    Ok(())
+}
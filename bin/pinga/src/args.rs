@@ -133,6 +133,14 @@ pub(crate) struct Args {
     /// back to an instance of a Pinga service.
     #[arg(long)]
     pub(crate) instance_id: Option<String>,
+
+    /// Disables the scheduler which enqueues jobs for due workspace schedules
+    #[arg(long)]
+    pub(crate) disable_scheduler: bool,
+
+    /// How often the scheduler checks for due workspace schedules, in seconds [default: 60]
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub(crate) scheduler_interval_secs: Option<u64>,
 }
 
 impl TryFrom<Args> for Config {
@@ -230,6 +238,12 @@ impl TryFrom<Args> for Config {
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
+            if args.disable_scheduler {
+                config_map.set("scheduler_enabled", false);
+            }
+            if let Some(scheduler_interval_secs) = args.scheduler_interval_secs {
+                config_map.set("scheduler_interval_secs", scheduler_interval_secs);
+            }
             config_map.set("nats.connection_name", NAME);
             config_map.set("pg.application_name", NAME);
             config_map.set("layer_db_config.pg_pool_config.application_name", NAME);
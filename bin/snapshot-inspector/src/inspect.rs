@@ -0,0 +1,370 @@
+use std::{collections::HashSet, fmt::Display};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use dal::{
+    workspace_snapshot::{
+        edge_weight::EdgeWeightKindDiscriminants,
+        graph::detect_updates::Update,
+        node_weight::{NodeWeight, NodeWeightDiscriminants},
+        NodeInformation,
+    },
+    EdgeWeightKind, Ulid, WorkspaceSnapshotGraph,
+};
+use petgraph::{
+    prelude::*,
+    visit::{Dfs, EdgeRef},
+};
+use strum::IntoEnumIterator;
+
+/// Lists every node, optionally only those of the given [`NodeWeight`] kind.
+pub(crate) fn nodes(graph: &WorkspaceSnapshotGraph, kind: Option<&str>) -> Result<()> {
+    let kind = kind
+        .map(parse_kind::<NodeWeightDiscriminants>)
+        .transpose()?;
+
+    let mut count = 0;
+    for (node_weight, _) in graph.nodes() {
+        if kind.is_some_and(|kind| kind != NodeWeightDiscriminants::from(node_weight)) {
+            continue;
+        }
+        println!("{}", describe(node_weight));
+        count += 1;
+    }
+    println!("{count} node(s)");
+
+    Ok(())
+}
+
+/// Lists every edge, optionally only those of the given [`EdgeWeightKind`] or those from or to
+/// the given nodes.
+pub(crate) fn edges(
+    graph: &WorkspaceSnapshotGraph,
+    kind: Option<&str>,
+    from: Option<Ulid>,
+    to: Option<Ulid>,
+) -> Result<()> {
+    let kind = kind
+        .map(parse_kind::<EdgeWeightKindDiscriminants>)
+        .transpose()?;
+
+    let mut count = 0;
+    for (edge_weight, source, target) in graph.edges() {
+        if kind.is_some_and(|kind| kind != EdgeWeightKindDiscriminants::from(edge_weight.kind())) {
+            continue;
+        }
+        let source = graph.get_node_weight(source)?;
+        let target = graph.get_node_weight(target)?;
+        if from.is_some_and(|id| id != source.id()) || to.is_some_and(|id| id != target.id()) {
+            continue;
+        }
+
+        println!(
+            "{} --{:?}--> {}",
+            describe(source),
+            edge_weight.kind(),
+            describe(target)
+        );
+        count += 1;
+    }
+    println!("{count} edge(s)");
+
+    Ok(())
+}
+
+/// Prints the attribute value tree of a component, starting from its root attribute value.
+///
+/// Values are held in the content store, so only their content addresses are shown.
+pub(crate) fn component(graph: &WorkspaceSnapshotGraph, component_id: Ulid) -> Result<()> {
+    let component_index = graph.get_node_index_by_id(component_id)?;
+    let node_weight = graph.get_node_weight(component_index)?;
+    if !matches!(node_weight, NodeWeight::Component(_)) {
+        bail!("{component_id} is not a component");
+    }
+    println!("{}", describe(node_weight));
+
+    let root_index = graph.get_edge_weight_kind_target_idx(
+        component_index,
+        Outgoing,
+        EdgeWeightKindDiscriminants::Root,
+    )?;
+    for line in attribute_value_tree(graph, root_index)? {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+/// Describes the attribute value tree beneath an attribute value, one line per value.
+///
+/// A value which has already been described is only marked as a cycle rather than described
+/// again, so that a corrupt snapshot can't recurse forever.
+fn attribute_value_tree(graph: &WorkspaceSnapshotGraph, index: NodeIndex) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    describe_attribute_value(graph, index, None, 1, &mut HashSet::new(), &mut lines)?;
+    Ok(lines)
+}
+
+fn describe_attribute_value(
+    graph: &WorkspaceSnapshotGraph,
+    index: NodeIndex,
+    key: Option<&str>,
+    depth: usize,
+    visited: &mut HashSet<Ulid>,
+    lines: &mut Vec<String>,
+) -> Result<()> {
+    let NodeWeight::AttributeValue(attribute_value) = graph.get_node_weight(index)? else {
+        bail!("{index:?} is not an attribute value");
+    };
+    if !visited.insert(attribute_value.id()) {
+        lines.push(format!(
+            "{:indent$}<cycle> {} was already printed",
+            "",
+            attribute_value.id(),
+            indent = depth * 2
+        ));
+        return Ok(());
+    }
+
+    let prop_name = match graph.get_edge_weight_kind_target_idx_opt(
+        index,
+        Outgoing,
+        EdgeWeightKindDiscriminants::Prop,
+    )? {
+        Some(prop_index) => match graph.get_node_weight(prop_index)? {
+            NodeWeight::Prop(prop) => prop.name().to_owned(),
+            _ => "<not a prop>".to_owned(),
+        },
+        // Entries of arrays and maps are identified by their key (or position), not their prop
+        None => "<no prop>".to_owned(),
+    };
+    let name = match key {
+        Some(key) => format!("{prop_name}[{key}]"),
+        None => prop_name,
+    };
+    let value = attribute_value
+        .value()
+        .map(|address| format!("{address:?}"))
+        .unwrap_or_else(|| "none".to_owned());
+    lines.push(format!(
+        "{:indent$}{name} ({}) value: {value}",
+        "",
+        attribute_value.id(),
+        indent = depth * 2
+    ));
+
+    let children = match graph.ordered_children_for_node(index)? {
+        Some(children) => children,
+        None => graph
+            .edges_directed_for_edge_weight_kind(
+                index,
+                Outgoing,
+                EdgeWeightKindDiscriminants::Contain,
+            )
+            .into_iter()
+            .map(|(_, _, target)| target)
+            .collect(),
+    };
+    for child in children {
+        let key =
+            graph
+                .edges_directed(child, Incoming)
+                .find_map(|edge| match edge.weight().kind() {
+                    EdgeWeightKind::Contain(key) if edge.source() == index => key.clone(),
+                    _ => None,
+                });
+        describe_attribute_value(graph, child, key.as_deref(), depth + 1, visited, lines)?;
+    }
+
+    Ok(())
+}
+
+/// Recalculates the merkle tree hash of every node reachable from the root and checks that the
+/// graph is acyclic, failing if either check finds a problem.
+pub(crate) fn verify(graph: &WorkspaceSnapshotGraph) -> Result<()> {
+    let mut problems = 0;
+
+    if graph.is_acyclic_directed() {
+        println!("graph is acyclic");
+    } else {
+        println!("graph contains a cycle");
+        problems += 1;
+    }
+
+    let mut reachable = HashSet::new();
+    let mut dfs = Dfs::new(graph.graph(), graph.root());
+    while let Some(index) = dfs.next(graph.graph()) {
+        reachable.insert(index);
+    }
+
+    let mut recalculated = graph.clone();
+    recalculated.recalculate_entire_merkle_tree_hash()?;
+
+    for (node_weight, index) in graph.nodes() {
+        if !reachable.contains(&index) {
+            println!("unreachable from root: {}", describe(node_weight));
+            problems += 1;
+            continue;
+        }
+
+        let expected = recalculated.get_node_weight(index)?.merkle_tree_hash();
+        if node_weight.merkle_tree_hash() != expected {
+            println!(
+                "merkle tree hash mismatch: {} has {}, expected {expected}",
+                describe(node_weight),
+                node_weight.merkle_tree_hash()
+            );
+            problems += 1;
+        }
+    }
+
+    if problems > 0 {
+        bail!(
+            "found {problems} problem(s) in {} node(s)",
+            graph.node_count()
+        );
+    }
+    println!("verified {} node(s)", graph.node_count());
+
+    Ok(())
+}
+
+/// Lists the [`Updates`](Update) which turn the base snapshot into the updated one.
+pub(crate) fn diff(base: &WorkspaceSnapshotGraph, updated: &WorkspaceSnapshotGraph) -> Result<()> {
+    let updates = base.detect_updates(updated);
+
+    for update in &updates {
+        match update {
+            Update::NewNode { node_weight } => println!("+ node {}", describe(node_weight)),
+            Update::ReplaceNode { node_weight } => println!("~ node {}", describe(node_weight)),
+            Update::NewEdge {
+                source,
+                destination,
+                edge_weight,
+            } => println!(
+                "+ edge {} --{:?}--> {}",
+                describe_info(source),
+                edge_weight.kind(),
+                describe_info(destination)
+            ),
+            Update::RemoveEdge {
+                source,
+                destination,
+                edge_kind,
+            } => println!(
+                "- edge {} --{edge_kind}--> {}",
+                describe_info(source),
+                describe_info(destination)
+            ),
+        }
+    }
+    println!("{} update(s)", updates.len());
+
+    Ok(())
+}
+
+fn describe(node_weight: &NodeWeight) -> String {
+    let kind = NodeWeightDiscriminants::from(node_weight);
+    let name = match node_weight {
+        NodeWeight::ActionPrototype(weight) => Some(weight.name().to_owned()),
+        NodeWeight::Category(weight) => Some(weight.kind().to_string()),
+        NodeWeight::Func(weight) => Some(weight.name().to_owned()),
+        NodeWeight::FuncArgument(weight) => Some(weight.name().to_owned()),
+        NodeWeight::Prop(weight) => Some(weight.name().to_owned()),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => format!("{} {kind} {name:?}", node_weight.id()),
+        None => format!("{} {kind}", node_weight.id()),
+    }
+}
+
+fn describe_info(info: &NodeInformation) -> String {
+    format!("{} {}", info.id, info.node_weight_kind)
+}
+
+/// Parses a node or edge weight kind by name, ignoring case.
+fn parse_kind<K: IntoEnumIterator + Display>(name: &str) -> Result<K> {
+    K::iter()
+        .find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let kinds: Vec<_> = K::iter().map(|kind| kind.to_string()).collect();
+            eyre!("unknown kind {name}, expected one of: {}", kinds.join(", "))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use dal::{ContentHash, EdgeWeight, WorkspaceSnapshotGraphVCurrent};
+
+    use super::*;
+
+    fn add_node(graph: &mut WorkspaceSnapshotGraph, node_weight: NodeWeight) -> NodeIndex {
+        graph
+            .add_or_replace_node(node_weight)
+            .expect("could not add node")
+    }
+
+    fn add_edge(
+        graph: &mut WorkspaceSnapshotGraph,
+        source: NodeIndex,
+        kind: EdgeWeightKind,
+        target: NodeIndex,
+    ) {
+        graph
+            .add_edge(source, EdgeWeight::new(kind), target)
+            .expect("could not add edge");
+    }
+
+    #[test]
+    fn attribute_value_cycles_are_marked() {
+        let mut graph = WorkspaceSnapshotGraph::V4(
+            WorkspaceSnapshotGraphVCurrent::new_with_categories_only()
+                .expect("could not create graph"),
+        );
+        let root = graph.root();
+        let component = add_node(
+            &mut graph,
+            NodeWeight::new_component(Ulid::new(), Ulid::new(), ContentHash::from("component")),
+        );
+        let root_value_id = Ulid::new();
+        let root_value = add_node(
+            &mut graph,
+            NodeWeight::new_attribute_value(root_value_id, Ulid::new(), None, None),
+        );
+        let child_value_id = Ulid::new();
+        let child_value = add_node(
+            &mut graph,
+            NodeWeight::new_attribute_value(child_value_id, Ulid::new(), None, None),
+        );
+        add_edge(&mut graph, root, EdgeWeightKind::new_use(), component);
+        add_edge(&mut graph, component, EdgeWeightKind::Root, root_value);
+        add_edge(
+            &mut graph,
+            root_value,
+            EdgeWeightKind::Contain(None),
+            child_value,
+        );
+        // A corrupt snapshot, where the child contains its parent
+        add_edge(
+            &mut graph,
+            child_value,
+            EdgeWeightKind::Contain(None),
+            root_value,
+        );
+
+        let lines = attribute_value_tree(&graph, root_value).expect("could not describe tree");
+
+        assert_eq!(
+            vec![
+                format!("  <no prop> ({root_value_id}) value: none"),
+                format!("    <no prop> ({child_value_id}) value: none"),
+                format!("      <cycle> {root_value_id} was already printed"),
+            ],
+            lines
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use color_eyre::Result;
+use dal::Ulid;
+
+mod inspect;
+mod snapshot;
+
+const NAME: &str = "snapshot-inspector";
+
+/// Inspects workspace snapshot files offline, without Postgres or NATS.
+///
+/// Snapshot files hold the compressed bytes stored in the layer cache, either raw or base64
+/// encoded as returned by the admin `get_snapshot` route.
+#[derive(Parser, Debug)]
+#[command(name = NAME, max_term_width = 100)]
+pub(crate) struct Args {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Lists the nodes in a snapshot.
+    Nodes {
+        /// The snapshot file.
+        snapshot: PathBuf,
+
+        /// Only list nodes of this node weight kind [example: Component]
+        #[arg(long, short = 'k')]
+        kind: Option<String>,
+    },
+
+    /// Lists the edges in a snapshot.
+    Edges {
+        /// The snapshot file.
+        snapshot: PathBuf,
+
+        /// Only list edges of this edge weight kind [example: Contain]
+        #[arg(long, short = 'k')]
+        kind: Option<String>,
+
+        /// Only list edges from the node with this id.
+        #[arg(long, value_parser = parse_ulid)]
+        from: Option<Ulid>,
+
+        /// Only list edges to the node with this id.
+        #[arg(long, value_parser = parse_ulid)]
+        to: Option<Ulid>,
+    },
+
+    /// Prints a component's attribute value tree.
+    Component {
+        /// The snapshot file.
+        snapshot: PathBuf,
+
+        /// The id of the component.
+        #[arg(value_parser = parse_ulid)]
+        component_id: Ulid,
+    },
+
+    /// Recalculates every merkle tree hash and checks that the graph is acyclic.
+    Verify {
+        /// The snapshot file.
+        snapshot: PathBuf,
+    },
+
+    /// Lists the updates that turn one snapshot into another.
+    Diff {
+        /// The snapshot to compare against.
+        base: PathBuf,
+
+        /// The updated snapshot.
+        updated: PathBuf,
+    },
+}
+
+fn parse_ulid(value: &str) -> Result<Ulid, String> {
+    Ulid::from_string(value).map_err(|err| err.to_string())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+
+    match args.command {
+        Command::Nodes { snapshot, kind } => {
+            inspect::nodes(&snapshot::load(&snapshot)?, kind.as_deref())
+        }
+        Command::Edges {
+            snapshot,
+            kind,
+            from,
+            to,
+        } => inspect::edges(&snapshot::load(&snapshot)?, kind.as_deref(), from, to),
+        Command::Component {
+            snapshot,
+            component_id,
+        } => inspect::component(&snapshot::load(&snapshot)?, component_id),
+        Command::Verify { snapshot } => inspect::verify(&snapshot::load(&snapshot)?),
+        Command::Diff { base, updated } => {
+            inspect::diff(&snapshot::load(&base)?, &snapshot::load(&updated)?)
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fs, path::Path};
+
+use base64::prelude::*;
+use color_eyre::{eyre::bail, Result};
+use dal::{workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants, WorkspaceSnapshotGraph};
+use si_layer_cache::db::serialize;
+
+/// Loads a snapshot file, which may be base64 encoded.
+///
+/// Only snapshots at the current graph version can be inspected, since older versions have to be
+/// migrated (which needs the content store) before the graph can be used.
+pub(crate) fn load(path: &Path) -> Result<WorkspaceSnapshotGraph> {
+    let bytes = fs::read(path)?;
+    // Compressed snapshots are binary, so only base64 encoded ones decode cleanly
+    let bytes = match BASE64_STANDARD.decode(bytes.trim_ascii()) {
+        Ok(decoded) => decoded,
+        Err(_) => bytes,
+    };
+
+    let graph: WorkspaceSnapshotGraph = serialize::from_bytes(&bytes)?;
+
+    let version = WorkspaceSnapshotGraphDiscriminants::from(&graph);
+    let current = WorkspaceSnapshotGraph::current_discriminant();
+    if version != current {
+        bail!(
+            "{} is a {version} snapshot, only {current} snapshots can be inspected",
+            path.display()
+        );
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use dal::{
+        workspace_snapshot::node_weight::NodeWeight, ContentHash, EdgeWeight, EdgeWeightKind, Ulid,
+        WorkspaceSnapshotGraphVCurrent,
+    };
+    use si_layer_cache::db::serialize;
+
+    use super::*;
+
+    fn write(bytes: &[u8]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().expect("could not create file");
+        file.write_all(bytes).expect("could not write file");
+        file
+    }
+
+    #[test]
+    fn load_round_trips_raw_and_base64_snapshots() {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_with_categories_only()
+            .expect("could not create graph");
+        let component_id = Ulid::new();
+        let component = graph
+            .add_or_replace_node(NodeWeight::new_component(
+                component_id,
+                Ulid::new(),
+                ContentHash::from("component"),
+            ))
+            .expect("could not add node");
+        graph
+            .add_edge(
+                graph.root(),
+                EdgeWeight::new(EdgeWeightKind::new_use()),
+                component,
+            )
+            .expect("could not add edge");
+        graph
+            .cleanup_and_merkle_tree_hash()
+            .expect("could not calculate merkle tree hash");
+        let graph = WorkspaceSnapshotGraph::V4(graph);
+        let (bytes, _) = serialize::to_vec(&graph).expect("could not serialize graph");
+
+        for file in [
+            write(&bytes),
+            write(BASE64_STANDARD.encode(&bytes).as_bytes()),
+        ] {
+            let loaded = load(file.path()).expect("could not load snapshot");
+
+            assert_eq!(graph.node_count(), loaded.node_count());
+            assert_eq!(
+                graph
+                    .get_node_weight(graph.root())
+                    .expect("could not get root")
+                    .merkle_tree_hash(),
+                loaded
+                    .get_node_weight(loaded.root())
+                    .expect("could not get root")
+                    .merkle_tree_hash()
+            );
+            assert!(loaded.get_node_index_by_id(component_id).is_ok());
+        }
+    }
+}
@@ -79,6 +79,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) cyclone_local_firecracker: bool,
 
+    /// Cyclone runtime type: LocalSandbox
+    #[arg(long)]
+    pub(crate) cyclone_local_sandbox: bool,
+
     /// Cyclone firecracker connect timeout
     #[arg(long)]
     pub(crate) cyclone_connect_timeout: Option<u64>,
@@ -87,6 +91,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) cyclone_pool_size: Option<u32>,
 
+    /// Cyclone pool minimum size, kept running even when idle
+    #[arg(long)]
+    pub(crate) cyclone_pool_min_size: Option<u32>,
+
     /// Veritech decryption key file location [example: /run/veritech/veritech.key]
     #[arg(long)]
     pub(crate) decryption_key: Option<PathBuf>,
@@ -130,12 +138,18 @@ impl TryFrom<Args> for Config {
             if args.cyclone_local_process {
                 config_map.set("cyclone.runtime_strategy", "LocalProcess");
             }
+            if args.cyclone_local_sandbox {
+                config_map.set("cyclone.runtime_strategy", "LocalSandbox");
+            }
             if let Some(timeout) = args.cyclone_connect_timeout {
                 config_map.set("cyclone.connect_timeout", timeout);
             }
             if let Some(size) = args.cyclone_pool_size {
                 config_map.set("cyclone.pool_size", size);
             }
+            if let Some(size) = args.cyclone_pool_min_size {
+                config_map.set("pool_min_size", size);
+            }
             if let Some(decryption_key_path) = args.decryption_key {
                 config_map.set(
                     "decryption_key_path",
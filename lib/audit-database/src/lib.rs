@@ -47,6 +47,7 @@ use thiserror::Error;
 mod config;
 mod context;
 mod migrate;
+mod query;
 
 pub use config::default_pg_pool_config;
 pub use config::AuditDatabaseConfig;
@@ -54,6 +55,10 @@ pub use config::DBNAME;
 pub use context::AuditDatabaseContext;
 pub use context::AuditDatabaseContextError;
 pub use migrate::{migrate, AuditDatabaseMigrationError};
+pub use query::{
+    AuditLogCursor, AuditLogMetadataPredicate, AuditLogPage, AuditLogQuery, DEFAULT_PAGE_SIZE,
+    MAX_PAGE_SIZE,
+};
 
 #[allow(missing_docs)]
 #[remain::sorted]
@@ -61,6 +66,8 @@ pub use migrate::{migrate, AuditDatabaseMigrationError};
 pub enum AuditDatabaseError {
     #[error("chrono parse error: {0}")]
     ChronoParse(#[from] chrono::ParseError),
+    #[error("invalid audit log cursor: {0}")]
+    InvalidCursor(String),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("pg pool error: {0}")]
@@ -89,6 +96,8 @@ pub struct AuditLogRow {
     pub change_set_id: Option<ChangeSetId>,
     /// The identifier of the user. If this is empty, it is the system user.
     pub user_id: Option<UserPk>,
+    /// Whether the user acted through an automation token, rather than through the web app.
+    pub automation: bool,
     /// The entity name.
     pub entity_name: Option<String>,
     /// The entity type.
@@ -119,9 +128,10 @@ impl AuditLogRow {
         entity_name: Option<String>,
     ) -> Result<()> {
         let kind_as_string = kind.to_string();
-        let user_id = match actor {
-            Actor::System => None,
-            Actor::User(user_id) => Some(user_id),
+        let (user_id, automation) = match actor {
+            Actor::System => (None, false),
+            Actor::User(user_id) => (Some(user_id), false),
+            Actor::Automation(user_id) => (Some(user_id), true),
         };
 
         let metadata = AuditLogMetadata::from(kind);
@@ -143,7 +153,8 @@ impl AuditLogRow {
                     user_id,
                     entity_name,
                     entity_type,
-                    metadata
+                    metadata,
+                    automation
                 ) VALUES (
                     $1,
                     $2,
@@ -153,7 +164,8 @@ impl AuditLogRow {
                     $6,
                     $7,
                     $8,
-                    $9
+                    $9,
+                    $10
                 ) RETURNING *",
                 &[
                     &workspace_id.to_string(),
@@ -165,6 +177,7 @@ impl AuditLogRow {
                     &entity_name,
                     &entity_type,
                     &serialized_metadata,
+                    &automation,
                 ],
             )
             .await?;
@@ -172,6 +185,9 @@ impl AuditLogRow {
     }
 
     /// Lists rows of the audit logs table in the audit database.
+    ///
+    /// This is a convenience wrapper around an unfiltered [`AuditLogQuery`]. The boolean returned
+    /// indicates whether or not more rows can be loaded.
     #[instrument(
         name = "audit_log.database.list",
         level = "debug",
@@ -187,34 +203,13 @@ impl AuditLogRow {
         size: usize,
         sort_ascending: bool,
     ) -> Result<(Vec<Self>, bool)> {
-        let size = size as i64;
-        let change_set_ids: Vec<String> = change_set_ids.iter().map(|id| id.to_string()).collect();
-
-        let client = context.pg_pool().get().await?;
-        let row = client
-            .query_one(
-                "SELECT COUNT(*) from audit_logs WHERE workspace_id = $1 AND change_set_id = ANY($2)",
-                &[&workspace_id, &change_set_ids],
-            )
-            .await?;
-        let count: i64 = row.try_get("count")?;
-        let can_load_more = count > size;
-
-        let query = if sort_ascending {
-            "SELECT * from audit_logs WHERE workspace_id = $1 AND change_set_id = ANY($2) ORDER BY timestamp ASC LIMIT $3"
-        } else {
-            "SELECT * from audit_logs WHERE workspace_id = $1 AND change_set_id = ANY($2) ORDER BY timestamp DESC LIMIT $3"
-        };
-
-        let mut result = Vec::new();
-        let rows = client
-            .query(query, &[&workspace_id, &change_set_ids, &size])
+        let page = AuditLogQuery::new(size)
+            .sort_ascending(sort_ascending)
+            .run(context, workspace_id, change_set_ids)
             .await?;
-        for row in rows {
-            result.push(Self::try_from(row)?);
-        }
+        let can_load_more = page.next_cursor.is_some();
 
-        Ok((result, can_load_more))
+        Ok((page.rows, can_load_more))
     }
 }
 
@@ -248,6 +243,7 @@ impl TryFrom<PgRow> for AuditLogRow {
             title: value.try_get("title")?,
             change_set_id,
             user_id,
+            automation: value.try_get("automation")?,
             entity_name: value.try_get("entity_name")?,
             entity_type: value.try_get("entity_type")?,
             metadata: value.try_get("metadata")?,
@@ -0,0 +1,3 @@
+CREATE INDEX audit_logs_workspace_timestamp_pk ON audit_logs (workspace_id, timestamp, pk);
+CREATE INDEX audit_logs_workspace_user ON audit_logs (workspace_id, user_id);
+CREATE INDEX audit_logs_workspace_entity ON audit_logs (workspace_id, entity_type, entity_name);
@@ -0,0 +1 @@
+ALTER TABLE audit_logs ADD COLUMN automation boolean NOT NULL DEFAULT FALSE;
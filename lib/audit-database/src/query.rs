@@ -0,0 +1,354 @@
+//! Contains a filterable, cursor-paginated query over the audit logs table.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::postgres_types::ToSql;
+use si_events::{ChangeSetId, UserPk, WorkspacePk};
+use telemetry::prelude::*;
+
+use crate::{AuditDatabaseContext, AuditDatabaseError, AuditLogRow, Result};
+
+const CURSOR_SEPARATOR: char = '_';
+
+/// The number of rows in a page when the caller does not ask for a specific size.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// The largest number of rows a page may hold.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// A keyset cursor pointing at a row in the audit logs table.
+///
+/// Rows are ordered by their timestamp first and their primary key second, so the pair uniquely
+/// identifies a position in the ordering, even when many rows share the same timestamp. The cursor
+/// is rendered as an opaque string for use in query strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditLogCursor {
+    timestamp: DateTime<Utc>,
+    pk: i64,
+}
+
+impl fmt::Display for AuditLogCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{CURSOR_SEPARATOR}{}",
+            self.timestamp.timestamp_micros(),
+            self.pk
+        )
+    }
+}
+
+impl FromStr for AuditLogCursor {
+    type Err = AuditDatabaseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || AuditDatabaseError::InvalidCursor(s.to_owned());
+
+        let (micros, pk) = s.split_once(CURSOR_SEPARATOR).ok_or_else(invalid)?;
+        let micros: i64 = micros.parse().map_err(|_| invalid())?;
+        let pk: i64 = pk.parse().map_err(|_| invalid())?;
+        let timestamp = DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+
+        Ok(Self { timestamp, pk })
+    }
+}
+
+impl Serialize for AuditLogCursor {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AuditLogCursor {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        Self::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A predicate on the serialized [`AuditLogMetadata`](si_events::audit_log::AuditLogMetadata) of
+/// an audit log. It matches when the value found at the path, rendered as text, equals the expected
+/// value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogMetadataPredicate {
+    path: Vec<String>,
+    value: String,
+}
+
+impl AuditLogMetadataPredicate {
+    /// Creates a new predicate from a path of object keys (or array indices) and the expected
+    /// value.
+    pub fn new(
+        path: impl IntoIterator<Item = impl Into<String>>,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into_iter().map(Into::into).collect(),
+            value: value.into(),
+        }
+    }
+}
+
+/// A page of rows returned from an [`AuditLogQuery`].
+#[derive(Debug, Clone)]
+pub struct AuditLogPage {
+    /// The rows found for the page.
+    pub rows: Vec<AuditLogRow>,
+    /// The cursor to pass to [`AuditLogQuery::after`] in order to load the next page, if there is
+    /// one.
+    pub next_cursor: Option<AuditLogCursor>,
+}
+
+/// A filterable query over the audit logs table, paginated with an [`AuditLogCursor`].
+///
+/// The query is always scoped to a workspace and a set of change sets when it is run. All other
+/// filters are optional and are combined with each other.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogQuery {
+    user_id: Option<UserPk>,
+    kinds: Vec<String>,
+    entity_type: Option<String>,
+    entity_name: Option<String>,
+    start_timestamp: Option<DateTime<Utc>>,
+    end_timestamp: Option<DateTime<Utc>>,
+    metadata_predicates: Vec<AuditLogMetadataPredicate>,
+    cursor: Option<AuditLogCursor>,
+    size: usize,
+    sort_ascending: bool,
+}
+
+impl AuditLogQuery {
+    /// Creates a new, unfiltered query returning pages of the given size, which is clamped to
+    /// between one row and [`MAX_PAGE_SIZE`].
+    pub fn new(size: usize) -> Self {
+        Self {
+            size: size.clamp(1, MAX_PAGE_SIZE),
+            ..Default::default()
+        }
+    }
+
+    /// Only include audit logs written by the given user.
+    pub fn user_id(mut self, user_id: UserPk) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Only include audit logs of one of the given kinds.
+    pub fn kinds(mut self, kinds: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.kinds = kinds.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Only include audit logs for the given entity type.
+    pub fn entity_type(mut self, entity_type: impl Into<String>) -> Self {
+        self.entity_type = Some(entity_type.into());
+        self
+    }
+
+    /// Only include audit logs for the given entity name.
+    pub fn entity_name(mut self, entity_name: impl Into<String>) -> Self {
+        self.entity_name = Some(entity_name.into());
+        self
+    }
+
+    /// Only include audit logs at or after the given timestamp.
+    pub fn start_timestamp(mut self, start_timestamp: DateTime<Utc>) -> Self {
+        self.start_timestamp = Some(start_timestamp);
+        self
+    }
+
+    /// Only include audit logs before the given timestamp.
+    pub fn end_timestamp(mut self, end_timestamp: DateTime<Utc>) -> Self {
+        self.end_timestamp = Some(end_timestamp);
+        self
+    }
+
+    /// Only include audit logs whose metadata matches the given predicate.
+    pub fn metadata(mut self, predicate: AuditLogMetadataPredicate) -> Self {
+        self.metadata_predicates.push(predicate);
+        self
+    }
+
+    /// Start the page after the row the cursor points at.
+    pub fn after(mut self, cursor: AuditLogCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Sort from the oldest to the newest audit log, rather than newest first.
+    pub fn sort_ascending(mut self, sort_ascending: bool) -> Self {
+        self.sort_ascending = sort_ascending;
+        self
+    }
+
+    /// Runs the query for the given workspace and change sets.
+    #[instrument(
+        name = "audit_log.database.query",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn run(
+        &self,
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        change_set_ids: Vec<ChangeSetId>,
+    ) -> Result<AuditLogPage> {
+        let change_set_ids: Vec<String> = change_set_ids.iter().map(|id| id.to_string()).collect();
+
+        let mut params = QueryParams::default();
+        let mut predicates = vec![
+            format!("workspace_id = {}", params.push(workspace_id.to_string())),
+            format!("change_set_id = ANY({})", params.push(change_set_ids)),
+        ];
+
+        if let Some(user_id) = self.user_id {
+            predicates.push(format!("user_id = {}", params.push(user_id.to_string())));
+        }
+        if !self.kinds.is_empty() {
+            predicates.push(format!("kind = ANY({})", params.push(self.kinds.clone())));
+        }
+        if let Some(entity_type) = &self.entity_type {
+            predicates.push(format!(
+                "entity_type = {}",
+                params.push(entity_type.clone())
+            ));
+        }
+        if let Some(entity_name) = &self.entity_name {
+            predicates.push(format!(
+                "entity_name = {}",
+                params.push(entity_name.clone())
+            ));
+        }
+        if let Some(start_timestamp) = self.start_timestamp {
+            predicates.push(format!("timestamp >= {}", params.push(start_timestamp)));
+        }
+        if let Some(end_timestamp) = self.end_timestamp {
+            predicates.push(format!("timestamp < {}", params.push(end_timestamp)));
+        }
+        for predicate in &self.metadata_predicates {
+            predicates.push(format!(
+                "metadata #>> {} = {}",
+                params.push(predicate.path.clone()),
+                params.push(predicate.value.clone())
+            ));
+        }
+
+        let (comparison, direction) = if self.sort_ascending {
+            (">", "ASC")
+        } else {
+            ("<", "DESC")
+        };
+        if let Some(cursor) = self.cursor {
+            predicates.push(format!(
+                "(timestamp, pk) {comparison} ({}, {})",
+                params.push(cursor.timestamp),
+                params.push(cursor.pk)
+            ));
+        }
+
+        // We fetch one row more than requested to know whether there is another page without
+        // needing to count the whole table.
+        let limit = params.push(self.size as i64 + 1);
+        let query = format!(
+            "SELECT * FROM audit_logs WHERE {} ORDER BY timestamp {direction}, pk {direction} LIMIT {limit}",
+            predicates.join(" AND "),
+        );
+
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(&query, &params.as_refs())
+            .await?;
+
+        let has_next_page = rows.len() > self.size;
+        let mut result = Vec::with_capacity(self.size);
+        let mut next_cursor = None;
+        for row in rows.into_iter().take(self.size) {
+            if has_next_page {
+                next_cursor = Some(AuditLogCursor {
+                    timestamp: row.try_get("timestamp")?,
+                    pk: row.try_get("pk")?,
+                });
+            }
+            result.push(AuditLogRow::try_from(row)?);
+        }
+
+        Ok(AuditLogPage {
+            rows: result,
+            next_cursor,
+        })
+    }
+}
+
+#[derive(Default)]
+struct QueryParams {
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+}
+
+impl QueryParams {
+    /// Adds a parameter and returns its positional placeholder.
+    fn push(&mut self, param: impl ToSql + Sync + Send + 'static) -> String {
+        self.params.push(Box::new(param));
+        format!("${}", self.params.len())
+    }
+
+    fn as_refs(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = AuditLogCursor {
+            timestamp: DateTime::from_timestamp_micros(1_733_000_000_123_456)
+                .expect("valid timestamp"),
+            pk: 42,
+        };
+
+        let rendered = cursor.to_string();
+        assert_eq!("1733000000123456_42", rendered);
+        assert_eq!(
+            cursor,
+            AuditLogCursor::from_str(&rendered).expect("could not parse cursor")
+        );
+    }
+
+    #[test]
+    fn page_sizes_are_clamped() {
+        assert_eq!(1, AuditLogQuery::new(0).size);
+        assert_eq!(
+            DEFAULT_PAGE_SIZE,
+            AuditLogQuery::new(DEFAULT_PAGE_SIZE).size
+        );
+        assert_eq!(MAX_PAGE_SIZE, AuditLogQuery::new(usize::MAX).size);
+    }
+
+    #[test]
+    fn invalid_cursors_are_rejected() {
+        for raw in ["", "42", "abc_1", "1_abc", "1_2_3"] {
+            assert!(
+                AuditLogCursor::from_str(raw).is_err(),
+                "cursor should be invalid: {raw}"
+            );
+        }
+    }
+}
@@ -448,9 +448,9 @@ mod tests {
     use base64::{engine::general_purpose, Engine};
     use buck2_resources::Buck2Resources;
     use cyclone_core::{
-        ActionRunRequest, ComponentKind, ComponentView, ComponentViewWithGeometry, FunctionResult,
-        ManagementRequest, ProgressMessage, ResolverFunctionComponent, ResolverFunctionRequest,
-        SchemaVariantDefinitionRequest, ValidationRequest,
+        ActionRunRequest, ComponentKind, ComponentView, ComponentViewWithGeometry, FuncRuntime,
+        FunctionResult, ManagementRequest, ProgressMessage, ResolverFunctionComponent,
+        ResolverFunctionRequest, SchemaVariantDefinitionRequest, ValidationRequest,
     };
     use cyclone_server::{Config, ConfigBuilder, Runnable as _, Server};
     use futures::StreamExt;
@@ -505,6 +505,28 @@ mod tests {
         }
     }
 
+    #[allow(clippy::disallowed_methods)] // Used to determine if running in development
+    fn lang_shell_server_path() -> String {
+        if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
+            let resources = Buck2Resources::read().expect("failed to read buck2 resources");
+
+            resources
+                .get_ends_with("lang-shell")
+                .expect("failed to get lang-shell resource")
+                .to_string_lossy()
+                .to_string()
+        } else if let Ok(dir) = env::var("CARGO_MANIFEST_DIR") {
+            Path::new(&dir)
+                .join("../../target/debug/lang-shell")
+                .canonicalize()
+                .expect("failed to canonicalize local dev build of <root>/target/debug/lang-shell")
+                .to_string_lossy()
+                .to_string()
+        } else {
+            unimplemented!("tests must be run either with Cargo or Buck2");
+        }
+    }
+
     async fn uds_server(builder: &mut ConfigBuilder, tmp_socket: &TempPath) -> Server {
         let config = builder
             .unix_domain_socket(tmp_socket)
@@ -927,6 +949,76 @@ mod tests {
         }
     }
 
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_shell_resolver() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        builder
+            .enable_resolver(true)
+            .try_runtime_lang_server_path(FuncRuntime::Shell, lang_shell_server_path())
+            .expect("failed to resolve lang shell server path");
+        let mut client = uds_client_for_running_server(&mut builder, &tmp_socket).await;
+
+        let req = ResolverFunctionRequest {
+            execution_id: "1234".to_string(),
+            handler: "doit".to_string(),
+            component: ResolverFunctionComponent {
+                data: ComponentView {
+                    properties: serde_json::json!({"salt": "n", "peppa": "pig"}),
+                    kind: ComponentKind::Standard,
+                },
+                parents: vec![],
+            },
+            response_type: cyclone_core::ResolverFunctionResponseType::Object,
+            code_base64: base64_encode(
+                r#"doit() {
+                    echo "my butt" >&2
+                    if grep -q '"salt":"n"'; then
+                        echo '{"a": "b"}'
+                    fi
+                }"#,
+            ),
+            before: vec![],
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .prepare_execution(
+                CycloneRequest::from_parts(req, Default::default())
+                    .with_runtime(FuncRuntime::Shell),
+            )
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        // Consume the output messages
+        let mut messages = Vec::new();
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(output))) => messages.push(output.message),
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive output: err={err:?}"),
+                None => break,
+            };
+        }
+        assert_eq!(messages, vec!["my butt".to_string()]);
+
+        // Get the result
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                assert!(!success.unset);
+                assert_eq!(success.data, json!({"a": "b"}));
+            }
+            FunctionResult::Failure(failure) => {
+                panic!("result should be success; failure={failure:?}")
+            }
+        }
+    }
+
     async fn execute_validation<C, Strm>(mut client: C)
     where
         Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
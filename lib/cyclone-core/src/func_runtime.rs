@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The language runtime a function is written for, which decides the lang server cyclone runs to
+/// execute it.
+#[remain::sorted]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum FuncRuntime {
+    /// TypeScript and JavaScript functions, run by lang-js
+    #[default]
+    LangJs,
+    /// Shell script functions, run by lang-shell
+    Shell,
+}
@@ -0,0 +1,510 @@
+//! The protocol cyclone speaks with the lang servers which execute functions.
+//!
+//! For every function execution, cyclone spawns the lang server for the function's
+//! [`FuncRuntime`](crate::FuncRuntime) as a child process:
+//!
+//! ```text
+//! <lang-server> <command> [--timeout <SECONDS>]
+//! ```
+//!
+//! where `<command>` is a [`LangServerCommand`] and the optional timeout bounds how long the
+//! function itself may run. The request (for example an
+//! [`ActionRunRequest`](crate::ActionRunRequest)) is written to the lang server's stdin as a
+//! single line of JSON, after which stdin is closed. The lang server answers on stdout with one
+//! [`LangServerMessage`] per line: any number of outputs followed by a single result. Anything
+//! written to stderr is passed through to cyclone's own stderr.
+//!
+//! Lang servers written in Rust can implement [`LangServer`] and hand it to [`serve`], which
+//! speaks the protocol for them.
+
+use std::{
+    io::{self, Read, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+use crate::{
+    ActionRunRequest, ActionRunResultSuccess, CycloneRequestable, FunctionResult,
+    FunctionResultFailure, FunctionResultFailureError, FunctionResultFailureErrorKind,
+    ManagementRequest, ManagementResultSuccess, OutputStream, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum LangServerError {
+    #[error("failed to serialize message")]
+    MessageSerialize(#[source] serde_json::Error),
+    #[error("failed to read request")]
+    Read(#[source] io::Error),
+    #[error("failed to deserialize request")]
+    RequestDeserialize(#[source] serde_json::Error),
+    #[error("failed to write message")]
+    Write(#[source] io::Error),
+}
+
+/// The kind of function execution a lang server is asked to perform, given as its first argument.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, Hash, PartialEq)]
+pub enum LangServerCommand {
+    #[strum(serialize = "actionRun")]
+    ActionRun,
+    #[strum(serialize = "management")]
+    Management,
+    #[strum(serialize = "resolverfunction")]
+    ResolverFunction,
+    #[strum(serialize = "schemaVariantDefinition")]
+    SchemaVariantDefinition,
+    #[strum(serialize = "validation")]
+    Validation,
+}
+
+/// A line written by a lang server to its stdout.
+#[remain::sorted]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "protocol", rename_all = "camelCase")]
+pub enum LangServerMessage<Success> {
+    Output(LangServerOutput),
+    Result(LangServerResult<Success>),
+}
+
+/// A line of output produced by the function, streamed back while it executes.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerOutput {
+    pub execution_id: String,
+    pub stream: String,
+    pub level: String,
+    pub group: Option<String>,
+    pub message: String,
+}
+
+impl From<LangServerOutput> for OutputStream {
+    fn from(value: LangServerOutput) -> Self {
+        Self {
+            execution_id: value.execution_id,
+            stream: value.stream,
+            level: value.level,
+            group: value.group,
+            message: value.message,
+            timestamp: timestamp(),
+        }
+    }
+}
+
+/// The final result of a function execution.
+#[remain::sorted]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LangServerResult<Success> {
+    Failure(LangServerFailure),
+    Success(Success),
+}
+
+impl<LangServerSuccess, Success> From<LangServerResult<LangServerSuccess>>
+    for FunctionResult<Success>
+where
+    LangServerSuccess: Into<Success>,
+{
+    fn from(value: LangServerResult<LangServerSuccess>) -> Self {
+        match value {
+            LangServerResult::Success(success) => Self::Success(success.into()),
+            LangServerResult::Failure(failure) => Self::Failure(FunctionResultFailure::new(
+                failure.execution_id,
+                FunctionResultFailureError {
+                    kind: failure.error.kind,
+                    message: failure.error.message,
+                },
+                timestamp(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerFailure {
+    #[serde(default)]
+    pub execution_id: String,
+    pub error: LangServerFailureError,
+}
+
+impl LangServerFailure {
+    pub fn new(
+        execution_id: impl Into<String>,
+        kind: FunctionResultFailureErrorKind,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            error: LangServerFailureError {
+                kind,
+                message: message.into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerFailureError {
+    pub kind: FunctionResultFailureErrorKind,
+    pub message: String,
+}
+
+/// The successful result of an action function. All fields without the `#[serde(default)]`
+/// macro must be populated.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerActionRunResultSuccess {
+    pub execution_id: String,
+    #[serde(default)]
+    pub resource_id: Option<String>,
+    #[serde(default)]
+    pub payload: Option<Value>,
+    pub health: ResourceStatus,
+    #[serde(default)]
+    pub message: Option<String>,
+    // Collects the error if the function throws
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl From<LangServerActionRunResultSuccess> for ActionRunResultSuccess {
+    fn from(value: LangServerActionRunResultSuccess) -> Self {
+        Self {
+            execution_id: value.execution_id,
+            resource_id: value.resource_id,
+            error: value.error,
+            status: value.health,
+            message: value.message,
+            payload: value.payload,
+        }
+    }
+}
+
+/// The successful result of an attribute, qualification or other resolver function.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerResolverFunctionResultSuccess {
+    pub execution_id: String,
+    #[serde(default)]
+    pub data: Value,
+    pub unset: bool,
+}
+
+impl From<LangServerResolverFunctionResultSuccess> for ResolverFunctionResultSuccess {
+    fn from(value: LangServerResolverFunctionResultSuccess) -> Self {
+        Self {
+            execution_id: value.execution_id,
+            data: value.data,
+            unset: value.unset,
+            timestamp: timestamp(),
+        }
+    }
+}
+
+/// The successful result of a validation function.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerValidationResultSuccess {
+    pub execution_id: String,
+    pub error: Option<String>,
+}
+
+impl From<LangServerValidationResultSuccess> for ValidationResultSuccess {
+    fn from(value: LangServerValidationResultSuccess) -> Self {
+        Self {
+            execution_id: value.execution_id,
+            error: value.error,
+        }
+    }
+}
+
+/// Streams a function's output back to cyclone while it executes.
+pub struct LangServerOutputWriter<'a> {
+    execution_id: String,
+    writer: &'a mut dyn Write,
+}
+
+impl<'a> LangServerOutputWriter<'a> {
+    pub fn new(execution_id: impl Into<String>, writer: &'a mut dyn Write) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            writer,
+        }
+    }
+
+    /// Writes a line of the function's output on the given stream, such as `"output"`.
+    pub fn output(
+        &mut self,
+        stream: impl Into<String>,
+        level: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Result<(), LangServerError> {
+        let output = LangServerOutput {
+            execution_id: self.execution_id.clone(),
+            stream: stream.into(),
+            level: level.into(),
+            group: None,
+            message: message.into(),
+        };
+        write_message::<Value>(self.writer, &LangServerMessage::Output(output))
+    }
+}
+
+/// A lang server, executing functions for one [`FuncRuntime`](crate::FuncRuntime).
+///
+/// Every kind of function execution defaults to failing as unsupported, so a lang server only
+/// implements the kinds its runtime can execute.
+pub trait LangServer {
+    fn action_run(
+        &mut self,
+        request: ActionRunRequest,
+        _output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<LangServerActionRunResultSuccess>, LangServerError> {
+        Ok(unsupported(
+            request.execution_id,
+            LangServerCommand::ActionRun,
+        ))
+    }
+
+    fn management(
+        &mut self,
+        request: ManagementRequest,
+        _output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<ManagementResultSuccess>, LangServerError> {
+        Ok(unsupported(
+            request.execution_id,
+            LangServerCommand::Management,
+        ))
+    }
+
+    fn resolver_function(
+        &mut self,
+        request: ResolverFunctionRequest,
+        _output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<LangServerResolverFunctionResultSuccess>, LangServerError> {
+        Ok(unsupported(
+            request.execution_id,
+            LangServerCommand::ResolverFunction,
+        ))
+    }
+
+    fn schema_variant_definition(
+        &mut self,
+        request: SchemaVariantDefinitionRequest,
+        _output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<SchemaVariantDefinitionResultSuccess>, LangServerError> {
+        Ok(unsupported(
+            request.execution_id,
+            LangServerCommand::SchemaVariantDefinition,
+        ))
+    }
+
+    fn validation(
+        &mut self,
+        request: ValidationRequest,
+        _output: &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<LangServerValidationResultSuccess>, LangServerError> {
+        Ok(unsupported(
+            request.execution_id,
+            LangServerCommand::Validation,
+        ))
+    }
+}
+
+/// Reads a single request for the command from `input`, executes it with the lang server and
+/// writes its output and result to `output`.
+pub fn serve<S>(
+    lang_server: &mut S,
+    command: LangServerCommand,
+    mut input: impl Read,
+    mut output: impl Write,
+) -> Result<(), LangServerError>
+where
+    S: LangServer + ?Sized,
+{
+    let mut request = String::new();
+    input
+        .read_to_string(&mut request)
+        .map_err(LangServerError::Read)?;
+
+    match command {
+        LangServerCommand::ActionRun => serve_request(&request, &mut output, |request, writer| {
+            lang_server.action_run(request, writer)
+        }),
+        LangServerCommand::Management => serve_request(&request, &mut output, |request, writer| {
+            lang_server.management(request, writer)
+        }),
+        LangServerCommand::ResolverFunction => {
+            serve_request(&request, &mut output, |request, writer| {
+                lang_server.resolver_function(request, writer)
+            })
+        }
+        LangServerCommand::SchemaVariantDefinition => {
+            serve_request(&request, &mut output, |request, writer| {
+                lang_server.schema_variant_definition(request, writer)
+            })
+        }
+        LangServerCommand::Validation => serve_request(&request, &mut output, |request, writer| {
+            lang_server.validation(request, writer)
+        }),
+    }
+}
+
+fn serve_request<Request, Success>(
+    request: &str,
+    output: &mut dyn Write,
+    execute: impl FnOnce(
+        Request,
+        &mut LangServerOutputWriter<'_>,
+    ) -> Result<LangServerResult<Success>, LangServerError>,
+) -> Result<(), LangServerError>
+where
+    Request: DeserializeOwned + CycloneRequestable,
+    Success: Serialize,
+{
+    let request: Request =
+        serde_json::from_str(request).map_err(LangServerError::RequestDeserialize)?;
+    let result = {
+        let mut writer = LangServerOutputWriter::new(request.execution_id(), output);
+        execute(request, &mut writer)?
+    };
+    write_message(output, &LangServerMessage::Result(result))?;
+    output.flush().map_err(LangServerError::Write)
+}
+
+fn write_message<Success>(
+    writer: &mut dyn Write,
+    message: &LangServerMessage<Success>,
+) -> Result<(), LangServerError>
+where
+    Success: Serialize,
+{
+    let mut line = serde_json::to_vec(message).map_err(LangServerError::MessageSerialize)?;
+    line.push(b'\n');
+    writer.write_all(&line).map_err(LangServerError::Write)
+}
+
+fn unsupported<Success>(
+    execution_id: String,
+    command: LangServerCommand,
+) -> LangServerResult<Success> {
+    LangServerResult::Failure(LangServerFailure::new(
+        execution_id,
+        FunctionResultFailureErrorKind::VeritechServer,
+        format!("{command} functions are not supported by this lang server"),
+    ))
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    struct Echo;
+
+    impl LangServer for Echo {
+        fn resolver_function(
+            &mut self,
+            request: ResolverFunctionRequest,
+            output: &mut LangServerOutputWriter<'_>,
+        ) -> Result<LangServerResult<LangServerResolverFunctionResultSuccess>, LangServerError>
+        {
+            output.output("output", "info", request.handler.as_str())?;
+            Ok(LangServerResult::Success(
+                LangServerResolverFunctionResultSuccess {
+                    execution_id: request.execution_id,
+                    data: json!(request.handler),
+                    unset: false,
+                },
+            ))
+        }
+    }
+
+    fn serve_lines(command: LangServerCommand, request: Value) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(
+            &mut Echo,
+            command,
+            request.to_string().as_bytes(),
+            &mut output,
+        )
+        .expect("failed to serve request");
+
+        String::from_utf8(output)
+            .expect("output is not utf8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("output line is not json"))
+            .collect()
+    }
+
+    #[test]
+    fn writes_outputs_then_result() {
+        let lines = serve_lines(
+            LangServerCommand::ResolverFunction,
+            json!({
+                "executionId": "1234",
+                "handler": "main",
+                "component": { "data": { "kind": "standard", "properties": {} }, "parents": [] },
+                "responseType": "String",
+                "codeBase64": "",
+                "before": [],
+            }),
+        );
+
+        assert_eq!(
+            vec![
+                json!({
+                    "protocol": "output",
+                    "executionId": "1234",
+                    "stream": "output",
+                    "level": "info",
+                    "group": null,
+                    "message": "main",
+                }),
+                json!({
+                    "protocol": "result",
+                    "status": "success",
+                    "executionId": "1234",
+                    "data": "main",
+                    "unset": false,
+                }),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn unimplemented_kinds_fail() {
+        let lines = serve_lines(
+            LangServerCommand::Validation,
+            json!({
+                "executionId": "1234",
+                "handler": "main",
+                "codeBase64": "",
+                "value": null,
+                "validationFormat": "{}",
+                "before": [],
+            }),
+        );
+
+        assert_eq!(1, lines.len());
+        assert_eq!("failure", lines[0]["status"]);
+        assert_eq!("1234", lines[0]["executionId"]);
+    }
+}
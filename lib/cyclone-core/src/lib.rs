@@ -15,7 +15,9 @@ mod action_run;
 mod before;
 mod canonical_command;
 mod component_view;
+mod func_runtime;
 mod kill_execution;
+pub mod lang_server;
 mod liveness;
 mod management;
 pub mod process;
@@ -33,6 +35,7 @@ pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
 pub use before::BeforeFunction;
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView, ComponentViewWithGeometry};
+pub use func_runtime::FuncRuntime;
 pub use kill_execution::KillExecutionRequest;
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
 pub use management::{ManagementFuncStatus, ManagementRequest, ManagementResultSuccess};
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use si_crypto::SensitiveStrings;
 use si_std::SensitiveString;
 
+use crate::FuncRuntime;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CycloneRequest<R>
@@ -12,6 +14,8 @@ where
 {
     request: R,
     sensitive_strings: HashSet<SensitiveString>,
+    #[serde(default)]
+    runtime: FuncRuntime,
 }
 
 impl<R> CycloneRequest<R>
@@ -22,9 +26,21 @@ where
         Self {
             request,
             sensitive_strings: sensitive_strings.into(),
+            runtime: FuncRuntime::default(),
         }
     }
 
+    /// Sets the runtime whose lang server executes the request.
+    #[must_use]
+    pub fn with_runtime(mut self, runtime: FuncRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn runtime(&self) -> FuncRuntime {
+        self.runtime
+    }
+
     pub fn websocket_path(&self) -> &str {
         self.request.websocket_path()
     }
@@ -1,9 +1,11 @@
 use std::{
+    collections::HashMap,
     net::{SocketAddr, ToSocketAddrs},
     path::{Path, PathBuf},
     time::Duration,
 };
 
+use cyclone_core::FuncRuntime;
 use derive_builder::Builder;
 use si_std::{CanonicalFile, CanonicalFileError};
 use thiserror::Error;
@@ -52,6 +54,9 @@ pub struct Config {
     #[builder(try_setter, setter(into))]
     lang_server_path: CanonicalFile,
 
+    #[builder(default, setter(custom))]
+    runtime_lang_server_paths: HashMap<FuncRuntime, CanonicalFile>,
+
     #[builder(default)]
     lang_server_function_timeout: Option<usize>,
 
@@ -129,6 +134,23 @@ impl Config {
         self.lang_server_path.as_path()
     }
 
+    /// Gets the lang server program for each runtime cyclone can execute functions for.
+    ///
+    /// The [`FuncRuntime::LangJs`] runtime always uses the config's lang server path.
+    #[must_use]
+    pub fn lang_server_paths(&self) -> HashMap<FuncRuntime, PathBuf> {
+        let mut paths: HashMap<_, _> = self
+            .runtime_lang_server_paths
+            .iter()
+            .map(|(runtime, path)| (*runtime, path.as_path().to_path_buf()))
+            .collect();
+        paths.insert(
+            FuncRuntime::LangJs,
+            self.lang_server_path.as_path().to_path_buf(),
+        );
+        paths
+    }
+
     /// Gets a reference to the config's lang server function timeout optional override.
     #[must_use]
     pub fn lang_server_function_timeout(&self) -> Option<usize> {
@@ -161,6 +183,20 @@ impl Config {
 }
 
 impl ConfigBuilder {
+    /// Sets the lang server program which executes functions for a runtime other than
+    /// [`FuncRuntime::LangJs`].
+    pub fn try_runtime_lang_server_path(
+        &mut self,
+        runtime: FuncRuntime,
+        path: impl Into<PathBuf>,
+    ) -> Result<&mut Self> {
+        let path = CanonicalFile::try_from(path.into())?;
+        self.runtime_lang_server_paths
+            .get_or_insert_with(HashMap::new)
+            .insert(runtime, path);
+        Ok(self)
+    }
+
     pub fn http_socket(&mut self, socket_addrs: impl ToSocketAddrs) -> Result<&mut Self> {
         Ok(self.incoming_stream(IncomingStream::http_socket(socket_addrs)?))
     }
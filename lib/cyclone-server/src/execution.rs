@@ -11,12 +11,12 @@ use std::{
 use axum::extract::ws::WebSocket;
 use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
+    lang_server::{LangServerCommand, LangServerMessage, LangServerOutput, LangServerResult},
     process::{self, ShutdownError},
-    CycloneRequest, CycloneRequestable, FunctionResult, FunctionResultFailure,
-    FunctionResultFailureError, FunctionResultFailureErrorKind, Message, OutputStream,
+    CycloneRequest, CycloneRequestable, FuncRuntime, Message,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use si_crypto::SensitiveStrings;
 use telemetry::prelude::*;
@@ -29,23 +29,23 @@ use tokio::{
 use tokio_serde::{formats::SymmetricalJson, Deserializer, Framed, SymmetricallyFramed};
 use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
 
-use crate::WebSocketMessage;
+use crate::{state::LangServerPaths, WebSocketMessage};
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
 const DEFAULT_LANG_SERVER_PROCESS_TIMEOUT: Duration = Duration::from_secs(32 * 60);
 
 pub fn new<Request, LangServerSuccess, Success>(
-    lang_server_path: impl Into<PathBuf>,
+    lang_server_paths: LangServerPaths,
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Option<u64>,
-    command: String,
+    command: LangServerCommand,
 ) -> Execution<Request, LangServerSuccess, Success>
 where
     Request: CycloneRequestable,
 {
     Execution {
-        lang_server_path: lang_server_path.into(),
+        lang_server_paths,
         lang_server_debugging,
         lang_server_function_timeout,
         lang_server_process_timeout: match lang_server_process_timeout {
@@ -82,6 +82,8 @@ pub enum ExecutionError {
     JSONSerialize(#[source] serde_json::Error),
     #[error("send timeout")]
     SendTimeout(#[source] tokio::time::error::Elapsed),
+    #[error("no lang server configured for runtime: {0}")]
+    UnsupportedRuntime(FuncRuntime),
     #[error("unexpected websocket message type: {0:?}")]
     UnexpectedMessageType(WebSocketMessage),
     #[error("failed to close websocket")]
@@ -101,11 +103,11 @@ pub struct Execution<Request, LangServerSuccess, Success>
 where
     Request: CycloneRequestable,
 {
-    lang_server_path: PathBuf,
+    lang_server_paths: LangServerPaths,
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Duration,
-    command: String,
+    command: LangServerCommand,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
     success_marker: PhantomData<Success>,
@@ -125,12 +127,20 @@ where
         Self::ws_send_start(ws).await?;
         // Read the request message from the web socket
         let cyclone_request = Self::read_request(ws).await?;
+        let runtime = cyclone_request.runtime();
         let (request, sensitive_strings) = cyclone_request.into_parts();
 
+        // Each runtime has its own lang server, all of which speak the same protocol
+        let lang_server_path = self
+            .lang_server_paths
+            .for_runtime(runtime)
+            .ok_or(ExecutionError::UnsupportedRuntime(runtime))?
+            .to_path_buf();
+
         // Spawn lang server as a child process with handles on all i/o descriptors
-        let mut command = Command::new(&self.lang_server_path);
+        let mut command = Command::new(&lang_server_path);
         command
-            .arg(&self.command)
+            .arg(self.command.to_string())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
@@ -145,7 +155,7 @@ where
         debug!(cmd = ?command, "spawning child process");
         let mut child = command
             .spawn()
-            .map_err(|err| ExecutionError::ChildSpawn(err, self.lang_server_path.clone()))?;
+            .map_err(|err| ExecutionError::ChildSpawn(err, lang_server_path))?;
 
         let stdin = child.stdin.take().ok_or(ExecutionError::ChildIO("stdin"))?;
         Self::child_send_function_request(stdin, request).await?;
@@ -427,77 +437,3 @@ where
         ws.close().await.map_err(ExecutionError::WSClose)
     }
 }
-
-#[remain::sorted]
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(tag = "protocol", rename_all = "camelCase")]
-pub enum LangServerMessage<Success> {
-    Output(LangServerOutput),
-    Result(LangServerResult<Success>),
-}
-
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LangServerOutput {
-    execution_id: String,
-    stream: String,
-    level: String,
-    group: Option<String>,
-    message: String,
-}
-
-impl From<LangServerOutput> for OutputStream {
-    fn from(value: LangServerOutput) -> Self {
-        Self {
-            execution_id: value.execution_id,
-            stream: value.stream,
-            level: value.level,
-            group: value.group,
-            message: value.message,
-            timestamp: crate::timestamp(),
-        }
-    }
-}
-
-#[remain::sorted]
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(tag = "status", rename_all = "camelCase")]
-pub enum LangServerResult<Success> {
-    Failure(LangServerFailure),
-    Success(Success),
-}
-
-impl<LangServerSuccess, Success> From<LangServerResult<LangServerSuccess>>
-    for FunctionResult<Success>
-where
-    LangServerSuccess: Into<Success>,
-{
-    fn from(value: LangServerResult<LangServerSuccess>) -> Self {
-        match value {
-            LangServerResult::Success(success) => Self::Success(success.into()),
-            LangServerResult::Failure(failure) => Self::Failure(FunctionResultFailure::new(
-                failure.execution_id,
-                FunctionResultFailureError {
-                    kind: failure.error.kind,
-                    message: failure.error.message,
-                },
-                crate::timestamp(),
-            )),
-        }
-    }
-}
-
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LangServerFailure {
-    #[serde(default)]
-    execution_id: String,
-    error: LangServerFailureError,
-}
-
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct LangServerFailureError {
-    kind: FunctionResultFailureErrorKind,
-    message: String,
-}
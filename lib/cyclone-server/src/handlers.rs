@@ -1,7 +1,6 @@
 use std::{
     fmt,
     marker::{PhantomData, Unpin},
-    path::PathBuf,
     sync::Arc,
 };
 
@@ -13,6 +12,10 @@ use axum::{
     response::IntoResponse,
 };
 use cyclone_core::{
+    lang_server::{
+        LangServerActionRunResultSuccess, LangServerCommand,
+        LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
+    },
     ActionRunRequest, ActionRunResultSuccess, CycloneRequestable, LivenessStatus,
     ManagementRequest, ManagementResultSuccess, Message, ReadinessStatus, ResolverFunctionRequest,
     ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
@@ -26,12 +29,8 @@ use telemetry_http::ParentSpan;
 use super::extract::LimitRequestGuard;
 use crate::{
     execution::{self, Execution},
-    result::{
-        LangServerActionRunResultSuccess, LangServerResolverFunctionResultSuccess,
-        LangServerValidationResultSuccess,
-    },
     state::{
-        LangServerFunctionTimeout, LangServerPath, LangServerProcessTimeout, TelemetryLevel,
+        LangServerFunctionTimeout, LangServerPaths, LangServerProcessTimeout, TelemetryLevel,
         WatchKeepalive,
     },
     watch,
@@ -100,14 +99,13 @@ pub async fn ws_execute_ping(
 
 pub async fn ws_execute_resolver(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
+    State(lang_server_paths): State<LangServerPaths>,
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ResolverFunctionRequest> = PhantomData;
@@ -115,12 +113,12 @@ pub async fn ws_execute_resolver(
         let success: PhantomData<ResolverFunctionResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            lang_server_paths,
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
             limit_request_guard,
-            "resolverfunction".to_owned(),
+            LangServerCommand::ResolverFunction,
             request,
             lang_server_success,
             success,
@@ -131,14 +129,13 @@ pub async fn ws_execute_resolver(
 
 pub async fn ws_execute_validation(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
+    State(lang_server_paths): State<LangServerPaths>,
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ValidationRequest> = PhantomData;
@@ -146,12 +143,12 @@ pub async fn ws_execute_validation(
         let success: PhantomData<ValidationResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            lang_server_paths,
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
             limit_request_guard,
-            "validation".to_owned(),
+            LangServerCommand::Validation,
             request,
             lang_server_success,
             success,
@@ -162,14 +159,13 @@ pub async fn ws_execute_validation(
 
 pub async fn ws_execute_action_run(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
+    State(lang_server_paths): State<LangServerPaths>,
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ActionRunRequest> = PhantomData;
@@ -177,12 +173,12 @@ pub async fn ws_execute_action_run(
         let success: PhantomData<ActionRunResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            lang_server_paths,
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
             limit_request_guard,
-            "actionRun".to_owned(),
+            LangServerCommand::ActionRun,
             request,
             lang_server_success,
             success,
@@ -193,14 +189,13 @@ pub async fn ws_execute_action_run(
 
 pub async fn ws_execute_schema_variant_definition(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
+    State(lang_server_paths): State<LangServerPaths>,
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<SchemaVariantDefinitionRequest> = PhantomData;
@@ -208,12 +203,12 @@ pub async fn ws_execute_schema_variant_definition(
         let success: PhantomData<SchemaVariantDefinitionResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            lang_server_paths,
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
             limit_request_guard,
-            "schemaVariantDefinition".to_owned(),
+            LangServerCommand::SchemaVariantDefinition,
             request,
             lang_server_success,
             success,
@@ -224,14 +219,13 @@ pub async fn ws_execute_schema_variant_definition(
 
 pub async fn ws_execute_management(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
+    State(lang_server_paths): State<LangServerPaths>,
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ManagementRequest> = PhantomData;
@@ -239,12 +233,12 @@ pub async fn ws_execute_management(
         let success: PhantomData<ManagementResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            lang_server_paths,
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
             limit_request_guard,
-            "management".to_owned(),
+            LangServerCommand::Management,
             request,
             lang_server_success,
             success,
@@ -263,12 +257,12 @@ pub async fn ws_execute_management(
 #[allow(clippy::too_many_arguments)]
 async fn handle_socket<Request, LangServerSuccess, Success>(
     mut socket: WebSocket,
-    lang_server_path: PathBuf,
+    lang_server_paths: LangServerPaths,
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Option<u64>,
     _limit_request_guard: LimitRequestGuard,
-    sub_command: String,
+    command: LangServerCommand,
     _request_marker: PhantomData<Request>,
     _lang_server_success_marker: PhantomData<LangServerSuccess>,
     success_marker: PhantomData<Success>,
@@ -280,19 +274,23 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
 {
     let proto = {
         let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
-            lang_server_path,
+            lang_server_paths,
             lang_server_debugging,
             lang_server_function_timeout,
             lang_server_process_timeout,
-            sub_command,
+            command,
         );
         match execution.start(&mut socket).await {
             Ok(started) => started,
             Err(err) => {
                 warn!(error = ?err, "failed to start protocol");
                 request_span.record_err(&err);
-                if let Err(err) =
-                    fail_to_process(socket, "failed to start protocol", success_marker).await
+                if let Err(err) = fail_to_process(
+                    socket,
+                    format!("failed to start protocol: {err}"),
+                    success_marker,
+                )
+                .await
                 {
                     warn!(
                         error = ?err,
@@ -4,7 +4,6 @@ mod extract;
 mod handlers;
 #[cfg(target_os = "linux")]
 pub mod process_gatherer;
-mod result;
 mod routes;
 mod server;
 mod state;
@@ -17,6 +16,7 @@ mod watch;
 
 pub use axum::extract::ws::Message as WebSocketMessage;
 pub use config::{Config, ConfigBuilder, ConfigError, IncomingStream};
+pub use cyclone_core::FuncRuntime;
 #[cfg(target_os = "linux")]
 pub use process_gatherer::init;
 pub use server::{Runnable, Server, ShutdownSource};
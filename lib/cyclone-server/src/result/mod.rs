@@ -1,7 +0,0 @@
-mod action_run;
-mod resolver_function;
-mod validation;
-
-pub use action_run::LangServerActionRunResultSuccess;
-pub use resolver_function::LangServerResolverFunctionResultSuccess;
-pub use validation::LangServerValidationResultSuccess;
@@ -215,7 +215,7 @@ fn build_service(
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
     let state = AppState::new(
-        config.lang_server_path(),
+        config.lang_server_paths(),
         telemetry_level,
         config.lang_server_function_timeout(),
         config.lang_server_process_timeout(),
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     ops::Deref,
     path::{Path, PathBuf},
     sync::Arc,
@@ -6,11 +7,12 @@ use std::{
 };
 
 use axum::extract::FromRef;
+use cyclone_core::FuncRuntime;
 use tokio::sync::mpsc;
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
-    lang_server_path: LangServerPath,
+    lang_server_paths: LangServerPaths,
     telemetry_level: TelemetryLevel,
     lang_server_function_timeout: LangServerFunctionTimeout,
     lang_server_process_timeout: LangServerProcessTimeout,
@@ -18,13 +20,13 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(
-        lang_server_path: impl Into<PathBuf>,
+        lang_server_paths: HashMap<FuncRuntime, PathBuf>,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
         lang_server_function_timeout: Option<usize>,
         lang_server_process_timeout: Option<u64>,
     ) -> Self {
         Self {
-            lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
+            lang_server_paths: LangServerPaths(Arc::new(lang_server_paths)),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
             lang_server_function_timeout: LangServerFunctionTimeout(Arc::new(
                 lang_server_function_timeout,
@@ -37,11 +39,12 @@ impl AppState {
 }
 
 #[derive(Clone, Debug, FromRef)]
-pub struct LangServerPath(Arc<PathBuf>);
+pub struct LangServerPaths(Arc<HashMap<FuncRuntime, PathBuf>>);
 
-impl LangServerPath {
-    pub fn as_path(&self) -> &Path {
-        self.0.as_path()
+impl LangServerPaths {
+    /// Gets the path of the lang server program for the runtime, if cyclone has one.
+    pub fn for_runtime(&self, runtime: FuncRuntime) -> Option<&Path> {
+        self.0.get(&runtime).map(PathBuf::as_path)
     }
 }
 
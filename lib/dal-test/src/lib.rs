@@ -40,6 +40,7 @@ use dal::{
     builtins::func,
     feature_flags::FeatureFlagService,
     job::processor::{JobQueueProcessor, NatsProcessor},
+    workspace_snapshot::integrity::CheckAfterUpdates,
     DalContext, DalLayerDb, JetstreamStreams, ModelResult, ServicesContext, Workspace,
 };
 use derive_builder::Builder;
@@ -102,6 +103,7 @@ const ENV_VAR_PG_DBNAME: &str = "SI_TEST_PG_DBNAME";
 const ENV_VAR_PG_USER: &str = "SI_TEST_PG_USER";
 const ENV_VAR_PG_PORT: &str = "SI_TEST_PG_PORT";
 const ENV_VAR_KEEP_OLD_DBS: &str = "SI_TEST_KEEP_OLD_DBS";
+const ENV_VAR_SKIP_INTEGRITY_CHECK: &str = "SI_TEST_SKIP_INTEGRITY_CHECK";
 
 const ENV_VAR_LAYER_CACHE_PG_DBNAME: &str = "SI_TEST_LAYER_CACHE_PG_DBNAME";
 const ENV_VAR_AUDIT_PG_DBNAME: &str = "SI_TEST_AUDIT_PG_DBNAME";
@@ -919,6 +921,18 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
     token.cancel();
     tracker.wait().await;
 
+    // Check the graph for integrity violations after every update made by the tests, failing the
+    // update if any are found. This is only turned on now so that the builtin migrations aren't
+    // slowed down.
+    #[allow(clippy::disallowed_methods)] // Environment variables are used exclusively in test and
+    // all are prefixed with `SI_TEST_`
+    let check_integrity = if env::var(ENV_VAR_SKIP_INTEGRITY_CHECK).is_ok_and(|v| !v.is_empty()) {
+        CheckAfterUpdates::Disabled
+    } else {
+        CheckAfterUpdates::Fail
+    };
+    dal::workspace_snapshot::integrity::set_check_after_updates(check_integrity);
+
     info!("global test setup complete");
     Ok(())
 }
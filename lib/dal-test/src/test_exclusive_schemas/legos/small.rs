@@ -333,6 +333,28 @@ pub(crate) async fn migrate_test_exclusive_schema_small_odd_lego(
     let deeply_nested_children =
         build_management_func(deeply_nested_children_code, "test:deeplyNestedChildren")?;
 
+    let delete_managed_code = r#"
+    async function main({ thisComponent, components }: Input): Promise<Output> {
+        return {
+            status: "ok",
+            ops: { delete: Object.keys(components) },
+        }
+    }
+    "#;
+    let delete_managed_func =
+        build_management_func(delete_managed_code, "test:deleteManagedComponents")?;
+
+    let erase_managed_code = r#"
+    async function main({ thisComponent, components }: Input): Promise<Output> {
+        return {
+            status: "ok",
+            ops: { erase: Object.keys(components) },
+        }
+    }
+    "#;
+    let erase_managed_func =
+        build_management_func(erase_managed_code, "test:eraseManagedComponents")?;
+
     let fn_name = "test:deleteActionSmallLego";
     let delete_action_func = build_action_func(delete_action_code, fn_name)?;
 
@@ -466,6 +488,24 @@ pub(crate) async fn migrate_test_exclusive_schema_small_odd_lego(
                         .func_unique_id(&create_and_connect_to_self_as_children_func.unique_id)
                         .build()?,
                 )
+                .management_func(
+                    ManagementFuncSpec::builder()
+                        .name("Delete Managed")
+                        .managed_schemas(Some(HashSet::from([
+                            SCHEMA_ID_SMALL_EVEN_LEGO.to_string()
+                        ])))
+                        .func_unique_id(&delete_managed_func.unique_id)
+                        .build()?,
+                )
+                .management_func(
+                    ManagementFuncSpec::builder()
+                        .name("Erase Managed")
+                        .managed_schemas(Some(HashSet::from([
+                            SCHEMA_ID_SMALL_EVEN_LEGO.to_string()
+                        ])))
+                        .func_unique_id(&erase_managed_func.unique_id)
+                        .build()?,
+                )
                 .build()?,
         )
         .build()?;
@@ -486,6 +526,8 @@ pub(crate) async fn migrate_test_exclusive_schema_small_odd_lego(
         .func(create_and_connect_to_self_func)
         .func(create_and_connect_to_self_as_children_func)
         .func(deeply_nested_children)
+        .func(delete_managed_func)
+        .func(erase_managed_func)
         .schema(small_lego_schema)
         .build()?;
 
@@ -47,7 +47,7 @@ impl ActorView {
         history_actor: HistoryActor,
     ) -> Result<Self, StandardModelError> {
         match history_actor {
-            HistoryActor::User(user_pk) => {
+            HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
                 let user = User::get_by_pk(ctx, user_pk)
                     .await?
                     .ok_or(StandardModelError::UserNotFound(user_pk))?;
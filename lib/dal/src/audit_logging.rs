@@ -2,6 +2,8 @@
 
 use audit_database::AuditDatabaseContext;
 use audit_database::AuditDatabaseError;
+use audit_database::AuditLogPage;
+use audit_database::AuditLogQuery;
 use audit_database::AuditLogRow;
 use audit_logs_stream::AuditLogsStream;
 use audit_logs_stream::AuditLogsStreamError;
@@ -252,29 +254,7 @@ pub async fn list(
     sort_ascending: bool,
 ) -> Result<(Vec<AuditLogRow>, bool)> {
     let workspace_id = ctx.workspace_pk().map_err(Box::new)?;
-    let change_set_id = ctx.change_set_id();
-
-    let change_set_ids = {
-        let mut change_set_ids = vec![change_set_id];
-        if ctx
-            .get_workspace_default_change_set_id()
-            .await
-            .map_err(Box::new)?
-            == change_set_id
-        {
-            // NOTE(nick,fletcher,brit,paul): we need to decide what this entails on HEAD in the long term. For now,
-            // it is all non-open, non-abandoned change sets... which are just the applied ones. In the future, we may
-            // or will need to ability to tell a story about abandoned change sets. This is for future us or future
-            // victims to solve. Good luck!
-            for applied_change_set in ChangeSet::list_all_applied(ctx, workspace_id)
-                .await
-                .map_err(Box::new)?
-            {
-                change_set_ids.push(applied_change_set.id);
-            }
-        }
-        change_set_ids
-    };
+    let change_set_ids = change_set_ids_in_scope(ctx).await?;
 
     Ok(AuditLogRow::list(
         audit_database_context,
@@ -286,6 +266,48 @@ pub async fn list(
     .await?)
 }
 
+/// Runs a filtered, paginated [`AuditLogQuery`] scoped to the workspace and change set of the
+/// [`DalContext`].
+#[instrument(name = "audit_logging.query", level = "debug", skip_all)]
+pub async fn query(
+    ctx: &DalContext,
+    audit_database_context: &AuditDatabaseContext,
+    query: &AuditLogQuery,
+) -> Result<AuditLogPage> {
+    let workspace_id = ctx.workspace_pk().map_err(Box::new)?;
+    let change_set_ids = change_set_ids_in_scope(ctx).await?;
+
+    Ok(query
+        .run(audit_database_context, workspace_id, change_set_ids)
+        .await?)
+}
+
+async fn change_set_ids_in_scope(ctx: &DalContext) -> Result<Vec<crate::ChangeSetId>> {
+    let workspace_id = ctx.workspace_pk().map_err(Box::new)?;
+    let change_set_id = ctx.change_set_id();
+
+    let mut change_set_ids = vec![change_set_id];
+    if ctx
+        .get_workspace_default_change_set_id()
+        .await
+        .map_err(Box::new)?
+        == change_set_id
+    {
+        // NOTE(nick,fletcher,brit,paul): we need to decide what this entails on HEAD in the long term. For now,
+        // it is all non-open, non-abandoned change sets... which are just the applied ones. In the future, we may
+        // or will need to ability to tell a story about abandoned change sets. This is for future us or future
+        // victims to solve. Good luck!
+        for applied_change_set in ChangeSet::list_all_applied(ctx, workspace_id)
+            .await
+            .map_err(Box::new)?
+        {
+            change_set_ids.push(applied_change_set.id);
+        }
+    }
+
+    Ok(change_set_ids)
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditLogsPublishedPayload {
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use rebaser_client::api_types::enqueue_updates_response::v1::RebaseStatus;
 use serde::{Deserialize, Serialize};
 use si_data_pg::{PgError, PgRow};
 use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
@@ -13,6 +14,9 @@ use thiserror::Error;
 use tokio::time;
 
 use crate::billing_publish::BillingPublishError;
+use crate::change_set::approval::{
+    ApprovalPolicy, ApprovalPolicyError, ApprovalRequirement, ApprovalScope, ChangeSetApproval,
+};
 use crate::slow_rt::SlowRuntimeError;
 use crate::workspace_snapshot::graph::RebaseBatch;
 use crate::{
@@ -26,6 +30,8 @@ use crate::{
     WorkspaceError,
 };
 
+pub mod approval;
+pub mod conflict;
 pub mod event;
 pub mod status;
 pub mod view;
@@ -35,10 +41,16 @@ const FIND_ANCESTORS_QUERY: &str = include_str!("queries/change_set/find_ancesto
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetError {
+    #[error("approval policy error: {0}")]
+    ApprovalPolicy(#[from] Box<ApprovalPolicyError>),
+    #[error("change set approval requirements not met for policies: {0:?}")]
+    ApprovalRequirementsNotMet(Vec<String>),
     #[error("billing publish error: {0}")]
     BillingPublish(#[from] Box<BillingPublishError>),
     #[error("change set not approved for apply. Current state: {0}")]
     ChangeSetNotApprovedForApply(ChangeSetStatus),
+    #[error("change set has {} conflict(s) with its base change set", .0.len())]
+    ConflictsWithBase(Vec<conflict::Conflict>),
     #[error("change set with id {0} not found")]
     ChangeSetNotFound(ChangeSetId),
     #[error("could not find default change set: {0}")]
@@ -67,6 +79,8 @@ pub enum ChangeSetError {
     Mutex(String),
     #[error("Changeset {0} does not have a base change set")]
     NoBaseChangeSet(ChangeSetId),
+    #[error("change set {0} does not have a merge base")]
+    NoMergeBase(ChangeSetId),
     #[error("no tenancy set in context")]
     NoTenancySet,
     #[error("no workspace_pk is set for change_set_id={0}")]
@@ -164,6 +178,9 @@ pub struct ChangeSet {
     pub merge_requested_at: Option<DateTime<Utc>>,
     pub reviewed_by_user_id: Option<UserPk>,
     pub reviewed_at: Option<DateTime<Utc>>,
+    /// The snapshot that the change set and its base change set last had in common, which
+    /// [`Conflicts`](conflict::Conflict) between them are detected against.
+    pub merge_base_snapshot_address: Option<WorkspaceSnapshotAddress>,
 }
 
 impl TryFrom<PgRow> for ChangeSet {
@@ -185,6 +202,7 @@ impl TryFrom<PgRow> for ChangeSet {
             merge_requested_at: value.try_get("merge_requested_at")?,
             reviewed_by_user_id: value.try_get("reviewed_by_user_id")?,
             reviewed_at: value.try_get("reviewed_at")?,
+            merge_base_snapshot_address: value.try_get("merge_base_snapshot_address")?,
         })
     }
 }
@@ -211,13 +229,15 @@ impl ChangeSet {
 
         let workspace_id = ctx.tenancy().workspace_pk_opt();
         let name = name.as_ref();
+        // Only change sets with a base change set can conflict with it
+        let merge_base_snapshot_address = base_change_set_id.map(|_| workspace_snapshot_address);
         let row = ctx
             .txns()
             .await?
             .pg()
             .query_one(
-                "INSERT INTO change_set_pointers (id, name, base_change_set_id, status, workspace_id, workspace_snapshot_address) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
-                &[&change_set_id, &name, &base_change_set_id, &ChangeSetStatus::Open.to_string(), &workspace_id, &workspace_snapshot_address],
+                "INSERT INTO change_set_pointers (id, name, base_change_set_id, status, workspace_id, workspace_snapshot_address, merge_base_snapshot_address) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+                &[&change_set_id, &name, &base_change_set_id, &ChangeSetStatus::Open.to_string(), &workspace_id, &workspace_snapshot_address, &merge_base_snapshot_address],
             )
             .await?;
         let change_set = Self::try_from(row)?;
@@ -415,6 +435,9 @@ impl ChangeSet {
                 &[&self.id, &status.to_string()],
             )
             .await?;
+        ChangeSetApproval::clear_for_change_set(ctx, self.id)
+            .await
+            .map_err(Box::new)?;
 
         self.status = status;
 
@@ -437,8 +460,8 @@ impl ChangeSet {
         Self::prepare_for_apply(ctx).await
     }
 
-    /// First, checks if DVU Roots still exist. Next, ensures the [`ChangeSet`] has an
-    /// [`ChangeSetStatus::Approved`]. Finally,
+    /// First, checks if DVU Roots still exist. Next, ensures every [`ApprovalPolicy`] applying to
+    /// the [`ChangeSet`] is satisfied and that it has an [`ChangeSetStatus::Approved`]. Finally,
     /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked
     pub async fn prepare_for_apply(ctx: &DalContext) -> ChangeSetResult<()> {
         let change_set = ChangeSet::find(ctx, ctx.change_set_id())
@@ -462,6 +485,19 @@ impl ChangeSet {
             return Err(ChangeSetError::DvuRootsNotEmpty(ctx.change_set_id()));
         }
 
+        // Approvals given before the latest edits landed don't count, so this is checked even if
+        // the change set was approved
+        let unmet_policies: Vec<String> = change_set
+            .approval_requirements(ctx)
+            .await?
+            .into_iter()
+            .filter(|requirement| !requirement.is_satisfied())
+            .map(|requirement| requirement.policy_name)
+            .collect();
+        if !unmet_policies.is_empty() {
+            return Err(ChangeSetError::ApprovalRequirementsNotMet(unmet_policies));
+        }
+
         // if the change set status isn't approved, we shouldn't go
         // locking stuff
         if change_set.status != ChangeSetStatus::Approved {
@@ -502,9 +538,24 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Records the current user's approval of the [`ChangeSet`]. It is only
+    /// [`ChangeSetStatus::Approved`] once every [`ApprovalPolicy`] applying to it is satisfied,
+    /// otherwise it continues to [`ChangeSetStatus::NeedsApproval`].
     pub async fn approve_change_set_for_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
-        let status = ChangeSetStatus::Approved;
+        ChangeSetApproval::record(ctx, self.id, user_pk)
+            .await
+            .map_err(Box::new)?;
+        let status = if self
+            .approval_requirements(ctx)
+            .await?
+            .iter()
+            .all(ApprovalRequirement::is_satisfied)
+        {
+            ChangeSetStatus::Approved
+        } else {
+            ChangeSetStatus::NeedsApproval
+        };
         ctx.txns()
             .await?
             .pg()
@@ -519,6 +570,44 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Works out how far the [`ChangeSet`] is from satisfying each [`ApprovalPolicy`] applying to
+    /// what it touches. Without any policies in the workspace, nothing is required.
+    ///
+    /// The [`DalContext`] must be for this [`ChangeSet`].
+    pub async fn approval_requirements(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<ApprovalRequirement>> {
+        let policies = ApprovalPolicy::list_for_workspace(ctx)
+            .await
+            .map_err(Box::new)?;
+        if policies.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let touched = match self.detect_updates_that_will_be_applied(ctx).await? {
+            Some(rebase_batch) => ApprovalScope::for_rebase_batch(ctx, &rebase_batch)
+                .await
+                .map_err(Box::new)?,
+            None => ApprovalScope::default(),
+        };
+        let approvals = ChangeSetApproval::list_for_change_set(ctx, self.id)
+            .await
+            .map_err(Box::new)?;
+        // Edits may have landed since this change set was fetched
+        let current = Self::find(ctx, self.id)
+            .await?
+            .ok_or(ChangeSetError::ChangeSetNotFound(self.id))?;
+
+        Ok(approval::evaluate(
+            &policies,
+            &touched,
+            &approvals,
+            self.merge_requested_by_user_id,
+            current.workspace_snapshot_address,
+        ))
+    }
+
     pub async fn reject_change_set_for_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
         let status = ChangeSetStatus::Rejected;
@@ -768,11 +857,15 @@ impl ChangeSet {
 
             // Wait on response from Rebaser after request has processed
             let timeout = Duration::from_secs(60);
-            let _reply = time::timeout(timeout, reply_fut)
+            let reply = time::timeout(timeout, reply_fut)
                 .await
                 .map_err(|_elapsed| {
                     TransactionsError::RebaserReplyDeadlineElasped(timeout, request_id)
                 })??;
+
+            if let RebaseStatus::Conflicts { conflicts } = &reply.status {
+                return Err(ChangeSetError::ConflictsWithBase(conflicts.clone()));
+            }
         }
 
         self.update_status(ctx, ChangeSetStatus::Applied).await?;
@@ -903,7 +996,7 @@ impl ChangeSet {
 
     pub async fn extract_userid_from_context(ctx: &DalContext) -> Option<UserPk> {
         let user_id = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => {
+            HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
                 let maybe_user = User::get_by_pk(ctx, *user_pk).await;
                 match maybe_user {
                     Ok(user_option) => user_option.map(|user| user.pk()),
@@ -916,7 +1009,7 @@ impl ChangeSet {
     }
     pub async fn extract_userid_from_context_or_error(ctx: &DalContext) -> ChangeSetResult<UserPk> {
         let user_id = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => {
+            HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
                 let maybe_user = User::get_by_pk_or_error(ctx, *user_pk).await;
                 match maybe_user {
                     Ok(user) => user.pk(),
@@ -946,7 +1039,7 @@ impl ChangeSet {
             .await?
             .pg()
             .query_one(
-                "SELECT count(id) AS count FROM change_set_pointers WHERE workspace_snapshot_address = $1",
+                "SELECT count(id) AS count FROM change_set_pointers WHERE workspace_snapshot_address = $1 OR merge_base_snapshot_address = $1",
                 &[&workspace_snapshot_address],
             )
             .await?;
@@ -959,6 +1052,38 @@ impl ChangeSet {
         }
     }
 
+    /// List every workspace snapshot address referenced by a change set pointer, either as its
+    /// snapshot or its merge base, across all workspaces. These are the roots for garbage
+    /// collection.
+    #[instrument(
+        name = "change_set.list_workspace_snapshot_addresses_in_use",
+        level = "info",
+        skip_all
+    )]
+    pub async fn list_workspace_snapshot_addresses_in_use(
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<WorkspaceSnapshotAddress>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT workspace_snapshot_address FROM change_set_pointers
+                UNION
+                SELECT merge_base_snapshot_address FROM change_set_pointers
+                    WHERE merge_base_snapshot_address IS NOT NULL",
+                &[],
+            )
+            .await?;
+
+        let mut result = Vec::with_capacity(rows.len());
+        for row in rows {
+            result.push(row.try_get("workspace_snapshot_address")?);
+        }
+
+        Ok(result)
+    }
+
     /// Walk the graph of change sets up to the change set that has no "base
     /// change set id" and return the set.
     pub async fn ancestors(
@@ -0,0 +1,680 @@
+//! Policies deciding who must approve a [`ChangeSet`](crate::ChangeSet) before it can be applied.
+//!
+//! Without any [`ApprovalPolicies`](ApprovalPolicy), a single approval is enough to apply a change
+//! set. Once a workspace has policies, each one which applies to what the change set touches
+//! (see [`ApprovalScope`]) must be satisfied by enough distinct approvers from its group. An
+//! approval only counts for the contents of the change set at the time it was given, so any edit
+//! landing afterwards invalidates it.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
+use strum::{AsRefStr, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    attribute::value::AttributeValueError,
+    diagram::{
+        geometry::{Geometry, GeometryRepresents},
+        view::ViewId,
+        DiagramError,
+    },
+    workspace_snapshot::{
+        graph::{detect_updates::Update, RebaseBatch},
+        NodeInformation,
+    },
+    AttributeValue, AttributeValueId, ChangeSetId, Component, ComponentError, ComponentId,
+    DalContext, NodeWeightDiscriminants, SchemaId, SchemaVariant, SchemaVariantError,
+    SchemaVariantId, TransactionsError, UserPk, WorkspacePk, WorkspaceSnapshotError,
+};
+
+pub use si_id::ApprovalPolicyId;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApprovalPolicyError {
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] Box<AttributeValueError>),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] Box<DiagramError>),
+    #[error("invalid approval policy scope {0}: {1:?}")]
+    InvalidScope(String, Option<String>),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("approval policy must require at least one approval")]
+    RequiredApprovalsTooLow,
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] Box<SchemaVariantError>),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] Box<WorkspaceSnapshotError>),
+}
+
+pub type ApprovalPolicyResult<T> = Result<T, ApprovalPolicyError>;
+
+impl From<AttributeValueError> for ApprovalPolicyError {
+    fn from(value: AttributeValueError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<ComponentError> for ApprovalPolicyError {
+    fn from(value: ComponentError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<DiagramError> for ApprovalPolicyError {
+    fn from(value: DiagramError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<SchemaVariantError> for ApprovalPolicyError {
+    fn from(value: SchemaVariantError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<WorkspaceSnapshotError> for ApprovalPolicyError {
+    fn from(value: WorkspaceSnapshotError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+enum ApprovalPolicyScopeKind {
+    Category,
+    Schema,
+    View,
+    Workspace,
+}
+
+/// Which change sets an [`ApprovalPolicy`] applies to.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "value")]
+pub enum ApprovalPolicyScope {
+    /// Change sets touching a component whose schema variant is in the category, or touching a
+    /// schema variant in the category.
+    Category(String),
+    /// Change sets touching a component of the schema, or touching the schema's variants.
+    Schema(SchemaId),
+    /// Change sets touching anything on the view.
+    View(ViewId),
+    /// Every change set.
+    Workspace,
+}
+
+impl ApprovalPolicyScope {
+    fn kind(&self) -> ApprovalPolicyScopeKind {
+        match self {
+            Self::Category(_) => ApprovalPolicyScopeKind::Category,
+            Self::Schema(_) => ApprovalPolicyScopeKind::Schema,
+            Self::View(_) => ApprovalPolicyScopeKind::View,
+            Self::Workspace => ApprovalPolicyScopeKind::Workspace,
+        }
+    }
+
+    fn value(&self) -> Option<String> {
+        match self {
+            Self::Category(category) => Some(category.clone()),
+            Self::Schema(schema_id) => Some(schema_id.to_string()),
+            Self::View(view_id) => Some(view_id.to_string()),
+            Self::Workspace => None,
+        }
+    }
+
+    fn from_parts(kind: &str, value: Option<String>) -> ApprovalPolicyResult<Self> {
+        let invalid = || ApprovalPolicyError::InvalidScope(kind.to_owned(), value.clone());
+
+        let kind = ApprovalPolicyScopeKind::from_str(kind).map_err(|_| invalid())?;
+        Ok(match (kind, value.as_deref()) {
+            (ApprovalPolicyScopeKind::Category, Some(category)) => {
+                Self::Category(category.to_owned())
+            }
+            (ApprovalPolicyScopeKind::Schema, Some(id)) => {
+                Self::Schema(SchemaId::from_str(id).map_err(|_| invalid())?)
+            }
+            (ApprovalPolicyScopeKind::View, Some(id)) => {
+                Self::View(ViewId::from_str(id).map_err(|_| invalid())?)
+            }
+            (ApprovalPolicyScopeKind::Workspace, None) => Self::Workspace,
+            _ => return Err(invalid()),
+        })
+    }
+
+    fn applies_to(&self, touched: &ApprovalScope) -> bool {
+        match self {
+            Self::Category(category) => touched.categories.contains(category),
+            Self::Schema(schema_id) => touched.schema_ids.contains(schema_id),
+            Self::View(view_id) => touched.view_ids.contains(view_id),
+            Self::Workspace => true,
+        }
+    }
+}
+
+/// Requires a number of distinct approvals for the change sets in its scope before they can be
+/// applied.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicy {
+    pub id: ApprovalPolicyId,
+    pub workspace_pk: WorkspacePk,
+    pub name: String,
+    pub scope: ApprovalPolicyScope,
+    pub required_approvals: u32,
+    /// The group of users whose approvals count towards the policy. When empty, approvals from
+    /// anyone in the workspace count.
+    pub approvers: Vec<UserPk>,
+    /// Whether the user who requested the change set be applied may approve it themselves.
+    pub allow_self_approval: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ApprovalPolicy {
+    type Error = ApprovalPolicyError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let scope_kind: String = row.try_get("scope_kind")?;
+        let required_approvals: i32 = row.try_get("required_approvals")?;
+        let approvers: serde_json::Value = row.try_get("approvers")?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            name: row.try_get("name")?,
+            scope: ApprovalPolicyScope::from_parts(&scope_kind, row.try_get("scope")?)?,
+            required_approvals: required_approvals.max(1) as u32,
+            approvers: serde_json::from_value(approvers)?,
+            allow_self_approval: row.try_get("allow_self_approval")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl ApprovalPolicy {
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        scope: ApprovalPolicyScope,
+        required_approvals: u32,
+        approvers: Vec<UserPk>,
+        allow_self_approval: bool,
+    ) -> ApprovalPolicyResult<Self> {
+        if required_approvals == 0 {
+            return Err(ApprovalPolicyError::RequiredApprovalsTooLow);
+        }
+        let workspace_pk = ctx.workspace_pk()?;
+        let required_approvals = i32::try_from(required_approvals).unwrap_or(i32::MAX);
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_approval_policies
+                    (workspace_pk, name, scope_kind, scope, required_approvals, approvers,
+                     allow_self_approval)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 RETURNING *",
+                &[
+                    &workspace_pk,
+                    &name.as_ref(),
+                    &scope.kind().as_ref(),
+                    &scope.value(),
+                    &required_approvals,
+                    &serde_json::to_value(&approvers)?,
+                    &allow_self_approval,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        id: ApprovalPolicyId,
+    ) -> ApprovalPolicyResult<Option<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM change_set_approval_policies WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> ApprovalPolicyResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approval_policies
+                 WHERE workspace_pk = $1
+                 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    pub async fn delete(self, ctx: &DalContext) -> ApprovalPolicyResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "DELETE FROM change_set_approval_policies WHERE id = $1",
+                &[&self.id],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// A user's approval of a change set, as it was when they approved it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApproval {
+    pub change_set_id: ChangeSetId,
+    pub user_pk: UserPk,
+    pub workspace_snapshot_address: WorkspaceSnapshotAddress,
+    pub approved_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ChangeSetApproval {
+    type Error = ApprovalPolicyError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            change_set_id: row.try_get("change_set_id")?,
+            user_pk: row.try_get("user_pk")?,
+            workspace_snapshot_address: row.try_get("workspace_snapshot_address")?,
+            approved_at: row.try_get("approved_at")?,
+        })
+    }
+}
+
+impl ChangeSetApproval {
+    /// Records the user's approval of the change set as its pointer currently stands, replacing
+    /// any approval they gave before.
+    pub async fn record(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        user_pk: UserPk,
+    ) -> ApprovalPolicyResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_approvals (change_set_id, user_pk, workspace_snapshot_address)
+                    SELECT id, $2, workspace_snapshot_address
+                    FROM change_set_pointers WHERE id = $1
+                 ON CONFLICT (change_set_id, user_pk) DO
+                    UPDATE SET workspace_snapshot_address = EXCLUDED.workspace_snapshot_address,
+                               approved_at = CLOCK_TIMESTAMP()
+                 RETURNING *",
+                &[&change_set_id, &user_pk],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn list_for_change_set(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> ApprovalPolicyResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approvals WHERE change_set_id = $1 ORDER BY approved_at",
+                &[&change_set_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    pub async fn clear_for_change_set(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> ApprovalPolicyResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "DELETE FROM change_set_approvals WHERE change_set_id = $1",
+                &[&change_set_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// The schemas, categories and views a change set touches, which decide the policies that apply
+/// to it.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalScope {
+    pub schema_ids: HashSet<SchemaId>,
+    pub categories: HashSet<String>,
+    pub view_ids: HashSet<ViewId>,
+}
+
+impl ApprovalScope {
+    /// Works out what the updates a change set would apply touch. The context must be for the
+    /// change set, since the updated nodes are looked up in its snapshot.
+    #[instrument(name = "approval_scope.for_rebase_batch", level = "debug", skip_all)]
+    pub async fn for_rebase_batch(
+        ctx: &DalContext,
+        rebase_batch: &RebaseBatch,
+    ) -> ApprovalPolicyResult<Self> {
+        let mut nodes: Vec<NodeInformation> = Vec::new();
+        for update in rebase_batch.updates() {
+            match update {
+                Update::NewNode { node_weight } | Update::ReplaceNode { node_weight } => {
+                    nodes.push(node_weight.into());
+                }
+                Update::NewEdge {
+                    source,
+                    destination,
+                    ..
+                }
+                | Update::RemoveEdge {
+                    source,
+                    destination,
+                    ..
+                } => {
+                    nodes.push(*source);
+                    nodes.push(*destination);
+                }
+            }
+        }
+
+        let snapshot = ctx.workspace_snapshot()?;
+        let mut scope = Self::default();
+        let mut component_ids = HashSet::new();
+        let mut seen = HashSet::new();
+
+        for node in nodes {
+            let id: Ulid = node.id.into();
+            // Nodes the change set removes aren't in its snapshot, but whatever they were
+            // removed from is, and is attributed instead
+            if !seen.insert(id) || snapshot.get_node_index_by_id_opt(id).await.is_none() {
+                continue;
+            }
+
+            match node.node_weight_kind {
+                NodeWeightDiscriminants::Component => {
+                    component_ids.insert(ComponentId::from(id));
+                }
+                NodeWeightDiscriminants::AttributeValue => {
+                    component_ids.insert(
+                        AttributeValue::component_id(ctx, AttributeValueId::from(id)).await?,
+                    );
+                }
+                NodeWeightDiscriminants::Geometry => {
+                    scope
+                        .view_ids
+                        .insert(Geometry::get_view_id_by_id(ctx, id.into()).await?);
+                    // Some change sets have orphaned geometries, which represent nothing
+                    match Geometry::represented_id(ctx, id.into()).await {
+                        Ok(GeometryRepresents::Component(component_id)) => {
+                            component_ids.insert(component_id);
+                        }
+                        Ok(GeometryRepresents::View(_))
+                        | Err(DiagramError::RepresentedNotFoundForGeometry(_)) => {}
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                NodeWeightDiscriminants::View => {
+                    scope.view_ids.insert(ViewId::from(id));
+                }
+                NodeWeightDiscriminants::SchemaVariant => {
+                    scope
+                        .add_schema_variant(ctx, SchemaVariantId::from(id))
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+
+        for component_id in component_ids {
+            let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+            scope.add_schema_variant(ctx, schema_variant_id).await?;
+
+            for geometry_id in Geometry::list_ids_by_component(ctx, component_id).await? {
+                scope
+                    .view_ids
+                    .insert(Geometry::get_view_id_by_id(ctx, geometry_id).await?);
+            }
+        }
+
+        Ok(scope)
+    }
+
+    async fn add_schema_variant(
+        &mut self,
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> ApprovalPolicyResult<()> {
+        let schema_variant = SchemaVariant::get_by_id_or_error(ctx, schema_variant_id).await?;
+        self.categories.insert(schema_variant.category().to_owned());
+        self.schema_ids
+            .insert(SchemaVariant::schema_id_for_schema_variant_id(ctx, schema_variant_id).await?);
+
+        Ok(())
+    }
+}
+
+/// How far a change set is from satisfying a single [`ApprovalPolicy`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequirement {
+    pub policy_id: ApprovalPolicyId,
+    pub policy_name: String,
+    pub required_approvals: u32,
+    /// The users whose approvals count towards the policy.
+    pub approved_by: Vec<UserPk>,
+    /// Users who approved the change set before it was last edited, and need to approve it again.
+    pub stale_approvals_by: Vec<UserPk>,
+}
+
+impl ApprovalRequirement {
+    pub fn is_satisfied(&self) -> bool {
+        self.approved_by.len() >= self.required_approvals as usize
+    }
+}
+
+/// Works out the requirement for each policy applying to the touched scope, given the change
+/// set's approvals. Only approvals given for the current snapshot address count, and approvals
+/// by the change set's author only count for policies allowing self approval.
+pub fn evaluate(
+    policies: &[ApprovalPolicy],
+    touched: &ApprovalScope,
+    approvals: &[ChangeSetApproval],
+    author: Option<UserPk>,
+    current_address: WorkspaceSnapshotAddress,
+) -> Vec<ApprovalRequirement> {
+    policies
+        .iter()
+        .filter(|policy| policy.scope.applies_to(touched))
+        .map(|policy| {
+            let mut approved_by = Vec::new();
+            let mut stale_approvals_by = Vec::new();
+
+            for approval in approvals {
+                let in_group =
+                    policy.approvers.is_empty() || policy.approvers.contains(&approval.user_pk);
+                let is_author = author == Some(approval.user_pk);
+                if !in_group || (is_author && !policy.allow_self_approval) {
+                    continue;
+                }
+
+                if approval.workspace_snapshot_address == current_address {
+                    approved_by.push(approval.user_pk);
+                } else {
+                    stale_approvals_by.push(approval.user_pk);
+                }
+            }
+
+            ApprovalRequirement {
+                policy_id: policy.id,
+                policy_name: policy.name.clone(),
+                required_approvals: policy.required_approvals,
+                approved_by,
+                stale_approvals_by,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(scope: ApprovalPolicyScope, required_approvals: u32) -> ApprovalPolicy {
+        ApprovalPolicy {
+            id: ApprovalPolicyId::new(),
+            workspace_pk: WorkspacePk::new(),
+            name: "policy".to_owned(),
+            scope,
+            required_approvals,
+            approvers: vec![],
+            allow_self_approval: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn approval(user_pk: UserPk, address: WorkspaceSnapshotAddress) -> ChangeSetApproval {
+        ChangeSetApproval {
+            change_set_id: ChangeSetId::new(),
+            user_pk,
+            workspace_snapshot_address: address,
+            approved_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn scope_round_trips_through_its_parts() {
+        for scope in [
+            ApprovalPolicyScope::Workspace,
+            ApprovalPolicyScope::Category("AWS EC2".to_owned()),
+            ApprovalPolicyScope::Schema(SchemaId::new()),
+            ApprovalPolicyScope::View(ViewId::new()),
+        ] {
+            assert_eq!(
+                scope,
+                ApprovalPolicyScope::from_parts(scope.kind().as_ref(), scope.value())
+                    .expect("valid scope")
+            );
+        }
+
+        assert!(ApprovalPolicyScope::from_parts("workspace", Some("nope".to_owned())).is_err());
+        assert!(ApprovalPolicyScope::from_parts("schema", None).is_err());
+        assert!(ApprovalPolicyScope::from_parts("schema", Some("nope".to_owned())).is_err());
+        assert!(ApprovalPolicyScope::from_parts("component", None).is_err());
+    }
+
+    #[test]
+    fn only_policies_for_touched_scopes_apply() {
+        let schema_id = SchemaId::new();
+        let touched = ApprovalScope {
+            schema_ids: HashSet::from([schema_id]),
+            categories: HashSet::from(["AWS EC2".to_owned()]),
+            view_ids: HashSet::new(),
+        };
+        let policies = [
+            policy(ApprovalPolicyScope::Workspace, 1),
+            policy(ApprovalPolicyScope::Schema(schema_id), 1),
+            policy(ApprovalPolicyScope::Schema(SchemaId::new()), 1),
+            policy(ApprovalPolicyScope::Category("AWS EC2".to_owned()), 1),
+            policy(ApprovalPolicyScope::Category("AWS IAM".to_owned()), 1),
+            policy(ApprovalPolicyScope::View(ViewId::new()), 1),
+        ];
+
+        let requirements = evaluate(
+            &policies,
+            &touched,
+            &[],
+            None,
+            WorkspaceSnapshotAddress::nil(),
+        );
+
+        assert_eq!(
+            vec![policies[0].id, policies[1].id, policies[3].id],
+            requirements
+                .iter()
+                .map(|requirement| requirement.policy_id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn counts_distinct_current_approvals_from_the_group() {
+        let (author, alice, bob, mallory) =
+            (UserPk::new(), UserPk::new(), UserPk::new(), UserPk::new());
+        let current = WorkspaceSnapshotAddress::new(b"current");
+        let previous = WorkspaceSnapshotAddress::new(b"previous");
+
+        let mut group_policy = policy(ApprovalPolicyScope::Workspace, 2);
+        group_policy.approvers = vec![author, alice, bob];
+        let mut self_approval_policy = policy(ApprovalPolicyScope::Workspace, 2);
+        self_approval_policy.allow_self_approval = true;
+
+        let approvals = [
+            approval(author, current),
+            approval(alice, current),
+            approval(bob, previous),
+            approval(mallory, current),
+        ];
+
+        let requirements = evaluate(
+            &[group_policy, self_approval_policy],
+            &ApprovalScope::default(),
+            &approvals,
+            Some(author),
+            current,
+        );
+
+        // The author can't approve, mallory isn't in the group and bob's approval is stale
+        assert_eq!(vec![alice], requirements[0].approved_by);
+        assert_eq!(vec![bob], requirements[0].stale_approvals_by);
+        assert!(!requirements[0].is_satisfied());
+
+        assert_eq!(vec![author, alice, mallory], requirements[1].approved_by);
+        assert!(requirements[1].is_satisfied());
+    }
+}
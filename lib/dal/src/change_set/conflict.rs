@@ -0,0 +1,237 @@
+//! Conflicts between a [`ChangeSet`] and its base change set: the same node, or the same
+//! exclusive edge, changed differently on both since the merge base the two last had in common.
+//!
+//! The merge base starts as the snapshot the change set was forked from. Changes replayed onto the
+//! change set from its base are applied to the merge base too, as both sides now have them in
+//! common; replayed changes that would overwrite the change set's own changes are held back from
+//! both, to be reported as conflicts when the change set is applied.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
+use strum::{Display, EnumString};
+use telemetry::prelude::*;
+
+pub use si_events::Conflict;
+
+use super::{ChangeSet, ChangeSetError, ChangeSetResult};
+use crate::{
+    workspace_snapshot::graph::detect_updates::Update, DalContext, EdgeWeightKindDiscriminants,
+    WorkspaceSnapshot,
+};
+
+/// Which side of a [`Conflict`] to keep.
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    /// Keep the base change set's side, changing the change set to match it.
+    KeepBase,
+    /// Keep the change set's side, which replaces the base change set's when it is applied.
+    KeepChangeSet,
+}
+
+impl ChangeSet {
+    /// Detects the [`Conflicts`](Conflict) between the change set and its base change set.
+    /// Change sets without a merge base, such as those created before merge bases were recorded,
+    /// never conflict.
+    #[instrument(
+        name = "change_set.conflicts_with_base",
+        level = "info",
+        skip_all,
+        fields(
+            si.change_set.id = %self.id,
+            si.conflicts.count = Empty,
+        ),
+    )]
+    pub async fn conflicts_with_base(&self, ctx: &DalContext) -> ChangeSetResult<Vec<Conflict>> {
+        let span = current_span_for_instrument_at!("info");
+
+        let (Some(merge_base_snapshot_address), Some(base_change_set_id)) =
+            (self.merge_base_snapshot_address, self.base_change_set_id)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let merge_base_snapshot = WorkspaceSnapshot::find(ctx, merge_base_snapshot_address)
+            .await
+            .map_err(Box::new)?;
+        let base_snapshot = WorkspaceSnapshot::find_for_change_set(ctx, base_change_set_id)
+            .await
+            .map_err(Box::new)?;
+        let change_set_snapshot = WorkspaceSnapshot::find(ctx, self.workspace_snapshot_address)
+            .await
+            .map_err(Box::new)?;
+
+        let conflicts = merge_base_snapshot
+            .detect_conflicts(&base_snapshot, &change_set_snapshot)
+            .await
+            .map_err(Box::new)?;
+
+        span.record("si.conflicts.count", conflicts.len());
+        Ok(conflicts)
+    }
+
+    /// Resolves a [`Conflict`] between the change set in the context and its base change set.
+    ///
+    /// Keeping the base change set's side changes the change set's snapshot, which is committed
+    /// like any other change. Keeping the change set's side moves the base change set's side
+    /// into the merge base instead, so that the change set's side reads as the only change.
+    pub async fn resolve_conflict(
+        ctx: &DalContext,
+        conflict: &Conflict,
+        resolution: ConflictResolution,
+    ) -> ChangeSetResult<()> {
+        let mut change_set = Self::find(ctx, ctx.change_set_id())
+            .await?
+            .ok_or(ChangeSetError::ChangeSetNotFound(ctx.change_set_id()))?;
+        let merge_base_snapshot_address = change_set
+            .merge_base_snapshot_address
+            .ok_or(ChangeSetError::NoMergeBase(change_set.id))?;
+        let base_change_set_id = change_set
+            .base_change_set_id
+            .ok_or(ChangeSetError::NoBaseChangeSet(change_set.id))?;
+
+        let base_snapshot = WorkspaceSnapshot::find_for_change_set(ctx, base_change_set_id)
+            .await
+            .map_err(Box::new)?;
+        let change_set_snapshot = ctx.workspace_snapshot().map_err(Box::new)?;
+
+        match resolution {
+            ConflictResolution::KeepBase => {
+                let updates = change_set_snapshot
+                    .updates_to_match(&base_snapshot, conflict)
+                    .await
+                    .map_err(Box::new)?;
+                change_set_snapshot
+                    .perform_updates(&updates)
+                    .await
+                    .map_err(Box::new)?;
+            }
+            ConflictResolution::KeepChangeSet => {
+                let merge_base_snapshot = WorkspaceSnapshot::find(ctx, merge_base_snapshot_address)
+                    .await
+                    .map_err(Box::new)?;
+                // When the base change set removed the node there is no side of its own to move
+                // into the merge base, so the change set's side goes there instead
+                let source_snapshot = match conflict {
+                    Conflict::ModifiedWhatBaseRemoved { .. } => &*change_set_snapshot,
+                    _ => &base_snapshot,
+                };
+                let updates = merge_base_snapshot
+                    .updates_to_match(source_snapshot, conflict)
+                    .await
+                    .map_err(Box::new)?;
+                merge_base_snapshot
+                    .perform_updates(&updates)
+                    .await
+                    .map_err(Box::new)?;
+                let merge_base_snapshot_address =
+                    merge_base_snapshot.write(ctx).await.map_err(Box::new)?;
+                change_set
+                    .update_merge_base(ctx, merge_base_snapshot_address)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies updates replayed onto the change set from its base change set to its merge base as
+    /// well. Otherwise the base change set changing a node again after it was replayed would read
+    /// as a conflict with the change set's copy of the earlier change.
+    ///
+    /// Change sets without a merge base are left alone, as they never conflict.
+    pub async fn advance_merge_base(
+        &mut self,
+        ctx: &DalContext,
+        replayed_updates: Vec<Update>,
+    ) -> ChangeSetResult<()> {
+        let Some(merge_base_snapshot_address) = self.merge_base_snapshot_address else {
+            return Ok(());
+        };
+        if replayed_updates.is_empty() {
+            return Ok(());
+        }
+
+        let merge_base_snapshot = WorkspaceSnapshot::find(ctx, merge_base_snapshot_address)
+            .await
+            .map_err(Box::new)?;
+        let updates = merge_base_snapshot
+            .correct_transforms(replayed_updates, true)
+            .await
+            .map_err(Box::new)?;
+        merge_base_snapshot
+            .perform_updates(&updates)
+            .await
+            .map_err(Box::new)?;
+        let merge_base_snapshot_address = merge_base_snapshot.write(ctx).await.map_err(Box::new)?;
+
+        self.update_merge_base(ctx, merge_base_snapshot_address)
+            .await
+    }
+
+    async fn update_merge_base(
+        &mut self,
+        ctx: &DalContext,
+        merge_base_snapshot_address: WorkspaceSnapshotAddress,
+    ) -> ChangeSetResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE change_set_pointers SET merge_base_snapshot_address = $2, updated_at = CLOCK_TIMESTAMP() WHERE id = $1",
+                &[&self.id, &merge_base_snapshot_address],
+            )
+            .await?;
+
+        self.merge_base_snapshot_address = Some(merge_base_snapshot_address);
+
+        Ok(())
+    }
+}
+
+/// Removes the updates that would overwrite either side of the conflicts: those replacing or
+/// removing a conflicting node, and those changing a conflicting exclusive edge.
+pub fn without_conflicting_updates(updates: Vec<Update>, conflicts: &[Conflict]) -> Vec<Update> {
+    let mut node_ids = HashSet::new();
+    let mut exclusive_edges = HashSet::new();
+    for conflict in conflicts {
+        match conflict {
+            Conflict::ExclusiveEdge {
+                source_id,
+                edge_kind,
+                ..
+            } => {
+                exclusive_edges.insert((*source_id, edge_kind.as_str()));
+            }
+            _ => {
+                node_ids.insert(conflict.node_id());
+            }
+        }
+    }
+    let is_conflicting_edge = |source_id: Ulid, edge_kind: EdgeWeightKindDiscriminants| {
+        exclusive_edges.contains(&(source_id, edge_kind.to_string().as_str()))
+    };
+
+    updates
+        .into_iter()
+        .filter(|update| match update {
+            Update::ReplaceNode { node_weight } => !node_ids.contains(&node_weight.id()),
+            Update::RemoveEdge {
+                source,
+                destination,
+                edge_kind,
+            } => {
+                !node_ids.contains(&destination.id.into())
+                    && !is_conflicting_edge(source.id.into(), *edge_kind)
+            }
+            Update::NewEdge {
+                source,
+                edge_weight,
+                ..
+            } => !is_conflicting_edge(source.id.into(), edge_weight.kind().into()),
+            Update::NewNode { .. } => true,
+        })
+        .collect()
+}
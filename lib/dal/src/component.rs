@@ -6,7 +6,7 @@ use petgraph::Direction::Outgoing;
 use serde::{Deserialize, Serialize};
 use si_pkg::KeyOrIndex;
 use socket::{ComponentInputSocket, ComponentOutputSocket};
-use std::collections::{hash_map, HashMap, HashSet, VecDeque};
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use std::num::{ParseFloatError, ParseIntError};
 use std::sync::Arc;
 use telemetry::prelude::*;
@@ -34,7 +34,7 @@ use crate::diagram::{
 };
 use crate::func::argument::FuncArgumentError;
 use crate::history_event::HistoryEventMetadata;
-use crate::layer_db_types::{ComponentContent, ComponentContentV2};
+use crate::layer_db_types::{ComponentContent, ComponentContentV3};
 use crate::module::{Module, ModuleError};
 use crate::prop::{PropError, PropPath};
 use crate::qualification::QualificationError;
@@ -75,6 +75,7 @@ pub mod frame;
 pub mod inferred_connection_graph;
 pub mod properties;
 pub mod qualification;
+pub mod query;
 pub mod resource;
 pub mod socket;
 
@@ -269,12 +270,14 @@ pub struct Component {
     #[serde(flatten)]
     timestamp: Timestamp,
     to_delete: bool,
+    tags: BTreeMap<String, String>,
 }
 
-impl From<Component> for ComponentContentV2 {
+impl From<Component> for ComponentContentV3 {
     fn from(value: Component) -> Self {
         Self {
             timestamp: value.timestamp,
+            tags: value.tags,
         }
     }
 }
@@ -287,11 +290,12 @@ pub struct ControllingFuncData {
 }
 
 impl Component {
-    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV2) -> Self {
+    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV3) -> Self {
         Self {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
             to_delete: node_weight.to_delete(),
+            tags: content.tags,
         }
     }
 
@@ -307,6 +311,11 @@ impl Component {
         self.to_delete
     }
 
+    /// The key/value tags labelling the [`Component`], ordered by key.
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
     pub async fn change_status(&self, ctx: &DalContext) -> ComponentResult<ChangeStatus> {
         let status = if self.exists_in_head(ctx).await? {
             if self.to_delete() {
@@ -407,12 +416,13 @@ impl Component {
         schema_variant_id: SchemaVariantId,
         view_id: ViewId,
     ) -> ComponentResult<Self> {
-        let content = ComponentContentV2 {
+        let content = ComponentContentV3 {
             timestamp: Timestamp::now(),
+            tags: BTreeMap::new(),
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(ComponentContent::V2(content.clone()).into()),
+            Arc::new(ComponentContent::V3(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -1273,7 +1283,7 @@ impl Component {
     async fn try_get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV2)>> {
+    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV3)>> {
         if let Some((component_node_weight, content_hash)) =
             Self::try_get_node_weight_and_content_hash(ctx, component_id).await?
         {
@@ -1295,7 +1305,7 @@ impl Component {
     async fn get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV2)> {
+    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV3)> {
         Self::try_get_node_weight_and_content(ctx, component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))
@@ -2436,7 +2446,7 @@ impl Component {
         let original_component = self.clone();
         let mut component = self;
 
-        let before = ComponentContentV2::from(component.clone());
+        let before = ComponentContentV3::from(component.clone());
         lambda(&mut component)?;
 
         // The `to_delete` lives on the node itself, not in the content, so we need to be a little
@@ -2458,10 +2468,10 @@ impl Component {
                 .await?;
         }
 
-        let updated = ComponentContentV2::from(component.clone());
+        let updated = ComponentContentV3::from(component.clone());
         if updated != before {
             let (hash, _) = ctx.layer_db().cas().write(
-                Arc::new(ComponentContent::V2(updated.clone()).into()),
+                Arc::new(ComponentContent::V3(updated.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -2685,6 +2695,20 @@ impl Component {
         Ok(modified)
     }
 
+    /// Replaces the tags labelling the [`Component`]. Tags live in the component's content, so
+    /// they change with the change set like everything else on the graph.
+    pub async fn set_tags(
+        self,
+        ctx: &DalContext,
+        tags: BTreeMap<String, String>,
+    ) -> ComponentResult<Self> {
+        self.modify(ctx, |component| {
+            component.tags = tags;
+            Ok(())
+        })
+        .await
+    }
+
     /// `AttributeValueId`s of all input sockets connected to any output socket of this component.
     async fn downstream_attribute_value_ids(
         &self,
@@ -3623,6 +3647,7 @@ impl Component {
             can_be_upgraded,
             from_base_change_set: false,
             view_data: geometry,
+            tags: self.tags.clone(),
         })
     }
 
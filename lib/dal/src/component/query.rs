@@ -0,0 +1,749 @@
+//! This module contains [`ComponentFilter`], which finds [`Components`](Component) by evaluating
+//! filters against the workspace snapshot.
+//!
+//! Filters are evaluated one component at a time, reading from the content store only when a
+//! filter needs a value that the graph does not hold, so no [`Component`] is ever materialized.
+//! Filters can be built directly (or deserialized) or parsed from a query string; see
+//! [`ComponentFilter::from_str`](std::str::FromStr) for the query syntax.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+use veritech_client::ResourceStatus;
+
+use crate::change_status::ChangeStatus;
+use crate::component::ComponentResult;
+use crate::diagram::view::ViewId;
+use crate::prop::PropPath;
+use crate::qualification::QualificationSubCheckStatus;
+use crate::workspace_snapshot::edge_weight::EdgeWeightKindDiscriminants;
+use crate::workspace_snapshot::node_weight::NodeWeight;
+use crate::{
+    AttributeValue, AttributeValueId, Component, ComponentId, DalContext, Prop, PropId,
+    SchemaVariant, SchemaVariantId,
+};
+
+/// The attribute path matched by bare words in a query string.
+const NAME_PATH: &str = "/si/name";
+
+#[remain::sorted]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ComponentFilterParseError {
+    #[error("invalid attribute filter {0:?}, expected a path, a comparison and a value")]
+    InvalidAttributeFilter(String),
+    #[error("invalid value {value:?} for {key}")]
+    InvalidValue { key: String, value: String },
+    #[error("unknown filter: {0}")]
+    UnknownFilter(String),
+    #[error("unterminated quote in query")]
+    UnterminatedQuote,
+}
+
+pub type ComponentFilterParseResult<T> = Result<T, ComponentFilterParseError>;
+
+/// A filter over the [`Components`](Component) in the workspace, used with [`Component::query`].
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentFilter {
+    /// Matches when every filter matches, so an empty list matches every component.
+    And(Vec<ComponentFilter>),
+    /// Compares the value at a prop path, such as `/domain/region`, which is relative to the root
+    /// prop unless it starts with `/root`. Components without the prop never match.
+    Attribute {
+        path: String,
+        op: ComparisonOperator,
+        value: Value,
+    },
+    ChangeStatus(ChangeStatus),
+    HasResource(bool),
+    Not(Box<ComponentFilter>),
+    /// Matches when any filter matches, so an empty list matches no component.
+    Or(Vec<ComponentFilter>),
+    /// Matches the direct children of the frame, or the components outside of any frame.
+    ParentFrame(Option<ComponentId>),
+    /// Matches on the overall qualification status: failure if any qualification failed, warning
+    /// if any warned and success otherwise.
+    QualificationStatus(QualificationSubCheckStatus),
+    ResourceStatus(ResourceStatus),
+    /// Matches the schema name, ignoring case.
+    SchemaName(String),
+    /// Matches components tagged with the key, and with the value too when one is given.
+    Tag {
+        key: String,
+        value: Option<String>,
+    },
+    View(ViewId),
+}
+
+/// How a [`ComponentFilter::Attribute`] compares the attribute's value to the given one.
+///
+/// Strings, numbers and booleans compare by their text, so `"3"` equals `3`. Ordering comparisons
+/// are numeric when both sides are numbers (or numeric strings) and lexical otherwise.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonOperator {
+    /// Substring match for strings, element match for arrays and key match for maps.
+    Contains,
+    Eq,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    Ne,
+}
+
+impl ComparisonOperator {
+    /// Operators in the order they are looked for in a query string, longest first.
+    const SYMBOLS: [(&'static str, Self); 7] = [
+        (">=", Self::Ge),
+        ("<=", Self::Le),
+        ("!=", Self::Ne),
+        ("=", Self::Eq),
+        (">", Self::Gt),
+        ("<", Self::Lt),
+        ("~", Self::Contains),
+    ];
+
+    fn compare(self, actual: &Value, expected: &Value) -> bool {
+        match self {
+            Self::Eq => loosely_equal(actual, expected),
+            Self::Ne => !loosely_equal(actual, expected),
+            Self::Contains => match actual {
+                Value::String(actual) => as_text(expected).is_some_and(|e| actual.contains(&e)),
+                Value::Array(items) => items.iter().any(|item| loosely_equal(item, expected)),
+                Value::Object(map) => expected.as_str().is_some_and(|key| map.contains_key(key)),
+                _ => false,
+            },
+            Self::Ge | Self::Gt | Self::Le | Self::Lt => {
+                let Some(ordering) = compare_values(actual, expected) else {
+                    return false;
+                };
+                match self {
+                    Self::Ge => ordering.is_ge(),
+                    Self::Gt => ordering.is_gt(),
+                    Self::Le => ordering.is_le(),
+                    _ => ordering.is_lt(),
+                }
+            }
+        }
+    }
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.to_owned()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(value) => value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn loosely_equal(actual: &Value, expected: &Value) -> bool {
+    actual == expected || as_text(actual).is_some_and(|actual| Some(actual) == as_text(expected))
+}
+
+fn compare_values(actual: &Value, expected: &Value) -> Option<Ordering> {
+    match (as_number(actual), as_number(expected)) {
+        (Some(actual), Some(expected)) => actual.partial_cmp(&expected),
+        _ => match (actual, expected) {
+            (Value::String(actual), Value::String(expected)) => Some(actual.cmp(expected)),
+            _ => None,
+        },
+    }
+}
+
+impl ComponentFilter {
+    /// A rough cost of evaluating the filter for one component, so that cheap filters can rule a
+    /// component out before expensive ones run.
+    fn cost(&self) -> u8 {
+        match self {
+            Self::ChangeStatus(_) | Self::ParentFrame(_) | Self::SchemaName(_) | Self::View(_) => 0,
+            Self::Attribute { .. }
+            | Self::HasResource(_)
+            | Self::ResourceStatus(_)
+            | Self::Tag { .. } => 1,
+            Self::QualificationStatus(_) => 2,
+            Self::Not(filter) => filter.cost(),
+            Self::And(filters) | Self::Or(filters) => {
+                filters.iter().map(Self::cost).max().unwrap_or(0)
+            }
+        }
+    }
+
+    fn parse_term(term: &str) -> ComponentFilterParseResult<Self> {
+        let Some((key, value)) = term.split_once(':') else {
+            return Ok(Self::Attribute {
+                path: NAME_PATH.to_owned(),
+                op: ComparisonOperator::Contains,
+                value: Value::String(term.to_owned()),
+            });
+        };
+        let invalid_value = || ComponentFilterParseError::InvalidValue {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+
+        Ok(match key {
+            "attr" => {
+                let (index, symbol, op) = ComparisonOperator::SYMBOLS
+                    .iter()
+                    .filter_map(|&(symbol, op)| value.find(symbol).map(|i| (i, symbol, op)))
+                    // The earliest operator wins, and the longest at the same position
+                    .min_by_key(|&(index, symbol, _)| (index, usize::MAX - symbol.len()))
+                    .ok_or_else(|| {
+                        ComponentFilterParseError::InvalidAttributeFilter(term.into())
+                    })?;
+                let path = &value[..index];
+                if path.is_empty() {
+                    return Err(ComponentFilterParseError::InvalidAttributeFilter(
+                        term.into(),
+                    ));
+                }
+                Self::Attribute {
+                    path: path.to_owned(),
+                    op,
+                    value: Value::String(value[index + symbol.len()..].to_owned()),
+                }
+            }
+            "change" => Self::ChangeStatus(value.parse().map_err(|_| invalid_value())?),
+            "parent" => Self::ParentFrame(match value {
+                "none" => None,
+                id => Some(id.parse().map_err(|_| invalid_value())?),
+            }),
+            "qualification" => {
+                Self::QualificationStatus(value.parse().map_err(|_| invalid_value())?)
+            }
+            "resource" => match value {
+                "any" => Self::HasResource(true),
+                "none" => Self::HasResource(false),
+                "error" => Self::ResourceStatus(ResourceStatus::Error),
+                "ok" => Self::ResourceStatus(ResourceStatus::Ok),
+                "warning" => Self::ResourceStatus(ResourceStatus::Warning),
+                _ => return Err(invalid_value()),
+            },
+            "schema" => Self::SchemaName(value.to_owned()),
+            "tag" => match value.split_once('=') {
+                Some(("", _)) => return Err(invalid_value()),
+                Some((key, value)) => Self::Tag {
+                    key: key.to_owned(),
+                    value: Some(value.to_owned()),
+                },
+                None if value.is_empty() => return Err(invalid_value()),
+                None => Self::Tag {
+                    key: value.to_owned(),
+                    value: None,
+                },
+            },
+            "view" => Self::View(value.parse().map_err(|_| invalid_value())?),
+            _ => return Err(ComponentFilterParseError::UnknownFilter(key.to_owned())),
+        })
+    }
+}
+
+/// Splits a query string on whitespace, keeping quoted text together. Each token records whether
+/// any of it was quoted, so that quoted keywords are taken literally.
+fn tokenize(query: &str) -> ComponentFilterParseResult<Vec<(String, bool)>> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+
+    for character in query.chars() {
+        match character {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            character if character.is_whitespace() && !in_quotes => {
+                if !token.is_empty() || quoted {
+                    tokens.push((std::mem::take(&mut token), quoted));
+                }
+                quoted = false;
+            }
+            character => token.push(character),
+        }
+    }
+    if in_quotes {
+        return Err(ComponentFilterParseError::UnterminatedQuote);
+    }
+    if !token.is_empty() || quoted {
+        tokens.push((token, quoted));
+    }
+
+    Ok(tokens)
+}
+
+impl FromStr for ComponentFilter {
+    type Err = ComponentFilterParseError;
+
+    /// Parses a query string made of whitespace separated terms, all of which must match. `OR`
+    /// between terms separates alternatives, and a term starting with `-` is negated. Values
+    /// containing whitespace can be quoted. The terms are:
+    ///
+    /// - `schema:<name>`
+    /// - `view:<view id>`
+    /// - `parent:<component id>`, or `parent:none` for components outside of any frame
+    /// - `attr:<path><op><value>`, where the operator is one of `=`, `!=`, `>`, `>=`, `<`, `<=`
+    ///   or `~` (contains)
+    /// - `resource:<status>`, or `resource:any` and `resource:none` for whether there is one
+    /// - `qualification:<status>`
+    /// - `change:<status>`
+    /// - `tag:<key>`, or `tag:<key>=<value>` to match the value too
+    /// - anything else, which matches components whose name contains it
+    ///
+    /// For example: `schema:"EC2 Instance" attr:/domain/region=us-east-1 qualification:failure`.
+    fn from_str(query: &str) -> ComponentFilterParseResult<Self> {
+        let mut alternatives = vec![Vec::new()];
+        for (token, quoted) in tokenize(query)? {
+            if token == "OR" && !quoted {
+                alternatives.push(Vec::new());
+                continue;
+            }
+
+            let filter = match token.strip_prefix('-') {
+                Some(term) if !term.is_empty() => Self::Not(Box::new(Self::parse_term(term)?)),
+                _ => Self::parse_term(&token)?,
+            };
+            if let Some(terms) = alternatives.last_mut() {
+                terms.push(filter);
+            }
+        }
+
+        let mut alternatives: Vec<Self> = alternatives.into_iter().map(Self::And).collect();
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Self::Or(alternatives),
+        })
+    }
+}
+
+impl Component {
+    /// Finds the [`Components`](Component) matching the filter.
+    #[instrument(
+        name = "component.query",
+        level = "info",
+        skip_all,
+        fields(si.components.count = Empty),
+    )]
+    pub async fn query(
+        ctx: &DalContext,
+        filter: &ComponentFilter,
+    ) -> ComponentResult<Vec<ComponentId>> {
+        let span = current_span_for_instrument_at!("info");
+
+        let mut evaluator = Evaluator::new(ctx);
+        let mut component_ids = Vec::new();
+        for component_id in Self::list_ids(ctx).await? {
+            if evaluator.matches(filter, component_id).await? {
+                component_ids.push(component_id);
+            }
+        }
+
+        span.record("si.components.count", component_ids.len());
+        Ok(component_ids)
+    }
+}
+
+/// Evaluates filters, caching what is shared between components.
+struct Evaluator<'a> {
+    ctx: &'a DalContext,
+    head_ctx: Option<DalContext>,
+    schema_names: HashMap<SchemaVariantId, String>,
+    view_members: HashMap<ViewId, HashSet<ComponentId>>,
+    /// The prop for each part of an attribute path, or none if the schema variant lacks it.
+    attribute_props: HashMap<(SchemaVariantId, String), Option<Vec<PropId>>>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(ctx: &'a DalContext) -> Self {
+        Self {
+            ctx,
+            head_ctx: None,
+            schema_names: HashMap::new(),
+            view_members: HashMap::new(),
+            attribute_props: HashMap::new(),
+        }
+    }
+
+    fn matches<'b>(
+        &'b mut self,
+        filter: &'b ComponentFilter,
+        component_id: ComponentId,
+    ) -> BoxFuture<'b, ComponentResult<bool>> {
+        Box::pin(async move {
+            let ctx = self.ctx;
+            Ok(match filter {
+                ComponentFilter::And(filters) => {
+                    for filter in by_cost(filters) {
+                        if !self.matches(filter, component_id).await? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+                ComponentFilter::Or(filters) => {
+                    for filter in by_cost(filters) {
+                        if self.matches(filter, component_id).await? {
+                            return Ok(true);
+                        }
+                    }
+                    false
+                }
+                ComponentFilter::Not(filter) => !self.matches(filter, component_id).await?,
+                ComponentFilter::Attribute { path, op, value } => {
+                    match self.attribute_value(component_id, path).await? {
+                        Some(actual) => op.compare(&actual, value),
+                        None => false,
+                    }
+                }
+                ComponentFilter::ChangeStatus(change_status) => {
+                    self.change_status(component_id).await? == *change_status
+                }
+                ComponentFilter::HasResource(has_resource) => {
+                    Component::resource_by_id(ctx, component_id)
+                        .await?
+                        .is_some()
+                        == *has_resource
+                }
+                ComponentFilter::ParentFrame(parent_id) => {
+                    Component::get_parent_by_id(ctx, component_id).await? == *parent_id
+                }
+                ComponentFilter::QualificationStatus(status) => {
+                    let statuses = Component::list_qualification_statuses(ctx, component_id)
+                        .await?
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>();
+                    let overall = if statuses.contains(&QualificationSubCheckStatus::Failure) {
+                        QualificationSubCheckStatus::Failure
+                    } else if statuses.contains(&QualificationSubCheckStatus::Warning) {
+                        QualificationSubCheckStatus::Warning
+                    } else {
+                        QualificationSubCheckStatus::Success
+                    };
+                    overall == *status
+                }
+                ComponentFilter::ResourceStatus(status) => {
+                    Component::resource_by_id(ctx, component_id)
+                        .await?
+                        .is_some_and(|resource| resource.status == *status)
+                }
+                ComponentFilter::SchemaName(name) => self
+                    .schema_name(component_id)
+                    .await?
+                    .eq_ignore_ascii_case(name),
+                ComponentFilter::Tag { key, value } => {
+                    let (_, content) =
+                        Component::get_node_weight_and_content(ctx, component_id).await?;
+                    match (content.tags.get(key), value) {
+                        (Some(actual), Some(expected)) => actual == expected,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    }
+                }
+                ComponentFilter::View(view_id) => {
+                    self.view_members(*view_id).await?.contains(&component_id)
+                }
+            })
+        })
+    }
+
+    async fn schema_name(&mut self, component_id: ComponentId) -> ComponentResult<&str> {
+        let schema_variant_id = Component::schema_variant_id(self.ctx, component_id).await?;
+        if !self.schema_names.contains_key(&schema_variant_id) {
+            let schema =
+                SchemaVariant::schema_for_schema_variant_id(self.ctx, schema_variant_id).await?;
+            self.schema_names
+                .insert(schema_variant_id, schema.name().to_owned());
+        }
+
+        Ok(self
+            .schema_names
+            .get(&schema_variant_id)
+            .map(String::as_str)
+            .unwrap_or_default())
+    }
+
+    /// The components with a geometry in the view, found without reading the geometries.
+    async fn view_members(&mut self, view_id: ViewId) -> ComponentResult<&HashSet<ComponentId>> {
+        if !self.view_members.contains_key(&view_id) {
+            let snapshot = self.ctx.workspace_snapshot()?;
+            let mut members = HashSet::new();
+            if snapshot.get_node_index_by_id_opt(view_id).await.is_some() {
+                for geometry_idx in snapshot
+                    .outgoing_targets_for_edge_weight_kind(
+                        view_id,
+                        EdgeWeightKindDiscriminants::Use,
+                    )
+                    .await?
+                {
+                    for represented_idx in snapshot
+                        .outgoing_targets_for_edge_weight_kind_by_index(
+                            geometry_idx,
+                            EdgeWeightKindDiscriminants::Represents,
+                        )
+                        .await?
+                    {
+                        if let NodeWeight::Component(component) =
+                            snapshot.get_node_weight(represented_idx).await?
+                        {
+                            members.insert(component.id().into());
+                        }
+                    }
+                }
+            }
+            self.view_members.insert(view_id, members);
+        }
+
+        Ok(self.view_members.entry(view_id).or_default())
+    }
+
+    /// Added and deleted as the frontend shows them, and modified when the component itself or
+    /// anything in its attribute value tree differs from HEAD.
+    async fn change_status(&mut self, component_id: ComponentId) -> ComponentResult<ChangeStatus> {
+        let ctx = self.ctx;
+        let head_ctx = match self.head_ctx {
+            Some(ref head_ctx) => head_ctx,
+            None => self.head_ctx.insert(ctx.clone_with_head().await?),
+        };
+        let snapshot = ctx.workspace_snapshot()?;
+        let head_snapshot = head_ctx.workspace_snapshot()?;
+
+        let Some(head_idx) = head_snapshot.get_node_index_by_id_opt(component_id).await else {
+            return Ok(ChangeStatus::Added);
+        };
+        let node_weight = snapshot.get_node_weight_by_id(component_id).await?;
+        if node_weight.get_component_node_weight()?.to_delete() {
+            return Ok(ChangeStatus::Deleted);
+        }
+        if node_weight.node_hash() != head_snapshot.get_node_weight(head_idx).await?.node_hash() {
+            return Ok(ChangeStatus::Modified);
+        }
+
+        let root_id = Component::root_attribute_value_id(ctx, component_id).await?;
+        let head_root_id = Component::root_attribute_value_id(head_ctx, component_id).await?;
+        let root_hash = snapshot
+            .get_node_weight_by_id(root_id)
+            .await?
+            .merkle_tree_hash();
+        let head_root_hash = head_snapshot
+            .get_node_weight_by_id(head_root_id)
+            .await?
+            .merkle_tree_hash();
+
+        Ok(if root_hash == head_root_hash {
+            ChangeStatus::Unmodified
+        } else {
+            ChangeStatus::Modified
+        })
+    }
+
+    /// Walks down from the root attribute value along the path's props, reading only the value
+    /// at the end of the path from the content store. Returns [`None`] when the prop or the
+    /// attribute value at the path doesn't exist, and [`Value::Null`] when it is unset.
+    async fn attribute_value(
+        &mut self,
+        component_id: ComponentId,
+        path: &str,
+    ) -> ComponentResult<Option<Value>> {
+        let ctx = self.ctx;
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let key = (schema_variant_id, path.to_owned());
+        if !self.attribute_props.contains_key(&key) {
+            let prop_ids = props_for_path(ctx, schema_variant_id, path).await?;
+            self.attribute_props.insert(key.clone(), prop_ids);
+        }
+        let Some(Some(prop_ids)) = self.attribute_props.get(&key) else {
+            return Ok(None);
+        };
+
+        let snapshot = ctx.workspace_snapshot()?;
+        let mut attribute_value_id = Component::root_attribute_value_id(ctx, component_id).await?;
+        // The first prop is the root prop, which the root attribute value is already for
+        for prop_id in prop_ids.iter().skip(1) {
+            let mut child_id = None;
+            for child_idx in snapshot
+                .outgoing_targets_for_edge_weight_kind(
+                    attribute_value_id,
+                    EdgeWeightKindDiscriminants::Contain,
+                )
+                .await?
+            {
+                let id: AttributeValueId = snapshot.get_node_weight(child_idx).await?.id().into();
+                if AttributeValue::prop_id(ctx, id).await? == *prop_id {
+                    child_id = Some(id);
+                    break;
+                }
+            }
+            match child_id {
+                Some(id) => attribute_value_id = id,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(
+            AttributeValue::get_by_id(ctx, attribute_value_id)
+                .await?
+                .view(ctx)
+                .await?
+                .unwrap_or(Value::Null),
+        ))
+    }
+}
+
+fn by_cost(filters: &[ComponentFilter]) -> Vec<&ComponentFilter> {
+    let mut filters: Vec<_> = filters.iter().collect();
+    filters.sort_by_key(|filter| filter.cost());
+    filters
+}
+
+async fn props_for_path(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+    path: &str,
+) -> ComponentResult<Option<Vec<PropId>>> {
+    let mut parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    if parts.first() != Some(&"root") {
+        parts.insert(0, "root");
+    }
+
+    let mut prop_ids = Vec::with_capacity(parts.len());
+    for end in 1..=parts.len() {
+        match Prop::find_prop_id_by_path_opt(ctx, schema_variant_id, &PropPath::new(&parts[..end]))
+            .await?
+        {
+            Some(prop_id) => prop_ids.push(prop_id),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(prop_ids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query() {
+        assert_eq!(
+            Ok(ComponentFilter::And(vec![
+                ComponentFilter::SchemaName("EC2 Instance".to_owned()),
+                ComponentFilter::Attribute {
+                    path: "/domain/region".to_owned(),
+                    op: ComparisonOperator::Eq,
+                    value: Value::String("us-east-1".to_owned()),
+                },
+                ComponentFilter::Not(Box::new(ComponentFilter::QualificationStatus(
+                    QualificationSubCheckStatus::Success
+                ))),
+            ])),
+            r#"schema:"EC2 Instance" attr:/domain/region=us-east-1 -qualification:success"#.parse()
+        );
+        assert_eq!(
+            Ok(ComponentFilter::Or(vec![
+                ComponentFilter::And(vec![ComponentFilter::ResourceStatus(ResourceStatus::Error)]),
+                ComponentFilter::And(vec![
+                    ComponentFilter::ChangeStatus(ChangeStatus::Added),
+                    ComponentFilter::Attribute {
+                        path: NAME_PATH.to_owned(),
+                        op: ComparisonOperator::Contains,
+                        value: Value::String("OR".to_owned()),
+                    },
+                ]),
+            ])),
+            r#"resource:error OR change:added "OR""#.parse()
+        );
+        assert_eq!(
+            Ok(ComponentFilter::And(vec![ComponentFilter::Attribute {
+                path: "/domain/count".to_owned(),
+                op: ComparisonOperator::Ge,
+                value: Value::String("3".to_owned()),
+            }])),
+            "attr:/domain/count>=3".parse()
+        );
+        assert_eq!(
+            Ok(ComponentFilter::And(vec![
+                ComponentFilter::Tag {
+                    key: "team".to_owned(),
+                    value: Some("platform".to_owned()),
+                },
+                ComponentFilter::Tag {
+                    key: "owner".to_owned(),
+                    value: None,
+                },
+            ])),
+            "tag:team=platform tag:owner".parse()
+        );
+        assert_eq!(Ok(ComponentFilter::And(vec![])), "".parse());
+    }
+
+    #[test]
+    fn parse_query_errors() {
+        assert_eq!(
+            Err(ComponentFilterParseError::UnknownFilter("color".to_owned())),
+            "color:red".parse::<ComponentFilter>()
+        );
+        assert_eq!(
+            Err(ComponentFilterParseError::InvalidValue {
+                key: "change".to_owned(),
+                value: "renamed".to_owned(),
+            }),
+            "change:renamed".parse::<ComponentFilter>()
+        );
+        assert_eq!(
+            Err(ComponentFilterParseError::InvalidAttributeFilter(
+                "attr:=x".to_owned()
+            )),
+            "attr:=x".parse::<ComponentFilter>()
+        );
+        assert_eq!(
+            Err(ComponentFilterParseError::UnterminatedQuote),
+            r#"schema:"EC2"#.parse::<ComponentFilter>()
+        );
+        assert_eq!(
+            Err(ComponentFilterParseError::InvalidValue {
+                key: "tag".to_owned(),
+                value: "=platform".to_owned(),
+            }),
+            "tag:=platform".parse::<ComponentFilter>()
+        );
+    }
+
+    #[test]
+    fn compare_attribute_values() {
+        let compare =
+            |op: ComparisonOperator, actual: Value, expected: Value| op.compare(&actual, &expected);
+
+        assert!(compare(ComparisonOperator::Eq, 3.into(), "3".into()));
+        assert!(compare(ComparisonOperator::Ne, true.into(), "false".into()));
+        assert!(compare(ComparisonOperator::Gt, "10".into(), "9".into()));
+        assert!(compare(ComparisonOperator::Lt, "a".into(), "b".into()));
+        assert!(!compare(ComparisonOperator::Lt, Value::Null, 1.into()));
+        assert!(compare(
+            ComparisonOperator::Contains,
+            "us-east-1".into(),
+            "east".into()
+        ));
+        assert!(compare(
+            ComparisonOperator::Contains,
+            serde_json::json!(["a", "b"]),
+            "b".into()
+        ));
+    }
+}
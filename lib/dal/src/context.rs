@@ -23,7 +23,7 @@ use si_runtime::DedicatedExecutor;
 use strum::EnumDiscriminants;
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard, OnceCell};
 use tokio::time;
 use tokio_util::task::TaskTracker;
 use veritech_client::Client as VeritechClient;
@@ -357,6 +357,8 @@ pub struct DalContext {
     change_set: Option<ChangeSet>,
     /// The event session identifier
     event_session_id: EventSessionId,
+    /// The workspace's component concurrency limit, looked up at most once for the tenancy
+    component_concurrency_limit: Arc<OnceCell<i32>>,
 }
 
 impl DalContext {
@@ -392,6 +394,19 @@ impl DalContext {
         Ok(workspace.token())
     }
 
+    /// Gets the workspace's component concurrency limit, only looking it up the first time it is
+    /// needed.
+    pub async fn component_concurrency_limit(&self) -> TransactionsResult<i32> {
+        self.component_concurrency_limit
+            .get_or_try_init(|| async {
+                self.get_workspace()
+                    .await
+                    .map(|workspace| workspace.component_concurrency_limit())
+            })
+            .await
+            .copied()
+    }
+
     pub async fn get_workspace(&self) -> Result<Workspace, TransactionsError> {
         let workspace_pk = self.tenancy().workspace_pk().unwrap_or(WorkspacePk::NONE);
         let workspace = Workspace::get_by_pk(self, &workspace_pk)
@@ -719,7 +734,7 @@ impl DalContext {
 
     /// Updates this context with a new [`Visibility`].
     pub fn update_access_builder(&mut self, access_builder: AccessBuilder) {
-        self.tenancy = access_builder.tenancy;
+        self.update_tenancy(access_builder.tenancy);
         self.history_actor = access_builder.history_actor;
     }
 
@@ -772,6 +787,7 @@ impl DalContext {
     /// Updates this context with a new [`Tenancy`]
     pub fn update_tenancy(&mut self, tenancy: Tenancy) {
         self.tenancy = tenancy;
+        self.component_concurrency_limit = Default::default();
     }
 
     /// Clones a new context from this one with a new [`Tenancy`] and [`Tenancy`].
@@ -963,6 +979,7 @@ impl DalContext {
     /// Gets the version of the "actor" (UserPk) used by the layerdb/si-events-crate
     pub fn events_actor(&self) -> si_events::Actor {
         match self.history_actor() {
+            HistoryActor::Automation(user_pk) => si_events::Actor::Automation(*user_pk),
             HistoryActor::User(user_pk) => si_events::Actor::User(*user_pk),
             HistoryActor::SystemInit => si_events::Actor::System,
         }
@@ -1147,6 +1164,7 @@ impl DalContextBuilder {
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            component_concurrency_limit: Default::default(),
         })
     }
 
@@ -1170,6 +1188,7 @@ impl DalContextBuilder {
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            component_concurrency_limit: Default::default(),
         };
 
         ctx.update_snapshot_to_visibility().await?;
@@ -1195,6 +1214,7 @@ impl DalContextBuilder {
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            component_concurrency_limit: Default::default(),
         };
 
         // TODO(nick): there's a chicken and egg problem here. We want a dal context to get the
@@ -1223,6 +1243,7 @@ impl DalContextBuilder {
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            component_concurrency_limit: Default::default(),
         };
 
         if ctx.history_actor() != &HistoryActor::SystemInit {
@@ -1322,6 +1343,8 @@ pub enum TransactionsError {
     Pg(#[from] PgError),
     #[error("pg pool error: {0}")]
     PgPool(#[from] PgPoolError),
+    #[error("rebase of batch {0} for change set id {1} has {} conflict(s)", .2.len())]
+    RebaseConflicts(RebaseBatchAddress, ChangeSetId, Vec<si_events::Conflict>),
     #[error("rebase of batch {0} for change set id {1} failed: {2}")]
     RebaseFailed(RebaseBatchAddress, ChangeSetId, String),
     #[error("rebaser client error: {0}")]
@@ -1631,5 +1654,10 @@ async fn rebase_with_reply(
             change_set_id,
             message.clone(),
         )),
+        RebaseStatus::Conflicts { conflicts } => Err(TransactionsError::RebaseConflicts(
+            updates_address,
+            change_set_id,
+            conflicts.clone(),
+        )),
     }
 }
@@ -5,7 +5,7 @@ use crate::{
         DiagramError, DiagramResult,
     },
     implement_add_edge_to,
-    layer_db_types::{ViewContent, ViewContentV1},
+    layer_db_types::{ViewContent, ViewContentV2},
     workspace_snapshot::{
         node_weight::{
             category_node_weight::CategoryNodeKind, traits::SiVersionedNodeWeight,
@@ -22,7 +22,7 @@ use serde::{Deserialize, Serialize};
 use si_events::{ulid::Ulid, ComponentId, ContentHash};
 use si_frontend_types::RawGeometry;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
 };
 
@@ -35,6 +35,7 @@ pub struct View {
     name: String,
     #[serde(flatten)]
     timestamp: Timestamp,
+    tags: BTreeMap<String, String>,
 }
 
 impl View {
@@ -66,6 +67,11 @@ impl View {
         &self.timestamp
     }
 
+    /// The key/value tags labelling the [`View`], ordered by key.
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
     pub async fn is_default(&self, ctx: &DalContext) -> DiagramResult<bool> {
         let default_id = Self::get_id_for_default(ctx).await?;
 
@@ -79,6 +85,7 @@ impl View {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
             name: content.name,
+            tags: content.tags,
         }
     }
 
@@ -87,9 +94,10 @@ impl View {
         let id = snap.generate_ulid().await?;
         let lineage_id = snap.generate_ulid().await?;
 
-        let content = ViewContent::V1(ViewContentV1 {
+        let content = ViewContent::V2(ViewContentV2 {
             timestamp: Timestamp::now(),
             name: name.as_ref().to_owned(),
+            tags: BTreeMap::new(),
         });
 
         let (content_address, _) = ctx.layer_db().cas().write(
@@ -276,14 +284,29 @@ impl View {
     }
 
     pub async fn set_name(&mut self, ctx: &DalContext, name: impl AsRef<str>) -> DiagramResult<()> {
+        self.name = name.as_ref().to_string();
+        self.update_content(ctx).await
+    }
+
+    /// Replaces the tags labelling the [`View`].
+    pub async fn set_tags(
+        &mut self,
+        ctx: &DalContext,
+        tags: BTreeMap<String, String>,
+    ) -> DiagramResult<()> {
+        self.tags = tags;
+        self.update_content(ctx).await
+    }
+
+    async fn update_content(&mut self, ctx: &DalContext) -> DiagramResult<()> {
+        self.timestamp.updated_at = Utc::now();
+
         let (hash, _) = ctx.layer_db().cas().write(
             Arc::new(
-                ViewContent::V1(ViewContentV1 {
-                    timestamp: Timestamp {
-                        created_at: self.timestamp.created_at,
-                        updated_at: Utc::now(),
-                    },
-                    name: name.as_ref().to_owned(),
+                ViewContent::V2(ViewContentV2 {
+                    timestamp: self.timestamp,
+                    name: self.name.clone(),
+                    tags: self.tags.clone(),
                 })
                 .into(),
             ),
@@ -296,8 +319,6 @@ impl View {
             .update_content(self.id.into(), hash)
             .await?;
 
-        self.name = name.as_ref().to_string();
-
         Ok(())
     }
 
@@ -424,6 +445,7 @@ pub struct ViewView {
     is_default: bool,
     #[serde(flatten)]
     timestamp: Timestamp,
+    tags: BTreeMap<String, String>,
 }
 
 impl ViewView {
@@ -433,6 +455,7 @@ impl ViewView {
             name: view.name().to_owned(),
             is_default: view.is_default(ctx).await?,
             timestamp: view.timestamp().to_owned(),
+            tags: view.tags().to_owned(),
         })
     }
 }
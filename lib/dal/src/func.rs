@@ -14,11 +14,12 @@ use strum::IntoEnumIterator;
 use telemetry::prelude::*;
 use thiserror::Error;
 use ulid::Ulid as CoreUlid;
+use veritech_client::FuncRuntime;
 
 use crate::change_set::ChangeSetError;
 use crate::func::argument::FuncArgumentId;
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::layer_db_types::{FuncContent, FuncContentV2};
+use crate::layer_db_types::{FuncContent, FuncContentV4};
 use crate::workspace_snapshot::edge_weight::{EdgeWeightKind, EdgeWeightKindDiscriminants};
 use crate::workspace_snapshot::graph::WorkspaceSnapshotGraphError;
 use crate::workspace_snapshot::node_weight::category_node_weight::CategoryNodeKind;
@@ -96,7 +97,7 @@ pub type FuncResult<T> = Result<T, FuncError>;
 
 impl From<Func> for FuncContent {
     fn from(value: Func) -> Self {
-        Self::V2(FuncContentV2 {
+        Self::V4(FuncContentV4 {
             timestamp: value.timestamp,
             display_name: value.display_name,
             description: value.description,
@@ -109,6 +110,8 @@ impl From<Func> for FuncContent {
             code_base64: value.code_base64,
             code_blake3: value.code_blake3,
             is_locked: value.is_locked,
+            is_nondeterministic: value.is_nondeterministic,
+            runtime: value.runtime,
         })
     }
 }
@@ -154,10 +157,16 @@ pub struct Func {
     pub code_base64: Option<String>,
     pub code_blake3: ContentHash,
     pub is_locked: bool,
+    /// Whether the func depends on more than its arguments (e.g. it reads the clock or generates
+    /// random values), which keeps its results from being memoized.
+    pub is_nondeterministic: bool,
+    /// The language runtime the func's code is written for, which decides the lang server that
+    /// executes it.
+    pub runtime: FuncRuntime,
 }
 
 impl Func {
-    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV2) -> Self {
+    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV4) -> Self {
         Self {
             id: node_weight.id().into(),
             name: node_weight.name().to_owned(),
@@ -175,6 +184,8 @@ impl Func {
             code_base64: content.code_base64,
             code_blake3: content.code_blake3,
             is_locked: content.is_locked,
+            is_nondeterministic: content.is_nondeterministic,
+            runtime: content.runtime,
         }
     }
 
@@ -226,7 +237,7 @@ impl Func {
             ContentHash::new("".as_bytes())
         };
 
-        let content = FuncContentV2 {
+        let content = FuncContentV4 {
             timestamp,
             display_name: display_name.map(Into::into),
             description: description.map(Into::into),
@@ -239,10 +250,12 @@ impl Func {
             code_base64,
             code_blake3,
             is_locked: false,
+            is_nondeterministic: false,
+            runtime: FuncRuntime::default(),
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(FuncContent::V2(content.clone()).into()),
+            Arc::new(FuncContent::V4(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -279,6 +292,29 @@ impl Func {
         .await
     }
 
+    /// Marks whether the func depends on more than its arguments. The results of nondeterministic
+    /// funcs are never memoized.
+    pub async fn set_nondeterministic(
+        self,
+        ctx: &DalContext,
+        is_nondeterministic: bool,
+    ) -> FuncResult<Func> {
+        self.modify(ctx, |func| {
+            func.is_nondeterministic = is_nondeterministic;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Sets the language runtime the func's code is written for.
+    pub async fn set_runtime(self, ctx: &DalContext, runtime: FuncRuntime) -> FuncResult<Func> {
+        self.modify(ctx, |func| {
+            func.runtime = runtime;
+            Ok(())
+        })
+        .await
+    }
+
     pub fn metadata_view(&self) -> FuncMetadataView {
         FuncMetadataView {
             display_name: self
@@ -340,7 +376,7 @@ impl Func {
         )?;
 
         // migrate if necessary!
-        let inner: FuncContentV2 = content.extract();
+        let inner: FuncContentV4 = content.extract();
 
         Ok(Self::assemble(func_node_weight, inner))
     }
@@ -388,6 +424,16 @@ impl Func {
         IntrinsicFunc::maybe_from_str(&self.name).is_some()
     }
 
+    /// Whether the results of running this func can be memoized by code and arguments. Only
+    /// JavaScript attribute funcs are memoized, and not those marked
+    /// [`nondeterministic`](Self::is_nondeterministic).
+    pub fn is_memoizable(&self) -> bool {
+        self.backend_kind == FuncBackendKind::JsAttribute
+            && self.runtime == FuncRuntime::LangJs
+            && !self.is_nondeterministic
+            && !self.is_intrinsic()
+    }
+
     /// A non-dynamic Func is an Intrinsic func that returns a fixed value, set by a StaticArgumentValue in the graph
     /// opposingly, a dynamic Func is a func that returns a non statically predictable value, possibly user defined.
     ///
@@ -675,6 +721,16 @@ impl Func {
             self.code_base64.clone(),
         )
         .await?;
+        let new_func = if self.is_nondeterministic {
+            new_func.set_nondeterministic(ctx, true).await?
+        } else {
+            new_func
+        };
+        let new_func = if self.runtime != FuncRuntime::default() {
+            new_func.set_runtime(ctx, self.runtime).await?
+        } else {
+            new_func
+        };
 
         for arg in FuncArgument::list_for_func(ctx, self.id)
             .await
@@ -714,6 +770,16 @@ impl Func {
             self.code_base64.clone(),
         )
         .await?;
+        let duplicated_func = if self.is_nondeterministic {
+            duplicated_func.set_nondeterministic(ctx, true).await?
+        } else {
+            duplicated_func
+        };
+        let duplicated_func = if self.runtime != FuncRuntime::default() {
+            duplicated_func.set_runtime(ctx, self.runtime).await?
+        } else {
+            duplicated_func
+        };
 
         Ok(duplicated_func)
     }
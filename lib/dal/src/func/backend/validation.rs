@@ -3,6 +3,8 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use veritech_client::{BeforeFunction, FunctionResult, ValidationRequest, ValidationResultSuccess};
 
+pub mod joi;
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct FuncBackendJsAttributeArgs {
     pub value: Option<serde_json::Value>,
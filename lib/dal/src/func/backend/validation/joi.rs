@@ -0,0 +1,519 @@
+//! An in-process evaluator for the subset of [Joi](https://joi.dev) schema descriptions used by
+//! prop validation formats, so that the most common validations don't need a round trip through
+//! veritech.
+//!
+//! The supported subset is `string` and `number` schemas with the `required` presence flag, `allow`
+//! and `valid` values, and the following rules:
+//!
+//! * `string`: `min`, `max` and `pattern`
+//! * `number`: `integer`, `min`, `max` and `port`
+//!
+//! Error messages match the ones Joi produces with its default preferences. Anything outside of
+//! this subset (other types, flags, rule options, references, regular expressions which can't be
+//! compiled natively, or a format which isn't valid JSON) is reported as unsupported, in which
+//! case the validation should be executed in veritech where the full Joi library is available.
+//! Notably, `email` and `uri` are left to veritech, since Joi checks email top level domains
+//! against the IANA registry and parses URIs with the full RFC 3986 grammar.
+
+use std::borrow::Cow;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Number, Value};
+
+use super::ValidationRunResult;
+
+const LABEL: &str = "\"value\"";
+
+// Joi's number coercion accepts strings of this shape, see `joi/lib/types/number.js`
+static NUMBER_STRING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*[+-]?(?:(?:\d+(?:\.\d*)?)|(?:\.\d+))(?:e[+-]?\d+)?\s*$")
+        .expect("number string regex is valid")
+});
+
+/// Evaluates a Joi validation format against a value, returning [`None`] if the format uses
+/// anything outside of the supported subset.
+pub fn evaluate(validation_format: &str, value: Option<&Value>) -> Option<ValidationRunResult> {
+    let schema = Schema::parse(validation_format)?;
+
+    Some(ValidationRunResult {
+        error: schema.validate(value),
+    })
+}
+
+#[derive(Debug)]
+struct Schema {
+    kind: Kind,
+    required: bool,
+    only: bool,
+    allow: Vec<Value>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    String,
+}
+
+#[derive(Debug)]
+enum Rule {
+    Integer,
+    NumberMax(Limit<f64>),
+    NumberMin(Limit<f64>),
+    Pattern {
+        regex: Regex,
+        source: String,
+        name: Option<String>,
+        invert: bool,
+    },
+    Port,
+    StringMax(Limit<usize>),
+    StringMin(Limit<usize>),
+}
+
+/// A rule's limit, along with how it was written so messages render it the way Joi would.
+#[derive(Debug)]
+struct Limit<T> {
+    value: T,
+    display: String,
+}
+
+impl Schema {
+    fn parse(validation_format: &str) -> Option<Self> {
+        let description: Map<String, Value> = serde_json::from_str(validation_format).ok()?;
+
+        let mut kind = None;
+        let mut required = false;
+        let mut only = false;
+        let mut allow = Vec::new();
+        let mut raw_rules = &Vec::new();
+
+        for (key, value) in &description {
+            match key.as_str() {
+                "type" => {
+                    kind = match value.as_str()? {
+                        "number" => Some(Kind::Number),
+                        "string" => Some(Kind::String),
+                        _ => return None,
+                    }
+                }
+                "flags" => {
+                    for (flag, flag_value) in value.as_object()? {
+                        match (flag.as_str(), flag_value) {
+                            ("presence", Value::String(presence)) => match presence.as_str() {
+                                "required" => required = true,
+                                "optional" => required = false,
+                                _ => return None,
+                            },
+                            ("only", Value::Bool(flag_value)) => only = *flag_value,
+                            _ => return None,
+                        }
+                    }
+                }
+                "allow" => {
+                    for allowed in value.as_array()? {
+                        // Objects describe references and other special values
+                        if allowed.is_object() || allowed.is_array() {
+                            return None;
+                        }
+                        allow.push(allowed.clone());
+                    }
+                }
+                "rules" => raw_rules = value.as_array()?,
+                _ => return None,
+            }
+        }
+
+        let kind = kind?;
+        let rules = raw_rules
+            .iter()
+            .map(|rule| Rule::parse(kind, rule))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            kind,
+            required,
+            only,
+            allow,
+            rules,
+        })
+    }
+
+    fn validate(&self, value: Option<&Value>) -> Option<String> {
+        // Joi treats null as a value, so it is passed as undefined to match what users expect
+        let Some(value) = value.filter(|value| !value.is_null()) else {
+            return self.required.then(|| format!("{LABEL} is required"));
+        };
+
+        let value = match self.kind {
+            Kind::Number => coerce_number(value),
+            Kind::String => Cow::Borrowed(value),
+        };
+
+        if self.allow.iter().any(|allowed| same_value(allowed, &value)) {
+            return None;
+        }
+        if self.only {
+            return Some(only_message(&self.allow));
+        }
+
+        match self.kind {
+            Kind::Number => {
+                let Some(number) = value.as_f64() else {
+                    return Some(format!("{LABEL} must be a number"));
+                };
+                self.rules
+                    .iter()
+                    .find_map(|rule| rule.validate_number(number))
+            }
+            Kind::String => {
+                let Some(string) = value.as_str() else {
+                    return Some(format!("{LABEL} must be a string"));
+                };
+                if string.is_empty() {
+                    return Some(format!("{LABEL} is not allowed to be empty"));
+                }
+                self.rules
+                    .iter()
+                    .find_map(|rule| rule.validate_string(string))
+            }
+        }
+    }
+}
+
+impl Rule {
+    fn parse(kind: Kind, rule: &Value) -> Option<Self> {
+        let rule = rule.as_object()?;
+        let name = rule.get("name")?.as_str()?;
+        let args = match rule.get("args") {
+            Some(args) => Some(args.as_object()?),
+            None => None,
+        };
+        if rule.keys().any(|key| key != "name" && key != "args") {
+            return None;
+        }
+
+        let parsed = match (kind, name) {
+            (Kind::Number, "integer") if args.is_none() => Self::Integer,
+            (Kind::Number, "port") if args.is_none() => Self::Port,
+            (Kind::Number, "min") => Self::NumberMin(number_limit(args?)?),
+            (Kind::Number, "max") => Self::NumberMax(number_limit(args?)?),
+            (Kind::String, "min") => Self::StringMin(length_limit(args?)?),
+            (Kind::String, "max") => Self::StringMax(length_limit(args?)?),
+            (Kind::String, "pattern") => {
+                let args = args?;
+                let source = args.get("regex")?.as_str()?;
+                let (regex, name, invert) = match args.get("options") {
+                    None => (js_regex(source)?, None, false),
+                    Some(options) => {
+                        let mut name = None;
+                        let mut invert = false;
+                        for (option, option_value) in options.as_object()? {
+                            match option.as_str() {
+                                "name" => name = Some(option_value.as_str()?.to_owned()),
+                                "invert" => invert = option_value.as_bool()?,
+                                _ => return None,
+                            }
+                        }
+                        (js_regex(source)?, name, invert)
+                    }
+                };
+                Self::Pattern {
+                    regex,
+                    source: source.to_owned(),
+                    name,
+                    invert,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(parsed)
+    }
+
+    fn validate_number(&self, number: f64) -> Option<String> {
+        match self {
+            Self::Integer => (number.fract() != 0.0).then(|| format!("{LABEL} must be an integer")),
+            Self::NumberMin(limit) => (number < limit.value)
+                .then(|| format!("{LABEL} must be greater than or equal to {}", limit.display)),
+            Self::NumberMax(limit) => (number > limit.value)
+                .then(|| format!("{LABEL} must be less than or equal to {}", limit.display)),
+            Self::Port => (number.fract() != 0.0 || !(0.0..=65535.0).contains(&number))
+                .then(|| format!("{LABEL} must be a valid port")),
+            _ => None,
+        }
+    }
+
+    fn validate_string(&self, string: &str) -> Option<String> {
+        // JavaScript string lengths are counted in UTF-16 code units
+        let length = || string.encode_utf16().count();
+
+        match self {
+            Self::StringMin(limit) => (length() < limit.value).then(|| {
+                format!(
+                    "{LABEL} length must be at least {} characters long",
+                    limit.display
+                )
+            }),
+            Self::StringMax(limit) => (length() > limit.value).then(|| {
+                format!(
+                    "{LABEL} length must be less than or equal to {} characters long",
+                    limit.display
+                )
+            }),
+            Self::Pattern {
+                regex,
+                source,
+                name,
+                invert,
+            } => match (regex.is_match(string), invert, name) {
+                (false, false, None) => Some(format!(
+                    "{LABEL} with value \"{string}\" fails to match the required pattern: {source}"
+                )),
+                (false, false, Some(name)) => Some(format!(
+                    "{LABEL} with value \"{string}\" fails to match the {name} pattern"
+                )),
+                (true, true, None) => Some(format!(
+                    "{LABEL} with value \"{string}\" matches the inverted pattern: {source}"
+                )),
+                (true, true, Some(name)) => Some(format!(
+                    "{LABEL} with value \"{string}\" matches the inverted {name} pattern"
+                )),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+fn number_limit(args: &Map<String, Value>) -> Option<Limit<f64>> {
+    if args.len() != 1 {
+        return None;
+    }
+    let Value::Number(limit) = args.get("limit")? else {
+        return None;
+    };
+
+    Some(Limit {
+        value: limit.as_f64()?,
+        display: limit.to_string(),
+    })
+}
+
+fn length_limit(args: &Map<String, Value>) -> Option<Limit<usize>> {
+    // An `encoding` arg changes how the length is counted
+    if args.len() != 1 {
+        return None;
+    }
+    let limit = args.get("limit")?.as_u64()?;
+
+    Some(Limit {
+        value: usize::try_from(limit).ok()?,
+        display: limit.to_string(),
+    })
+}
+
+/// Converts a JavaScript regular expression literal, such as `/^[a-z]+$/i`, into a native
+/// regular expression, matching JavaScript's ASCII-only character class escapes.
+fn js_regex(literal: &str) -> Option<Regex> {
+    let body = literal.strip_prefix('/')?;
+    let (source, flags) = body.rsplit_once('/')?;
+
+    let mut pattern = String::with_capacity(source.len() + 8);
+    let mut inline_flags = String::new();
+    for flag in flags.chars() {
+        match flag {
+            'i' | 'm' | 's' => inline_flags.push(flag),
+            // Native regular expressions are always unicode aware
+            'u' => {}
+            _ => return None,
+        }
+    }
+    if !inline_flags.is_empty() {
+        pattern.push_str(&format!("(?{inline_flags})"));
+    }
+
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            pattern.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'd' => pattern.push_str("[0-9]"),
+            'D' => pattern.push_str("[^0-9]"),
+            'w' => pattern.push_str("[A-Za-z0-9_]"),
+            'W' => pattern.push_str("[^A-Za-z0-9_]"),
+            'b' => pattern.push_str(r"(?-u:\b)"),
+            'B' => pattern.push_str(r"(?-u:\B)"),
+            escaped => {
+                pattern.push('\\');
+                pattern.push(escaped);
+            }
+        }
+    }
+
+    Regex::new(&pattern).ok()
+}
+
+/// Joi converts numeric strings to numbers before validating a number schema.
+fn coerce_number(value: &Value) -> Cow<'_, Value> {
+    let Some(string) = value.as_str() else {
+        return Cow::Borrowed(value);
+    };
+    if !NUMBER_STRING.is_match(string) {
+        return Cow::Borrowed(value);
+    }
+
+    match string.trim().parse::<f64>() {
+        Ok(number) => Cow::Owned(number_value(number)),
+        Err(_) => Cow::Borrowed(value),
+    }
+}
+
+fn number_value(number: f64) -> Value {
+    // Keep whole numbers as integers so they compare equal to integers in `allow` lists
+    if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 {
+        Value::from(number as i64)
+    } else {
+        Number::from_f64(number).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Compares values the way JavaScript's `===` would for JSON values.
+fn same_value(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(left), Some(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+fn only_message(allow: &[Value]) -> String {
+    let valids = allow
+        .iter()
+        .map(|valid| match valid {
+            Value::String(string) => string.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if allow.len() == 1 {
+        format!("{LABEL} must be [{valids}]")
+    } else {
+        format!("{LABEL} must be one of [{valids}]")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn error(validation_format: &str, value: Value) -> Option<String> {
+        evaluate(validation_format, Some(&value))
+            .expect("validation format is supported")
+            .error
+    }
+
+    #[test]
+    fn number_rules() {
+        let format = r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":0}},{"name":"max","args":{"limit":2}}]}"#;
+
+        assert_eq!(
+            Some("\"value\" is required".to_owned()),
+            evaluate(format, None).expect("supported").error
+        );
+        assert_eq!(None, error(format, json!(1)));
+        assert_eq!(None, error(format, json!("2")));
+        assert_eq!(
+            Some("\"value\" must be less than or equal to 2".to_owned()),
+            error(format, json!(3))
+        );
+        assert_eq!(
+            Some("\"value\" must be greater than or equal to 0".to_owned()),
+            error(format, json!(-1))
+        );
+        assert_eq!(
+            Some("\"value\" must be an integer".to_owned()),
+            error(format, json!(1.5))
+        );
+        assert_eq!(
+            Some("\"value\" must be a number".to_owned()),
+            error(format, json!("one"))
+        );
+        assert_eq!(
+            Some("\"value\" must be a valid port".to_owned()),
+            error(
+                r#"{"type":"number","rules":[{"name":"port"}]}"#,
+                json!(70000)
+            )
+        );
+    }
+
+    #[test]
+    fn string_rules() {
+        let format = r#"{"type":"string","rules":[{"name":"min","args":{"limit":2}},{"name":"max","args":{"limit":4}},{"name":"pattern","args":{"regex":"/^[a-z\\d]+$/i"}}]}"#;
+
+        assert_eq!(None, evaluate(format, None).expect("supported").error);
+        assert_eq!(None, error(format, json!("Ab1")));
+        assert_eq!(
+            Some("\"value\" is not allowed to be empty".to_owned()),
+            error(format, json!(""))
+        );
+        assert_eq!(
+            Some("\"value\" length must be at least 2 characters long".to_owned()),
+            error(format, json!("a"))
+        );
+        assert_eq!(
+            Some("\"value\" length must be less than or equal to 4 characters long".to_owned()),
+            error(format, json!("abcde"))
+        );
+        assert_eq!(
+            Some(
+                "\"value\" with value \"a-b\" fails to match the required pattern: /^[a-z\\d]+$/i"
+                    .to_owned()
+            ),
+            error(format, json!("a-b"))
+        );
+        assert_eq!(
+            Some("\"value\" must be a string".to_owned()),
+            error(format, json!(12))
+        );
+    }
+
+    #[test]
+    fn allow_and_valid() {
+        let allow = r#"{"type":"string","allow":[""],"rules":[{"name":"min","args":{"limit":3}}]}"#;
+        assert_eq!(None, error(allow, json!("")));
+
+        let valid = r#"{"type":"string","flags":{"only":true},"allow":["a","b"]}"#;
+        assert_eq!(None, error(valid, json!("b")));
+        assert_eq!(
+            Some("\"value\" must be one of [a, b]".to_owned()),
+            error(valid, json!("c"))
+        );
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        assert!(evaluate("'{}'", None).is_none());
+        assert!(evaluate("5", None).is_none());
+        assert!(evaluate(r#"{"type":"boolean"}"#, None).is_none());
+        assert!(evaluate(r#"{"type":"string","rules":[{"name":"email"}]}"#, None).is_none());
+        assert!(evaluate(r#"{"type":"string","rules":[{"name":"uri"}]}"#, None).is_none());
+        assert!(evaluate(r#"{"type":"string","flags":{"label":"name"}}"#, None).is_none());
+        assert!(evaluate(
+            r#"{"type":"string","rules":[{"name":"pattern","args":{"regex":"/^(?=a)/"}}]}"#,
+            None
+        )
+        .is_none());
+        assert!(evaluate(
+            r#"{"type":"number","rules":[{"name":"min","args":{"limit":{"ref":{"path":["a"]}}}}]}"#,
+            None
+        )
+        .is_none());
+    }
+}
@@ -168,7 +168,9 @@ type Output = {{
     actions?: {{ [key: string]: {{
       add?: ("create" | "update" | "refresh" | "delete" | string)[];
       remove?: ("create" | "update" | "refresh" | "delete" | string)[];
-    }} }}
+    }} }},
+    delete?: string[],
+    erase?: string[],
   }},
   message?: string | null;
 }};
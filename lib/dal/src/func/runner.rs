@@ -9,14 +9,15 @@ use si_events::{
     ActionId, ActionResultState, CasValue, ContentHash, EncryptedSecretKey, FuncRun,
     FuncRunBuilder, FuncRunBuilderError, FuncRunId, FuncRunLog, FuncRunLogId, FuncRunValue,
 };
+use si_layer_cache::db::func_result::{self, FuncResultKey};
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 use veritech_client::{
-    encrypt_value_tree, BeforeFunction, FunctionResult, FunctionResultFailure,
-    FunctionResultFailureErrorKind, KillExecutionRequest, OutputStream, ResolverFunctionComponent,
-    VeritechValueEncryptError,
+    contains_secret_reference, encrypt_value_tree, BeforeFunction, FunctionResult,
+    FunctionResultFailure, FunctionResultFailureErrorKind, KillExecutionRequest, OutputStream,
+    ResolverFunctionComponent, VeritechValueEncryptError,
 };
 
 use crate::attribute::prototype::argument::value_source::ValueSource;
@@ -56,7 +57,7 @@ use super::backend::{
     map::FuncBackendMap,
     object::FuncBackendObject,
     string::FuncBackendString,
-    validation::FuncBackendValidation,
+    validation::{joi, FuncBackendValidation},
     FuncBackend, FuncDispatch, FuncDispatchContext, InvalidResolverFunctionTypeError,
 };
 
@@ -150,6 +151,16 @@ pub type FuncRunnerResult<T> = Result<T, FuncRunnerError>;
 
 pub type FuncRunnerValueChannel = oneshot::Receiver<FuncRunnerResult<FuncRunValue>>;
 
+/// Whether an attribute func run can use, or should record, a memoized result.
+enum AttributeValueMemo {
+    /// The func's results are never memoized.
+    Unmemoizable,
+    /// No result is memoized for these inputs yet; it is memoized under the key once the func
+    /// succeeds.
+    Miss(FuncResultKey),
+    Hit(Arc<func_result::FuncResult>),
+}
+
 pub struct FuncRunner {
     func_run: Arc<FuncRun>,
 
@@ -288,7 +299,7 @@ impl FuncRunner {
             .map_err(|err| span.record_err(err))?;
 
         let func_run_id = runner.id();
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = runner.execute(ctx.clone(), span, None).await;
 
         Ok((func_run_id, result_channel))
     }
@@ -434,7 +445,7 @@ impl FuncRunner {
             .await
             .map_err(|err| span.record_err(err))?;
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = runner.execute(ctx.clone(), span, None).await;
 
         Ok(result_channel)
     }
@@ -605,11 +616,20 @@ impl FuncRunner {
             })
         }
 
+        // Most validation formats only use a handful of Joi rules, which can be evaluated here
+        // rather than in veritech
+        let native_result = joi::evaluate(&validation_format, value.as_ref());
+
         let runner = prepare(ctx, attribute_value_id, value, validation_format, &span)
             .await
             .map_err(|err| span.record_err(err))?;
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = match native_result {
+            Some(native_result) => runner
+                .resolve_natively(native_result, span.clone())
+                .map_err(|err| span.record_err(err))?,
+            None => runner.execute(ctx.clone(), span, None).await,
+        };
 
         Ok(result_channel)
     }
@@ -663,13 +683,15 @@ impl FuncRunner {
             func_id: FuncId,
             args: serde_json::Value,
             parent_span: &Span,
-        ) -> FuncRunnerResult<FuncRunner> {
+        ) -> FuncRunnerResult<(FuncRunner, AttributeValueMemo)> {
             let func = Func::get_by_id_or_error(ctx, func_id).await?;
 
             let function_args: CasValue = args.clone().into();
 
             let component_id = AttributeValue::component_id(ctx, attribute_value_id).await?;
-            let before = FuncRunner::before_funcs(ctx, component_id).await?;
+            let ordered_before_funcs_with_secret_keys =
+                FuncRunner::ordered_before_funcs_with_secret_keys(ctx, component_id).await?;
+            let mut memo = AttributeValueMemo::Unmemoizable;
 
             let func_run_create_time = Utc::now();
             let mut func_run_builder = FuncRunBuilder::default();
@@ -711,6 +733,20 @@ impl FuncRunner {
                     ContentHash::new("".as_bytes())
                 };
 
+                if func.is_memoizable() {
+                    let key = FuncRunner::func_result_key(
+                        ctx,
+                        &func,
+                        code_cas_hash,
+                        function_args_cas_address,
+                        &ordered_before_funcs_with_secret_keys,
+                    );
+                    memo = match ctx.layer_db().func_result().read(&key).await? {
+                        Some(result) => AttributeValueMemo::Hit(result),
+                        None => AttributeValueMemo::Miss(key),
+                    };
+                }
+
                 func_run_builder.function_args_cas_address(function_args_cas_address);
                 func_run_builder.function_code_cas_address(code_cas_hash);
             } else {
@@ -720,6 +756,30 @@ impl FuncRunner {
                 func_run_builder.function_code_cas_address(ContentHash::new("".as_bytes()));
             }
 
+            // A memoized result is used as is, so there is no need to decrypt any secrets for
+            // the before funcs
+            let before = match memo {
+                AttributeValueMemo::Hit(_) => vec![],
+                _ => {
+                    FuncRunner::before_funcs_for_secret_keys(
+                        ctx,
+                        ordered_before_funcs_with_secret_keys,
+                    )
+                    .await?
+                }
+            };
+
+            // Secret references are resolved by veritech, so the values they refer to can change
+            // without the secret's key changing. Results which depend on them are never
+            // memoized, which also means there is never a memoized result to hit for them.
+            if matches!(memo, AttributeValueMemo::Miss(_))
+                && before
+                    .iter()
+                    .any(|before| contains_secret_reference(&before.arg))
+            {
+                memo = AttributeValueMemo::Unmemoizable;
+            }
+
             let func_run_inner = func_run_builder.build()?;
 
             if !parent_span.is_disabled() {
@@ -766,19 +826,34 @@ impl FuncRunner {
                     .await?;
             }
 
-            Ok(FuncRunner {
-                func_run,
-                func,
-                args,
-                before,
-            })
+            Ok((
+                FuncRunner {
+                    func_run,
+                    func,
+                    args,
+                    before,
+                },
+                memo,
+            ))
         }
 
-        let runner = prepare(ctx, attribute_value_id, func_id, args, &span)
+        let (runner, memo) = prepare(ctx, attribute_value_id, func_id, args, &span)
             .await
             .map_err(|err| span.record_err(err))?;
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = match memo {
+            AttributeValueMemo::Unmemoizable => runner.execute(ctx.clone(), span, None).await,
+            AttributeValueMemo::Miss(key) => {
+                metric!(monotonic_counter.func_runner.attribute_value.memo_miss = 1);
+                runner.execute(ctx.clone(), span, Some(key)).await
+            }
+            AttributeValueMemo::Hit(result) => {
+                metric!(monotonic_counter.func_runner.attribute_value.memo_hit = 1);
+                runner
+                    .resolve_from_memo(&result, span.clone())
+                    .map_err(|err| span.record_err(err))?
+            }
+        };
 
         Ok(result_channel)
     }
@@ -963,7 +1038,7 @@ impl FuncRunner {
         .await
         .map_err(|err| span.record_err(err))?;
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = runner.execute(ctx.clone(), span, None).await;
 
         Ok(result_channel)
     }
@@ -1173,7 +1248,7 @@ impl FuncRunner {
             .await
             .map_err(|err| span.record_err(err))?;
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        let result_channel = runner.execute(ctx.clone(), span, None).await;
 
         Ok(result_channel)
     }
@@ -1234,7 +1309,51 @@ impl FuncRunner {
         self.func_run.id()
     }
 
-    async fn execute(self, ctx: DalContext, execution_parent_span: Span) -> FuncRunnerValueChannel {
+    /// Resolves a [`FuncRunner`] with a result computed in-process, skipping execution in
+    /// veritech.
+    fn resolve_natively<T>(self, result: T, span: Span) -> FuncRunnerResult<FuncRunnerValueChannel>
+    where
+        T: Serialize,
+    {
+        let (result_tx, result_rx) = oneshot::channel();
+
+        let value = serde_json::to_value(result)?;
+        let _ = result_tx.send(Ok(FuncRunValue::new(
+            self.func_run.id(),
+            Some(value.clone()),
+            Some(value),
+        )));
+        span.record_ok();
+
+        Ok(result_rx)
+    }
+
+    /// Resolves a [`FuncRunner`] with the memoized result of an earlier run of the same code with
+    /// the same arguments, skipping execution in veritech.
+    fn resolve_from_memo(
+        self,
+        result: &func_result::FuncResult,
+        span: Span,
+    ) -> FuncRunnerResult<FuncRunnerValueChannel> {
+        let (result_tx, result_rx) = oneshot::channel();
+
+        let _ = result_tx.send(Ok(FuncRunValue::new(
+            self.func_run.id(),
+            result.unprocessed_value.clone().map(Into::into),
+            result.value.clone().map(Into::into),
+        )));
+        span.record_ok();
+
+        Ok(result_rx)
+    }
+
+    /// Executes the func, memoizing its result under the given key (if any) when it succeeds.
+    async fn execute(
+        self,
+        ctx: DalContext,
+        execution_parent_span: Span,
+        memo_key: Option<FuncResultKey>,
+    ) -> FuncRunnerValueChannel {
         let func_run_id = self.func_run.id();
         let action_id = self.func_run.action_id();
         let (func_dispatch_context, output_stream_rx) = FuncDispatchContext::new(
@@ -1260,6 +1379,7 @@ impl FuncRunner {
             func: self.func,
             args: self.args,
             before: self.before,
+            memo_key,
             parent_span: execution_parent_span,
         };
 
@@ -1280,6 +1400,15 @@ impl FuncRunner {
         let ordered_before_funcs_with_secret_keys =
             Self::ordered_before_funcs_with_secret_keys(ctx, component_id).await?;
 
+        Self::before_funcs_for_secret_keys(ctx, ordered_before_funcs_with_secret_keys).await
+    }
+
+    /// This _private_ method builds the [`BeforeFunctions`](BeforeFunction) for the output of
+    /// [`Self::ordered_before_funcs_with_secret_keys`], decrypting each secret.
+    async fn before_funcs_for_secret_keys(
+        ctx: &DalContext,
+        ordered_before_funcs_with_secret_keys: Vec<(EncryptedSecretKey, Vec<Func>)>,
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
         let mut before_functions = Vec::new();
 
         for (key, funcs) in ordered_before_funcs_with_secret_keys {
@@ -1321,6 +1450,49 @@ impl FuncRunner {
         Ok(before_functions)
     }
 
+    /// This _private_ method builds the key a [`Func's`](Func) result is memoized under in the
+    /// current workspace.
+    ///
+    /// The handler and response type are hashed alongside the code, since both change what
+    /// running the code produces. Before funcs are hashed by their code and the keys of their
+    /// secrets, which change whenever the secret does, so no secrets need to be decrypted.
+    fn func_result_key(
+        ctx: &DalContext,
+        func: &Func,
+        code_cas_hash: ContentHash,
+        args_cas_hash: ContentHash,
+        ordered_before_funcs_with_secret_keys: &[(EncryptedSecretKey, Vec<Func>)],
+    ) -> FuncResultKey {
+        let code_hash = ContentHash::new(
+            format!(
+                "{}\0{}\0{code_cas_hash}",
+                func.handler.as_deref().unwrap_or_default(),
+                func.backend_response_type.as_ref(),
+            )
+            .as_bytes(),
+        );
+
+        let mut before_input = Vec::new();
+        for (key, funcs) in ordered_before_funcs_with_secret_keys {
+            before_input.extend_from_slice(key.as_bytes());
+            for func in funcs {
+                before_input
+                    .extend_from_slice(func.handler.as_deref().unwrap_or_default().as_bytes());
+                before_input.push(0);
+                before_input
+                    .extend_from_slice(func.code_base64.as_deref().unwrap_or_default().as_bytes());
+                before_input.push(0);
+            }
+        }
+
+        FuncResultKey::new(
+            ctx.events_tenancy().workspace_pk,
+            code_hash,
+            args_cas_hash,
+            ContentHash::new(&before_input),
+        )
+    }
+
     /// This _private_ method generates a flattened graph of before [`Funcs`](Func) with corresponding
     /// [`keys`](EncryptedSecretKey).
     #[instrument(
@@ -1602,6 +1774,7 @@ struct FuncRunnerExecutionTask {
     func: Func,
     args: serde_json::Value,
     before: Vec<BeforeFunction>,
+    memo_key: Option<FuncResultKey>,
     parent_span: Span,
 }
 
@@ -1640,12 +1813,26 @@ impl FuncRunnerExecutionTask {
         }
     }
 
-    async fn try_run(self) -> FuncRunnerResult<()> {
+    async fn try_run(mut self) -> FuncRunnerResult<()> {
         let mut running_state_func_run_inner = Arc::unwrap_or_clone(self.func_run.clone());
         running_state_func_run_inner.set_state_to_running();
         let running_state_func_run = Arc::new(running_state_func_run_inner);
 
         if !self.func.is_intrinsic() {
+            // Veritech schedules executions fairly between workspaces, giving each as many at
+            // once as it allows components to run concurrently
+            let concurrency_limit = self.ctx.component_concurrency_limit().await?;
+            if let Ok(limit) = u32::try_from(concurrency_limit) {
+                self.func_dispatch_context.veritech = self
+                    .func_dispatch_context
+                    .veritech
+                    .with_workspace_concurrency_limit(limit);
+            }
+            self.func_dispatch_context.veritech = self
+                .func_dispatch_context
+                .veritech
+                .with_func_runtime(self.func.runtime);
+
             self.ctx
                 .layer_db()
                 .func_run()
@@ -1784,6 +1971,18 @@ impl FuncRunnerExecutionTask {
                         .await?;
                 }
 
+                if let Some(memo_key) = self.memo_key {
+                    self.ctx.layer_db().func_result().write(
+                        memo_key,
+                        Arc::new(func_result::FuncResult {
+                            unprocessed_value: unprocessed_value.clone().map(Into::into),
+                            value: value.clone().map(Into::into),
+                        }),
+                        self.ctx.events_tenancy(),
+                        self.ctx.events_actor(),
+                    )?;
+                }
+
                 let _ = self.result_tx.send(Ok(FuncRunValue::new(
                     next_state.id(),
                     unprocessed_value,
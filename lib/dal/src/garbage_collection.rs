@@ -0,0 +1,119 @@
+//! This module marks everything reachable from the workspace snapshots in use, so that the layer
+//! db can sweep the rest. See [`si_layer_cache::gc`] for how the sweep works.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use si_events::workspace_snapshot_address::WorkspaceSnapshotAddressParseError;
+use si_layer_cache::db::serialize;
+use si_layer_cache::gc::{
+    GarbageCollectionConfig, GarbageCollectionMarks, GarbageCollectionReport,
+};
+use si_layer_cache::LayerDbError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::workspace_snapshot::graph::detect_updates::Update;
+use crate::workspace_snapshot::graph::RebaseBatch;
+use crate::{
+    ChangeSet, ChangeSetError, DalContext, WorkspaceSnapshotAddress, WorkspaceSnapshotGraph,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum GarbageCollectionError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("cannot mark content for workspace snapshot {0}; it has not been migrated")]
+    UnmigratedSnapshot(WorkspaceSnapshotAddress),
+    #[error("workspace snapshot address parse error: {0}")]
+    WorkspaceSnapshotAddressParse(#[from] WorkspaceSnapshotAddressParseError),
+}
+
+type Result<T> = std::result::Result<T, GarbageCollectionError>;
+
+/// Collects every workspace snapshot, CAS entry and rebase batch in the layer db that can no
+/// longer be reached, returning a report of what was (or would have been, for a dry run) swept.
+///
+/// The roots are every snapshot a change set pointer refers to, along with every snapshot and
+/// rebase batch written within the grace period, since those may be in the middle of being
+/// applied. Abandoned change sets still hold on to their snapshots.
+#[instrument(
+    name = "garbage_collection.collect_garbage",
+    level = "info",
+    skip_all,
+    fields(
+        si.layer_cache.gc.dry_run = config.dry_run,
+    )
+)]
+pub async fn collect_garbage(
+    ctx: &DalContext,
+    config: GarbageCollectionConfig,
+) -> Result<GarbageCollectionReport> {
+    // The marks must exist before we read any roots, so that anything written while we are
+    // marking falls within the grace period.
+    let mut marks = GarbageCollectionMarks::new();
+    let cutoff = marks.cutoff(config.grace_period)?;
+    let layer_db = ctx.layer_db();
+
+    let mut roots = ChangeSet::list_workspace_snapshot_addresses_in_use(ctx).await?;
+    for key in layer_db
+        .workspace_snapshot()
+        .cache
+        .pg()
+        .keys_created_since(cutoff)
+        .await?
+    {
+        roots.push(WorkspaceSnapshotAddress::from_str(&key)?);
+    }
+
+    for address in roots {
+        if !marks.mark_snapshot(address) {
+            continue;
+        }
+
+        // Prefer durable storage, so that walking every snapshot does not churn the memory cache.
+        let graph: Arc<WorkspaceSnapshotGraph> = match layer_db
+            .workspace_snapshot()
+            .read_bytes_from_durable_storage(&address)
+            .await?
+        {
+            Some(bytes) => serialize::from_bytes(&bytes)?,
+            None => match layer_db.workspace_snapshot().read(&address).await? {
+                Some(graph) => graph,
+                None => {
+                    warn!(
+                        si.workspace_snapshot.address = %address,
+                        "workspace snapshot in use but not found",
+                    );
+                    continue;
+                }
+            },
+        };
+
+        let WorkspaceSnapshotGraph::V4(graph) = graph.as_ref() else {
+            return Err(GarbageCollectionError::UnmigratedSnapshot(address));
+        };
+        for (node_weight, _) in graph.nodes() {
+            marks.mark_content(node_weight.content_store_hashes());
+        }
+    }
+
+    // Content introduced by a pending rebase batch may not be in any snapshot yet.
+    let rebase_batch_pg = layer_db.rebase_batch().cache.pg();
+    for key in rebase_batch_pg.keys_created_since(cutoff).await? {
+        let Some(bytes) = rebase_batch_pg.get(&key).await? else {
+            continue;
+        };
+        let rebase_batch: RebaseBatch = serialize::from_bytes(&bytes)?;
+        for update in rebase_batch.updates() {
+            if let Update::NewNode { node_weight } | Update::ReplaceNode { node_weight } = update {
+                marks.mark_content(node_weight.content_store_hashes());
+            }
+        }
+    }
+
+    Ok(layer_db.collect_garbage(marks, config).await?)
+}
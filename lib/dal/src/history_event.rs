@@ -35,14 +35,24 @@ pub type HistoryEventResult<T> = Result<T, HistoryEventError>;
 #[remain::sorted]
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, StrumDisplay, Clone, Copy, Hash)]
 pub enum HistoryActor {
+    /// A user acting through an automation token, rather than through the web app.
+    Automation(UserPk),
     SystemInit,
     User(UserPk),
 }
 
 impl HistoryActor {
+    /// The user acting, whether through the web app or an automation token.
+    pub fn user_pk(&self) -> Option<UserPk> {
+        match self {
+            HistoryActor::Automation(pk) | HistoryActor::User(pk) => Some(*pk),
+            HistoryActor::SystemInit => None,
+        }
+    }
+
     pub fn distinct_id(&self) -> String {
         match self {
-            HistoryActor::User(pk) => pk.to_string(),
+            HistoryActor::Automation(pk) | HistoryActor::User(pk) => pk.to_string(),
             HistoryActor::SystemInit => "unknown-backend".to_string(),
         }
     }
@@ -50,11 +60,13 @@ impl HistoryActor {
     pub async fn email(&self, ctx: &DalContext) -> HistoryEventResult<String> {
         Ok(match self {
             HistoryActor::SystemInit => "sally@systeminit.com".to_string(),
-            HistoryActor::User(user_pk) => User::get_by_pk_or_error(ctx, *user_pk)
-                .await
-                .map_err(|e| HistoryEventError::User(e.to_string()))?
-                .email()
-                .clone(),
+            HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
+                User::get_by_pk_or_error(ctx, *user_pk)
+                    .await
+                    .map_err(|e| HistoryEventError::User(e.to_string()))?
+                    .email()
+                    .clone()
+            }
         })
     }
 
@@ -1,9 +1,11 @@
 mod action;
 pub mod compute_validation;
 pub mod dependent_values_update;
+mod refresh_resources;
 
 pub use action::ActionJob;
 pub use dependent_values_update::DependentValuesUpdate;
+pub use refresh_resources::RefreshResourcesJob;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum AttributeValueBasedJobIdentifier {
@@ -24,6 +24,7 @@ use crate::{
         },
         producer::{JobProducer, JobProducerResult},
     },
+    schedule::drift,
     AccessBuilder, ActionPrototypeId, Component, ComponentId, DalContext, Func, Visibility,
     WsEvent,
 };
@@ -224,10 +225,31 @@ async fn process_execution(
     if let Some(run_result) = action_run_result {
         // Set the resource if we have a payload, regardless of status *and* assemble a
         // summary
-        if run_result.payload.is_some() {
+        if let Some(payload) = &run_result.payload {
             // Send the create resource event if we're not updating an existing resource
-            if component.resource(ctx).await?.is_none() {
-                billing_publish::for_resource_create(ctx, component_id, func_run_id).await?;
+            match component.resource(ctx).await? {
+                None => {
+                    billing_publish::for_resource_create(ctx, component_id, func_run_id).await?;
+                }
+                // Report anything that changed outside of SI since the resource was last synced
+                Some(previous) if prototype.kind == ActionKind::Refresh => {
+                    if let Some(previous_payload) = &previous.payload {
+                        let changed_paths = drift::changed_paths(previous_payload, payload);
+                        if !changed_paths.is_empty() {
+                            WsEvent::resource_drift_detected(
+                                ctx,
+                                component_id,
+                                action_id,
+                                changed_paths,
+                                previous.last_synced,
+                            )
+                            .await?
+                            .publish_on_commit(ctx)
+                            .await?;
+                        }
+                    }
+                }
+                Some(_) => {}
             }
 
             component.set_resource(ctx, run_result.into()).await?;
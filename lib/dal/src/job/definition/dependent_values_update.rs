@@ -256,7 +256,7 @@ impl DependentValuesUpdate {
             finished_values.clear();
         }
 
-        let concurrency_limit = ctx.get_workspace().await?.component_concurrency_limit() as usize;
+        let concurrency_limit = ctx.component_concurrency_limit().await? as usize;
 
         let mut dependency_graph = DependentValueGraph::new(ctx, roots).await?;
 
@@ -0,0 +1,131 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    action::{
+        prototype::{ActionKind, ActionPrototype},
+        Action,
+    },
+    job::{
+        consumer::{
+            JobCompletionState, JobConsumer, JobConsumerError, JobConsumerMetadata,
+            JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    AccessBuilder, Component, DalContext, Visibility,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RefreshResourcesArgs;
+
+impl From<RefreshResourcesJob> for RefreshResourcesArgs {
+    fn from(_value: RefreshResourcesJob) -> Self {
+        Self
+    }
+}
+
+/// Enqueues a refresh action for every component with a resource, which is run by the rebaser
+/// once the job commits. Enqueued by the [`ResourceRefresh`] scheduled job on HEAD.
+///
+/// [`ResourceRefresh`]: crate::schedule::ScheduledJobKind::ResourceRefresh
+#[derive(Clone, Debug, Serialize)]
+pub struct RefreshResourcesJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl RefreshResourcesJob {
+    pub fn new(access_builder: AccessBuilder, visibility: Visibility) -> Box<Self> {
+        Box::new(Self {
+            access_builder,
+            visibility,
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for RefreshResourcesJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(RefreshResourcesArgs::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl JobConsumerMetadata for RefreshResourcesJob {
+    fn type_name(&self) -> String {
+        "RefreshResourcesJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for RefreshResourcesJob {
+    #[instrument(
+        name = "refresh_resources_job.run",
+        skip_all,
+        level = "info",
+        fields(
+            si.refresh_resources.enqueued = Empty,
+        )
+    )]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<JobCompletionState> {
+        let span = Span::current();
+
+        let mut enqueued = 0;
+        for component in Component::list(ctx).await? {
+            if component.resource(ctx).await?.is_none() {
+                continue;
+            }
+
+            // Don't pile up refreshes for a component whose last one hasn't run yet
+            if !Action::find_for_kind_and_component_id(ctx, component.id(), ActionKind::Refresh)
+                .await?
+                .is_empty()
+            {
+                continue;
+            }
+
+            let schema_variant_id = Component::schema_variant_id(ctx, component.id()).await?;
+            for prototype in ActionPrototype::for_variant(ctx, schema_variant_id).await? {
+                if prototype.kind == ActionKind::Refresh {
+                    Action::new(ctx, prototype.id(), Some(component.id())).await?;
+                    enqueued += 1;
+                }
+            }
+        }
+        span.record("si.refresh_resources.enqueued", enqueued);
+
+        if enqueued > 0 {
+            ctx.commit().await?;
+        }
+
+        Ok(JobCompletionState::Done)
+    }
+}
+
+impl TryFrom<JobInfo> for RefreshResourcesJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        RefreshResourcesArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
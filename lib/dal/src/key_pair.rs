@@ -22,6 +22,8 @@ const PUBLIC_KEY_GET_CURRENT: &str = include_str!("./queries/key_pair/public_key
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum KeyPairError {
+    #[error("cannot retire the current key pair for the workspace: {0}")]
+    CannotRetireCurrentKeyPair(KeyPairPk),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
     #[error("invalid secret key bytes")]
@@ -126,6 +128,58 @@ impl KeyPair {
         Ok(key_pair)
     }
 
+    /// Gets the [`KeyPair`] that new secrets in the workspace should be sealed to, which is the
+    /// most recently created one that hasn't been retired.
+    pub async fn get_current(ctx: &DalContext) -> KeyPairResult<Self> {
+        let public_key = PublicKey::get_current(ctx).await?;
+        Self::get_by_pk(ctx, public_key.pk).await
+    }
+
+    /// Creates a new [`KeyPair`] with the same name as the current one, making it the current key
+    /// pair for the workspace. Older key pairs stay usable until they are [retired](Self::retire).
+    pub async fn rotate(ctx: &DalContext) -> KeyPairResult<Self> {
+        let current = Self::get_current(ctx).await?;
+        Self::new(ctx, current.name).await
+    }
+
+    /// Retires the [`KeyPair`]. Once retired, it can no longer be fetched, so anything still
+    /// sealed to it can no longer be decrypted.
+    ///
+    /// The current key pair for the workspace can't be retired; [rotate](Self::rotate) it first.
+    /// Key pairs belonging to other workspaces can't be retired either.
+    pub async fn retire(self, ctx: &DalContext) -> KeyPairResult<()> {
+        if self.workspace_pk != ctx.tenancy().workspace_pk()? {
+            return Err(KeyPairError::UnauthorizedKeyAccess);
+        }
+
+        let current = PublicKey::get_current(ctx).await?;
+        if current.pk == self.pk {
+            return Err(KeyPairError::CannotRetireCurrentKeyPair(self.pk));
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE key_pairs
+                    SET retired_at = CLOCK_TIMESTAMP(), updated_at = CLOCK_TIMESTAMP()
+                 WHERE pk = $1 AND workspace_pk = $2",
+                &[&self.pk, &self.workspace_pk],
+            )
+            .await?;
+
+        // HistoryEvent won't be accessible by any tenancy (null tenancy_workspace_pk)
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.retire".to_owned(),
+            "Key Pair retired".to_owned(),
+            &serde_json::json![{ "pk": self.pk, "visibility": ctx.visibility() }],
+        )
+        .await?;
+
+        Ok(())
+    }
+
     standard_model_accessor_ro!(name, String);
     standard_model_accessor_ro!(workspace_pk, WorkspacePk);
     standard_model_accessor_ro!(public_key, BoxPublicKey);
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -6,6 +6,7 @@ use si_events::ulid::Ulid;
 use si_events::{CasValue, ContentHash};
 use strum::EnumDiscriminants;
 use thiserror::Error;
+use veritech_client::FuncRuntime;
 
 use crate::action::prototype::ActionKind;
 use crate::validation::ValidationStatus;
@@ -222,15 +223,21 @@ pub struct AttributePrototypeContentV1 {
 pub enum ComponentContent {
     V1(ComponentContentV1),
     V2(ComponentContentV2),
+    V3(ComponentContentV3),
 }
 
 impl ComponentContent {
-    pub fn extract(self) -> ComponentContentV2 {
+    pub fn extract(self) -> ComponentContentV3 {
         match self {
-            ComponentContent::V1(v1) => ComponentContentV2 {
+            ComponentContent::V1(v1) => ComponentContentV3 {
                 timestamp: v1.timestamp,
+                tags: BTreeMap::new(),
             },
-            ComponentContent::V2(v2) => v2,
+            ComponentContent::V2(v2) => ComponentContentV3 {
+                timestamp: v2.timestamp,
+                tags: BTreeMap::new(),
+            },
+            ComponentContent::V3(v3) => v3,
         }
     }
 }
@@ -249,15 +256,29 @@ pub struct ComponentContentV2 {
     pub timestamp: Timestamp,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ComponentContentV3 {
+    pub timestamp: Timestamp,
+    /// Ordered by key, so that the same tags always hash the same.
+    pub tags: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ViewContent {
     V1(ViewContentV1),
+    V2(ViewContentV2),
 }
 
 impl ViewContent {
-    pub fn extract(self) -> ViewContentV1 {
-        let ViewContent::V1(content) = self;
-        content
+    pub fn extract(self) -> ViewContentV2 {
+        match self {
+            ViewContent::V1(v1) => ViewContentV2 {
+                timestamp: v1.timestamp,
+                name: v1.name,
+                tags: BTreeMap::new(),
+            },
+            ViewContent::V2(v2) => v2,
+        }
     }
 }
 
@@ -267,6 +288,14 @@ pub struct ViewContentV1 {
     pub name: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ViewContentV2 {
+    pub timestamp: Timestamp,
+    pub name: String,
+    /// Ordered by key, so that the same tags always hash the same.
+    pub tags: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum GeometryContent {
     V1(GeometryContentV1),
@@ -292,6 +321,8 @@ pub struct GeometryContentV1 {
 pub enum FuncContent {
     V1(FuncContentV1),
     V2(FuncContentV2),
+    V3(FuncContentV3),
+    V4(FuncContentV4),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -326,10 +357,50 @@ pub struct FuncContentV2 {
     pub is_locked: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV3 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// Whether the func depends on more than its arguments (the clock, randomness, etc.)
+    pub is_nondeterministic: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV4 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// Whether the func depends on more than its arguments (the clock, randomness, etc.)
+    pub is_nondeterministic: bool,
+    /// The language runtime the code above is written for
+    pub runtime: FuncRuntime,
+}
+
 impl FuncContent {
-    pub fn extract(self) -> FuncContentV2 {
+    pub fn extract(self) -> FuncContentV4 {
         match self {
-            FuncContent::V1(v1) => FuncContentV2 {
+            FuncContent::V1(v1) => FuncContentV4 {
                 timestamp: v1.timestamp,
                 hidden: v1.hidden,
                 display_name: v1.display_name,
@@ -342,8 +413,42 @@ impl FuncContent {
                 handler: v1.handler,
                 code_base64: v1.code_base64,
                 code_blake3: v1.code_blake3,
+                is_nondeterministic: false,
+                runtime: FuncRuntime::default(),
+            },
+            FuncContent::V2(v2) => FuncContentV4 {
+                timestamp: v2.timestamp,
+                hidden: v2.hidden,
+                display_name: v2.display_name,
+                link: v2.link,
+                description: v2.description,
+                is_locked: v2.is_locked,
+                builtin: v2.builtin,
+                backend_response_type: v2.backend_response_type,
+                backend_kind: v2.backend_kind,
+                handler: v2.handler,
+                code_base64: v2.code_base64,
+                code_blake3: v2.code_blake3,
+                is_nondeterministic: false,
+                runtime: FuncRuntime::default(),
+            },
+            FuncContent::V3(v3) => FuncContentV4 {
+                timestamp: v3.timestamp,
+                hidden: v3.hidden,
+                display_name: v3.display_name,
+                link: v3.link,
+                description: v3.description,
+                is_locked: v3.is_locked,
+                builtin: v3.builtin,
+                backend_response_type: v3.backend_response_type,
+                backend_kind: v3.backend_kind,
+                handler: v3.handler,
+                code_base64: v3.code_base64,
+                code_blake3: v3.code_blake3,
+                is_nondeterministic: v3.is_nondeterministic,
+                runtime: FuncRuntime::default(),
             },
-            FuncContent::V2(v2) => v2,
+            FuncContent::V4(v4) => v4,
         }
     }
 }
@@ -36,6 +36,7 @@ pub mod dependency_graph;
 pub mod diagram;
 pub mod feature_flags;
 pub mod func;
+pub mod garbage_collection;
 pub mod history_event;
 pub mod input_sources;
 pub mod jetstream_streams;
@@ -51,6 +52,7 @@ pub mod prop;
 pub mod property_editor;
 pub mod qualification;
 pub mod resource_metadata;
+pub mod schedule;
 pub mod schema;
 pub mod secret;
 pub mod serde_impls;
@@ -102,6 +104,7 @@ pub use schema::{
     variant::SchemaVariantError, Schema, SchemaError, SchemaId, SchemaVariant, SchemaVariantId,
 };
 pub use secret::EncryptedSecret;
+pub use secret::KeyPairRotation;
 pub use secret::Secret;
 pub use secret::SecretAlgorithm;
 pub use secret::SecretCreatedPayload;
@@ -109,6 +112,7 @@ pub use secret::SecretDefinitionView;
 pub use secret::SecretDefinitionViewError;
 pub use secret::SecretError;
 pub use secret::SecretId;
+pub use secret::SecretReference;
 pub use secret::SecretResult;
 pub use secret::SecretUpdatedPayload;
 pub use secret::SecretVersion;
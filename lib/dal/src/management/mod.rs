@@ -2,6 +2,7 @@ use std::collections::{hash_map, HashMap, HashSet, VecDeque};
 
 use prototype::ManagementPrototypeExecution;
 use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
 use thiserror::Error;
 
 use veritech_client::{ManagementFuncStatus, ManagementResultSuccess};
@@ -20,7 +21,7 @@ use crate::{
         prototype::argument::{AttributePrototypeArgument, AttributePrototypeArgumentError},
         value::AttributeValueError,
     },
-    change_status::ChangeStatus::Added,
+    change_status::ChangeStatus::{self, Added},
     component::IncomingConnection,
     diagram::{geometry::RawGeometry, SummaryDiagramEdge},
     history_event::HistoryEventMetadata,
@@ -28,7 +29,8 @@ use crate::{
     socket::{input::InputSocketError, output::OutputSocketError},
     ActorView, AttributeValue, Component, ComponentError, ComponentId, ComponentType, DalContext,
     Func, FuncError, InputSocket, InputSocketId, OutputSocket, OutputSocketId, Prop, PropKind,
-    Schema, SchemaError, SchemaId, SchemaVariantId, StandardModelError, WsEvent, WsEventError,
+    Schema, SchemaError, SchemaId, SchemaVariantId, StandardModelError, TransactionsError, WsEvent,
+    WsEventError,
 };
 use crate::{EdgeWeightKind, WorkspaceSnapshotError};
 
@@ -55,6 +57,8 @@ pub enum ManagementError {
         "cannot add a manual action named {0} because component {1} does not have a manual action with that name"
     )]
     ComponentDoesNotHaveManualAction(String, ComponentId),
+    #[error("cannot delete or erase component {0} because it is not managed by component {1}")]
+    ComponentNotManaged(ComponentId, ComponentId),
     #[error("Component with management placeholder {0} could not be found")]
     ComponentWithPlaceholderNotFound(String),
     #[error("Diagram Error {0}")]
@@ -81,6 +85,8 @@ pub enum ManagementError {
     SchemaDoesNotExist(String),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
     #[error("workspace snapshot error: {0}")]
     WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
     #[error("ws event error: {0}")]
@@ -225,6 +231,8 @@ pub struct ManagementOperations {
     create: Option<ManagementCreateOperations>,
     update: Option<ManagementUpdateOperations>,
     actions: Option<ManagementActionOperations>,
+    delete: Option<Vec<String>>,
+    erase: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -935,6 +943,151 @@ impl<'a> ManagementOperator<'a> {
         Ok(())
     }
 
+    /// Resolve the placeholders in `placeholders` to component ids, ensuring
+    /// that every one of them is managed by the manager component. Management
+    /// functions can only delete or erase the components they manage.
+    async fn resolve_managed_placeholders(
+        &self,
+        managed_component_ids: &HashSet<ComponentId>,
+        placeholders: &[String],
+    ) -> ManagementResult<Vec<ComponentId>> {
+        let mut component_ids = Vec::with_capacity(placeholders.len());
+        for placeholder in placeholders {
+            let component_id = self.get_real_component_id(placeholder).await?;
+            if !managed_component_ids.contains(&component_id) {
+                return Err(ManagementError::ComponentNotManaged(
+                    component_id,
+                    self.manager_component_id,
+                ));
+            }
+            component_ids.push(component_id);
+        }
+
+        Ok(component_ids)
+    }
+
+    async fn deletes_and_erases(&mut self) -> ManagementResult<()> {
+        let deletes = self.operations.delete.take().unwrap_or_default();
+        let erases = self.operations.erase.take().unwrap_or_default();
+        if deletes.is_empty() && erases.is_empty() {
+            return Ok(());
+        }
+
+        let managed_component_ids: HashSet<ComponentId> =
+            Component::get_by_id(self.ctx, self.manager_component_id)
+                .await?
+                .get_managed(self.ctx)
+                .await?
+                .into_iter()
+                .collect();
+
+        // Resolve everything up front so that we fail before mutating the graph
+        let delete_ids = self
+            .resolve_managed_placeholders(&managed_component_ids, &deletes)
+            .await?;
+        let erase_ids = self
+            .resolve_managed_placeholders(&managed_component_ids, &erases)
+            .await?;
+
+        let components_existing_on_head = Component::exists_on_head(
+            self.ctx,
+            delete_ids.iter().chain(erase_ids.iter()).copied().collect(),
+        )
+        .await?;
+
+        for component_id in delete_ids {
+            // An erase wins over a delete for the same component
+            if erase_ids.contains(&component_id) {
+                continue;
+            }
+
+            let component = Component::get_by_id(self.ctx, component_id).await?;
+            self.write_delete_audit_log(&component).await?;
+
+            let component_still_exists = component.delete(self.ctx).await?.is_some();
+            self.send_deleted_event(
+                component_id,
+                component_still_exists,
+                components_existing_on_head.contains(&component_id),
+            )
+            .await?;
+        }
+
+        for component_id in erase_ids {
+            let component = Component::get_by_id(self.ctx, component_id).await?;
+            self.write_delete_audit_log(&component).await?;
+
+            Component::remove(self.ctx, component_id).await?;
+            self.send_deleted_event(
+                component_id,
+                false,
+                components_existing_on_head.contains(&component_id),
+            )
+            .await?;
+        }
+
+        self.ctx.workspace_snapshot()?.cleanup().await?;
+
+        Ok(())
+    }
+
+    async fn write_delete_audit_log(&self, component: &Component) -> ManagementResult<()> {
+        let component_name = component.name(self.ctx).await?;
+        let schema_variant = component.schema_variant(self.ctx).await?;
+
+        self.ctx
+            .write_audit_log(
+                AuditLogKind::DeleteComponent {
+                    component_id: component.id(),
+                    name: component_name.to_owned(),
+                    schema_variant_id: schema_variant.id(),
+                    schema_variant_name: schema_variant.display_name().to_string(),
+                },
+                component_name,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn send_deleted_event(
+        &self,
+        id: ComponentId,
+        component_still_exists: bool,
+        exists_on_head: bool,
+    ) -> ManagementResult<()> {
+        if component_still_exists {
+            // The component was marked to_delete
+            let component = Component::get_by_id(self.ctx, id).await?;
+            let payload = component
+                .into_frontend_type(self.ctx, None, ChangeStatus::Deleted, &mut HashMap::new())
+                .await?;
+            WsEvent::component_updated(self.ctx, payload)
+                .await?
+                .publish_on_commit(self.ctx)
+                .await?;
+        } else if exists_on_head {
+            // The component is gone from this change set, but will be removed
+            // from HEAD when applied
+            let base_ctx = self.ctx.clone_with_base().await?;
+            let component = Component::get_by_id(&base_ctx, id).await?;
+            let payload = component
+                .into_frontend_type(&base_ctx, None, ChangeStatus::Deleted, &mut HashMap::new())
+                .await?;
+            WsEvent::component_updated(self.ctx, payload)
+                .await?
+                .publish_on_commit(self.ctx)
+                .await?;
+        } else {
+            WsEvent::component_deleted(self.ctx, id)
+                .await?
+                .publish_on_commit(self.ctx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     // Using the dep graph to ensure we send ws events for components in parent
     // to child order, so that parents exist in the frontend before their
     // children / parents are rendered as frames before their children report
@@ -1082,6 +1235,10 @@ impl<'a> ManagementOperator<'a> {
 
         self.actions().await?;
 
+        // Deletes and erases run last, so that the other operations can still
+        // reference the components being removed
+        self.deletes_and_erases().await?;
+
         Ok(created_component_ids)
     }
 }
@@ -0,0 +1,14 @@
+CREATE TABLE scheduled_jobs
+(
+    id                          ident primary key        default ident_create_v1(),
+    workspace_pk                ident                    NOT NULL,
+    kind                        text                     NOT NULL,
+    schedule                    text                     NOT NULL,
+    enabled                     bool                     NOT NULL DEFAULT TRUE,
+    next_run_at                 timestamp with time zone NOT NULL,
+    last_run_at                 timestamp with time zone NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE UNIQUE INDEX ON scheduled_jobs (workspace_pk, kind);
+CREATE INDEX ON scheduled_jobs (next_run_at) WHERE enabled;
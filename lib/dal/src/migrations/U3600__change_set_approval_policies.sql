@@ -0,0 +1,23 @@
+CREATE TABLE change_set_approval_policies
+(
+    id                          ident primary key        default ident_create_v1(),
+    workspace_pk                ident                    NOT NULL,
+    name                        text                     NOT NULL,
+    scope_kind                  text                     NOT NULL,
+    scope                       text                     NULL,
+    required_approvals          integer                  NOT NULL CHECK (required_approvals > 0),
+    approvers                   jsonb                    NOT NULL DEFAULT '[]'::jsonb,
+    allow_self_approval         bool                     NOT NULL DEFAULT FALSE,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON change_set_approval_policies (workspace_pk);
+
+CREATE TABLE change_set_approvals
+(
+    change_set_id               ident                    NOT NULL,
+    user_pk                     ident                    NOT NULL,
+    workspace_snapshot_address  text                     NOT NULL,
+    approved_at                 timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (change_set_id, user_pk)
+);
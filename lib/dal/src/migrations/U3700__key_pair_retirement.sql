@@ -0,0 +1 @@
+ALTER TABLE key_pairs ADD COLUMN retired_at timestamp with time zone NULL;
@@ -0,0 +1,3 @@
+-- The snapshot a change set was forked from, which conflicts with its base change set are
+-- detected against. Change sets created before this was recorded have none.
+ALTER TABLE change_set_pointers ADD COLUMN merge_base_snapshot_address text NULL;
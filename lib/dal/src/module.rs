@@ -25,9 +25,8 @@ use crate::workspace_snapshot::node_weight::traits::SiNodeWeight;
 use crate::workspace_snapshot::node_weight::{NodeWeight, NodeWeightError};
 use crate::workspace_snapshot::WorkspaceSnapshotError;
 use crate::{
-    ChangeSetError, DalContext, Func, FuncError, HistoryActor, Schema, SchemaError, SchemaId,
-    SchemaVariant, SchemaVariantError, SchemaVariantId, Timestamp, TransactionsError, User,
-    UserError,
+    ChangeSetError, DalContext, Func, FuncError, Schema, SchemaError, SchemaId, SchemaVariant,
+    SchemaVariantError, SchemaVariantId, Timestamp, TransactionsError, User, UserError,
 };
 
 #[remain::sorted]
@@ -585,9 +584,9 @@ impl Module {
         String,
         String,
     )> {
-        let user = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => User::get_by_pk(ctx, *user_pk).await?,
-            _ => None,
+        let user = match ctx.history_actor().user_pk() {
+            Some(user_pk) => User::get_by_pk(ctx, user_pk).await?,
+            None => None,
         };
         let (created_by_name, created_by_email) = user
             .map(|user| (user.name().to_owned(), user.email().to_owned()))
@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
+use si_pkg::{
+    FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecRuntime, SiPkgError, SpecError,
+};
 use std::collections::HashMap;
 use thiserror::Error;
 use url::ParseError;
+use veritech_client::FuncRuntime;
 
 use crate::attribute::prototype::argument::{
     AttributePrototypeArgumentError, AttributePrototypeArgumentId,
@@ -182,6 +185,24 @@ impl From<FuncSpecBackendKind> for FuncBackendKind {
     }
 }
 
+impl From<FuncRuntime> for FuncSpecRuntime {
+    fn from(value: FuncRuntime) -> Self {
+        match value {
+            FuncRuntime::LangJs => Self::LangJs,
+            FuncRuntime::Shell => Self::Shell,
+        }
+    }
+}
+
+impl From<FuncSpecRuntime> for FuncRuntime {
+    fn from(value: FuncSpecRuntime) -> Self {
+        match value {
+            FuncSpecRuntime::LangJs => Self::LangJs,
+            FuncSpecRuntime::Shell => Self::Shell,
+        }
+    }
+}
+
 impl From<FuncBackendResponseType> for FuncSpecBackendResponseType {
     fn from(value: FuncBackendResponseType) -> Self {
         match value {
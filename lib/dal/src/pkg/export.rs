@@ -906,6 +906,7 @@ impl PkgExporter {
         data_builder.backend_kind(func.backend_kind);
 
         data_builder.hidden(func.hidden);
+        data_builder.runtime(func.runtime);
 
         func_spec_builder.data(data_builder.build()?);
         func_spec_builder.unique_id(func.id.to_string());
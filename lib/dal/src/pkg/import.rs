@@ -319,6 +319,12 @@ async fn create_func(
         Some(func_spec_data.code_base64().to_owned()),
     )
     .await?;
+    let func = if func_spec_data.runtime().is_default() {
+        func
+    } else {
+        func.set_runtime(ctx, func_spec_data.runtime().into())
+            .await?
+    };
 
     Ok(func)
 }
@@ -342,6 +348,7 @@ async fn update_func(
             func.handler = Some(func_spec_data.handler().to_owned());
             func.hidden = func_spec_data.hidden();
             func.link = func_spec_data.link().map(|l| l.to_string());
+            func.runtime = func_spec_data.runtime().into();
 
             Ok(())
         })
@@ -0,0 +1,297 @@
+//! Per-workspace schedules for jobs which run periodically rather than in response to a change.
+//!
+//! Each [`ScheduledJob`] pairs a [`ScheduledJobKind`] with a [cron expression](cron) and is stored
+//! in Postgres, at most one per kind for each workspace. Pinga's scheduler periodically calls
+//! [`ScheduledJob::enqueue_due`], which enqueues the job for every schedule that is due and works
+//! out when it should next run.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    job::definition::RefreshResourcesJob, AccessBuilder, ChangeSetId, DalContext, HistoryActor,
+    Tenancy, TransactionsError, Visibility, WorkspacePk,
+};
+
+pub mod cron;
+pub mod drift;
+
+pub use cron::CronSchedule;
+pub use si_id::ScheduledJobId;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ScheduleError {
+    #[error("invalid schedule {0:?}: {1}")]
+    InvalidSchedule(String, String),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("schedule {0:?} never fires")]
+    ScheduleNeverFires(String),
+    #[error("scheduled job not found in the current workspace: {0}")]
+    ScheduledJobNotFound(ScheduledJobId),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ScheduleResult<T> = Result<T, ScheduleError>;
+
+/// The work a [`ScheduledJob`] enqueues when it is due.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum ScheduledJobKind {
+    /// Enqueues a refresh action on HEAD for every component with a resource.
+    ResourceRefresh,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledJob {
+    pub id: ScheduledJobId,
+    pub workspace_pk: WorkspacePk,
+    pub kind: ScheduledJobKind,
+    pub schedule: String,
+    pub enabled: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ScheduledJob {
+    type Error = ScheduleError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let kind: String = row.try_get("kind")?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            kind: ScheduledJobKind::from_str(&kind)?,
+            schedule: row.try_get("schedule")?,
+            enabled: row.try_get("enabled")?,
+            next_run_at: row.try_get("next_run_at")?,
+            last_run_at: row.try_get("last_run_at")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl ScheduledJob {
+    /// Creates the schedule for the given kind of job in the current workspace, or replaces the
+    /// schedule if one already exists.
+    pub async fn upsert(
+        ctx: &DalContext,
+        kind: ScheduledJobKind,
+        schedule: impl AsRef<str>,
+    ) -> ScheduleResult<Self> {
+        let schedule = schedule.as_ref().trim();
+        let next_run_at = next_run_at(schedule, Utc::now())?;
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO scheduled_jobs (workspace_pk, kind, schedule, next_run_at)
+                    VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (workspace_pk, kind) DO
+                    UPDATE SET schedule = $3, next_run_at = $4, updated_at = CLOCK_TIMESTAMP()
+                 RETURNING *",
+                &[&workspace_pk, &kind.as_ref(), &schedule, &next_run_at],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn get_by_id(ctx: &DalContext, id: ScheduledJobId) -> ScheduleResult<Option<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM scheduled_jobs WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    pub async fn find_by_kind(
+        ctx: &DalContext,
+        kind: ScheduledJobKind,
+    ) -> ScheduleResult<Option<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM scheduled_jobs WHERE workspace_pk = $1 AND kind = $2",
+                &[&workspace_pk, &kind.as_ref()],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> ScheduleResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM scheduled_jobs WHERE workspace_pk = $1 ORDER BY kind",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Pauses or resumes the schedule. Resuming it picks up from now, rather than running the
+    /// job to catch up on the runs missed while it was paused.
+    pub async fn set_enabled(&mut self, ctx: &DalContext, enabled: bool) -> ScheduleResult<()> {
+        let next_run_at = if enabled && !self.enabled {
+            next_run_at(&self.schedule, Utc::now())?
+        } else {
+            self.next_run_at
+        };
+
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE scheduled_jobs
+                    SET enabled = $3, next_run_at = $4, updated_at = CLOCK_TIMESTAMP()
+                 WHERE id = $1 AND workspace_pk = $2
+                 RETURNING *",
+                &[&self.id, &workspace_pk, &enabled, &next_run_at],
+            )
+            .await?
+            .ok_or(ScheduleError::ScheduledJobNotFound(self.id))?;
+        *self = Self::try_from(row)?;
+
+        Ok(())
+    }
+
+    pub async fn delete(self, ctx: &DalContext) -> ScheduleResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_opt(
+                "DELETE FROM scheduled_jobs WHERE id = $1 AND workspace_pk = $2 RETURNING id",
+                &[&self.id, &workspace_pk],
+            )
+            .await?
+            .ok_or(ScheduleError::ScheduledJobNotFound(self.id))?;
+
+        Ok(())
+    }
+
+    /// Enqueues the job for every enabled schedule, across all workspaces, which is due at the
+    /// given time, returning how many were enqueued. The jobs are sent when the context commits.
+    ///
+    /// Due schedules are locked until then, so concurrent schedulers skip them rather than
+    /// enqueueing the same job twice. If a scheduler was down for a while, each schedule runs once
+    /// rather than once per missed run.
+    #[instrument(name = "scheduled_job.enqueue_due", level = "info", skip(ctx))]
+    pub async fn enqueue_due(ctx: &DalContext, now: DateTime<Utc>) -> ScheduleResult<usize> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT scheduled_jobs.*, workspaces.default_change_set_id
+                 FROM scheduled_jobs
+                 JOIN workspaces ON workspaces.pk = scheduled_jobs.workspace_pk
+                 WHERE scheduled_jobs.enabled AND scheduled_jobs.next_run_at <= $1
+                 FOR UPDATE OF scheduled_jobs SKIP LOCKED",
+                &[&now],
+            )
+            .await?;
+
+        let mut enqueued = 0;
+        for row in rows {
+            let head_change_set_id: ChangeSetId = row.try_get("default_change_set_id")?;
+            let scheduled_job = Self::try_from(row)?;
+
+            // A schedule which can't be evaluated any more shouldn't stay due forever, so it is
+            // paused until someone fixes it
+            let (enabled, next_run_at) = match next_run_at(&scheduled_job.schedule, now) {
+                Ok(next_run_at) => (true, next_run_at),
+                Err(err) => {
+                    warn!(
+                        si.error.message = ?err,
+                        si.workspace.id = %scheduled_job.workspace_pk,
+                        "disabling scheduled job with an invalid schedule",
+                    );
+                    (false, scheduled_job.next_run_at)
+                }
+            };
+
+            ctx.txns()
+                .await?
+                .pg()
+                .query_none(
+                    "UPDATE scheduled_jobs
+                        SET enabled = $2, next_run_at = $3, last_run_at = $4,
+                            updated_at = CLOCK_TIMESTAMP()
+                     WHERE id = $1",
+                    &[&scheduled_job.id, &enabled, &next_run_at, &now],
+                )
+                .await?;
+
+            if !enabled {
+                continue;
+            }
+
+            let access_builder = AccessBuilder::new(
+                Tenancy::new(scheduled_job.workspace_pk),
+                HistoryActor::SystemInit,
+            );
+            let visibility = Visibility::new(head_change_set_id);
+            let job = match scheduled_job.kind {
+                ScheduledJobKind::ResourceRefresh => {
+                    RefreshResourcesJob::new(access_builder, visibility)
+                }
+            };
+            ctx.txns().await?.job_queue().enqueue_job(job).await;
+            enqueued += 1;
+        }
+
+        Ok(enqueued)
+    }
+}
+
+fn next_run_at(schedule: &str, after: DateTime<Utc>) -> ScheduleResult<DateTime<Utc>> {
+    CronSchedule::parse(schedule)?
+        .next_after(after)
+        .ok_or_else(|| ScheduleError::ScheduleNeverFires(schedule.to_owned()))
+}
@@ -0,0 +1,297 @@
+//! A minimal parser and evaluator for five field cron expressions.
+//!
+//! Expressions are made up of `minute hour day-of-month month day-of-week` fields, each of which
+//! may be a `*`, a number, a range (`a-b`), a step (`*/n`, `a/n` or `a-b/n`) or a comma separated
+//! list of those. Day-of-week runs from `0` (Sunday) to `6`, with `7` accepted as Sunday too. The
+//! `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` shorthands are also accepted.
+//!
+//! As with cron, if both the day-of-month and day-of-week fields are restricted, a day matches if
+//! _either_ of them does. All times are in UTC.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+
+use super::{ScheduleError, ScheduleResult};
+
+/// How far ahead [`CronSchedule::next_after`] looks before giving up. Five years covers every leap
+/// year combination, so any schedule that never fires in that window never fires at all.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parses a cron expression.
+    pub fn parse(expression: &str) -> ScheduleResult<Self> {
+        let invalid =
+            |reason: String| ScheduleError::InvalidSchedule(expression.to_owned(), reason);
+
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        };
+
+        // Sunday can be written as either 0 or 7, so fold the latter onto the former
+        let mut days_of_week = parse_field(day_of_week, 0, 7).map_err(invalid)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23).map_err(invalid)?,
+            days_of_month: parse_field(day_of_month, 1, 31).map_err(invalid)?,
+            months: parse_field(month, 1, 12).map_err(invalid)?,
+            days_of_week,
+            days_of_month_restricted: day_of_month != "*",
+            days_of_week_restricted: day_of_week != "*",
+        })
+    }
+
+    /// Returns the first time strictly after the given one at which the schedule fires, or
+    /// `None` if it never does (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = next + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        while next < limit {
+            if !contains(self.months, next.month()) {
+                let (year, month) = match next.month() {
+                    12 => (next.year() + 1, 1),
+                    month => (next.year(), month + 1),
+                };
+                next = midnight(NaiveDate::from_ymd_opt(year, month, 1)?)?;
+            } else if !self.day_matches(next) {
+                next = midnight(next.date_naive().succ_opt()?)?;
+            } else if !contains(self.hours, next.hour()) {
+                next = next.with_minute(0)? + Duration::hours(1);
+            } else if !contains(self.minutes, next.minute()) {
+                next += Duration::minutes(1);
+            } else {
+                return Some(next);
+            }
+        }
+
+        None
+    }
+
+    fn day_matches(&self, at: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, at.day());
+        let day_of_week = contains(self.days_of_week, at.weekday().num_days_from_sunday());
+
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+/// Parses a single field into a bit set of the values it matches.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut set = 0;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step {step:?} in {field:?}"))?;
+                if step == 0 {
+                    return Err(format!("step cannot be zero in {field:?}"));
+                }
+                (range, Some(step))
+            }
+            None => (item, None),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (parse_value(start, min, max)?, parse_value(end, min, max)?),
+                // A single value with a step runs to the end of the field's range, as in cron
+                None => {
+                    let value = parse_value(range, min, max)?;
+                    (value, if step.is_some() { max } else { value })
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("range {range:?} is backwards"));
+        }
+
+        // Steps can be as large as a u32, so stop rather than overflow past the end
+        let step = step.unwrap_or(1);
+        let mut value = Some(start);
+        while let Some(current) = value.filter(|value| *value <= end) {
+            set |= 1 << current;
+            value = current.checked_add(step);
+        }
+    }
+
+    Ok(set)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    match value.parse() {
+        Ok(parsed) if (min..=max).contains(&parsed) => Ok(parsed),
+        _ => Err(format!("{value:?} is not between {min} and {max}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date_time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date_time)
+            .expect("valid timestamp")
+            .with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expression)
+            .expect("valid expression")
+            .next_after(at(after))
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "expected {expression:?} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn every_minute_is_strictly_after() {
+        assert_eq!(
+            Some(at("2024-01-01T00:01:00Z")),
+            next("* * * * *", "2024-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-01T00:01:00Z")),
+            next("* * * * *", "2024-01-01T00:00:59.5Z")
+        );
+    }
+
+    #[test]
+    fn steps_and_lists() {
+        assert_eq!(
+            Some(at("2024-01-01T10:15:00Z")),
+            next("*/15 * * * *", "2024-01-01T10:05:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-01T11:00:00Z")),
+            next("*/15 * * * *", "2024-01-01T10:45:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-01T12:00:00Z")),
+            next("0 6,12,18 * * *", "2024-01-01T06:00:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-01T09:40:00Z")),
+            next("10-50/15 9 * * *", "2024-01-01T09:30:00Z")
+        );
+    }
+
+    #[test]
+    fn steps_past_the_end_only_hit_the_start() {
+        assert_eq!(
+            Some(at("2024-01-01T11:00:00Z")),
+            next("0/4294967295 * * * *", "2024-01-01T10:05:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-01T10:59:00Z")),
+            next("59/4294967295 * * * *", "2024-01-01T10:05:00Z")
+        );
+    }
+
+    #[test]
+    fn rolls_over_days_months_and_years() {
+        assert_eq!(
+            Some(at("2024-01-02T02:30:00Z")),
+            next("30 2 * * *", "2024-01-01T02:30:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-02-01T00:00:00Z")),
+            next("@monthly", "2024-01-15T12:00:00Z")
+        );
+        assert_eq!(
+            Some(at("2025-01-01T00:00:00Z")),
+            next("@yearly", "2024-12-31T23:59:00Z")
+        );
+        assert_eq!(
+            Some(at("2028-02-29T00:00:00Z")),
+            next("0 0 29 2 *", "2024-03-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // 2024-01-01 is a Monday, so the next Sunday is the 7th
+        assert_eq!(
+            Some(at("2024-01-07T00:00:00Z")),
+            next("0 0 * * 0", "2024-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-07T00:00:00Z")),
+            next("0 0 * * 7", "2024-01-01T00:00:00Z")
+        );
+        // With both restricted, either the 15th or any Friday matches
+        assert_eq!(
+            Some(at("2024-01-05T00:00:00Z")),
+            next("0 0 15 * 5", "2024-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            Some(at("2024-01-15T00:00:00Z")),
+            next("0 0 15 * 5", "2024-01-12T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn never_fires() {
+        assert_eq!(None, next("0 0 30 2 *", "2024-01-01T00:00:00Z"));
+    }
+}
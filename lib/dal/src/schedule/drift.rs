@@ -0,0 +1,166 @@
+//! Detecting resources that have drifted from what was last synced.
+//!
+//! When a refresh action returns a resource payload that differs from the one stored on the
+//! component, the paths that changed are reported with a [`ResourceDriftDetected`] event so that
+//! users can see what changed outside of System Initiative.
+//!
+//! [`ResourceDriftDetected`]: crate::WsPayload::ResourceDriftDetected
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    action::ActionId, ChangeSetId, ComponentId, DalContext, WsEvent, WsEventResult, WsPayload,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDriftDetectedPayload {
+    pub component_id: ComponentId,
+    pub action_id: ActionId,
+    /// JSON pointers to every value in the resource payload that was added, removed or changed.
+    pub changed_paths: Vec<String>,
+    /// When the previous payload was synced.
+    pub last_synced: DateTime<Utc>,
+    pub change_set_id: ChangeSetId,
+}
+
+impl WsEvent {
+    pub async fn resource_drift_detected(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        action_id: ActionId,
+        changed_paths: Vec<String>,
+        last_synced: DateTime<Utc>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ResourceDriftDetected(ResourceDriftDetectedPayload {
+                component_id,
+                action_id,
+                changed_paths,
+                last_synced,
+                change_set_id: ctx.change_set_id(),
+            }),
+        )
+        .await
+    }
+}
+
+/// Returns [JSON pointers](https://datatracker.ietf.org/doc/html/rfc6901) to every value that
+/// differs between two resource payloads, sorted.
+///
+/// Objects are compared key by key and arrays index by index; any other difference (including a
+/// change of type) reports the path of the differing value itself.
+pub fn changed_paths(previous: &Value, current: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_changed_paths(previous, current, &mut String::new(), &mut paths);
+    paths.sort();
+    paths
+}
+
+fn collect_changed_paths(
+    previous: &Value,
+    current: &Value,
+    path: &mut String,
+    paths: &mut Vec<String>,
+) {
+    match (previous, current) {
+        (Value::Object(previous), Value::Object(current)) => {
+            for (key, previous_value) in previous {
+                match current.get(key) {
+                    Some(current_value) => with_segment(path, key, |path| {
+                        collect_changed_paths(previous_value, current_value, path, paths)
+                    }),
+                    None => with_segment(path, key, |path| paths.push(path.clone())),
+                }
+            }
+            for key in current.keys().filter(|key| !previous.contains_key(*key)) {
+                with_segment(path, key, |path| paths.push(path.clone()));
+            }
+        }
+        (Value::Array(previous), Value::Array(current)) => {
+            for index in 0..previous.len().max(current.len()) {
+                let segment = index.to_string();
+                match (previous.get(index), current.get(index)) {
+                    (Some(previous_value), Some(current_value)) => {
+                        with_segment(path, &segment, |path| {
+                            collect_changed_paths(previous_value, current_value, path, paths)
+                        });
+                    }
+                    _ => with_segment(path, &segment, |path| paths.push(path.clone())),
+                }
+            }
+        }
+        (previous, current) => {
+            if previous != current {
+                paths.push(path.clone());
+            }
+        }
+    }
+}
+
+fn with_segment(path: &mut String, segment: &str, f: impl FnOnce(&mut String)) {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    f(path);
+    path.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn identical_payloads_have_no_changes() {
+        let payload = json!({ "name": "poop", "tags": ["a", "b"], "nested": { "size": 3 } });
+        assert!(changed_paths(&payload, &payload).is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_values() {
+        let previous = json!({
+            "name": "poop",
+            "removed": true,
+            "nested": { "size": 3, "same": "yes" },
+            "tags": ["a", "b", "c"],
+        });
+        let current = json!({
+            "name": "canoe",
+            "added": null,
+            "nested": { "size": 4, "same": "yes" },
+            "tags": ["a", "z"],
+        });
+
+        assert_eq!(
+            vec![
+                "/added",
+                "/name",
+                "/nested/size",
+                "/removed",
+                "/tags/1",
+                "/tags/2",
+            ],
+            changed_paths(&previous, &current)
+        );
+    }
+
+    #[test]
+    fn type_changes_and_escaping() {
+        assert_eq!(
+            vec![""],
+            changed_paths(&json!({ "a": 1 }), &json!(["a", 1]))
+        );
+        assert_eq!(
+            vec!["/a~1b/c~0d"],
+            changed_paths(
+                &json!({ "a/b": { "c~d": 1 } }),
+                &json!({ "a/b": { "c~d": 2 } })
+            )
+        );
+    }
+}
@@ -63,7 +63,7 @@ use crate::workspace_snapshot::node_weight::{NodeWeight, NodeWeightError};
 use crate::workspace_snapshot::WorkspaceSnapshotError;
 use crate::{
     implement_add_edge_to, AttributePrototype, AttributeValue, AttributeValueId, ChangeSetError,
-    ComponentError, ComponentId, DalContext, Func, FuncError, FuncId, HelperError, HistoryActor,
+    ComponentError, ComponentId, DalContext, Func, FuncError, FuncId, HelperError,
     HistoryEventError, KeyPair, KeyPairError, Prop, SchemaVariant, SchemaVariantError,
     StandardModelError, Timestamp, TransactionsError, UserPk,
 };
@@ -72,6 +72,7 @@ use si_events::encrypted_secret::EncryptedSecretKeyParseError;
 mod algorithm;
 mod definition_view;
 mod event;
+mod rotation;
 mod view;
 
 pub use algorithm::SecretAlgorithm;
@@ -81,6 +82,8 @@ pub use definition_view::SecretDefinitionViewError;
 pub use event::SecretCreatedPayload;
 pub use event::SecretDeletedPayload;
 pub use event::SecretUpdatedPayload;
+pub use rotation::KeyPairRotation;
+pub use veritech_client::SecretReference;
 pub use view::SecretView;
 pub use view::SecretViewError;
 pub use view::SecretViewResult;
@@ -121,6 +124,8 @@ pub enum SecretError {
     KeyPair(#[from] KeyPairError),
     #[error("key pair not found for secret")]
     KeyPairNotFound,
+    #[error("key pair {0} cannot be retired while secrets need it or are undecryptable: {1:?}")]
+    KeyPairRetirementBlocked(KeyPairPk, Vec<SecretId>),
     #[error("layer db error: {0}")]
     LayerDb(#[from] LayerDbError),
     #[error("node weight error: {0}")]
@@ -217,10 +222,7 @@ impl Secret {
         version: SecretVersion,
         algorithm: SecretAlgorithm,
     ) -> SecretResult<Self> {
-        let user = match ctx.history_actor() {
-            HistoryActor::SystemInit => None,
-            HistoryActor::User(user_pk) => Some(*user_pk),
-        };
+        let user = ctx.history_actor().user_pk();
 
         let id = ctx.workspace_snapshot()?.generate_ulid().await?;
         let lineage_id = ctx.workspace_snapshot()?.generate_ulid().await?;
@@ -272,6 +274,38 @@ impl Secret {
         Ok(secret)
     }
 
+    /// Creates a new [`Secret`] whose contents are a [`SecretReference`] to a value held by an
+    /// external provider, rather than the value itself. The reference is sealed to the current
+    /// [`KeyPair`] like any other contents.
+    ///
+    /// Veritech resolves the reference right before running the before functions which use the
+    /// secret, so the value is never stored by System Initiative.
+    pub async fn new_reference(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        definition: impl Into<String>,
+        description: Option<String>,
+        reference: &SecretReference,
+    ) -> SecretResult<Self> {
+        let key_pair = KeyPair::get_current(ctx).await?;
+        let crypted = sealedbox::seal(
+            &serde_json::to_vec(&reference.to_value())?,
+            key_pair.public_key(),
+        );
+
+        Self::new(
+            ctx,
+            name,
+            definition,
+            description,
+            &crypted,
+            key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+    }
+
     /// Generates a key based on the [`Tenancy`](crate::Tenancy), [`SecretId`] and a newly generated
     /// [`Ulid`].
     ///
@@ -704,11 +738,8 @@ impl Secret {
         let before = SecretContentV1::from(secret.clone());
         lambda(&mut secret)?;
         if before != SecretContentV1::from(secret.clone()) {
-            match ctx.history_actor() {
-                HistoryActor::SystemInit => {}
-                HistoryActor::User(id) => {
-                    secret.updated_by = Some(*id);
-                }
+            if let Some(user_pk) = ctx.history_actor().user_pk() {
+                secret.updated_by = Some(user_pk);
             }
             secret.timestamp.updated_at = Utc::now();
         }
@@ -784,10 +815,7 @@ impl EncryptedSecret {
         version: SecretVersion,
         algorithm: SecretAlgorithm,
     ) -> SecretResult<()> {
-        let user = match ctx.history_actor() {
-            HistoryActor::SystemInit => None,
-            HistoryActor::User(user_pk) => Some(*user_pk),
-        };
+        let user = ctx.history_actor().user_pk();
 
         let (double_crypted, nonce, key_hash) = ctx.symmetric_crypto_service().encrypt(crypted);
 
@@ -858,6 +886,11 @@ impl EncryptedSecret {
         }
     }
 
+    /// Returns the [`KeyPairPk`] of the [`KeyPair`] that the contents are sealed to.
+    pub fn key_pair_pk(&self) -> KeyPairPk {
+        self.key_pair_pk
+    }
+
     /// Gets the [`KeyPair`] corresponding to the [`KeyPairPk`] on the [`EncryptedSecret`].
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
@@ -880,6 +913,12 @@ impl DecryptedSecret {
     pub(crate) fn message(&self) -> SensitiveContainer<Value> {
         self.message.clone().into()
     }
+
+    /// Returns the [`SecretReference`] if the secret refers to a value held by an external
+    /// provider, rather than holding the value itself.
+    pub fn secret_reference(&self) -> Option<SecretReference> {
+        SecretReference::from_value(&self.message).map(|(reference, _)| reference)
+    }
 }
 
 impl fmt::Debug for DecryptedSecret {
@@ -0,0 +1,154 @@
+//! Rotating the [`KeyPair`] that a workspace's [`Secrets`](Secret) are sealed to.
+//!
+//! Rotation happens in two steps:
+//!
+//! 1. [`Secret::rotate_key_pair`] creates a new current [`KeyPair`] and re-seals every secret in
+//!    the change set to it, via [`Secret::update_encrypted_contents`].
+//! 2. Once that change set has been applied, [`Secret::retire_key_pair`] is called on HEAD for
+//!    each of the previous key pairs. It only retires a key pair when every secret can be
+//!    decrypted and none of them are still sealed to it.
+//!
+//! Secrets in other open change sets are not re-sealed. If they are still sealed to a key pair
+//! when it is retired, they can no longer be decrypted and have to be re-entered.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use sodiumoxide::crypto::sealedbox;
+use telemetry::prelude::*;
+
+use super::{EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretResult, SecretVersion};
+use crate::{key_pair::KeyPairPk, DalContext, KeyPair, SecretId};
+
+/// The outcome of [`Secret::rotate_key_pair`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyPairRotation {
+    /// The new current key pair.
+    pub key_pair_pk: KeyPairPk,
+    /// The key pairs that secrets were sealed to before the rotation. They can be retired once
+    /// the rotation has been applied.
+    pub previous_key_pair_pks: Vec<KeyPairPk>,
+    /// The secrets that were re-sealed to the new key pair.
+    pub resealed: Vec<SecretId>,
+    /// The secrets that could not be decrypted, and so could not be re-sealed.
+    pub undecryptable: Vec<SecretId>,
+}
+
+impl Secret {
+    /// Returns the [`KeyPairPk`] that the [`Secret`] is sealed to, or `None` if its
+    /// [`EncryptedSecret`] can't be found.
+    pub async fn key_pair_pk(&self, ctx: &DalContext) -> SecretResult<Option<KeyPairPk>> {
+        Ok(EncryptedSecret::get_by_key(ctx, self.encrypted_secret_key)
+            .await?
+            .map(|encrypted_secret| encrypted_secret.key_pair_pk()))
+    }
+
+    /// Lists the [`Secrets`](Secret) in the current snapshot by the [`KeyPair`] they are sealed
+    /// to. Secrets without an [`EncryptedSecret`] are not included.
+    pub async fn list_by_key_pair(
+        ctx: &DalContext,
+    ) -> SecretResult<BTreeMap<KeyPairPk, Vec<SecretId>>> {
+        let mut secrets_by_key_pair: BTreeMap<KeyPairPk, Vec<SecretId>> = BTreeMap::new();
+        for secret in Self::list(ctx).await? {
+            if let Some(key_pair_pk) = secret.key_pair_pk(ctx).await? {
+                secrets_by_key_pair
+                    .entry(key_pair_pk)
+                    .or_default()
+                    .push(secret.id);
+            }
+        }
+
+        Ok(secrets_by_key_pair)
+    }
+
+    /// Decrypts the [`Secret`] and seals its contents to the given [`KeyPair`], keeping its
+    /// version and algorithm. Does nothing if it is already sealed to that key pair.
+    pub async fn reseal(self, ctx: &DalContext, key_pair: &KeyPair) -> SecretResult<Self> {
+        let encrypted_secret = EncryptedSecret::get_by_key(ctx, self.encrypted_secret_key)
+            .await?
+            .ok_or(SecretError::EncryptedSecretNotFound(
+                self.encrypted_secret_key,
+            ))?;
+        if encrypted_secret.key_pair_pk == key_pair.pk() {
+            return Ok(self);
+        }
+
+        let (version, algorithm) = (encrypted_secret.version, encrypted_secret.algorithm);
+        let decrypted = encrypted_secret.decrypt(ctx).await?;
+
+        // Explicitly match on (version, algorithm) tuple to ensure that any new
+        // versions/algorithms will trigger a compilation failure
+        let crypted = match (version, algorithm) {
+            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => sealedbox::seal(
+                &serde_json::to_vec(&decrypted.message)?,
+                key_pair.public_key(),
+            ),
+        };
+
+        self.update_encrypted_contents(ctx, &crypted, key_pair.pk(), version, algorithm)
+            .await
+    }
+
+    /// Creates a new current [`KeyPair`] for the workspace and re-seals every [`Secret`] in the
+    /// change set to it. See the [module docs](self) for how to finish the rotation.
+    #[instrument(name = "secret.rotate_key_pair", level = "info", skip_all)]
+    pub async fn rotate_key_pair(ctx: &DalContext) -> SecretResult<KeyPairRotation> {
+        let key_pair = KeyPair::rotate(ctx).await?;
+
+        let mut previous_key_pair_pks = BTreeSet::new();
+        let mut resealed = Vec::new();
+        let mut undecryptable = Vec::new();
+        for secret in Self::list(ctx).await? {
+            let secret_id = secret.id;
+            if !secret.can_be_decrypted(ctx).await? {
+                warn!(si.secret.id = %secret_id, "skipping secret which cannot be decrypted");
+                undecryptable.push(secret_id);
+                continue;
+            }
+            if let Some(key_pair_pk) = secret.key_pair_pk(ctx).await? {
+                previous_key_pair_pks.insert(key_pair_pk);
+            }
+
+            secret.reseal(ctx, &key_pair).await?;
+            resealed.push(secret_id);
+        }
+
+        Ok(KeyPairRotation {
+            key_pair_pk: key_pair.pk(),
+            previous_key_pair_pks: previous_key_pair_pks.into_iter().collect(),
+            resealed,
+            undecryptable,
+        })
+    }
+
+    /// Retires the given [`KeyPair`], provided that every [`Secret`] in the current snapshot can
+    /// be decrypted and none of them are sealed to it.
+    #[instrument(
+        name = "secret.retire_key_pair",
+        level = "info",
+        skip_all,
+        fields(si.key_pair.pk = %key_pair_pk)
+    )]
+    pub async fn retire_key_pair(ctx: &DalContext, key_pair_pk: KeyPairPk) -> SecretResult<()> {
+        // Look the key pair up first so that key pairs from other workspaces are refused before
+        // checking this workspace's secrets.
+        let key_pair = KeyPair::get_by_pk(ctx, key_pair_pk).await?;
+
+        let mut blocking = Vec::new();
+        for secret in Self::list(ctx).await? {
+            if !secret.can_be_decrypted(ctx).await?
+                || secret.key_pair_pk(ctx).await? == Some(key_pair_pk)
+            {
+                blocking.push(secret.id);
+            }
+        }
+        if !blocking.is_empty() {
+            return Err(SecretError::KeyPairRetirementBlocked(key_pair_pk, blocking));
+        }
+
+        key_pair.retire(ctx).await?;
+
+        Ok(())
+    }
+}
@@ -25,9 +25,9 @@ use crate::workspace_integrations::{WorkspaceIntegration, WorkspaceIntegrationsE
 use crate::workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants;
 use crate::workspace_snapshot::WorkspaceSnapshotError;
 use crate::{
-    standard_model, standard_model_accessor_ro, BuiltinsError, DalContext, HistoryActor,
-    HistoryEvent, HistoryEventError, KeyPairError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, User, UserError, UserPk, WorkspaceSnapshot, WorkspaceSnapshotGraph,
+    standard_model, standard_model_accessor_ro, BuiltinsError, DalContext, HistoryEvent,
+    HistoryEventError, KeyPairError, StandardModelError, Tenancy, Timestamp, TransactionsError,
+    User, UserError, UserPk, WorkspaceSnapshot, WorkspaceSnapshotGraph,
 };
 
 pub use si_id::WorkspaceId;
@@ -265,10 +265,10 @@ impl Workspace {
     }
 
     pub async fn list_for_user(ctx: &DalContext) -> WorkspaceResult<Vec<Self>> {
-        let user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => *user_pk,
-            _ => return Err(WorkspaceError::NoUserInContext),
-        };
+        let user_pk = ctx
+            .history_actor()
+            .user_pk()
+            .ok_or(WorkspaceError::NoUserInContext)?;
         let rows = ctx
             .txns()
             .await?
@@ -541,10 +541,10 @@ impl Workspace {
 
         let (content_store_values, _) = serialize::to_vec(&store_values_map)?;
 
-        let created_by = if let HistoryActor::User(user_pk) = ctx.history_actor() {
-            let user = User::get_by_pk(ctx, *user_pk)
+        let created_by = if let Some(user_pk) = ctx.history_actor().user_pk() {
+            let user = User::get_by_pk(ctx, user_pk)
                 .await?
-                .ok_or(WorkspaceError::InvalidUser(*user_pk))?;
+                .ok_or(WorkspaceError::InvalidUser(user_pk))?;
 
             user.email().clone()
         } else {
@@ -27,6 +27,7 @@
 pub mod content_address;
 pub mod edge_weight;
 pub mod graph;
+pub mod integrity;
 pub mod lamport_clock;
 pub mod migrator;
 pub mod node_weight;
@@ -48,7 +49,7 @@ use petgraph::prelude::*;
 pub use petgraph::Direction;
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
-use si_events::{ulid::Ulid, ContentHash, WorkspaceSnapshotAddress};
+use si_events::{ulid::Ulid, Conflict, ContentHash, WorkspaceSnapshotAddress};
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -125,6 +126,8 @@ pub enum WorkspaceSnapshotError {
     InferredConnectionGraph(#[from] Box<InferredConnectionGraphError>),
     #[error("InputSocket error: {0}")]
     InputSocket(#[from] Box<InputSocketError>),
+    #[error("integrity violations after performing updates: {0:?}")]
+    IntegrityViolations(Vec<integrity::IntegrityViolation>),
     #[error("join error: {0}")]
     Join(#[from] JoinError),
     #[error("layer db error: {0}")]
@@ -735,6 +738,46 @@ impl WorkspaceSnapshot {
         .await?)
     }
 
+    /// Detects the [`Conflicts`](Conflict) between a change set and its base change set, where
+    /// `self` is the merge base of the two.
+    #[instrument(
+        name = "workspace_snapshot.detect_conflicts",
+        level = "debug",
+        skip_all,
+        fields()
+    )]
+    pub async fn detect_conflicts(
+        &self,
+        base_workspace_snapshot: &WorkspaceSnapshot,
+        change_set_workspace_snapshot: &WorkspaceSnapshot,
+    ) -> WorkspaceSnapshotResult<Vec<Conflict>> {
+        let self_clone = self.clone();
+        let base_clone = base_workspace_snapshot.clone();
+        let change_set_clone = change_set_workspace_snapshot.clone();
+
+        Ok(slow_rt::spawn(async move {
+            self_clone.working_copy().await.detect_conflicts(
+                &*base_clone.working_copy().await,
+                &*change_set_clone.working_copy().await,
+            )
+        })?
+        .await?)
+    }
+
+    /// Calculates the [`Updates`](Update) which make `self` agree with `source_workspace_snapshot`
+    /// about what the [`Conflict`] is over.
+    pub async fn updates_to_match(
+        &self,
+        source_workspace_snapshot: &WorkspaceSnapshot,
+        conflict: &Conflict,
+    ) -> WorkspaceSnapshotResult<Vec<Update>> {
+        Ok(graph::detect_conflicts::updates_to_match(
+            &*self.working_copy().await,
+            &*source_workspace_snapshot.working_copy().await,
+            conflict,
+        ))
+    }
+
     /// Gives the exact node index endpoints of an edge.
     pub async fn edge_endpoints(
         &self,
@@ -1280,13 +1323,17 @@ impl WorkspaceSnapshot {
     pub async fn perform_updates(&self, updates: &[Update]) -> WorkspaceSnapshotResult<()> {
         let self_clone = self.clone();
         let updates = updates.to_vec();
-        Ok(slow_rt::spawn(async move {
+        slow_rt::spawn(async move {
             self_clone
                 .working_copy_mut()
                 .await
                 .perform_updates(&updates)
         })?
-        .await??)
+        .await??;
+
+        self.check_integrity_after_updates().await?;
+
+        Ok(())
     }
 
     /// Mark whether a prop can be used as an input to a function. Props below
@@ -0,0 +1,315 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use petgraph::{prelude::*, stable_graph::EdgeReference};
+use si_events::{ulid::Ulid, Conflict, ContentHash};
+
+use crate::{
+    workspace_snapshot::{node_weight::NodeWeight, NodeInformation},
+    EdgeWeight, EdgeWeightKindDiscriminants,
+};
+
+use super::{detect_updates::Update, WorkspaceSnapshotGraphVCurrent};
+
+/// Detects [`Conflicts`](Conflict) between a change set and its base change set, by comparing
+/// the changes each made since their merge base.
+pub struct ConflictDetector<'a> {
+    merge_base_graph: &'a WorkspaceSnapshotGraphVCurrent,
+    base_graph: &'a WorkspaceSnapshotGraphVCurrent,
+    change_set_graph: &'a WorkspaceSnapshotGraphVCurrent,
+}
+
+/// The changes made on one side of the comparison, relative to the merge base.
+struct Changes<'a> {
+    graph: &'a WorkspaceSnapshotGraphVCurrent,
+    /// The node hash of each replaced node.
+    replaced_nodes: BTreeMap<Ulid, ContentHash>,
+    /// The target of each new edge that its source can only have one of, by source and kind.
+    exclusive_edges: HashMap<(Ulid, EdgeWeightKindDiscriminants), Ulid>,
+}
+
+impl<'a> Changes<'a> {
+    fn new(
+        merge_base_graph: &WorkspaceSnapshotGraphVCurrent,
+        graph: &'a WorkspaceSnapshotGraphVCurrent,
+    ) -> Self {
+        let mut replaced_nodes = BTreeMap::new();
+        let mut exclusive_edges = HashMap::new();
+
+        for update in merge_base_graph.detect_updates(graph) {
+            match update {
+                // The order of ordering nodes is merged when their elements are, so the ordering
+                // nodes themselves never conflict
+                Update::ReplaceNode { node_weight }
+                    if !matches!(node_weight, NodeWeight::Ordering(_)) =>
+                {
+                    replaced_nodes.insert(node_weight.id(), node_weight.node_hash());
+                }
+                Update::NewEdge {
+                    source,
+                    destination,
+                    edge_weight,
+                } => {
+                    let kind = edge_weight.kind().into();
+                    let is_exclusive = node_weight_by_id(graph, source.id.into())
+                        .is_some_and(|source| source.exclusive_outgoing_edges().contains(&kind));
+                    if is_exclusive {
+                        exclusive_edges.insert((source.id.into(), kind), destination.id.into());
+                    }
+                }
+                Update::ReplaceNode { .. } | Update::RemoveEdge { .. } | Update::NewNode { .. } => {
+                }
+            }
+        }
+
+        Self {
+            graph,
+            replaced_nodes,
+            exclusive_edges,
+        }
+    }
+
+    fn contains(&self, id: Ulid) -> bool {
+        node_weight_by_id(self.graph, id).is_some()
+    }
+}
+
+impl<'a> ConflictDetector<'a> {
+    pub fn new(
+        merge_base_graph: &'a WorkspaceSnapshotGraphVCurrent,
+        base_graph: &'a WorkspaceSnapshotGraphVCurrent,
+        change_set_graph: &'a WorkspaceSnapshotGraphVCurrent,
+    ) -> Self {
+        Self {
+            merge_base_graph,
+            base_graph,
+            change_set_graph,
+        }
+    }
+
+    /// Finds the nodes and exclusive edges that both the base change set and the change set
+    /// changed, differently, since the merge base.
+    pub fn detect_conflicts(&self) -> Vec<Conflict> {
+        let base = Changes::new(self.merge_base_graph, self.base_graph);
+        let change_set = Changes::new(self.merge_base_graph, self.change_set_graph);
+
+        let mut conflicts = Vec::new();
+
+        for (&node_id, node_hash) in &change_set.replaced_nodes {
+            match base.replaced_nodes.get(&node_id) {
+                Some(base_node_hash) if base_node_hash != node_hash => {
+                    conflicts.push(Conflict::ModifiedOnBoth { node_id });
+                }
+                None if !base.contains(node_id) => {
+                    conflicts.push(Conflict::ModifiedWhatBaseRemoved { node_id });
+                }
+                _ => {}
+            }
+        }
+        for &node_id in base.replaced_nodes.keys() {
+            if !change_set.contains(node_id) {
+                conflicts.push(Conflict::RemovedWhatBaseModified { node_id });
+            }
+        }
+
+        let mut edge_conflicts: Vec<Conflict> = change_set
+            .exclusive_edges
+            .iter()
+            .filter_map(|(&(source_id, kind), &change_set_target_id)| {
+                base.exclusive_edges
+                    .get(&(source_id, kind))
+                    .filter(|&&base_target_id| base_target_id != change_set_target_id)
+                    .map(|&base_target_id| Conflict::ExclusiveEdge {
+                        source_id,
+                        edge_kind: kind.to_string(),
+                        base_target_id,
+                        change_set_target_id,
+                    })
+            })
+            .collect();
+        edge_conflicts.sort_by_key(|conflict| match conflict {
+            Conflict::ExclusiveEdge {
+                source_id,
+                edge_kind,
+                ..
+            } => (*source_id, edge_kind.clone()),
+            _ => (conflict.node_id(), String::new()),
+        });
+        conflicts.extend(edge_conflicts);
+
+        conflicts
+    }
+}
+
+/// Calculates the updates which make `target_graph` agree with `source_graph` about what the
+/// conflict is over: the node, or the exclusive edge. Nodes missing from `target_graph` are
+/// copied over along with everything beneath them that it is also missing.
+pub fn updates_to_match(
+    target_graph: &WorkspaceSnapshotGraphVCurrent,
+    source_graph: &WorkspaceSnapshotGraphVCurrent,
+    conflict: &Conflict,
+) -> Vec<Update> {
+    let mut updates = Vec::new();
+
+    match conflict {
+        Conflict::ModifiedOnBoth { node_id }
+        | Conflict::ModifiedWhatBaseRemoved { node_id }
+        | Conflict::RemovedWhatBaseModified { node_id } => {
+            match (
+                node_index_by_id(source_graph, *node_id),
+                node_index_by_id(target_graph, *node_id),
+            ) {
+                (Some(source_idx), Some(_)) => {
+                    if let Some(node_weight) = source_graph.get_node_weight_opt(source_idx) {
+                        updates.push(Update::ReplaceNode {
+                            node_weight: node_weight.to_owned(),
+                        });
+                    }
+                }
+                (Some(source_idx), None) => {
+                    updates.extend(copy_missing_subgraph(
+                        target_graph,
+                        source_graph,
+                        source_idx,
+                    ));
+                    for edge_ref in source_graph.edges_directed(source_idx, Incoming) {
+                        if let Some(parent) = source_graph.get_node_weight_opt(edge_ref.source()) {
+                            if node_weight_by_id(target_graph, parent.id()).is_some() {
+                                updates.extend(new_edge(source_graph, edge_ref));
+                            }
+                        }
+                    }
+                }
+                (None, Some(target_idx)) => {
+                    for edge_ref in target_graph.edges_directed(target_idx, Incoming) {
+                        if let (Some(parent), Some(node_weight)) = (
+                            target_graph.get_node_weight_opt(edge_ref.source()),
+                            target_graph.get_node_weight_opt(target_idx),
+                        ) {
+                            updates.push(Update::RemoveEdge {
+                                source: parent.into(),
+                                destination: node_weight.into(),
+                                edge_kind: edge_ref.weight().kind().into(),
+                            });
+                        }
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        Conflict::ExclusiveEdge {
+            source_id,
+            edge_kind,
+            base_target_id,
+            change_set_target_id,
+        } => {
+            let (Some(source_graph_source_idx), Some(target_graph_source_idx)) = (
+                node_index_by_id(source_graph, *source_id),
+                node_index_by_id(target_graph, *source_id),
+            ) else {
+                return updates;
+            };
+
+            // Whichever target the source graph has wins
+            let Some(edge_ref) = source_graph
+                .edges_directed(source_graph_source_idx, Outgoing)
+                .find(|edge_ref| {
+                    is_kind(edge_ref.weight(), edge_kind)
+                        && source_graph
+                            .node_index_to_id(edge_ref.target())
+                            .is_some_and(|id| id == *base_target_id || id == *change_set_target_id)
+                })
+            else {
+                return updates;
+            };
+            let winning_target_id = source_graph.node_index_to_id(edge_ref.target());
+
+            updates.extend(copy_missing_subgraph(
+                target_graph,
+                source_graph,
+                edge_ref.target(),
+            ));
+            for target_edge_ref in target_graph.edges_directed(target_graph_source_idx, Outgoing) {
+                if !is_kind(target_edge_ref.weight(), edge_kind) {
+                    continue;
+                }
+                if let (Some(source), Some(destination)) = (
+                    target_graph.get_node_weight_opt(target_edge_ref.source()),
+                    target_graph.get_node_weight_opt(target_edge_ref.target()),
+                ) {
+                    if Some(destination.id()) != winning_target_id {
+                        updates.push(Update::RemoveEdge {
+                            source: source.into(),
+                            destination: destination.into(),
+                            edge_kind: target_edge_ref.weight().kind().into(),
+                        });
+                    }
+                }
+            }
+            updates.extend(new_edge(source_graph, edge_ref));
+        }
+    }
+
+    updates
+}
+
+/// Copies the node, and everything beneath it, that `target_graph` is missing. Descent stops at
+/// nodes `target_graph` already has, though edges to them are still copied.
+fn copy_missing_subgraph(
+    target_graph: &WorkspaceSnapshotGraphVCurrent,
+    source_graph: &WorkspaceSnapshotGraphVCurrent,
+    root_idx: NodeIndex,
+) -> Vec<Update> {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![root_idx];
+
+    while let Some(node_idx) = stack.pop() {
+        if !seen.insert(node_idx) {
+            continue;
+        }
+        let Some(node_weight) = source_graph.get_node_weight_opt(node_idx) else {
+            continue;
+        };
+        if node_weight_by_id(target_graph, node_weight.id()).is_some() {
+            continue;
+        }
+
+        nodes.push(Update::NewNode {
+            node_weight: node_weight.to_owned(),
+        });
+        for edge_ref in source_graph.edges_directed(node_idx, Outgoing) {
+            edges.extend(new_edge(source_graph, edge_ref));
+            stack.push(edge_ref.target());
+        }
+    }
+
+    // Every node has to exist before the edges between them can be added
+    nodes.extend(edges);
+    nodes
+}
+
+fn new_edge(
+    graph: &WorkspaceSnapshotGraphVCurrent,
+    edge_ref: EdgeReference<'_, EdgeWeight>,
+) -> Option<Update> {
+    Some(Update::NewEdge {
+        source: graph.get_node_weight_opt(edge_ref.source())?.into(),
+        destination: graph.get_node_weight_opt(edge_ref.target())?.into(),
+        edge_weight: edge_ref.weight().to_owned(),
+    })
+}
+
+fn is_kind(edge_weight: &EdgeWeight, edge_kind: &str) -> bool {
+    EdgeWeightKindDiscriminants::from(edge_weight.kind()).to_string() == edge_kind
+}
+
+fn node_index_by_id(graph: &WorkspaceSnapshotGraphVCurrent, id: Ulid) -> Option<NodeIndex> {
+    graph
+        .get_node_index_by_id_opt(id)
+        .filter(|node_idx| graph.get_node_weight_opt(*node_idx).is_some())
+}
+
+fn node_weight_by_id(graph: &WorkspaceSnapshotGraphVCurrent, id: Ulid) -> Option<&NodeWeight> {
+    node_index_by_id(graph, id).and_then(|node_idx| graph.get_node_weight_opt(node_idx))
+}
@@ -7,8 +7,10 @@ use crate::{
     EdgeWeight, EdgeWeightKind, PropKind,
 };
 
+mod detect_conflicts;
 mod detect_updates;
 mod exclusive_outgoing_edges;
+mod integrity;
 mod rebase;
 
 #[allow(dead_code)]
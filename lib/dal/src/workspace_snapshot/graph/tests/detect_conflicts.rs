@@ -0,0 +1,113 @@
+#[allow(clippy::panic)]
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod test {
+    use si_events::{ulid::Ulid, Conflict, ContentHash};
+
+    use crate::{
+        workspace_snapshot::{
+            content_address::ContentAddress,
+            graph::{detect_conflicts::updates_to_match, WorkspaceSnapshotGraphResult},
+            node_weight::NodeWeight,
+        },
+        EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants, WorkspaceSnapshotGraphVCurrent,
+    };
+
+    fn add_component(
+        graph: &mut WorkspaceSnapshotGraphVCurrent,
+        name: &str,
+    ) -> WorkspaceSnapshotGraphResult<Ulid> {
+        let id = graph.generate_ulid()?;
+        let idx = graph.add_or_replace_node(NodeWeight::new_content(
+            id,
+            Ulid::new(),
+            ContentAddress::Component(ContentHash::from(name)),
+        ))?;
+        graph.add_edge(
+            graph.root(),
+            EdgeWeight::new(EdgeWeightKind::new_use()),
+            idx,
+        )?;
+        Ok(id)
+    }
+
+    #[test]
+    fn modified_on_both() -> WorkspaceSnapshotGraphResult<()> {
+        let mut merge_base_graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()?;
+        let conflicting_id = add_component(&mut merge_base_graph, "Component A")?;
+        let other_id = add_component(&mut merge_base_graph, "Component B")?;
+        merge_base_graph.cleanup_and_merkle_tree_hash()?;
+
+        let mut base_graph = merge_base_graph.clone();
+        base_graph.update_content(conflicting_id, ContentHash::from("Component A on base"))?;
+        base_graph.cleanup_and_merkle_tree_hash()?;
+
+        let mut change_set_graph = merge_base_graph.clone();
+        change_set_graph.update_content(
+            conflicting_id,
+            ContentHash::from("Component A in change set"),
+        )?;
+        change_set_graph
+            .update_content(other_id, ContentHash::from("Component B in change set"))?;
+        change_set_graph.cleanup_and_merkle_tree_hash()?;
+
+        let conflicts = merge_base_graph.detect_conflicts(&base_graph, &change_set_graph);
+        assert_eq!(
+            vec![Conflict::ModifiedOnBoth {
+                node_id: conflicting_id
+            }],
+            conflicts
+        );
+
+        // Keeping the base's side brings the change set in line with it
+        let updates = updates_to_match(&change_set_graph, &base_graph, &conflicts[0]);
+        change_set_graph.perform_updates(&updates)?;
+        change_set_graph.cleanup_and_merkle_tree_hash()?;
+
+        assert!(merge_base_graph
+            .detect_conflicts(&base_graph, &change_set_graph)
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn modified_what_base_removed() -> WorkspaceSnapshotGraphResult<()> {
+        let mut merge_base_graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()?;
+        let component_id = add_component(&mut merge_base_graph, "Component A")?;
+        merge_base_graph.cleanup_and_merkle_tree_hash()?;
+
+        let mut base_graph = merge_base_graph.clone();
+        let component_idx = base_graph.get_node_index_by_id(component_id)?;
+        base_graph.remove_edge(
+            base_graph.root(),
+            component_idx,
+            EdgeWeightKindDiscriminants::Use,
+        )?;
+        base_graph.cleanup_and_merkle_tree_hash()?;
+
+        let mut change_set_graph = merge_base_graph.clone();
+        change_set_graph.update_content(component_id, ContentHash::from("Component A changed"))?;
+        change_set_graph.cleanup_and_merkle_tree_hash()?;
+
+        let conflicts = merge_base_graph.detect_conflicts(&base_graph, &change_set_graph);
+        assert_eq!(
+            vec![Conflict::ModifiedWhatBaseRemoved {
+                node_id: component_id
+            }],
+            conflicts
+        );
+
+        // Keeping the change set's side moves it into the merge base, which leaves the removal
+        // on the base as the only change left to conflict with, and it no longer does
+        let updates = updates_to_match(&merge_base_graph, &change_set_graph, &conflicts[0]);
+        merge_base_graph.perform_updates(&updates)?;
+        merge_base_graph.cleanup_and_merkle_tree_hash()?;
+
+        assert!(merge_base_graph
+            .detect_conflicts(&base_graph, &change_set_graph)
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+#[allow(clippy::panic)]
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod test {
+    use si_events::ContentHash;
+
+    use crate::{
+        workspace_snapshot::{
+            graph::WorkspaceSnapshotGraphResult,
+            integrity::{IntegrityViolation, IntegrityViolationKind},
+            node_weight::NodeWeight,
+        },
+        EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants, NodeWeightDiscriminants, PropKind,
+        WorkspaceSnapshotGraphVCurrent,
+    };
+
+    #[test]
+    fn repair_ordering_violations() -> WorkspaceSnapshotGraphResult<()> {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()?;
+
+        let container_id = graph.generate_ulid()?;
+        let container_idx = graph.add_ordered_node(NodeWeight::new_prop(
+            container_id,
+            container_id,
+            PropKind::Object,
+            "container",
+            ContentHash::new(b"container"),
+        ))?;
+        graph.add_edge(
+            graph.root(),
+            EdgeWeight::new(EdgeWeightKind::new_use()),
+            container_idx,
+        )?;
+
+        let mut element_ids = Vec::new();
+        for name in ["first", "second"] {
+            let element_id = graph.generate_ulid()?;
+            let element_idx = graph.add_or_replace_node(NodeWeight::new_prop(
+                element_id,
+                element_id,
+                PropKind::String,
+                name,
+                ContentHash::new(name.as_bytes()),
+            ))?;
+            graph.add_ordered_edge(
+                container_idx,
+                EdgeWeight::new(EdgeWeightKind::new_use()),
+                element_idx,
+            )?;
+            element_ids.push(element_id);
+        }
+
+        assert!(graph.integrity_violations().is_empty());
+
+        let ordering_idx = graph
+            .ordering_node_index_for_container(container_idx)?
+            .expect("container should have an ordering node");
+        let ordering_id = graph.get_node_weight(ordering_idx)?.id();
+
+        // Drop the first element from the order, and the second element's ordinal edge
+        graph.update_node_weight(ordering_idx, |node_weight| {
+            if let NodeWeight::Ordering(ordering) = node_weight {
+                ordering.remove_from_order(element_ids[0]);
+            }
+            Ok(())
+        })?;
+        let second_idx = graph.get_node_index_by_id(element_ids[1])?;
+        graph.remove_edge(
+            ordering_idx,
+            second_idx,
+            EdgeWeightKindDiscriminants::Ordinal,
+        )?;
+
+        let violations = graph.integrity_violations();
+        assert_eq!(
+            vec![
+                IntegrityViolation {
+                    node_id: ordering_id,
+                    node_kind: NodeWeightDiscriminants::Ordering,
+                    kind: IntegrityViolationKind::OrderedElementWithoutOrdinal {
+                        element_id: element_ids[1],
+                    },
+                },
+                IntegrityViolation {
+                    node_id: ordering_id,
+                    node_kind: NodeWeightDiscriminants::Ordering,
+                    kind: IntegrityViolationKind::OrdinalNotInOrder {
+                        element_id: element_ids[0],
+                    },
+                },
+            ],
+            violations
+        );
+
+        let repaired = graph.repair_integrity_violations(&violations)?;
+        assert_eq!(violations, repaired);
+        assert!(graph.integrity_violations().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn repair_stale_dependent_value_root() -> WorkspaceSnapshotGraphResult<()> {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()?;
+
+        let root_id = graph.generate_ulid()?;
+        let value_id = graph.generate_ulid()?;
+        let root_idx = graph.add_or_replace_node(NodeWeight::new_dependent_value_root(
+            root_id, root_id, value_id,
+        ))?;
+        graph.add_edge(
+            graph.root(),
+            EdgeWeight::new(EdgeWeightKind::new_use()),
+            root_idx,
+        )?;
+
+        let violations = graph.integrity_violations();
+        assert_eq!(
+            vec![IntegrityViolation {
+                node_id: root_id,
+                node_kind: NodeWeightDiscriminants::DependentValueRoot,
+                kind: IntegrityViolationKind::StaleDependentValueRoot { value_id },
+            }],
+            violations
+        );
+
+        graph.repair_integrity_violations(&violations)?;
+        assert!(graph.integrity_violations().is_empty());
+        assert!(graph.get_node_index_by_id_opt(root_id).is_none());
+
+        Ok(())
+    }
+}
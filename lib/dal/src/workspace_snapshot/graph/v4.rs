@@ -13,7 +13,7 @@ use petgraph::{
     visit::DfsEvent,
 };
 use serde::{Deserialize, Serialize};
-use si_events::{ulid::Ulid, ContentHash};
+use si_events::{ulid::Ulid, Conflict, ContentHash};
 use si_layer_cache::db::serialize;
 use telemetry::prelude::*;
 use ulid::Generator;
@@ -23,6 +23,7 @@ use crate::{
     workspace_snapshot::{
         content_address::ContentAddress,
         graph::{
+            detect_conflicts::ConflictDetector,
             detect_updates::{Detector, Update},
             MerkleTreeHash, WorkspaceSnapshotGraphError, WorkspaceSnapshotGraphResult,
         },
@@ -689,6 +690,12 @@ impl WorkspaceSnapshotGraphV4 {
         Detector::new(self, updated_graph).detect_updates()
     }
 
+    /// Detects the conflicts between a change set and its base change set, where `self` is the
+    /// merge base of the two.
+    pub fn detect_conflicts(&self, base_graph: &Self, change_set_graph: &Self) -> Vec<Conflict> {
+        ConflictDetector::new(self, base_graph, change_set_graph).detect_conflicts()
+    }
+
     #[allow(dead_code)]
     pub fn dot(&self) {
         // NOTE(nick): copy the output and execute this on macOS. It will create a file in the
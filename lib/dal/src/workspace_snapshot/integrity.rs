@@ -0,0 +1,421 @@
+//! Checks for invariants of the [`WorkspaceSnapshotGraphVCurrent`] that are not enforced as the
+//! graph is changed, and repairs for the violations that can be fixed mechanically.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::atomic::{self, AtomicU8},
+};
+
+use petgraph::prelude::*;
+use serde::{Deserialize, Serialize};
+use si_events::{ulid::Ulid, ContentHash};
+use telemetry::prelude::*;
+
+use super::{
+    edge_weight::{EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants},
+    graph::{WorkspaceSnapshotGraphResult, WorkspaceSnapshotGraphVCurrent},
+    node_weight::{NodeWeight, NodeWeightDiscriminants, OrderingNodeWeight},
+    WorkspaceSnapshot, WorkspaceSnapshotError, WorkspaceSnapshotResult,
+};
+use crate::DalContext;
+
+/// The number of content hashes to look up in the content store at once.
+const CAS_LOOKUP_CHUNK_SIZE: usize = 1000;
+
+static CHECK_AFTER_UPDATES: AtomicU8 = AtomicU8::new(CheckAfterUpdates::Disabled as u8);
+
+/// What to do about the graph's integrity after every call to
+/// [`WorkspaceSnapshot::perform_updates`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CheckAfterUpdates {
+    /// The graph isn't checked.
+    Disabled,
+    /// Any violations found are logged and then fail the updates, which is how tests are run.
+    Fail,
+    /// Any violations found are logged as warnings.
+    Warn,
+}
+
+/// Sets whether (and how) the graph's integrity is checked after every call to
+/// [`WorkspaceSnapshot::perform_updates`]. The check is only ever run in debug builds.
+pub fn set_check_after_updates(check: CheckAfterUpdates) {
+    CHECK_AFTER_UPDATES.store(check as u8, atomic::Ordering::Relaxed);
+}
+
+pub(crate) fn check_after_updates() -> CheckAfterUpdates {
+    if !cfg!(debug_assertions) {
+        return CheckAfterUpdates::Disabled;
+    }
+
+    match CHECK_AFTER_UPDATES.load(atomic::Ordering::Relaxed) {
+        check if check == CheckAfterUpdates::Fail as u8 => CheckAfterUpdates::Fail,
+        check if check == CheckAfterUpdates::Warn as u8 => CheckAfterUpdates::Warn,
+        _ => CheckAfterUpdates::Disabled,
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "violation", rename_all = "camelCase")]
+pub enum IntegrityViolationKind {
+    /// An attribute value with neither a `Prop` edge nor, for socket values, a `Socket` edge.
+    AttributeValueWithoutProp,
+    /// A component without a `Root` edge to its root attribute value.
+    ComponentWithoutRoot,
+    /// Content that the node refers to, but which is not in the content store.
+    #[serde(rename_all = "camelCase")]
+    ContentMissingFromCas { content_hash: ContentHash },
+    /// An element in an ordering node's order which it has no `Ordinal` edge to.
+    #[serde(rename_all = "camelCase")]
+    OrderedElementWithoutOrdinal { element_id: Ulid },
+    /// An ordering node without an incoming `Ordering` edge from a container.
+    OrderingWithoutContainer,
+    /// An `Ordinal` edge from an ordering node to an element that is not in its order.
+    #[serde(rename_all = "camelCase")]
+    OrdinalNotInOrder { element_id: Ulid },
+    /// A dependent value root for an attribute value that is no longer in the graph.
+    #[serde(rename_all = "camelCase")]
+    StaleDependentValueRoot { value_id: Ulid },
+}
+
+/// A violation of a graph invariant, found on the given node.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityViolation {
+    pub node_id: Ulid,
+    pub node_kind: NodeWeightDiscriminants,
+    #[serde(flatten)]
+    pub kind: IntegrityViolationKind,
+}
+
+impl IntegrityViolation {
+    fn new(node_weight: &NodeWeight, kind: IntegrityViolationKind) -> Self {
+        Self {
+            node_id: node_weight.id(),
+            node_kind: node_weight.into(),
+            kind,
+        }
+    }
+
+    /// Whether [`WorkspaceSnapshotGraphVCurrent::repair_integrity_violations`] can repair the
+    /// violation. Violations that would need data that is no longer in the graph (or the content
+    /// store) to repair are only reported.
+    pub fn is_repairable(&self) -> bool {
+        match self.kind {
+            IntegrityViolationKind::OrderedElementWithoutOrdinal { .. }
+            | IntegrityViolationKind::OrderingWithoutContainer
+            | IntegrityViolationKind::OrdinalNotInOrder { .. }
+            | IntegrityViolationKind::StaleDependentValueRoot { .. } => true,
+            IntegrityViolationKind::AttributeValueWithoutProp
+            | IntegrityViolationKind::ComponentWithoutRoot
+            | IntegrityViolationKind::ContentMissingFromCas { .. } => false,
+        }
+    }
+}
+
+impl WorkspaceSnapshotGraphVCurrent {
+    /// Checks the graph for violations of its invariants. Content is not checked against the
+    /// content store, see [`WorkspaceSnapshot::integrity_violations`] for that.
+    pub fn integrity_violations(&self) -> Vec<IntegrityViolation> {
+        let mut violations = Vec::new();
+
+        for (node_weight, node_index) in self.nodes() {
+            let has_outgoing = |kind| self.has_edge_of_kind(node_index, Outgoing, kind);
+            match node_weight {
+                NodeWeight::AttributeValue(_) => {
+                    if !has_outgoing(EdgeWeightKindDiscriminants::Prop)
+                        && !has_outgoing(EdgeWeightKindDiscriminants::Socket)
+                    {
+                        violations.push(IntegrityViolation::new(
+                            node_weight,
+                            IntegrityViolationKind::AttributeValueWithoutProp,
+                        ));
+                    }
+                }
+                NodeWeight::Component(_) => {
+                    if !has_outgoing(EdgeWeightKindDiscriminants::Root) {
+                        violations.push(IntegrityViolation::new(
+                            node_weight,
+                            IntegrityViolationKind::ComponentWithoutRoot,
+                        ));
+                    }
+                }
+                NodeWeight::DependentValueRoot(root) => {
+                    if !self.contains_node_id(root.value_id()) {
+                        violations.push(IntegrityViolation::new(
+                            node_weight,
+                            IntegrityViolationKind::StaleDependentValueRoot {
+                                value_id: root.value_id(),
+                            },
+                        ));
+                    }
+                }
+                NodeWeight::Ordering(ordering) => {
+                    if !self.has_edge_of_kind(
+                        node_index,
+                        Incoming,
+                        EdgeWeightKindDiscriminants::Ordering,
+                    ) {
+                        violations.push(IntegrityViolation::new(
+                            node_weight,
+                            IntegrityViolationKind::OrderingWithoutContainer,
+                        ));
+                    }
+
+                    let ordinal_ids: BTreeSet<Ulid> = self
+                        .edges_directed_for_edge_weight_kind(
+                            node_index,
+                            Outgoing,
+                            EdgeWeightKindDiscriminants::Ordinal,
+                        )
+                        .into_iter()
+                        .filter_map(|(_, _, target)| self.node_index_to_id(target))
+                        .collect();
+                    let order: HashSet<Ulid> = ordering.order().iter().copied().collect();
+
+                    for &element_id in ordering.order() {
+                        if !ordinal_ids.contains(&element_id) {
+                            violations.push(IntegrityViolation::new(
+                                node_weight,
+                                IntegrityViolationKind::OrderedElementWithoutOrdinal { element_id },
+                            ));
+                        }
+                    }
+                    for element_id in ordinal_ids {
+                        if !order.contains(&element_id) {
+                            violations.push(IntegrityViolation::new(
+                                node_weight,
+                                IntegrityViolationKind::OrdinalNotInOrder { element_id },
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        violations
+    }
+
+    /// Repairs the violations that can be fixed mechanically, returning the ones it repaired:
+    ///
+    /// - Ordering nodes without a container, and stale dependent value roots, are removed.
+    /// - An element with an `Ordinal` edge but missing from the order is appended to the order,
+    ///   and an element in the order without an `Ordinal` edge gets one, provided that the
+    ///   container still has an edge to the element. Otherwise the element is removed from the
+    ///   ordering.
+    pub fn repair_integrity_violations(
+        &mut self,
+        violations: &[IntegrityViolation],
+    ) -> WorkspaceSnapshotGraphResult<Vec<IntegrityViolation>> {
+        let mut repaired = Vec::new();
+
+        for violation in violations
+            .iter()
+            .filter(|violation| violation.is_repairable())
+        {
+            // An earlier repair may have removed the node
+            let Some(node_index) = self
+                .get_node_index_by_id_opt(violation.node_id)
+                .filter(|node_index| self.get_node_weight_opt(*node_index).is_some())
+            else {
+                continue;
+            };
+
+            match violation.kind {
+                IntegrityViolationKind::OrderingWithoutContainer
+                | IntegrityViolationKind::StaleDependentValueRoot { .. } => {
+                    self.remove_node(node_index);
+                    self.remove_node_id(violation.node_id);
+                }
+                IntegrityViolationKind::OrdinalNotInOrder { element_id } => {
+                    let element_index = self.get_node_index_by_id(element_id)?;
+                    if self.container_has_edge_to(node_index, element_index) {
+                        self.update_ordering(node_index, |order| order.push_to_order(element_id))?;
+                    } else {
+                        self.remove_edge(
+                            node_index,
+                            element_index,
+                            EdgeWeightKindDiscriminants::Ordinal,
+                        )?;
+                    }
+                }
+                IntegrityViolationKind::OrderedElementWithoutOrdinal { element_id } => {
+                    match self.get_node_index_by_id_opt(element_id) {
+                        Some(element_index)
+                            if self.get_node_weight_opt(element_index).is_some()
+                                && self.container_has_edge_to(node_index, element_index) =>
+                        {
+                            self.add_edge(
+                                node_index,
+                                EdgeWeight::new(EdgeWeightKind::Ordinal),
+                                element_index,
+                            )?;
+                        }
+                        _ => {
+                            self.update_ordering(node_index, |order| {
+                                order.remove_from_order(element_id);
+                            })?;
+                        }
+                    }
+                }
+                IntegrityViolationKind::AttributeValueWithoutProp
+                | IntegrityViolationKind::ComponentWithoutRoot
+                | IntegrityViolationKind::ContentMissingFromCas { .. } => continue,
+            }
+
+            repaired.push(violation.clone());
+        }
+
+        Ok(repaired)
+    }
+
+    fn has_edge_of_kind(
+        &self,
+        node_index: NodeIndex,
+        direction: Direction,
+        kind: EdgeWeightKindDiscriminants,
+    ) -> bool {
+        self.edges_directed(node_index, direction)
+            .any(|edge_ref| kind == edge_ref.weight().kind().into())
+    }
+
+    fn contains_node_id(&self, id: Ulid) -> bool {
+        self.get_node_index_by_id_opt(id)
+            .and_then(|node_index| self.get_node_weight_opt(node_index))
+            .is_some()
+    }
+
+    /// Whether the container of the ordering node has an edge (other than the `Ordering` edge)
+    /// to the element.
+    fn container_has_edge_to(&self, ordering_index: NodeIndex, element_index: NodeIndex) -> bool {
+        self.edges_directed(ordering_index, Incoming)
+            .filter(|edge_ref| {
+                EdgeWeightKindDiscriminants::Ordering == edge_ref.weight().kind().into()
+            })
+            .any(|edge_ref| {
+                self.edges_directed(edge_ref.source(), Outgoing)
+                    .any(|container_edge| {
+                        container_edge.target() == element_index
+                            && EdgeWeightKindDiscriminants::Ordering
+                                != container_edge.weight().kind().into()
+                    })
+            })
+    }
+
+    fn update_ordering(
+        &mut self,
+        ordering_index: NodeIndex,
+        update: impl FnOnce(&mut OrderingNodeWeight),
+    ) -> WorkspaceSnapshotGraphResult<()> {
+        self.update_node_weight(ordering_index, |node_weight| {
+            if let NodeWeight::Ordering(ordering) = node_weight {
+                update(ordering);
+            }
+            Ok(())
+        })
+    }
+}
+
+impl WorkspaceSnapshot {
+    /// Checks the graph for violations of its invariants, including content which is missing
+    /// from the content store.
+    #[instrument(
+        name = "workspace_snapshot.integrity_violations",
+        level = "info",
+        skip_all,
+        fields(si.workspace_snapshot.integrity_violations = Empty)
+    )]
+    pub async fn integrity_violations(
+        &self,
+        ctx: &DalContext,
+    ) -> WorkspaceSnapshotResult<Vec<IntegrityViolation>> {
+        let span = current_span_for_instrument_at!("info");
+
+        let (mut violations, content) = {
+            let graph = self.working_copy().await;
+            let content: Vec<(IntegrityViolation, ContentHash)> = graph
+                .nodes()
+                .flat_map(|(node_weight, _)| {
+                    node_weight
+                        .content_store_hashes()
+                        .into_iter()
+                        .map(move |content_hash| {
+                            (
+                                IntegrityViolation::new(
+                                    node_weight,
+                                    IntegrityViolationKind::ContentMissingFromCas { content_hash },
+                                ),
+                                content_hash,
+                            )
+                        })
+                })
+                .collect();
+            (graph.integrity_violations(), content)
+        };
+
+        let content_hashes: Vec<ContentHash> = content
+            .iter()
+            .map(|(_, content_hash)| *content_hash)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut found = HashSet::new();
+        for chunk in content_hashes.chunks(CAS_LOOKUP_CHUNK_SIZE) {
+            found.extend(ctx.layer_db().cas().read_many(chunk).await?.into_keys());
+        }
+        violations.extend(
+            content
+                .into_iter()
+                .filter(|(_, content_hash)| !found.contains(content_hash))
+                .map(|(violation, _)| violation),
+        );
+
+        span.record(
+            "si.workspace_snapshot.integrity_violations",
+            violations.len(),
+        );
+        Ok(violations)
+    }
+
+    /// Repairs the violations that can be fixed mechanically (see
+    /// [`WorkspaceSnapshotGraphVCurrent::repair_integrity_violations`]), returning the ones it
+    /// repaired. The repairs are made to the working copy, like any other change to the snapshot.
+    pub async fn repair_integrity_violations(
+        &self,
+        ctx: &DalContext,
+    ) -> WorkspaceSnapshotResult<Vec<IntegrityViolation>> {
+        let violations = self.integrity_violations(ctx).await?;
+        Ok(self
+            .working_copy_mut()
+            .await
+            .repair_integrity_violations(&violations)?)
+    }
+
+    /// Logs any integrity violations in the graph, if [enabled](set_check_after_updates), failing
+    /// with [`WorkspaceSnapshotError::IntegrityViolations`] if set to
+    /// [fail](CheckAfterUpdates::Fail).
+    pub(crate) async fn check_integrity_after_updates(&self) -> WorkspaceSnapshotResult<()> {
+        let check = check_after_updates();
+        if check == CheckAfterUpdates::Disabled {
+            return Ok(());
+        }
+
+        let violations = self.working_copy().await.integrity_violations();
+        for violation in &violations {
+            warn!(
+                si.workspace_snapshot.node.id = %violation.node_id,
+                si.workspace_snapshot.node.kind = %violation.node_kind,
+                "integrity violation after performing updates: {:?}",
+                violation.kind
+            );
+        }
+
+        if check == CheckAfterUpdates::Fail && !violations.is_empty() {
+            return Err(WorkspaceSnapshotError::IntegrityViolations(violations));
+        }
+
+        Ok(())
+    }
+}
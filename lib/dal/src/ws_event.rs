@@ -33,6 +33,7 @@ use crate::pkg::{
 };
 use crate::prompt_override::PromptUpdatedPayload;
 use crate::qualification::QualificationCheckPayload;
+use crate::schedule::drift::ResourceDriftDetectedPayload;
 use crate::schema::variant::{
     SchemaVariantClonedPayload, SchemaVariantDeletedPayload, SchemaVariantReplacedPayload,
     SchemaVariantSavedPayload, SchemaVariantUpdatedPayload,
@@ -119,6 +120,7 @@ pub enum WsPayload {
     ModuleImported(Vec<si_frontend_types::SchemaVariant>),
     Online(OnlinePayload),
     PromptUpdated(PromptUpdatedPayload),
+    ResourceDriftDetected(ResourceDriftDetectedPayload),
     ResourceRefreshed(ComponentUpdatedPayload),
     SchemaVariantCloned(SchemaVariantClonedPayload),
     SchemaVariantCreated(frontend_types::SchemaVariant),
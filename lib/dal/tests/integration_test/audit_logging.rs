@@ -1,6 +1,11 @@
-use audit_database::AuditDatabaseContext;
+use std::{collections::HashSet, time::Duration};
+
+use audit_database::{
+    AuditDatabaseContext, AuditLogMetadataPredicate, AuditLogPage, AuditLogQuery,
+};
 use audit_logs_stream::AuditLogsStream;
-use dal::{prop::PropPath, AttributeValue, DalContext, Prop, Schema, SchemaVariant};
+use chrono::Utc;
+use dal::{audit_logging, prop::PropPath, AttributeValue, DalContext, Prop, Schema, SchemaVariant};
 use dal_test::helpers::{
     confirm_jetstream_stream_has_no_messages,
     create_named_component_for_schema_variant_on_default_view,
@@ -9,7 +14,8 @@ use dal_test::helpers::{
 use dal_test::{helpers::ChangeSetTestHelpers, test};
 use pending_events::PendingEventsStream;
 use pretty_assertions_sorted::assert_eq;
-use si_events::audit_log::AuditLogKind;
+use si_events::{audit_log::AuditLogKind, UserPk};
+use tokio::time::Instant;
 
 const DATABASE_RETRY_TIMEOUT_SECONDS: u64 = 2;
 const DATABASE_RETRY_INTERVAL_MILLISECONDS: u64 = 100;
@@ -240,3 +246,237 @@ async fn round_trip(ctx: &mut DalContext, audit_database_context: AuditDatabaseC
         .expect("could not list audit logs");
     }
 }
+
+#[test]
+async fn query_filters_and_pagination(
+    ctx: &mut DalContext,
+    audit_database_context: AuditDatabaseContext,
+) {
+    let context = audit_database_context;
+    let before_writes = Utc::now();
+
+    let schema = Schema::find_by_name(ctx, "swifty")
+        .await
+        .expect("could not perform find by name")
+        .expect("schema not found by name");
+    let schema_variant_id = schema
+        .get_default_schema_variant_id(ctx)
+        .await
+        .expect("could not get default schema variant id")
+        .expect("no default schema variant id found");
+    let schema_variant = SchemaVariant::get_by_id_or_error(ctx, schema_variant_id)
+        .await
+        .expect("could not get schema variant");
+
+    // Create three components, mimicking sdf by audit logging each one, and then log a deletion
+    // for the first component so that its name is shared by two kinds of logs.
+    let component_names = ["paginated-one", "paginated-two", "paginated-three"];
+    let mut components = Vec::new();
+    for component_name in component_names {
+        let component = create_named_component_for_schema_variant_on_default_view(
+            ctx,
+            component_name,
+            schema_variant_id,
+        )
+        .await
+        .expect("could not create component");
+        ctx.write_audit_log(
+            AuditLogKind::CreateComponent {
+                name: component_name.to_string(),
+                component_id: component.id(),
+                schema_variant_id,
+                schema_variant_name: schema_variant.display_name().to_string(),
+            },
+            component_name.to_string(),
+        )
+        .await
+        .expect("could not write audit log");
+        components.push(component);
+    }
+    ctx.write_audit_log(
+        AuditLogKind::DeleteComponent {
+            name: component_names[0].to_string(),
+            component_id: components[0].id(),
+            schema_variant_id,
+            schema_variant_name: schema_variant.display_name().to_string(),
+        },
+        component_names[0].to_string(),
+    )
+    .await
+    .expect("could not write audit log");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let created = AuditLogQuery::new(SIZE)
+        .kinds(["CreateComponent"])
+        .start_timestamp(before_writes);
+    query_until_expected_number_of_rows(ctx, &context, &created, component_names.len()).await;
+
+    // Filter by entity name, which matches both the creation and the deletion.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE).entity_name(component_names[0]),
+    )
+    .await;
+    assert_eq!(2, page.rows.len());
+    assert!(page.next_cursor.is_none());
+
+    // Combine the entity name with a kind.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE)
+            .entity_name(component_names[0])
+            .kinds(["DeleteComponent"]),
+    )
+    .await;
+    assert_eq!(
+        vec!["DeleteComponent".to_string()],
+        page.rows
+            .into_iter()
+            .map(|row| row.kind)
+            .collect::<Vec<_>>()
+    );
+
+    // Filter by entity type.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE)
+            .entity_type("Component")
+            .entity_name(component_names[1]),
+    )
+    .await;
+    assert_eq!(1, page.rows.len());
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE)
+            .entity_type("Function")
+            .entity_name(component_names[1]),
+    )
+    .await;
+    assert!(page.rows.is_empty());
+
+    // Filter by a value in the metadata.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE)
+            .kinds(["CreateComponent"])
+            .metadata(AuditLogMetadataPredicate::new(["name"], component_names[2])),
+    )
+    .await;
+    assert_eq!(
+        vec![Some(component_names[2].to_string())],
+        page.rows
+            .into_iter()
+            .map(|row| row.entity_name)
+            .collect::<Vec<_>>()
+    );
+
+    // Filter by a user who has not written anything.
+    let page = query(ctx, &context, &created.clone().user_id(UserPk::generate())).await;
+    assert!(page.rows.is_empty());
+
+    // Filter by timestamps on either side of the writes.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(SIZE)
+            .kinds(["CreateComponent"])
+            .end_timestamp(before_writes),
+    )
+    .await;
+    assert!(page
+        .rows
+        .iter()
+        .all(|row| !component_names.contains(&row.entity_name.as_deref().unwrap_or_default())));
+    let page = query(
+        ctx,
+        &context,
+        &created
+            .clone()
+            .start_timestamp(Utc::now() + chrono::Duration::hours(1)),
+    )
+    .await;
+    assert!(page.rows.is_empty());
+
+    // Walk the creations one row at a time, in both directions, and check that the pages cover
+    // every row exactly once and in order.
+    for sort_ascending in [false, true] {
+        let mut seen = Vec::new();
+        let mut page_query = AuditLogQuery::new(1)
+            .kinds(["CreateComponent"])
+            .start_timestamp(before_writes)
+            .sort_ascending(sort_ascending);
+        loop {
+            let page = query(ctx, &context, &page_query).await;
+            assert!(page.rows.len() <= 1);
+            seen.extend(page.rows);
+            match page.next_cursor {
+                Some(cursor) => page_query = page_query.after(cursor),
+                None => break,
+            }
+        }
+
+        let mut names: Vec<String> = seen
+            .into_iter()
+            .map(|row| row.entity_name.expect("entity name not found"))
+            .collect();
+        assert_eq!(
+            names.len(),
+            names.iter().collect::<HashSet<_>>().len(),
+            "pages should not overlap"
+        );
+        if !sort_ascending {
+            names.reverse();
+        }
+        assert_eq!(component_names.to_vec(), names);
+    }
+
+    // Ask for exactly as many rows as there are, which should not report another page.
+    let page = query(
+        ctx,
+        &context,
+        &AuditLogQuery::new(component_names.len())
+            .kinds(["CreateComponent"])
+            .start_timestamp(before_writes),
+    )
+    .await;
+    assert_eq!(component_names.len(), page.rows.len());
+    assert!(page.next_cursor.is_none());
+}
+
+async fn query(
+    ctx: &DalContext,
+    context: &AuditDatabaseContext,
+    query: &AuditLogQuery,
+) -> AuditLogPage {
+    audit_logging::query(ctx, context, query)
+        .await
+        .expect("could not query audit logs")
+}
+
+async fn query_until_expected_number_of_rows(
+    ctx: &DalContext,
+    context: &AuditDatabaseContext,
+    audit_log_query: &AuditLogQuery,
+    expected_number_of_rows: usize,
+) {
+    let timeout = Duration::from_secs(DATABASE_RETRY_TIMEOUT_SECONDS);
+    let interval = Duration::from_millis(DATABASE_RETRY_INTERVAL_MILLISECONDS);
+
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        let page = query(ctx, context, audit_log_query).await;
+        if page.rows.len() == expected_number_of_rows {
+            return;
+        }
+        tokio::time::sleep(interval).await;
+    }
+
+    panic!("hit timeout before audit logs query returns {expected_number_of_rows} rows");
+}
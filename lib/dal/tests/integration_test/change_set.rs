@@ -1,3 +1,4 @@
+use dal::change_set::approval::{ApprovalPolicy, ApprovalPolicyScope};
 use dal::change_set::view::OpenChangeSetsView;
 use dal::{
     context::TransactionsErrorDiscriminants, DalContext, DalContextBuilder, HistoryActor,
@@ -474,3 +475,107 @@ async fn change_set_approval_flow(ctx: &mut DalContext) {
         .collect_vec();
     assert_eq!(components.len(), 2);
 }
+
+#[test]
+async fn change_set_approval_policies(ctx: &mut DalContext) {
+    let author = ChangeSet::extract_userid_from_context(ctx)
+        .await
+        .expect("test context has a user");
+    let reviewer_1 = create_user(ctx).await.expect("Unable to create user");
+    let reviewer_2 = create_user(ctx).await.expect("Unable to create user");
+    ApprovalPolicy::new(
+        ctx,
+        "two reviewers",
+        ApprovalPolicyScope::Workspace,
+        2,
+        vec![],
+        false,
+    )
+    .await
+    .expect("could not create approval policy");
+
+    let new_change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork head");
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    let mut change_set = ChangeSet::find(ctx, new_change_set.id)
+        .await
+        .expect("could not find change set")
+        .expect("change set is some");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+
+    // the author and one reviewer aren't enough
+    for user_pk in [author, reviewer_1.pk()] {
+        ctx.update_history_actor(HistoryActor::User(user_pk));
+        change_set
+            .approve_change_set_for_apply(ctx)
+            .await
+            .expect("could not approve");
+        assert_eq!(change_set.status, ChangeSetStatus::NeedsApproval);
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    let requirements = change_set
+        .approval_requirements(ctx)
+        .await
+        .expect("could not get approval requirements");
+    assert_eq!(1, requirements.len());
+    assert_eq!(vec![reviewer_1.pk()], requirements[0].approved_by);
+    assert!(
+        ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+            .await
+            .is_err()
+    );
+
+    ctx.update_history_actor(HistoryActor::User(reviewer_2.pk()));
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    assert_eq!(change_set.status, ChangeSetStatus::Approved);
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // new edits invalidate the approvals
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small 2")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    let requirements = change_set
+        .approval_requirements(ctx)
+        .await
+        .expect("could not get approval requirements");
+    assert!(requirements[0].approved_by.is_empty());
+    assert_eq!(2, requirements[0].stale_approvals_by.len());
+    assert!(
+        ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+            .await
+            .is_err()
+    );
+
+    for user_pk in [reviewer_1.pk(), reviewer_2.pk()] {
+        ctx.update_history_actor(HistoryActor::User(user_pk));
+        change_set
+            .approve_change_set_for_apply(ctx)
+            .await
+            .expect("could not approve");
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+        .await
+        .expect("could not apply to head");
+}
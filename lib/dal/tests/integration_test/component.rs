@@ -19,7 +19,9 @@ mod delete;
 mod get_code;
 mod get_diff;
 mod property_order;
+mod query;
 mod set_type;
+mod tags;
 mod upgrade;
 
 #[test]
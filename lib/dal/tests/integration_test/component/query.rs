@@ -0,0 +1,139 @@
+use dal::change_status::ChangeStatus;
+use dal::component::frame::Frame;
+use dal::component::query::{ComparisonOperator, ComponentFilter};
+use dal::{Component, ComponentId, ComponentType, DalContext};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view,
+    create_component_for_schema_name_with_type_on_default_view,
+    update_attribute_value_for_component, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn query_components(ctx: &mut DalContext) {
+    let frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "frame",
+        ComponentType::ConfigurationFrameUp,
+    )
+    .await
+    .expect("could not create component");
+    let first =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "first")
+            .await
+            .expect("could not create component");
+    let second =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "second")
+            .await
+            .expect("could not create component");
+    Frame::upsert_parent(ctx, first.id(), frame.id())
+        .await
+        .expect("could not upsert parent");
+    update_attribute_value_for_component(
+        ctx,
+        second.id(),
+        &["root", "domain", "one"],
+        serde_json::json!["2"],
+    )
+    .await
+    .expect("could not update attribute value");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let mut both = vec![first.id(), second.id()];
+    both.sort();
+
+    assert_eq!(
+        both,
+        query(
+            ctx,
+            ComponentFilter::SchemaName("Small Even Lego".to_owned())
+        )
+        .await
+    );
+    assert_eq!(
+        vec![first.id()],
+        query(ctx, ComponentFilter::ParentFrame(Some(frame.id()))).await
+    );
+    assert_eq!(
+        vec![second.id()],
+        query(
+            ctx,
+            ComponentFilter::Attribute {
+                path: "/domain/one".to_owned(),
+                op: ComparisonOperator::Eq,
+                value: serde_json::json!(2),
+            }
+        )
+        .await
+    );
+    // Components without the prop never match, whatever the comparison
+    assert!(query(
+        ctx,
+        ComponentFilter::Attribute {
+            path: "/domain/does-not-exist".to_owned(),
+            op: ComparisonOperator::Ne,
+            value: serde_json::json!(2),
+        }
+    )
+    .await
+    .is_empty());
+    assert_eq!(
+        vec![first.id()],
+        query(
+            ctx,
+            r#"schema:"small even lego" -attr:/domain/one=2"#
+                .parse()
+                .expect("could not parse query")
+        )
+        .await
+    );
+    assert_eq!(
+        vec![frame.id()],
+        query(
+            ctx,
+            "fram change:added".parse().expect("could not parse query")
+        )
+        .await
+    );
+
+    // Once applied, nothing differs from HEAD until a component is changed again
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    update_attribute_value_for_component(
+        ctx,
+        second.id(),
+        &["root", "domain", "one"],
+        serde_json::json!["3"],
+    )
+    .await
+    .expect("could not update attribute value");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    assert_eq!(
+        vec![second.id()],
+        query(ctx, ComponentFilter::ChangeStatus(ChangeStatus::Modified)).await
+    );
+    assert!(
+        query(ctx, ComponentFilter::ChangeStatus(ChangeStatus::Added))
+            .await
+            .is_empty()
+    );
+}
+
+async fn query(ctx: &DalContext, filter: ComponentFilter) -> Vec<ComponentId> {
+    let mut component_ids = Component::query(ctx, &filter)
+        .await
+        .expect("could not query components");
+    component_ids.sort();
+    component_ids
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use dal::component::query::ComponentFilter;
+use dal::{Component, DalContext};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn set_tags(ctx: &mut DalContext) {
+    let tagged =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "tagged")
+            .await
+            .expect("could not create component");
+    let untagged = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "untagged",
+    )
+    .await
+    .expect("could not create component");
+    assert!(tagged.tags().is_empty());
+
+    let tags = BTreeMap::from([
+        ("owner".to_owned(), "fletcher".to_owned()),
+        ("team".to_owned(), "platform".to_owned()),
+    ]);
+    tagged
+        .set_tags(ctx, tags.clone())
+        .await
+        .expect("could not set tags");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let tagged = Component::get_by_id(ctx, tagged.id())
+        .await
+        .expect("could not get component");
+    assert_eq!(&tags, tagged.tags());
+    let untagged = Component::get_by_id(ctx, untagged.id())
+        .await
+        .expect("could not get component");
+    assert!(untagged.tags().is_empty());
+
+    for (query, expected) in [
+        ("tag:team", vec![tagged.id()]),
+        ("tag:team=platform", vec![tagged.id()]),
+        ("tag:team=web", vec![]),
+        ("-tag:owner", vec![untagged.id()]),
+    ] {
+        let filter: ComponentFilter = query.parse().expect("could not parse query");
+        assert_eq!(
+            expected,
+            Component::query(ctx, &filter)
+                .await
+                .expect("could not query components"),
+            "{query}"
+        );
+    }
+
+    // Tags flow through the change set like any other change
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    let tagged = Component::get_by_id(ctx, tagged.id())
+        .await
+        .expect("could not get component");
+    assert_eq!(&tags, tagged.tags());
+}
@@ -7,6 +7,7 @@ use pretty_assertions_sorted::assert_eq;
 mod argument;
 mod authoring;
 mod kill_execution;
+mod memoization;
 
 #[test]
 async fn summary(ctx: &mut DalContext) {
@@ -0,0 +1,122 @@
+use dal::func::authoring::FuncAuthoringClient;
+use dal::func::binding::AttributeFuncDestination;
+use dal::prop::PropPath;
+use dal::{ComponentId, DalContext, Func, FuncId, Prop};
+use dal_test::helpers::{
+    create_component_for_unlocked_schema_name_on_default_view,
+    create_unlocked_variant_copy_for_schema_name, get_attribute_value_for_component,
+    ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::{assert_eq, assert_ne};
+
+const RANDOM_NAME_CODE: &str = "async function main() {
+    return Math.random().toString();
+}";
+
+#[test]
+async fn memoized_results_are_reused_for_the_same_code_and_arguments(ctx: &mut DalContext) {
+    let func_id = setup_random_name_func(ctx).await;
+    let func = Func::get_by_id_or_error(ctx, func_id)
+        .await
+        .expect("could not get func");
+    assert!(func.is_memoizable());
+
+    let first = create_swifty_and_get_name(ctx, "first").await;
+    let second = create_swifty_and_get_name(ctx, "second").await;
+
+    // The second run is a hit, so it gets the first run's "random" value
+    assert_eq!(first, second);
+
+    // Changing the code changes the key, so the next run is a miss
+    FuncAuthoringClient::save_code(
+        ctx,
+        func_id,
+        format!("{RANDOM_NAME_CODE}\n// a new revision"),
+    )
+    .await
+    .expect("could not save code");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let third = create_swifty_and_get_name(ctx, "third").await;
+    assert_ne!(first, third);
+}
+
+#[test]
+async fn nondeterministic_funcs_are_never_memoized(ctx: &mut DalContext) {
+    let func_id = setup_random_name_func(ctx).await;
+    let func = Func::get_by_id_or_error(ctx, func_id)
+        .await
+        .expect("could not get func")
+        .set_nondeterministic(ctx, true)
+        .await
+        .expect("could not mark func as nondeterministic");
+    assert!(func.is_nondeterministic);
+    assert!(!func.is_memoizable());
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let first = create_swifty_and_get_name(ctx, "first").await;
+    let second = create_swifty_and_get_name(ctx, "second").await;
+
+    // Every run is a miss, so each gets its own random value
+    assert_ne!(first, second);
+
+    // Unlocked copies keep the flag
+    let copy = func
+        .clone_func_with_new_name(ctx, "randomNameCopy".to_string())
+        .await
+        .expect("could not clone func");
+    assert!(copy.is_nondeterministic);
+}
+
+/// Sets "/root/domain/name" on an unlocked copy of swifty with a func returning a random string.
+async fn setup_random_name_func(ctx: &mut DalContext) -> FuncId {
+    let schema_variant_id = create_unlocked_variant_copy_for_schema_name(ctx, "swifty")
+        .await
+        .expect("could not create unlocked copy");
+    let prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "name"]),
+    )
+    .await
+    .expect("could not find prop id by path");
+
+    let func = FuncAuthoringClient::create_new_attribute_func(
+        ctx,
+        Some("randomName".to_string()),
+        None,
+        AttributeFuncDestination::Prop(prop_id),
+        Vec::new(),
+    )
+    .await
+    .expect("could not create func");
+    FuncAuthoringClient::save_code(ctx, func.id, RANDOM_NAME_CODE.to_string())
+        .await
+        .expect("could not save code");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    func.id
+}
+
+async fn create_swifty_and_get_name(ctx: &mut DalContext, name: &str) -> serde_json::Value {
+    let component_id: ComponentId =
+        create_component_for_unlocked_schema_name_on_default_view(ctx, "swifty", name)
+            .await
+            .expect("could not create component")
+            .id();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    get_attribute_value_for_component(ctx, component_id, &["root", "domain", "name"])
+        .await
+        .expect("could not get value")
+        .expect("no value")
+}
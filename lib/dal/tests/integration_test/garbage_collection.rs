@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use dal::garbage_collection;
+use dal::{Component, DalContext};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use si_layer_cache::gc::GarbageCollectionConfig;
+
+#[test]
+async fn dry_run_keeps_snapshots_in_use(ctx: &mut DalContext) {
+    let component = create_component_for_default_schema_name_in_default_view(ctx, "swifty", "rush")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Other tests share the layer db, so we can only ever do a dry run here.
+    let report = garbage_collection::collect_garbage(
+        ctx,
+        GarbageCollectionConfig {
+            grace_period: Duration::ZERO,
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not collect garbage");
+
+    assert!(report.dry_run);
+    assert!(report.marked_snapshots > 0);
+    assert!(report.marked_content > 0);
+
+    let address = ctx
+        .change_set()
+        .expect("could not get change set")
+        .workspace_snapshot_address
+        .to_string();
+    assert!(!report.workspace_snapshots.swept_keys.contains(&address));
+
+    // Nothing was actually removed
+    let component = Component::get_by_id(ctx, component.id())
+        .await
+        .expect("could not get component");
+    assert_eq!(
+        "rush",
+        component.name(ctx).await.expect("could not get name")
+    );
+}
@@ -3,8 +3,8 @@ use std::collections::HashSet;
 use dal::{
     diagram::{geometry::Geometry, view::View},
     management::{
-        prototype::ManagementPrototype, ManagementFuncReturn, ManagementGeometry,
-        ManagementOperator,
+        prototype::ManagementPrototype, ManagementError, ManagementFuncReturn, ManagementGeometry,
+        ManagementOperations, ManagementOperator,
     },
     AttributeValue, Component, DalContext, SchemaId,
 };
@@ -854,3 +854,182 @@ async fn deeply_nested_children(ctx: &DalContext) {
         component_names
     )
 }
+
+#[test]
+async fn delete_managed_components(ctx: &DalContext) {
+    let small_odd_lego = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small odd lego",
+        "small odd lego",
+    )
+    .await
+    .expect("could not create component");
+    let small_even_lego = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "small even lego",
+    )
+    .await
+    .expect("could not create component");
+
+    Component::manage_component(ctx, small_odd_lego.id(), small_even_lego.id())
+        .await
+        .expect("add manages edge");
+
+    let variant = small_odd_lego
+        .schema_variant(ctx)
+        .await
+        .expect("get variant");
+
+    let management_prototype = ManagementPrototype::list_for_variant_id(ctx, variant.id())
+        .await
+        .expect("get prototypes")
+        .into_iter()
+        .find(|proto| proto.name() == "Delete Managed")
+        .expect("could not find prototype");
+
+    let mut execution_result = management_prototype
+        .execute(ctx, small_odd_lego.id(), None)
+        .await
+        .expect("should execute management prototype func");
+
+    let result: ManagementFuncReturn = execution_result
+        .result
+        .take()
+        .expect("should have a result success")
+        .try_into()
+        .expect("should be a valid management func return");
+
+    assert_eq!(result.status, ManagementFuncStatus::Ok);
+
+    let operations = result.operations.expect("should have operations");
+
+    ManagementOperator::new(ctx, small_odd_lego.id(), operations, execution_result, None)
+        .await
+        .expect("should create operator")
+        .operate()
+        .await
+        .expect("should operate");
+
+    // The managed component has no resource, so it is removed outright
+    let components = Component::list(ctx).await.expect("list components");
+    assert_eq!(1, components.len());
+    assert_eq!(small_odd_lego.id(), components[0].id());
+}
+
+#[test]
+async fn erase_managed_components(ctx: &DalContext) {
+    let small_odd_lego = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small odd lego",
+        "small odd lego",
+    )
+    .await
+    .expect("could not create component");
+    let small_even_lego = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "small even lego",
+    )
+    .await
+    .expect("could not create component");
+
+    Component::manage_component(ctx, small_odd_lego.id(), small_even_lego.id())
+        .await
+        .expect("add manages edge");
+
+    let variant = small_odd_lego
+        .schema_variant(ctx)
+        .await
+        .expect("get variant");
+
+    let management_prototype = ManagementPrototype::list_for_variant_id(ctx, variant.id())
+        .await
+        .expect("get prototypes")
+        .into_iter()
+        .find(|proto| proto.name() == "Erase Managed")
+        .expect("could not find prototype");
+
+    let mut execution_result = management_prototype
+        .execute(ctx, small_odd_lego.id(), None)
+        .await
+        .expect("should execute management prototype func");
+
+    let result: ManagementFuncReturn = execution_result
+        .result
+        .take()
+        .expect("should have a result success")
+        .try_into()
+        .expect("should be a valid management func return");
+
+    assert_eq!(result.status, ManagementFuncStatus::Ok);
+
+    let operations = result.operations.expect("should have operations");
+
+    ManagementOperator::new(ctx, small_odd_lego.id(), operations, execution_result, None)
+        .await
+        .expect("should create operator")
+        .operate()
+        .await
+        .expect("should operate");
+
+    assert!(Component::try_get_by_id(ctx, small_even_lego.id())
+        .await
+        .expect("try get component")
+        .is_none());
+    assert!(small_odd_lego
+        .get_managed(ctx)
+        .await
+        .expect("get managed")
+        .is_empty());
+}
+
+#[test]
+async fn cannot_erase_unmanaged_components(ctx: &DalContext) {
+    let small_odd_lego = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small odd lego",
+        "small odd lego",
+    )
+    .await
+    .expect("could not create component");
+    let variant = small_odd_lego
+        .schema_variant(ctx)
+        .await
+        .expect("get variant");
+
+    let management_prototype = ManagementPrototype::list_for_variant_id(ctx, variant.id())
+        .await
+        .expect("get prototypes")
+        .into_iter()
+        .find(|proto| proto.name() == "Erase Managed")
+        .expect("could not find prototype");
+
+    let mut execution_result = management_prototype
+        .execute(ctx, small_odd_lego.id(), None)
+        .await
+        .expect("should execute management prototype func");
+    execution_result.result.take();
+
+    let operations: ManagementOperations =
+        serde_json::from_value(serde_json::json!({ "erase": ["self"] }))
+            .expect("should deserialize operations");
+
+    let result =
+        ManagementOperator::new(ctx, small_odd_lego.id(), operations, execution_result, None)
+            .await
+            .expect("should create operator")
+            .operate()
+            .await;
+
+    assert!(matches!(
+        result,
+        Err(ManagementError::ComponentNotManaged(component_id, manager_id))
+            if component_id == small_odd_lego.id() && manager_id == small_odd_lego.id()
+    ));
+
+    assert!(Component::try_get_by_id(ctx, small_odd_lego.id())
+        .await
+        .expect("try get component")
+        .is_some());
+}
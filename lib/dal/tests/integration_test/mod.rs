@@ -11,6 +11,7 @@ mod deserialize;
 mod diagram;
 mod frame;
 mod func;
+mod garbage_collection;
 mod input_sources;
 mod management;
 mod module;
@@ -22,6 +23,7 @@ mod property_editor;
 mod qualifications;
 mod rebaser;
 mod resource_metadata;
+mod schedule;
 mod schema;
 mod secret;
 mod validations;
@@ -354,3 +354,55 @@ async fn correctly_detect_unrelated_unmodified_data(ctx: &mut DalContext) -> dal
 
     Ok(())
 }
+
+#[test]
+async fn replaying_the_same_node_twice_does_not_conflict(
+    ctx: &mut DalContext,
+) -> dal_test::Result<()> {
+    let component_id =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "Shared component")
+            .await?
+            .id();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+
+    // This change set stays open while HEAD changes the shared component twice
+    let open_change_set =
+        ChangeSetTestHelpers::fork_from_head_change_set_with_name(ctx, "Open change set").await?;
+    create_component_for_default_schema_name_in_default_view(ctx, "swifty", "Unrelated component")
+        .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    for name in ["First name", "Second name"] {
+        ChangeSetTestHelpers::fork_from_head_change_set(ctx).await?;
+        Component::get_by_id(ctx, component_id)
+            .await?
+            .set_name(ctx, name)
+            .await?;
+        ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+        ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    }
+
+    // Both changes were replayed, and the second doesn't conflict with the first
+    ctx.update_visibility_and_snapshot_to_visibility(open_change_set.id)
+        .await?;
+    assert_eq!(
+        "Second name",
+        Component::get_by_id(ctx, component_id)
+            .await?
+            .name(ctx)
+            .await?
+    );
+    assert!(ctx.change_set()?.conflicts_with_base(ctx).await?.is_empty());
+
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    assert_eq!(
+        "Second name",
+        Component::get_by_id(ctx, component_id)
+            .await?
+            .name(ctx)
+            .await?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use chrono::{Duration, Utc};
+use dal::{
+    action::{prototype::ActionKind, Action, ActionId},
+    component::resource::ResourceData,
+    job::{consumer::JobConsumer, definition::RefreshResourcesJob},
+    schedule::{drift, ScheduleError, ScheduledJob, ScheduledJobKind},
+    Component, ComponentId, DalContext, Workspace, WorkspacePk, WsEvent,
+};
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use si_events::ResourceStatus;
+
+#[test]
+async fn upsert_replaces_schedule(ctx: &DalContext) {
+    let created = ScheduledJob::upsert(ctx, ScheduledJobKind::ResourceRefresh, "0 * * * *")
+        .await
+        .expect("could not upsert schedule");
+    assert!(created.enabled);
+    assert!(created.next_run_at > Utc::now() - Duration::minutes(1));
+
+    let updated = ScheduledJob::upsert(ctx, ScheduledJobKind::ResourceRefresh, " */5 * * * * ")
+        .await
+        .expect("could not upsert schedule");
+    assert_eq!(created.id, updated.id);
+    assert_eq!("*/5 * * * *", updated.schedule);
+
+    assert_eq!(
+        vec![updated.clone()],
+        ScheduledJob::list_for_workspace(ctx)
+            .await
+            .expect("could not list schedules")
+    );
+    assert_eq!(
+        Some(updated),
+        ScheduledJob::find_by_kind(ctx, ScheduledJobKind::ResourceRefresh)
+            .await
+            .expect("could not find schedule")
+    );
+
+    let result = ScheduledJob::upsert(ctx, ScheduledJobKind::ResourceRefresh, "every hour").await;
+    assert!(matches!(result, Err(ScheduleError::InvalidSchedule(_, _))));
+}
+
+#[test]
+async fn schedules_are_scoped_to_their_workspace(ctx: &mut DalContext) {
+    let scheduled_job = ScheduledJob::upsert(ctx, ScheduledJobKind::ResourceRefresh, "0 * * * *")
+        .await
+        .expect("could not upsert schedule");
+
+    // Creating a workspace switches the context over to it
+    Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "other workspace", "token")
+        .await
+        .expect("could not create workspace");
+    assert_ne!(
+        scheduled_job.workspace_pk,
+        ctx.workspace_pk().expect("could not get workspace pk")
+    );
+
+    assert!(ScheduledJob::list_for_workspace(ctx)
+        .await
+        .expect("could not list schedules")
+        .is_empty());
+    assert!(
+        ScheduledJob::find_by_kind(ctx, ScheduledJobKind::ResourceRefresh)
+            .await
+            .expect("could not find schedule")
+            .is_none()
+    );
+    assert!(ScheduledJob::get_by_id(ctx, scheduled_job.id)
+        .await
+        .expect("could not get schedule")
+        .is_none());
+
+    let mut other_workspace_job = scheduled_job.clone();
+    assert!(matches!(
+        other_workspace_job.set_enabled(ctx, false).await,
+        Err(ScheduleError::ScheduledJobNotFound(id)) if id == scheduled_job.id
+    ));
+    assert!(matches!(
+        other_workspace_job.delete(ctx).await,
+        Err(ScheduleError::ScheduledJobNotFound(id)) if id == scheduled_job.id
+    ));
+}
+
+#[test]
+async fn enqueue_due_runs_each_due_schedule_once(ctx: &DalContext) {
+    let scheduled_job = ScheduledJob::upsert(ctx, ScheduledJobKind::ResourceRefresh, "0 * * * *")
+        .await
+        .expect("could not upsert schedule");
+
+    // Nothing is due before the next run
+    let before = scheduled_job.next_run_at - Duration::seconds(1);
+    ScheduledJob::enqueue_due(ctx, before)
+        .await
+        .expect("could not enqueue due schedules");
+    assert_eq!(Some(scheduled_job.clone()), find(ctx).await);
+
+    // Even when many runs were missed, the schedule runs once and moves on to its next run
+    let now = scheduled_job.next_run_at + Duration::hours(5) + Duration::minutes(30);
+    let enqueued = ScheduledJob::enqueue_due(ctx, now)
+        .await
+        .expect("could not enqueue due schedules");
+    assert!(enqueued >= 1);
+    let ran = find(ctx).await.expect("schedule not found");
+    assert_eq!(Some(now), ran.last_run_at);
+    assert_eq!(
+        scheduled_job.next_run_at + Duration::hours(6),
+        ran.next_run_at
+    );
+
+    // Running again at the same time finds nothing due
+    ScheduledJob::enqueue_due(ctx, now)
+        .await
+        .expect("could not enqueue due schedules");
+    assert_eq!(Some(ran.clone()), find(ctx).await);
+
+    // Paused schedules are skipped, and resuming picks up from now
+    let mut paused = ran.clone();
+    paused
+        .set_enabled(ctx, false)
+        .await
+        .expect("could not pause schedule");
+    ScheduledJob::enqueue_due(ctx, ran.next_run_at + Duration::days(1))
+        .await
+        .expect("could not enqueue due schedules");
+    let still_paused = find(ctx).await.expect("schedule not found");
+    assert!(!still_paused.enabled);
+    assert_eq!(ran.last_run_at, still_paused.last_run_at);
+
+    paused
+        .set_enabled(ctx, true)
+        .await
+        .expect("could not resume schedule");
+    assert!(paused.enabled);
+    assert!(paused.next_run_at > Utc::now());
+
+    paused.delete(ctx).await.expect("could not delete schedule");
+    assert_eq!(None, find(ctx).await);
+}
+
+#[test]
+async fn refresh_resources_job_enqueues_refresh_actions(ctx: &mut DalContext) {
+    let with_resource =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "with resource")
+            .await
+            .expect("could not create component");
+    with_resource
+        .set_resource(
+            ctx,
+            ResourceData::new(
+                ResourceStatus::Ok,
+                Some(serde_json::json!({ "poop": true })),
+            ),
+        )
+        .await
+        .expect("could not set resource");
+    let without_resource =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "no resource")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Running the job twice shouldn't pile up refreshes
+    for _ in 0..2 {
+        let job = RefreshResourcesJob::new(ctx.access_builder(), *ctx.visibility());
+        job.run(ctx).await.expect("could not run job");
+        ctx.update_snapshot_to_visibility()
+            .await
+            .expect("could not update snapshot to visibility");
+
+        assert_eq!(
+            1,
+            refresh_actions(ctx, &with_resource).await.len(),
+            "component with a resource should have one refresh action"
+        );
+        assert!(refresh_actions(ctx, &without_resource).await.is_empty());
+    }
+}
+
+#[test]
+async fn resource_drift_detected_event(ctx: &DalContext) {
+    let component_id = ComponentId::generate();
+    let action_id = ActionId::generate();
+    let last_synced = Utc::now();
+    let changed_paths = drift::changed_paths(
+        &serde_json::json!({ "size": 1, "tags": ["a"] }),
+        &serde_json::json!({ "size": 2, "tags": ["a"], "owner": "me" }),
+    );
+    assert_eq!(vec!["/owner", "/size"], changed_paths);
+
+    let event = WsEvent::resource_drift_detected(
+        ctx,
+        component_id,
+        action_id,
+        changed_paths.clone(),
+        last_synced,
+    )
+    .await
+    .expect("could not create event");
+
+    let event = serde_json::to_value(event).expect("could not serialize event");
+    assert_eq!(
+        serde_json::json!({
+            "kind": "ResourceDriftDetected",
+            "data": {
+                "componentId": component_id,
+                "actionId": action_id,
+                "changedPaths": changed_paths,
+                "lastSynced": last_synced,
+                "changeSetId": ctx.change_set_id(),
+            },
+        }),
+        event["payload"]
+    );
+}
+
+async fn find(ctx: &DalContext) -> Option<ScheduledJob> {
+    ScheduledJob::find_by_kind(ctx, ScheduledJobKind::ResourceRefresh)
+        .await
+        .expect("could not find schedule")
+}
+
+async fn refresh_actions(ctx: &DalContext, component: &Component) -> Vec<ActionId> {
+    Action::find_for_kind_and_component_id(ctx, component.id(), ActionKind::Refresh)
+        .await
+        .expect("could not find actions")
+}
@@ -3,7 +3,10 @@ use dal::prop::PropPath;
 use dal::property_editor::values::PropertyEditorValues;
 use dal::qualification::QualificationSubCheckStatus;
 use dal::secret::DecryptedSecret;
-use dal::{Component, DalContext, EncryptedSecret, Prop, Secret, SecretAlgorithm, SecretVersion};
+use dal::{
+    Component, DalContext, EncryptedSecret, KeyPair, KeyPairError, Prop, PublicKey, Secret,
+    SecretAlgorithm, SecretError, SecretVersion, Workspace, WorkspacePk,
+};
 use dal_test::expected::{self, ExpectComponent, ExpectView};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, encrypt_message, ChangeSetTestHelpers,
@@ -11,6 +14,7 @@ use dal_test::helpers::{
 use dal_test::{helpers::generate_fake_name, test, WorkspaceSignup};
 use pretty_assertions_sorted::assert_eq;
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 mod with_actions;
 mod with_schema_variant_authoring;
@@ -259,6 +263,131 @@ async fn update_metadata_and_encrypted_contents(ctx: &DalContext, nw: &Workspace
     );
 }
 
+#[test]
+async fn rotate_and_retire_key_pair(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let original_key_pair_pk = nw.key_pair.pk();
+    let message = serde_json::json!({"song": "Cold Cold Cold", "artist": "Cage the Elephant"});
+    let crypted = encrypt_message(ctx, original_key_pair_pk, &message)
+        .await
+        .expect("could not encrypt message");
+    let secret = Secret::new(
+        ctx,
+        generate_fake_name().expect("could not generate fake name"),
+        "Mock",
+        None,
+        &crypted,
+        original_key_pair_pk,
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await
+    .expect("failed to create secret");
+    assert_eq!(
+        Some(original_key_pair_pk),
+        secret
+            .key_pair_pk(ctx)
+            .await
+            .expect("could not get key pair pk")
+    );
+
+    // The current key pair can't be retired.
+    let err = KeyPair::get_by_pk(ctx, original_key_pair_pk)
+        .await
+        .expect("could not get key pair")
+        .retire(ctx)
+        .await
+        .expect_err("retired the current key pair");
+    assert!(matches!(err, KeyPairError::CannotRetireCurrentKeyPair(_)));
+
+    // Rotate, which re-seals the secret to the new current key pair.
+    let rotation = Secret::rotate_key_pair(ctx)
+        .await
+        .expect("could not rotate key pair");
+    assert_ne!(original_key_pair_pk, rotation.key_pair_pk);
+    assert_eq!(vec![original_key_pair_pk], rotation.previous_key_pair_pks);
+    assert_eq!(vec![secret.id()], rotation.resealed);
+    assert!(rotation.undecryptable.is_empty());
+    assert_eq!(
+        rotation.key_pair_pk,
+        *PublicKey::get_current(ctx)
+            .await
+            .expect("could not get current public key")
+            .pk()
+    );
+    assert_eq!(
+        BTreeMap::from([(rotation.key_pair_pk, vec![secret.id()])]),
+        Secret::list_by_key_pair(ctx)
+            .await
+            .expect("could not list secrets by key pair")
+    );
+
+    // Retire the old key pair and ensure the secret still decrypts to the original message.
+    Secret::retire_key_pair(ctx, original_key_pair_pk)
+        .await
+        .expect("could not retire key pair");
+    assert!(matches!(
+        KeyPair::get_by_pk(ctx, original_key_pair_pk).await,
+        Err(KeyPairError::KeyPairNotFound(_))
+    ));
+
+    let resealed_secret = Secret::get_by_id_or_error(ctx, secret.id())
+        .await
+        .expect("could not perform get by id or secret not found");
+    assert_ne!(
+        secret.encrypted_secret_key(),
+        resealed_secret.encrypted_secret_key()
+    );
+    assert!(resealed_secret
+        .can_be_decrypted(ctx)
+        .await
+        .expect("could not check if secret can be decrypted"));
+    let decrypted = EncryptedSecret::get_by_key(ctx, resealed_secret.encrypted_secret_key())
+        .await
+        .expect("failed to perform get by key for encrypted secret")
+        .expect("no encrypted secret found")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    assert_eq!(message, prepare_decrypted_secret_for_assertions(&decrypted));
+
+    // The new key pair is still in use, so it can't be retired.
+    let err = Secret::retire_key_pair(ctx, rotation.key_pair_pk)
+        .await
+        .expect_err("retired a key pair which is in use");
+    assert!(matches!(
+        err,
+        SecretError::KeyPairRetirementBlocked(key_pair_pk, _) if key_pair_pk == rotation.key_pair_pk
+    ));
+}
+
+#[test]
+async fn key_pairs_from_other_workspaces_cannot_be_retired(
+    ctx: &mut DalContext,
+    nw: &WorkspaceSignup,
+) {
+    let original_key_pair_pk = nw.key_pair.pk();
+    Secret::rotate_key_pair(ctx)
+        .await
+        .expect("could not rotate key pair");
+    let original_key_pair = KeyPair::get_by_pk(ctx, original_key_pair_pk)
+        .await
+        .expect("could not get key pair");
+
+    // Creating a workspace switches the context over to it
+    Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "other workspace", "token")
+        .await
+        .expect("could not create workspace");
+
+    assert!(matches!(
+        Secret::retire_key_pair(ctx, original_key_pair_pk).await,
+        Err(SecretError::KeyPair(KeyPairError::UnauthorizedKeyAccess))
+    ));
+    assert!(matches!(
+        original_key_pair.retire(ctx).await,
+        Err(KeyPairError::UnauthorizedKeyAccess)
+    ));
+}
+
 #[test]
 async fn copy_paste_component_with_secrets_being_used(ctx: &mut DalContext, nw: &WorkspaceSignup) {
     // Create a component and commit.
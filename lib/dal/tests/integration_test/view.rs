@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use dal::{
     diagram::{geometry::Geometry, view::View, Diagram, DiagramError},
     workspace_snapshot::graph::WorkspaceSnapshotGraphError,
@@ -364,3 +366,25 @@ async fn remove_view_that_previously_contained_another_view_that_has_been_remove
         View::list(ctx).await.expect("Unable to list Views").len(),
     );
 }
+
+#[test]
+async fn set_tags_and_rename_view(ctx: &mut DalContext) {
+    let mut view = View::new(ctx, "tagged")
+        .await
+        .expect("could not create view");
+    assert!(view.tags().is_empty());
+
+    let tags = BTreeMap::from([("env".to_owned(), "prod".to_owned())]);
+    view.set_tags(ctx, tags.clone())
+        .await
+        .expect("could not set tags");
+    view.set_name(ctx, "renamed")
+        .await
+        .expect("could not rename view");
+
+    let view = View::get_by_id(ctx, view.id())
+        .await
+        .expect("could not get view");
+    assert_eq!("renamed", view.name());
+    assert_eq!(&tags, view.tags());
+}
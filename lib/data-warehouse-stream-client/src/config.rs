@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgPoolConfig;
+
+const DEFAULT_FILE_PREFIX: &str = "records";
+const DEFAULT_MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_MAX_FILE_AGE_SECS: u64 = 60 * 60;
+const DEFAULT_TABLE: &str = "data_warehouse_records";
+
+/// Selects the sink that a [`DataWarehouseStreamClient`](crate::DataWarehouseStreamClient) is
+/// created for, by its `kind`.
+#[remain::sorted]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataWarehouseStreamConfig {
+    /// Appends records to rotating newline-delimited JSON files.
+    File(FileStreamConfig),
+    /// Puts records onto an AWS Kinesis Firehose delivery stream.
+    Firehose {
+        /// The name of the delivery stream.
+        delivery_stream_name: String,
+    },
+    /// Posts each record to an HTTP endpoint.
+    Http(HttpStreamConfig),
+    /// Inserts each record into a PostgreSQL table.
+    Postgres(PostgresStreamConfig),
+}
+
+/// The configuration for a [`FileStreamClient`](crate::FileStreamClient).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FileStreamConfig {
+    /// The directory that files are written to, which is created if it doesn't exist.
+    pub directory: PathBuf,
+    /// The prefix of each file name.
+    pub file_prefix: String,
+    /// The size in bytes after which a new file is started.
+    pub max_file_bytes: u64,
+    /// The age in seconds after which a new file is started.
+    pub max_file_age_secs: u64,
+}
+
+impl Default for FileStreamConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("data-warehouse"),
+            file_prefix: DEFAULT_FILE_PREFIX.to_string(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_file_age_secs: DEFAULT_MAX_FILE_AGE_SECS,
+        }
+    }
+}
+
+/// The configuration for an [`HttpStreamClient`](crate::HttpStreamClient).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HttpStreamConfig {
+    /// The URL that each record is posted to.
+    pub url: String,
+}
+
+/// The configuration for a [`PostgresStreamClient`](crate::PostgresStreamClient).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PostgresStreamConfig {
+    /// The configuration for the PostgreSQL pool.
+    ///
+    /// _Note:_ this is called "pg" for ease of use with layered load configuration files.
+    pub pg: PgPoolConfig,
+    /// The table that records are inserted into, which is created if it doesn't exist.
+    pub table: String,
+}
+
+impl Default for PostgresStreamConfig {
+    fn default() -> Self {
+        Self {
+            pg: PgPoolConfig::default(),
+            table: DEFAULT_TABLE.to_string(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use telemetry::prelude::*;
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+use ulid::Ulid;
+
+use crate::{DataWarehouseStreamClient, DataWarehouseStreamClientResult, FileStreamConfig};
+
+/// A client which appends records to newline-delimited JSON files in a directory.
+///
+/// A new file is started once the current one reaches a maximum size or age. File names end in a
+/// ULID, so sorting them by name gives the files in the order they were written.
+#[derive(Debug)]
+pub struct FileStreamClient {
+    directory: PathBuf,
+    file_prefix: String,
+    max_file_bytes: u64,
+    max_file_age: Duration,
+    current: Mutex<Option<CurrentFile>>,
+}
+
+#[derive(Debug)]
+struct CurrentFile {
+    file: File,
+    path: PathBuf,
+    bytes_written: u64,
+    opened_at: Instant,
+}
+
+impl FileStreamClient {
+    /// Creates a new [client for rotating files](FileStreamClient), creating its directory if it
+    /// doesn't exist.
+    #[instrument(
+        name = "data_warehouse_stream_client.file.new",
+        level = "info",
+        skip_all,
+        fields(directory = %config.directory.display())
+    )]
+    pub async fn new(config: &FileStreamConfig) -> DataWarehouseStreamClientResult<Self> {
+        fs::create_dir_all(&config.directory).await?;
+
+        Ok(Self {
+            directory: config.directory.clone(),
+            file_prefix: config.file_prefix.clone(),
+            max_file_bytes: config.max_file_bytes,
+            max_file_age: Duration::from_secs(config.max_file_age_secs),
+            current: Mutex::new(None),
+        })
+    }
+
+    fn needs_rotation(&self, current: &CurrentFile, len: u64) -> bool {
+        // Never rotate away from an empty file, even if a single record is over the limit
+        (current.bytes_written > 0 && current.bytes_written + len > self.max_file_bytes)
+            || current.opened_at.elapsed() >= self.max_file_age
+    }
+
+    async fn open_file(&self) -> DataWarehouseStreamClientResult<CurrentFile> {
+        let path = self
+            .directory
+            .join(format!("{}-{}.ndjson", self.file_prefix, Ulid::new()));
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        debug!(path = %path.display(), "opened new data warehouse stream file");
+
+        Ok(CurrentFile {
+            file,
+            path,
+            bytes_written: 0,
+            opened_at: Instant::now(),
+        })
+    }
+}
+
+#[async_trait]
+impl DataWarehouseStreamClient for FileStreamClient {
+    #[instrument(
+        name = "data_warehouse_stream_client.file.publish",
+        level = "debug",
+        skip_all
+    )]
+    async fn publish(&self, raw_data: &[u8]) -> DataWarehouseStreamClientResult<()> {
+        let mut line = Vec::with_capacity(raw_data.len() + 1);
+        line.extend_from_slice(raw_data);
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        let mut guard = self.current.lock().await;
+        if let Some(current) = guard.as_mut() {
+            if self.needs_rotation(current, len) {
+                current.file.flush().await?;
+                debug!(path = %current.path.display(), "rotating data warehouse stream file");
+                *guard = None;
+            }
+        }
+        let current = match guard.as_mut() {
+            Some(current) => current,
+            None => guard.insert(self.open_file().await?),
+        };
+
+        // A single write per record so that a line is never interleaved with another, flushed so
+        // that a tokio file doesn't hold the record back in its background write
+        current.file.write_all(&line).await?;
+        current.file.flush().await?;
+        current.bytes_written += len;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    async fn written_files(directory: &Path) -> Vec<String> {
+        let mut entries = fs::read_dir(directory).await.expect("failed to read dir");
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await.expect("failed to read entry") {
+            files.push(entry.path());
+        }
+        files.sort();
+
+        let mut contents = Vec::new();
+        for file in files {
+            contents.push(fs::read_to_string(file).await.expect("failed to read file"));
+        }
+        contents
+    }
+
+    #[tokio::test]
+    async fn rotates_files_by_size() {
+        let tempdir = tempfile::tempdir().expect("failed to create tempdir");
+        let client = FileStreamClient::new(&FileStreamConfig {
+            directory: tempdir.path().to_path_buf(),
+            max_file_bytes: 20,
+            ..Default::default()
+        })
+        .await
+        .expect("failed to create client");
+
+        for record in [r#"{"n":1}"#, r#"{"n":2}"#, r#"{"n":3}"#] {
+            client
+                .publish(record.as_bytes())
+                .await
+                .expect("failed to publish");
+        }
+        if let Some(current) = client.current.lock().await.as_mut() {
+            current.file.flush().await.expect("failed to flush");
+        }
+
+        assert_eq!(
+            vec![
+                "{\"n\":1}\n{\"n\":2}\n".to_string(),
+                "{\"n\":3}\n".to_string()
+            ],
+            written_files(tempdir.path()).await,
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use aws_sdk_firehose::{primitives::Blob, types::Record};
+use telemetry::prelude::*;
+
+use crate::{DataWarehouseStreamClient, DataWarehouseStreamClientResult};
+
+/// A client which puts records onto an AWS Kinesis Firehose delivery stream.
+#[derive(Debug, Clone)]
+pub struct FirehoseStreamClient {
+    delivery_stream_name: String,
+    inner: Box<aws_sdk_firehose::Client>,
+}
+
+impl FirehoseStreamClient {
+    /// Creates a new [client for a Firehose delivery stream](FirehoseStreamClient), loading AWS
+    /// configuration from the environment.
+    #[instrument(
+        name = "data_warehouse_stream_client.firehose.new",
+        level = "info",
+        skip(delivery_stream_name)
+    )]
+    pub async fn new(delivery_stream_name: impl Into<String>) -> Self {
+        let config = aws_config::load_from_env().await;
+        let client = aws_sdk_firehose::Client::new(&config);
+        Self {
+            inner: Box::new(client),
+            delivery_stream_name: delivery_stream_name.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl DataWarehouseStreamClient for FirehoseStreamClient {
+    #[instrument(
+        name = "data_warehouse_stream_client.firehose.publish",
+        level = "debug",
+        skip_all
+    )]
+    async fn publish(&self, raw_data: &[u8]) -> DataWarehouseStreamClientResult<()> {
+        let record = Record::builder().data(Blob::new(raw_data)).build()?;
+        let output = self
+            .inner
+            .put_record()
+            .delivery_stream_name(&self.delivery_stream_name)
+            .record(record)
+            .send()
+            .await?;
+        debug!(
+            ?output,
+            "output from sending put record request to kinesis firehose stream"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use telemetry::prelude::*;
+
+use crate::{DataWarehouseStreamClient, DataWarehouseStreamClientResult, HttpStreamConfig};
+
+/// A client which posts each record as a JSON body to an HTTP endpoint.
+#[derive(Debug, Clone)]
+pub struct HttpStreamClient {
+    url: String,
+    inner: reqwest::Client,
+}
+
+impl HttpStreamClient {
+    /// Creates a new [client for an HTTP endpoint](HttpStreamClient).
+    pub fn new(config: &HttpStreamConfig) -> Self {
+        Self {
+            url: config.url.clone(),
+            inner: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl DataWarehouseStreamClient for HttpStreamClient {
+    #[instrument(
+        name = "data_warehouse_stream_client.http.publish",
+        level = "debug",
+        skip_all,
+        fields(url = %self.url)
+    )]
+    async fn publish(&self, raw_data: &[u8]) -> DataWarehouseStreamClientResult<()> {
+        let response = self
+            .inner
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(raw_data.to_vec())
+            .send()
+            .await?
+            .error_for_status()?;
+        debug!(status = %response.status(), "posted record to data warehouse http endpoint");
+        Ok(())
+    }
+}
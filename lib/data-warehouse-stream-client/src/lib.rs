@@ -1,4 +1,13 @@
-//! This crate provides a client for streaming data directly or eventually to a data warehouse.
+//! This crate provides clients for streaming data directly or eventually to a data warehouse.
+//!
+//! Every client implements [`DataWarehouseStreamClient`], with one implementation per sink:
+//!
+//! - [`FirehoseStreamClient`]: puts records onto an AWS Kinesis Firehose delivery stream
+//! - [`FileStreamClient`]: appends records to rotating newline-delimited JSON files
+//! - [`HttpStreamClient`]: posts each record to an HTTP endpoint
+//! - [`PostgresStreamClient`]: inserts each record into a PostgreSQL table
+//!
+//! The sink is usually chosen from a [`DataWarehouseStreamConfig`] with [`from_config`].
 
 #![warn(
     bad_style,
@@ -25,10 +34,28 @@
     while_true
 )]
 
-use aws_sdk_firehose::{operation::put_record::PutRecordError, primitives::Blob, types::Record};
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use aws_sdk_firehose::operation::put_record::PutRecordError;
+use si_data_pg::{PgError, PgPoolError};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+mod config;
+mod file;
+mod firehose;
+mod http;
+mod postgres;
+
+pub use config::{
+    DataWarehouseStreamConfig, FileStreamConfig, HttpStreamConfig, PostgresStreamConfig,
+};
+pub use file::FileStreamClient;
+pub use firehose::FirehoseStreamClient;
+pub use http::HttpStreamClient;
+pub use postgres::PostgresStreamClient;
+
 #[allow(missing_docs)]
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -39,54 +66,54 @@ pub enum DataWarehouseStreamClientError {
     FirehoseBuild(#[from] aws_sdk_firehose::error::BuildError),
     #[error("firehose put record error: {0}")]
     FirehosePutRecord(#[from] aws_sdk_firehose::error::SdkError<PutRecordError>),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("invalid table name: {0}")]
+    InvalidTableName(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
 }
 
-type DataWarehouseStreamClientResult<T> = Result<T, DataWarehouseStreamClientError>;
+/// The result type returned by [`DataWarehouseStreamClient`] implementations.
+pub type DataWarehouseStreamClientResult<T> = Result<T, DataWarehouseStreamClientError>;
+
+/// A shareable handle to a [`DataWarehouseStreamClient`] chosen at runtime.
+pub type DynDataWarehouseStreamClient = Arc<dyn DataWarehouseStreamClient>;
 
 /// A client for communicating with a stream to a data warehouse.
-#[derive(Debug, Clone)]
-pub struct DataWarehouseStreamClient {
-    delivery_stream_name: String,
-    inner: Box<aws_sdk_firehose::Client>,
+///
+/// Records are expected to be serialized JSON documents.
+#[async_trait]
+pub trait DataWarehouseStreamClient: fmt::Debug + Send + Sync {
+    /// Publishes a message to a stream to a data warehouse.
+    async fn publish(&self, raw_data: &[u8]) -> DataWarehouseStreamClientResult<()>;
 }
 
-impl DataWarehouseStreamClient {
-    /// Creates a new [client for communicating with a stream to a data warehouse](DataWarehouseStreamClient).
-    #[instrument(
-        name = "data_warehouse_stream_client.new",
-        level = "info",
-        skip(delivery_stream_name)
-    )]
-    pub async fn new(delivery_stream_name: impl Into<String>) -> Self {
-        let config = aws_config::load_from_env().await;
-        let client = aws_sdk_firehose::Client::new(&config);
-        Self {
-            inner: Box::new(client),
-            delivery_stream_name: delivery_stream_name.into(),
+/// Creates a [`DataWarehouseStreamClient`] for the sink described by the config.
+#[instrument(
+    name = "data_warehouse_stream_client.from_config",
+    level = "info",
+    skip_all
+)]
+pub async fn from_config(
+    config: &DataWarehouseStreamConfig,
+) -> DataWarehouseStreamClientResult<DynDataWarehouseStreamClient> {
+    let client: DynDataWarehouseStreamClient = match config {
+        DataWarehouseStreamConfig::File(config) => Arc::new(FileStreamClient::new(config).await?),
+        DataWarehouseStreamConfig::Firehose {
+            delivery_stream_name,
+        } => Arc::new(FirehoseStreamClient::new(delivery_stream_name).await),
+        DataWarehouseStreamConfig::Http(config) => Arc::new(HttpStreamClient::new(config)),
+        DataWarehouseStreamConfig::Postgres(config) => {
+            Arc::new(PostgresStreamClient::new(config).await?)
         }
-    }
+    };
 
-    /// Publishes a message to a stream to a data warehouse.
-    #[instrument(
-        name = "data_warehouse_stream_client.publish",
-        level = "debug",
-        skip(raw_data)
-    )]
-    pub async fn publish(&self, raw_data: impl AsRef<[u8]>) -> DataWarehouseStreamClientResult<()> {
-        let record = Record::builder()
-            .data(Blob::new(raw_data.as_ref()))
-            .build()?;
-        let output = self
-            .inner
-            .put_record()
-            .delivery_stream_name(&self.delivery_stream_name)
-            .record(record)
-            .send()
-            .await?;
-        debug!(
-            ?output,
-            "output from sending put record request to kinesis firehose stream"
-        );
-        Ok(())
-    }
+    Ok(client)
 }
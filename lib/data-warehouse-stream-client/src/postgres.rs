@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use si_data_pg::PgPool;
+use telemetry::prelude::*;
+
+use crate::{
+    DataWarehouseStreamClient, DataWarehouseStreamClientError, DataWarehouseStreamClientResult,
+    PostgresStreamConfig,
+};
+
+/// A client which inserts each record into a PostgreSQL table as a `jsonb` value.
+#[derive(Debug, Clone)]
+pub struct PostgresStreamClient {
+    pg_pool: PgPool,
+    insert_statement: String,
+}
+
+impl PostgresStreamClient {
+    /// Creates a new [client for a PostgreSQL table](PostgresStreamClient), creating the table if
+    /// it doesn't exist.
+    #[instrument(
+        name = "data_warehouse_stream_client.postgres.new",
+        level = "info",
+        skip_all,
+        fields(table = %config.table)
+    )]
+    pub async fn new(config: &PostgresStreamConfig) -> DataWarehouseStreamClientResult<Self> {
+        let table = &config.table;
+        // The table name is interpolated into statements, so only accept plain identifiers
+        let mut chars = table.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            return Err(DataWarehouseStreamClientError::InvalidTableName(
+                table.to_owned(),
+            ));
+        }
+
+        let pg_pool = PgPool::new(&config.pg).await?;
+        pg_pool
+            .get()
+            .await?
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {table} (
+                        id bigserial PRIMARY KEY,
+                        received_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+                        record jsonb NOT NULL
+                    )"
+                ),
+                &[],
+            )
+            .await?;
+
+        Ok(Self {
+            pg_pool,
+            insert_statement: format!("INSERT INTO {table} (record) VALUES ($1)"),
+        })
+    }
+}
+
+#[async_trait]
+impl DataWarehouseStreamClient for PostgresStreamClient {
+    #[instrument(
+        name = "data_warehouse_stream_client.postgres.publish",
+        level = "debug",
+        skip_all
+    )]
+    async fn publish(&self, raw_data: &[u8]) -> DataWarehouseStreamClientResult<()> {
+        let record: serde_json::Value = serde_json::from_slice(raw_data)?;
+        self.pg_pool
+            .get()
+            .await?
+            .execute(&self.insert_statement, &[&record])
+            .await?;
+        Ok(())
+    }
+}
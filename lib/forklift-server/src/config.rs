@@ -2,6 +2,7 @@ use std::{env, path::Path};
 
 use audit_database::AuditDatabaseConfig;
 use buck2_resources::Buck2Resources;
+use data_warehouse_stream_client::DataWarehouseStreamConfig;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
@@ -50,8 +51,8 @@ pub struct Config {
     #[builder(default = "NatsConfig::default()")]
     nats: NatsConfig,
 
-    #[builder(default = "default_data_warehouse_stream_name()")]
-    data_warehouse_stream_name: Option<String>,
+    #[builder(default)]
+    data_warehouse_stream: Option<DataWarehouseStreamConfig>,
 
     #[builder(default = "default_enable_audit_logs_app()")]
     enable_audit_logs_app: bool,
@@ -86,9 +87,9 @@ impl Config {
         self.nats.subject_prefix.as_deref()
     }
 
-    /// Gets a reference to the (optional) data warehouse stream name.
-    pub fn data_warehouse_stream_name(&self) -> Option<&str> {
-        self.data_warehouse_stream_name.as_deref()
+    /// Gets a reference to the (optional) data warehouse stream config.
+    pub fn data_warehouse_stream(&self) -> Option<&DataWarehouseStreamConfig> {
+        self.data_warehouse_stream.as_ref()
     }
 
     /// Indicates whether or not the audit logs app will be enabled.
@@ -113,6 +114,8 @@ pub struct ConfigFile {
     pub nats: NatsConfig,
     #[serde(default = "default_data_warehouse_stream_name")]
     pub data_warehouse_stream_name: Option<String>,
+    #[serde(default)]
+    pub data_warehouse_stream: Option<DataWarehouseStreamConfig>,
     #[serde(default = "default_enable_audit_logs_app")]
     pub enable_audit_logs_app: bool,
     #[serde(default)]
@@ -126,6 +129,7 @@ impl Default for ConfigFile {
             concurrency_limit: default_concurrency_limit(),
             nats: Default::default(),
             data_warehouse_stream_name: default_data_warehouse_stream_name(),
+            data_warehouse_stream: None,
             enable_audit_logs_app: default_enable_audit_logs_app(),
             audit: Default::default(),
         }
@@ -146,7 +150,14 @@ impl TryFrom<ConfigFile> for Config {
             instance_id: value.instance_id,
             concurrency_limit: value.concurrency_limit,
             nats: value.nats,
-            data_warehouse_stream_name: value.data_warehouse_stream_name,
+            // A bare stream name is shorthand for a Firehose delivery stream
+            data_warehouse_stream: value.data_warehouse_stream.or_else(|| {
+                value
+                    .data_warehouse_stream_name
+                    .map(|delivery_stream_name| DataWarehouseStreamConfig::Firehose {
+                        delivery_stream_name,
+                    })
+            }),
             enable_audit_logs_app: value.enable_audit_logs_app,
             audit: value.audit,
         })
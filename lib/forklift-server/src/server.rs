@@ -1,6 +1,7 @@
 use std::{fmt, future::Future, io, sync::Arc};
 
 use audit_database::{AuditDatabaseContext, AuditDatabaseContextError};
+use data_warehouse_stream_client::DataWarehouseStreamConfig;
 use si_data_nats::{jetstream, ConnectionMetadata, NatsClient};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -94,7 +95,7 @@ impl Server {
             config.instance_id(),
             config.concurrency_limit(),
             audit_bag,
-            config.data_warehouse_stream_name(),
+            config.data_warehouse_stream(),
             token,
         )
         .await
@@ -108,7 +109,7 @@ impl Server {
         instance_id: &str,
         concurrency_limit: usize,
         audit_bag: Option<(AuditDatabaseContext, usize)>,
-        data_warehouse_stream_name: Option<&str>,
+        data_warehouse_stream: Option<&DataWarehouseStreamConfig>,
         token: CancellationToken,
     ) -> Result<Self> {
         let metadata = Arc::new(ServerMetadata {
@@ -137,7 +138,7 @@ impl Server {
             DURABLE_CONSUMER_NAME.to_string(),
             connection_metadata,
             concurrency_limit,
-            data_warehouse_stream_name,
+            data_warehouse_stream,
             token.clone(),
         )
         .await?;
@@ -1,6 +1,7 @@
 use std::{future::Future, io, sync::Arc};
 
 use audit_database::AuditDatabaseContext;
+use data_warehouse_stream_client::DataWarehouseStreamConfig;
 use si_data_nats::{jetstream::Context, ConnectionMetadata};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -58,7 +59,7 @@ pub(crate) async fn billing_events(
     durable_consumer_name: String,
     connection_metadata: Arc<ConnectionMetadata>,
     concurrency_limit: usize,
-    data_warehouse_stream_name: Option<&str>,
+    data_warehouse_stream: Option<&DataWarehouseStreamConfig>,
     token: CancellationToken,
 ) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
     Ok(billing_events::build_and_run(
@@ -66,7 +67,7 @@ pub(crate) async fn billing_events(
         durable_consumer_name,
         connection_metadata,
         concurrency_limit,
-        data_warehouse_stream_name,
+        data_warehouse_stream,
         token,
     )
     .await?)
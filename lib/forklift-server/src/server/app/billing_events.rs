@@ -6,7 +6,7 @@ use std::{
 
 use app_state::{AppState, NoopAppState};
 use billing_events::{BillingEventsError, BillingEventsWorkQueue};
-use data_warehouse_stream_client::DataWarehouseStreamClient;
+use data_warehouse_stream_client::{DataWarehouseStreamClientError, DataWarehouseStreamConfig};
 use naxum::{
     extract::MatchedSubject,
     handler::Handler as _,
@@ -45,6 +45,8 @@ pub enum BillingEventsAppSetupError {
     AsyncNatsStream(#[from] AsyncNatsError<StreamErrorKind>),
     #[error("billing events error: {0}")]
     BillingEvents(#[from] BillingEventsError),
+    #[error("data warehouse stream client error: {0}")]
+    DataWarehouseStreamClient(#[from] DataWarehouseStreamClientError),
 }
 
 type Result<T> = std::result::Result<T, BillingEventsAppSetupError>;
@@ -59,7 +61,7 @@ pub(crate) async fn build_and_run(
     durable_consumer_name: String,
     connection_metadata: Arc<ConnectionMetadata>,
     concurrency_limit: usize,
-    data_warehouse_stream_name: Option<&str>,
+    data_warehouse_stream: Option<&DataWarehouseStreamConfig>,
     token: CancellationToken,
 ) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
     let incoming = {
@@ -78,10 +80,13 @@ pub(crate) async fn build_and_run(
             .await?
     };
 
-    let inner = match data_warehouse_stream_name {
-        Some(stream_name) => {
-            info!(%stream_name, "creating billing events app in data warehouse stream delivery mode...");
-            let client = DataWarehouseStreamClient::new(stream_name).await;
+    let inner = match data_warehouse_stream {
+        Some(stream_config) => {
+            info!(
+                ?stream_config,
+                "creating billing events app in data warehouse stream delivery mode..."
+            );
+            let client = data_warehouse_stream_client::from_config(stream_config).await?;
             let state = AppState::new(client);
             build_app(
                 state,
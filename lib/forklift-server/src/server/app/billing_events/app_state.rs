@@ -1,12 +1,12 @@
-use data_warehouse_stream_client::DataWarehouseStreamClient;
+use data_warehouse_stream_client::DynDataWarehouseStreamClient;
 
 #[derive(Debug, Clone)]
 pub(crate) struct AppState {
-    pub(crate) data_warehouse_stream_client: DataWarehouseStreamClient,
+    pub(crate) data_warehouse_stream_client: DynDataWarehouseStreamClient,
 }
 
 impl AppState {
-    pub(crate) fn new(data_warehouse_stream_client: DataWarehouseStreamClient) -> Self {
+    pub(crate) fn new(data_warehouse_stream_client: DynDataWarehouseStreamClient) -> Self {
         Self {
             data_warehouse_stream_client,
         }
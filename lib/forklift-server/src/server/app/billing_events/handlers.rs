@@ -42,7 +42,7 @@ pub(crate) async fn process_request(
     let serialized_request = serde_json::to_vec(&request)?;
     state
         .data_warehouse_stream_client
-        .publish(serialized_request)
+        .publish(&serialized_request)
         .await?;
 
     info!(kind = ?request.kind, ?request, "processed billing event");
@@ -2,14 +2,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::extract::FromRef;
-use s3::creds::Credentials as AwsCredentials;
 use sea_orm::DatabaseConnection;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 pub use si_posthog::PosthogClient;
 
 use tokio::sync::{mpsc, Mutex};
 
-use crate::s3::S3Config;
+use crate::storage::DynModuleStorage;
 
 #[remain::sorted]
 #[derive(Debug, Eq, PartialEq)]
@@ -21,8 +20,7 @@ pub struct AppState {
     pg_pool: DatabaseConnection,
     jwt_public_signing_key_chain: JwtPublicSigningKeyChain,
     posthog_client: PosthogClient,
-    aws_creds: AwsCredentials,
-    s3_config: S3Config,
+    storage: DynModuleStorage,
     token_emails: Arc<Mutex<HashMap<String, String>>>,
 
     // see notes in sdf AppState
@@ -37,16 +35,14 @@ impl AppState {
         pg_pool: DatabaseConnection,
         jwt_public_signing_key_chain: JwtPublicSigningKeyChain,
         posthog_client: PosthogClient,
-        aws_creds: AwsCredentials,
-        s3_config: S3Config,
+        storage: DynModuleStorage,
         tmp_shutdown_tx: mpsc::Sender<ShutdownSource>,
     ) -> Self {
         Self {
             pg_pool,
             jwt_public_signing_key_chain,
             posthog_client,
-            aws_creds,
-            s3_config,
+            storage,
             token_emails: Arc::new(Mutex::new(HashMap::new())),
             _tmp_shutdown_tx: Arc::new(tmp_shutdown_tx),
         }
@@ -67,13 +63,9 @@ impl AppState {
         &self.posthog_client
     }
 
-    /// Gets a reference to the aws creds.
-    pub fn aws_creds(&self) -> &AwsCredentials {
-        &self.aws_creds
-    }
-    /// Gets a reference to the s3 config (bucket, region, etc)
-    pub fn s3_config(&self) -> &S3Config {
-        &self.s3_config
+    /// Gets a reference to the module storage backend.
+    pub fn storage(&self) -> &DynModuleStorage {
+        &self.storage
     }
 
     /// Clones the ArcMutex that holds a hashmap between auth tokens and emails
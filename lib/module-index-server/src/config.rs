@@ -13,7 +13,7 @@ use thiserror::Error;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 use ulid::Ulid;
 
-use crate::s3::S3Config;
+use crate::{s3::S3Config, storage::StorageConfig};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -63,6 +63,9 @@ pub struct Config {
     posthog: PosthogConfig,
 
     s3: S3Config,
+
+    #[builder(default)]
+    storage: StorageConfig,
 }
 
 impl StandardConfig for Config {
@@ -120,6 +123,12 @@ impl Config {
     pub fn s3(&self) -> &S3Config {
         &self.s3
     }
+
+    /// Gets a reference to the config's module storage backend details
+    #[must_use]
+    pub fn storage(&self) -> &StorageConfig {
+        &self.storage
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -142,6 +151,8 @@ pub struct ConfigFile {
     pub posthog: PosthogConfig,
     #[serde(default)]
     pub s3: S3Config,
+    #[serde(default)]
+    pub storage: StorageConfig,
 }
 
 impl Default for ConfigFile {
@@ -162,6 +173,7 @@ impl Default for ConfigFile {
             jwt_secondary_signing_public_key_algo: None,
             posthog: Default::default(),
             s3: Default::default(),
+            storage: Default::default(),
         }
     }
 }
@@ -184,6 +196,7 @@ impl TryFrom<ConfigFile> for Config {
         config.jwt_signing_public_key_algo(value.jwt_signing_public_key_algo);
         config.posthog(value.posthog);
         config.s3(value.s3);
+        config.storage(value.storage);
         config.build().map_err(Into::into)
     }
 }
@@ -5,6 +5,7 @@ mod models;
 mod routes;
 mod s3;
 pub mod server;
+mod storage;
 mod whoami;
 
 pub use crate::{
@@ -13,4 +14,8 @@ pub use crate::{
         StandardConfig, StandardConfigFile,
     },
     server::{Server, ServerError},
+    storage::{
+        DynModuleStorage, LocalStorage, ModuleStorage, S3Storage, StorageBackend, StorageConfig,
+        StorageError, StoredObject,
+    },
 };
@@ -1,16 +1,16 @@
 use axum::{
     extract::Path,
-    response::{IntoResponse, Redirect, Response},
+    response::{IntoResponse, Response},
     Json,
 };
 use hyper::StatusCode;
-use s3::error::S3Error;
 use sea_orm::{DbErr, EntityTrait};
 use thiserror::Error;
 
 use crate::{
-    extract::{DbConnection, ExtractedS3Bucket},
+    extract::{DbConnection, ExtractedStorage},
     models::si_module::{self, ModuleId},
+    storage::{module_key, StorageError, StoredObject},
 };
 
 #[remain::sorted]
@@ -22,15 +22,17 @@ pub enum DownloadBuiltinError {
     NotBuiltin(ModuleId),
     #[error(r#"Module "{0}" not found"#)]
     NotFound(ModuleId),
-    #[error("s3 error: {0}")]
-    S3Error(#[from] S3Error),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 // TODO: figure out how to not keep this serialization logic here
 impl IntoResponse for DownloadBuiltinError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            Self::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::NotFound(_) | Self::Storage(StorageError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -44,9 +46,9 @@ impl IntoResponse for DownloadBuiltinError {
 
 pub async fn download_builtin_route(
     Path(module_id): Path<ModuleId>,
-    ExtractedS3Bucket(s3_bucket): ExtractedS3Bucket,
+    ExtractedStorage(storage): ExtractedStorage,
     DbConnection(txn): DbConnection,
-) -> Result<Redirect, DownloadBuiltinError> {
+) -> Result<StoredObject, DownloadBuiltinError> {
     let module = match si_module::Entity::find_by_id(module_id).one(&txn).await? {
         Some(module) => module,
         _ => return Err(DownloadBuiltinError::NotFound(module_id)),
@@ -56,9 +58,5 @@ pub async fn download_builtin_route(
         return Err(DownloadBuiltinError::NotBuiltin(module_id));
     }
 
-    let download_url = s3_bucket
-        .presign_get(format!("{}.sipkg", module.latest_hash), 60 * 5, None)
-        .await?;
-
-    Ok(Redirect::temporary(&download_url))
+    Ok(storage.download(&module_key(&module.latest_hash)).await?)
 }
@@ -14,7 +14,6 @@ use module_index_types::{
 use module_index_types::{
     MODULE_BASED_ON_HASH_FIELD_NAME, MODULE_BUNDLE_FIELD_NAME, MODULE_SCHEMA_ID_FIELD_NAME,
 };
-use s3::error::S3Error;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, Set};
 use serde::{Deserialize, Serialize};
 use si_pkg::{SiPkg, SiPkgError, SiPkgKind};
@@ -22,10 +21,11 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
+    extract::{Authorization, DbConnection, ExtractedStorage},
     models::si_module::{
         self, make_module_details_response, ModuleId, ModuleKind, SchemaId, SchemaVariantId,
     },
+    storage::{module_key, StorageError},
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -45,12 +45,12 @@ pub enum UpsertModuleError {
     Multipart(#[from] MultipartError),
     #[error("module with {0} could not be found after insert!")]
     NotFoundAfterInsert(ModuleId),
-    #[error("s3 error: {0}")]
-    S3Error(#[from] S3Error),
     #[error("JSON serialization/deserialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("module parsing error: {0}")]
     SiPkgError(#[from] SiPkgError),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
     #[error("Ulid decode error: {0}")]
     UlidDecode(#[from] ulid::DecodeError),
     #[error("upload is required")]
@@ -75,7 +75,7 @@ impl IntoResponse for UpsertModuleError {
 // #[debug_handler]
 pub async fn upsert_module_route(
     Authorization { user_claim, .. }: Authorization,
-    ExtractedS3Bucket(s3_bucket): ExtractedS3Bucket,
+    ExtractedStorage(storage): ExtractedStorage,
     DbConnection(txn): DbConnection,
     mut multipart: Multipart,
 ) -> Result<Json<ModuleDetailsResponse>, UpsertModuleError> {
@@ -209,9 +209,8 @@ pub async fn upsert_module_route(
     };
 
     // TODO: put below
-    // upload to s3
-    s3_bucket
-        .put_object(format!("{}.sipkg", module_metadata.hash()), &data)
+    storage
+        .put(&module_key(module_metadata.hash()), &data)
         .await?;
 
     let new_module: si_module::Model = new_module.insert(&txn).await?;
@@ -1,4 +1,4 @@
-use std::{io, net::SocketAddr, time::Duration};
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
 
 use super::routes;
 
@@ -23,6 +23,7 @@ use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use crate::{
     app_state::{AppState, ShutdownSource},
     s3::S3Config,
+    storage::{DynModuleStorage, LocalStorage, S3Storage, StorageBackend, StorageError},
     Config,
 };
 
@@ -53,6 +54,8 @@ pub enum ServerError {
     SerdeJson(#[from] serde_json::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 impl From<PgPoolError> for ServerError {
@@ -78,48 +81,22 @@ impl Server<(), ()> {
         jwt_public_signing_key: JwtPublicSigningKeyChain,
         posthog_client: PosthogClient,
     ) -> ServerResult<(Server<AddrIncoming, SocketAddr>, broadcast::Receiver<()>)> {
-        // socket_addr
-
-        // try to load aws creds from a few different places
-        let aws_creds = match (&config.s3().access_key_id, &config.s3().secret_access_key) {
-            (Some(aws_key), Some(aws_secret)) => {
-                AwsCredentials::new(Some(aws_key), Some(aws_secret), None, None, None)?
+        let storage: DynModuleStorage = match config.storage().backend {
+            StorageBackend::S3 => {
+                let aws_creds = load_aws_creds(config.s3())?;
+                Arc::new(S3Storage::new(aws_creds, config.s3())?)
             }
-            (None, None) => match AwsCredentials::from_env() {
-                Ok(creds) => creds,
-                Err(CredentialsError::MissingEnvVar(_, _)) => {
-                    // Attempt to load from local AWS Profile
-                    info!("could not load credentials from environment; falling back to profile");
-                    match AwsCredentials::from_profile(None) {
-                        Ok(creds) => creds,
-                        Err(err) => {
-                            info!(
-                                ?err,
-                                "could not load credentials from profile; falling back to instance metadata"
-                            );
-
-                            // Attempt to load from instance metadata
-                            match AwsCredentials::from_instance_metadata() {
-                                Ok(creds) => creds,
-                                Err(err) => return Err(err.into()),
-                            }
-                        }
-                    }
-                }
-                Err(err) => return Err(err.into()),
-            },
-            _ => {
-                return Err(ServerError::AwsConfigError);
+            StorageBackend::Local => {
+                info!(
+                    path = %config.storage().local_path.display(),
+                    "storing modules on the local filesystem",
+                );
+                Arc::new(LocalStorage::new(&config.storage().local_path)?)
             }
         };
 
-        let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
-            pg_pool,
-            jwt_public_signing_key,
-            posthog_client,
-            aws_creds,
-            config.s3().clone(),
-        )?;
+        let (service, shutdown_rx, shutdown_broadcast_rx) =
+            build_service(pg_pool, jwt_public_signing_key, posthog_client, storage)?;
 
         info!(
             "binding to HTTP socket; socket_addr={}",
@@ -259,8 +236,7 @@ pub fn build_service(
     pg_pool: DatabaseConnection,
     jwt_public_signing_key_chain: JwtPublicSigningKeyChain,
     posthog_client: PosthogClient,
-    aws_creds: AwsCredentials,
-    s3_config: S3Config,
+    storage: DynModuleStorage,
 ) -> ServerResult<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
     let (shutdown_broadcast_tx, shutdown_broadcast_rx) = broadcast::channel(1);
@@ -269,8 +245,7 @@ pub fn build_service(
         pg_pool,
         jwt_public_signing_key_chain,
         posthog_client,
-        aws_creds,
-        s3_config,
+        storage,
         shutdown_tx,
     );
 
@@ -287,6 +262,40 @@ pub fn build_service(
     Ok((routes, graceful_shutdown_rx, shutdown_broadcast_rx))
 }
 
+// try to load aws creds from a few different places
+fn load_aws_creds(s3_config: &S3Config) -> ServerResult<AwsCredentials> {
+    let aws_creds = match (&s3_config.access_key_id, &s3_config.secret_access_key) {
+        (Some(aws_key), Some(aws_secret)) => {
+            AwsCredentials::new(Some(aws_key), Some(aws_secret), None, None, None)?
+        }
+        (None, None) => match AwsCredentials::from_env() {
+            Ok(creds) => creds,
+            Err(CredentialsError::MissingEnvVar(_, _)) => {
+                // Attempt to load from local AWS Profile
+                info!("could not load credentials from environment; falling back to profile");
+                match AwsCredentials::from_profile(None) {
+                    Ok(creds) => creds,
+                    Err(err) => {
+                        info!(
+                            ?err,
+                            "could not load credentials from profile; falling back to instance metadata"
+                        );
+
+                        // Attempt to load from instance metadata
+                        AwsCredentials::from_instance_metadata()?
+                    }
+                }
+            }
+            Err(err) => return Err(err.into()),
+        },
+        _ => {
+            return Err(ServerError::AwsConfigError);
+        }
+    };
+
+    Ok(aws_creds)
+}
+
 fn prepare_graceful_shutdown(
     mut shutdown_rx: mpsc::Receiver<ShutdownSource>,
     shutdown_broadcast_tx: broadcast::Sender<()>,
@@ -0,0 +1,108 @@
+use std::{fmt, io, path::PathBuf, sync::Arc};
+
+use axum::{
+    async_trait,
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+};
+use s3::error::S3Error;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod local;
+mod s3_storage;
+
+pub use local::LocalStorage;
+pub use s3_storage::S3Storage;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("invalid s3 region: {0}")]
+    InvalidRegion(String),
+    #[error("storage io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("object not found in storage: {0}")]
+    NotFound(String),
+    #[error("s3 error: {0}")]
+    S3(#[from] S3Error),
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// A shareable handle to the configured [`ModuleStorage`] backend.
+pub type DynModuleStorage = Arc<dyn ModuleStorage>;
+
+/// Where module bundles and workspace exports are stored.
+///
+/// Objects are content-addressed: their keys are derived from the hash of their contents (see
+/// [`module_key`] and [`workspace_export_key`]), so writing the same key twice is a no-op.
+#[async_trait]
+pub trait ModuleStorage: fmt::Debug + Send + Sync {
+    /// Stores an object under the given key.
+    async fn put(&self, key: &str, data: &[u8]) -> StorageResult<()>;
+
+    /// Returns the object stored under the given key, either as a URL to download it from or its
+    /// contents.
+    async fn download(&self, key: &str) -> StorageResult<StoredObject>;
+}
+
+/// A response to a download request for a stored object.
+#[derive(Debug)]
+pub enum StoredObject {
+    /// A short-lived URL the object can be downloaded from.
+    Redirect(String),
+    /// The contents of the object.
+    Bytes(Vec<u8>),
+}
+
+impl IntoResponse for StoredObject {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Redirect(url) => Redirect::temporary(&url).into_response(),
+            Self::Bytes(bytes) => {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response()
+            }
+        }
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// Objects are stored in a directory on the local filesystem.
+    Local,
+    /// Objects are stored in the S3 bucket from the `s3` config.
+    #[default]
+    S3,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// The directory objects are stored in when using the local backend.
+    pub local_path: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: StorageBackend::default(),
+            local_path: PathBuf::from("/var/lib/module-index/storage"),
+        }
+    }
+}
+
+/// The storage key for a module bundle with the given hash.
+pub fn module_key(hash: impl fmt::Display) -> String {
+    format!("{hash}.sipkg")
+}
+
+/// The storage key for a workspace export with the given hash.
+pub fn workspace_export_key(hash: impl fmt::Display) -> String {
+    format!("{hash}.workspace_export")
+}
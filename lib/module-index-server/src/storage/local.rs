@@ -0,0 +1,114 @@
+use std::{io, path::PathBuf};
+
+use axum::async_trait;
+use tokio::fs;
+use ulid::Ulid;
+
+use super::{ModuleStorage, StorageError, StorageResult, StoredObject};
+
+/// Stores objects as files in a directory on the local filesystem, named by their key.
+#[derive(Clone, Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Creates a new local storage backend, creating its root directory if it doesn't exist.
+    pub fn new(root: impl Into<PathBuf>) -> StorageResult<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)?;
+
+        Ok(Self { root })
+    }
+
+    fn path_for(&self, key: &str) -> StorageResult<PathBuf> {
+        // Keys are flat file names, so never let one escape the root directory
+        if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\']) {
+            return Err(StorageError::InvalidKey(key.to_owned()));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ModuleStorage for LocalStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> StorageResult<()> {
+        let path = self.path_for(key)?;
+
+        // Keys are content-addressed, so an existing object already holds this data
+        if fs::try_exists(&path).await? {
+            return Ok(());
+        }
+
+        // Write to a temporary file first so that a partially written object is never visible
+        // under its key
+        let tmp_path = self.root.join(format!(".{key}.{}.tmp", Ulid::new()));
+        if let Err(err) = fs::write(&tmp_path, data).await {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(err.into());
+        }
+        fs::rename(&tmp_path, &path).await?;
+
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> StorageResult<StoredObject> {
+        let path = self.path_for(key)?;
+
+        match fs::read(&path).await {
+            Ok(bytes) => Ok(StoredObject::Bytes(bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_owned()))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn put_then_download() {
+        let tempdir = tempfile::tempdir().expect("failed to create tempdir");
+        let storage = LocalStorage::new(tempdir.path()).expect("failed to create storage");
+
+        storage
+            .put("abc123.sipkg", b"module bytes")
+            .await
+            .expect("failed to put");
+        // Putting the same key again is a no-op
+        storage
+            .put("abc123.sipkg", b"module bytes")
+            .await
+            .expect("failed to put again");
+
+        match storage
+            .download("abc123.sipkg")
+            .await
+            .expect("failed to download")
+        {
+            StoredObject::Bytes(bytes) => assert_eq!(b"module bytes".as_slice(), bytes),
+            other => panic!("expected bytes, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_and_invalid_keys() {
+        let tempdir = tempfile::tempdir().expect("failed to create tempdir");
+        let storage = LocalStorage::new(tempdir.path()).expect("failed to create storage");
+
+        assert!(matches!(
+            storage.download("missing.sipkg").await,
+            Err(StorageError::NotFound(_))
+        ));
+        for key in ["", "../escape.sipkg", "nested/key.sipkg", ".hidden"] {
+            assert!(matches!(
+                storage.put(key, b"data").await,
+                Err(StorageError::InvalidKey(_))
+            ));
+        }
+    }
+}
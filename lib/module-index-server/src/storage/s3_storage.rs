@@ -0,0 +1,53 @@
+use std::fmt;
+
+use axum::async_trait;
+use s3::{creds::Credentials as AwsCredentials, Bucket as S3Bucket, Region as AwsRegion};
+
+use super::{ModuleStorage, StorageError, StorageResult, StoredObject};
+use crate::s3::S3Config;
+
+// How long presigned download URLs stay valid for, in seconds
+const PRESIGNED_URL_EXPIRY_SECS: u32 = 60 * 5;
+
+/// Stores objects in an S3 bucket, serving downloads through presigned URLs.
+#[derive(Clone)]
+pub struct S3Storage {
+    bucket: S3Bucket,
+}
+
+impl S3Storage {
+    pub fn new(aws_creds: AwsCredentials, s3_config: &S3Config) -> StorageResult<Self> {
+        let region = s3_config
+            .region
+            .parse::<AwsRegion>()
+            .map_err(|err| StorageError::InvalidRegion(err.to_string()))?;
+        let bucket = S3Bucket::new(&s3_config.bucket, region, aws_creds)?;
+
+        Ok(Self { bucket })
+    }
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Storage")
+            .field("bucket", &self.bucket.name())
+            .field("region", &self.bucket.region())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl ModuleStorage for S3Storage {
+    async fn put(&self, key: &str, data: &[u8]) -> StorageResult<()> {
+        self.bucket.put_object(key, data).await?;
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> StorageResult<StoredObject> {
+        let url = self
+            .bucket
+            .presign_get(key, PRESIGNED_URL_EXPIRY_SECS, None)
+            .await?;
+        Ok(StoredObject::Redirect(url))
+    }
+}
@@ -0,0 +1,30 @@
+//! Headers added to a message when it is republished to a dead letter subject.
+//!
+//! Any headers on the original message are preserved alongside these.
+
+/// The subject the message was originally published on.
+pub const ORIGINAL_SUBJECT: &str = "Naxum-Dlq-Original-Subject";
+/// The stream the message was consumed from.
+pub const STREAM: &str = "Naxum-Dlq-Stream";
+/// The consumer the message was delivered to.
+pub const CONSUMER: &str = "Naxum-Dlq-Consumer";
+/// The sequence number of the message in its original stream.
+pub const STREAM_SEQUENCE: &str = "Naxum-Dlq-Stream-Sequence";
+/// The number of times the message was delivered before it was dead lettered.
+pub const DELIVERED: &str = "Naxum-Dlq-Delivered";
+/// The status code of the final failed attempt.
+pub const ERROR_STATUS: &str = "Naxum-Dlq-Error-Status";
+/// A description of the final failed attempt, if one was available.
+pub const ERROR_DESCRIPTION: &str = "Naxum-Dlq-Error-Description";
+
+/// Every header added when dead lettering a message, which can be used to strip them when
+/// replaying it.
+pub const ALL: &[&str] = &[
+    ORIGINAL_SUBJECT,
+    STREAM,
+    CONSUMER,
+    STREAM_SEQUENCE,
+    DELIVERED,
+    ERROR_STATUS,
+    ERROR_DESCRIPTION,
+];
@@ -1,9 +1,18 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use nats_dead_letter_queue_core::headers;
 use si_data_nats::{
     async_nats::jetstream::{
-        context::CreateStreamError,
-        stream::{Config, RetentionPolicy},
+        consumer::{
+            pull::{self, MessagesError},
+            AckPolicy, DeliverPolicy, StreamError,
+        },
+        context::{CreateStreamError, GetStreamError, PublishError, UpdateStreamError},
+        stream::{Config, ConsumerError, DeleteMessageError, RetentionPolicy},
     },
     jetstream::Context,
+    HeaderMap, InnerError,
 };
 use thiserror::Error;
 
@@ -14,13 +23,36 @@ const STREAM_DESCRIPTION: &str = "Dead Letter Queues";
 //
 // See: https://docs.nats.io/running-a-nats-service/nats_admin/monitoring/monitoring_jetstream
 const STREAM_SUBJECTS: &str = "$JS.EVENT.ADVISORY.CONSUMER.MAX_DELIVERIES.*.*";
+// Messages which exhausted their retries, published by naxum's `RetryLayer`. These subjects are
+// of the form: `dead_letter.<STREAM>.<CONSUMER>`
+const MESSAGES_SUBJECT_PREFIX: &str = "dead_letter";
+// Replayed messages keep their headers, except for the ones added when dead lettering them and
+// the message id, which would otherwise be dropped if still inside the stream's duplicate window
+const NATS_MSG_ID_HEADER: &str = "Nats-Msg-Id";
+const REPLAY_CONSUMER_INACTIVE_THRESHOLD: Duration = Duration::from_secs(60);
 
 #[allow(missing_docs)]
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("consumer error: {0}")]
+    Consumer(#[from] ConsumerError),
+    #[error("consumer stream error: {0}")]
+    ConsumerStream(#[from] StreamError),
     #[error("create stream error: {0}")]
     CreateStream(#[from] CreateStreamError),
+    #[error("delete message error: {0}")]
+    DeleteMessage(#[from] DeleteMessageError),
+    #[error("get stream error: {0}")]
+    GetStream(#[from] GetStreamError),
+    #[error("message info error: {0}")]
+    MessageInfo(#[source] InnerError),
+    #[error("messages error: {0}")]
+    Messages(#[from] MessagesError),
+    #[error("publish error: {0}")]
+    Publish(#[from] PublishError),
+    #[error("update stream error: {0}")]
+    UpdateStream(#[from] UpdateStreamError),
 }
 
 pub type NatsDeadLetterQueueError = Error;
@@ -31,17 +63,117 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 pub async fn create_stream(context: &Context) -> Result<()> {
     let prefix = context.metadata().subject_prefix();
 
-    context
-        .get_or_create_stream(Config {
-            name: prefixed_stream_name(prefix, STREAM_NAME),
-            description: Some(STREAM_DESCRIPTION.to_string()),
-            retention: RetentionPolicy::Limits,
-            subjects: vec![prefixed_subject(prefix, STREAM_SUBJECTS)],
+    let config = Config {
+        name: prefixed_stream_name(prefix, STREAM_NAME),
+        description: Some(STREAM_DESCRIPTION.to_string()),
+        retention: RetentionPolicy::Limits,
+        subjects: vec![
+            prefixed_subject(prefix, STREAM_SUBJECTS),
+            prefixed_subject(prefix, &format!("{MESSAGES_SUBJECT_PREFIX}.>")),
+        ],
+        ..Default::default()
+    };
+
+    let stream = context.get_or_create_stream(config.clone()).await?;
+
+    // Streams created before dead lettered messages were captured only have the advisory subject
+    if stream.cached_info().config.subjects != config.subjects {
+        context.update_stream(&config).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the subject prefix to configure naxum's `RetryLayer` with, so that dead lettered
+/// messages are captured by the "dead letter queue" stream.
+pub fn dead_letter_subject_prefix(prefix: Option<&str>) -> String {
+    prefixed_subject(prefix, MESSAGES_SUBJECT_PREFIX)
+}
+
+/// Moves dead lettered messages back onto the subjects they were originally published on,
+/// returning the number of messages replayed.
+///
+/// Only messages dead lettered from the given stream, and consumer if provided, are replayed, up
+/// to an optional limit. Each message is removed from the "dead letter queue" stream once it has
+/// been republished.
+pub async fn replay(
+    context: &Context,
+    stream_name: &str,
+    consumer_name: Option<&str>,
+    limit: Option<usize>,
+) -> Result<usize> {
+    let prefix = context.metadata().subject_prefix();
+
+    let dead_letter_stream = context
+        .get_stream(prefixed_stream_name(prefix, STREAM_NAME))
+        .await?;
+
+    let filter_subject = prefixed_subject(
+        prefix,
+        &format!(
+            "{MESSAGES_SUBJECT_PREFIX}.{stream_name}.{}",
+            consumer_name.unwrap_or("*")
+        ),
+    );
+    let consumer = dead_letter_stream
+        .create_consumer(pull::Config {
+            filter_subject,
+            deliver_policy: DeliverPolicy::All,
+            ack_policy: AckPolicy::None,
+            inactive_threshold: REPLAY_CONSUMER_INACTIVE_THRESHOLD,
             ..Default::default()
         })
         .await?;
 
-    Ok(())
+    // Only replay what is in the queue now, so that messages which fail again and are dead
+    // lettered during the replay aren't picked straight back up
+    let pending = usize::try_from(consumer.cached_info().num_pending).unwrap_or(usize::MAX);
+    let to_replay = limit.map_or(pending, |limit| limit.min(pending));
+
+    let mut messages = consumer.messages().await?.take(to_replay);
+    let mut replayed = 0;
+
+    while let Some(message) = messages.next().await {
+        let message = message?;
+        let stream_sequence = message.info().map_err(Error::MessageInfo)?.stream_sequence;
+
+        let Some((original_subject, replay_headers)) = message
+            .headers
+            .as_ref()
+            .and_then(replay_subject_and_headers)
+        else {
+            continue;
+        };
+
+        context
+            .publish_with_headers(original_subject, replay_headers, message.payload.clone())
+            .await?
+            .await?;
+        dead_letter_stream.delete_message(stream_sequence).await?;
+
+        replayed += 1;
+    }
+
+    Ok(replayed)
+}
+
+/// Returns the subject a dead lettered message was originally published on, along with the headers
+/// to republish it with, or `None` if it was not dead lettered with the original subject.
+fn replay_subject_and_headers(dead_letter_headers: &HeaderMap) -> Option<(String, HeaderMap)> {
+    let original_subject = dead_letter_headers.get(headers::ORIGINAL_SUBJECT)?;
+
+    let mut replay_headers = HeaderMap::new();
+    for (name, values) in dead_letter_headers.iter() {
+        let name_str = name.to_string();
+        if name_str == NATS_MSG_ID_HEADER || headers::ALL.contains(&name_str.as_str()) {
+            continue;
+        }
+        for value in values {
+            replay_headers.append(name.clone(), value.clone());
+        }
+    }
+
+    Some((original_subject.to_string(), replay_headers))
 }
 
 fn prefixed_stream_name(prefix: Option<&str>, stream_name: &str) -> String {
@@ -57,3 +189,67 @@ fn prefixed_subject(prefix: Option<&str>, subject: &str) -> String {
         None => subject.to_owned(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_restores_original_subject_and_headers() {
+        let mut dead_letter_headers = HeaderMap::new();
+        dead_letter_headers.insert("X-Request-Id", "abc");
+        dead_letter_headers.append("X-Tag", "one");
+        dead_letter_headers.append("X-Tag", "two");
+        dead_letter_headers.insert(NATS_MSG_ID_HEADER, "duplicate-me-not");
+        dead_letter_headers.insert(headers::ORIGINAL_SUBJECT, "pinga.w1.cs1.job");
+        dead_letter_headers.insert(headers::STREAM, "PINGA_JOBS");
+        dead_letter_headers.insert(headers::CONSUMER, "pinga");
+        dead_letter_headers.insert(headers::STREAM_SEQUENCE, "42");
+        dead_letter_headers.insert(headers::DELIVERED, "5");
+        dead_letter_headers.insert(headers::ERROR_STATUS, "500");
+        dead_letter_headers.insert(headers::ERROR_DESCRIPTION, "boom");
+
+        let (subject, replay_headers) =
+            replay_subject_and_headers(&dead_letter_headers).expect("has an original subject");
+
+        assert_eq!("pinga.w1.cs1.job", subject);
+        assert_eq!(
+            Some("abc"),
+            replay_headers
+                .get("X-Request-Id")
+                .map(|value| value.as_str())
+        );
+        assert_eq!(
+            Some(vec!["one", "two"]),
+            replay_headers
+                .iter()
+                .find(|(name, _)| name.to_string() == "X-Tag")
+                .map(|(_, values)| values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>())
+        );
+        assert!(replay_headers.get(NATS_MSG_ID_HEADER).is_none());
+        for header in headers::ALL {
+            assert!(replay_headers.get(*header).is_none(), "{header} was kept");
+        }
+    }
+
+    #[test]
+    fn replay_skips_messages_without_original_subject() {
+        let mut dead_letter_headers = HeaderMap::new();
+        dead_letter_headers.insert("X-Request-Id", "abc");
+
+        assert!(replay_subject_and_headers(&dead_letter_headers).is_none());
+    }
+
+    #[test]
+    fn prefixes_subjects_and_stream_names() {
+        assert_eq!("dead_letter", dead_letter_subject_prefix(None));
+        assert_eq!("si.dead_letter", dead_letter_subject_prefix(Some("si")));
+        assert_eq!(
+            "si_DEAD_LETTER_QUEUES",
+            prefixed_stream_name(Some("si"), STREAM_NAME)
+        );
+    }
+}
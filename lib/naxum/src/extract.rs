@@ -9,11 +9,12 @@ use crate::{
 
 mod matched_subject;
 pub mod message_parts;
+mod path;
 pub mod rejection;
 mod state;
 mod tuple;
 
-pub use self::{matched_subject::MatchedSubject, state::State};
+pub use self::{matched_subject::MatchedSubject, path::Path, state::State};
 
 mod private {
     #[derive(Debug, Clone, Copy)]
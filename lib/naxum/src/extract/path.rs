@@ -0,0 +1,60 @@
+use std::ops;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use crate::{message::Head, routing::PathParams};
+
+use super::{
+    rejection::{FailedToDeserializePathParams, MissingPathParams, PathRejection},
+    FromMessageHead,
+};
+
+mod de;
+
+/// Extractor for the subject tokens captured by the [`Router`](crate::routing::Router) route
+/// that matched the message.
+///
+/// `T` can be a struct or map, where captures are found by name, a tuple, where captures are
+/// found by position, or a single value when only one token was captured:
+///
+/// ```ignore
+/// // Routed with `.route("pinga.:workspace_id.:change_set_id.job", process_job)`
+/// async fn process_job(Path((workspace_id, change_set_id)): Path<(String, String)>) {}
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromMessageHead<S> for Path<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Send,
+{
+    type Rejection = PathRejection;
+
+    async fn from_message_head(head: &mut Head, _state: &S) -> Result<Self, Self::Rejection> {
+        let params = head
+            .extensions
+            .get::<PathParams>()
+            .ok_or(PathRejection::MissingPathParams(MissingPathParams))?;
+
+        T::deserialize(de::PathDeserializer::new(&params.0))
+            .map(Self)
+            .map_err(|err| FailedToDeserializePathParams::from_err(err).into())
+    }
+}
+
+impl<T> ops::Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
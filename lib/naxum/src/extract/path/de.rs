@@ -0,0 +1,466 @@
+use std::{error, fmt, sync::Arc};
+
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor},
+    forward_to_deserialize_any,
+};
+
+type Params = [(Arc<str>, String)];
+
+#[derive(Debug)]
+pub(super) struct PathDeserializationError(String);
+
+impl fmt::Display for PathDeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for PathDeserializationError {}
+
+impl de::Error for PathDeserializationError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+macro_rules! single_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.single_value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident($ty:ty),)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let value = self.value.parse::<$ty>().map_err(|_| {
+                    PathDeserializationError(format!(
+                        "cannot parse `{}` as {}",
+                        self.value,
+                        stringify!($ty),
+                    ))
+                })?;
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+/// Deserializes captured subject tokens into a struct or map (by name), a tuple or sequence (by
+/// position), or a single value.
+pub(super) struct PathDeserializer<'de> {
+    params: &'de Params,
+}
+
+impl<'de> PathDeserializer<'de> {
+    pub(super) fn new(params: &'de Params) -> Self {
+        Self { params }
+    }
+
+    fn single_value(&self) -> Result<ValueDeserializer<'de>, PathDeserializationError> {
+        match self.params {
+            [(_, value)] => Ok(ValueDeserializer { value }),
+            params => Err(PathDeserializationError(format!(
+                "expected 1 captured token but found {}",
+                params.len()
+            ))),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for PathDeserializer<'de> {
+    type Error = PathDeserializationError;
+
+    single_value! {
+        deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf
+        deserialize_option
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single_value()?
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(ValueSeq {
+            params: self.params.iter(),
+        })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.params.len() != len {
+            return Err(PathDeserializationError(format!(
+                "expected {len} captured tokens but found {}",
+                self.params.len()
+            )));
+        }
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(ParamMap {
+            params: self.params.iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+struct ParamMap<'de> {
+    params: std::slice::Iter<'de, (Arc<str>, String)>,
+    value: Option<&'de str>,
+}
+
+impl<'de> MapAccess<'de> for ParamMap<'de> {
+    type Error = PathDeserializationError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.params.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                seed.deserialize(ValueDeserializer { value: name })
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer { value }),
+            None => Err(PathDeserializationError(
+                "value requested before key".to_owned(),
+            )),
+        }
+    }
+}
+
+struct ValueSeq<'de> {
+    params: std::slice::Iter<'de, (Arc<str>, String)>,
+}
+
+impl<'de> SeqAccess<'de> for ValueSeq<'de> {
+    type Error = PathDeserializationError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.params.next() {
+            Some((_, value)) => seed.deserialize(ValueDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializes a single captured token, parsing it into the requested type.
+struct ValueDeserializer<'de> {
+    value: &'de str,
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = PathDeserializationError;
+
+    parse_value! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.value)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::value::BorrowedStrDeserializer::<Self::Error>::new(self.value)
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, PathDeserializationError> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(Arc<str>, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (Arc::from(*name), value.to_string()))
+            .collect()
+    }
+
+    fn deserialize<'de, T>(params: &'de Params) -> Result<T, PathDeserializationError>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(PathDeserializer::new(params))
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    struct JobParams {
+        workspace_id: String,
+        attempt: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "camelCase")]
+    enum Kind {
+        ActionRun,
+        Validation,
+    }
+
+    #[test]
+    fn struct_by_name() {
+        let params = params(&[("attempt", "3"), ("workspace_id", "w1")]);
+
+        assert_eq!(
+            JobParams {
+                workspace_id: "w1".to_owned(),
+                attempt: 3,
+            },
+            deserialize::<JobParams>(&params).expect("deserializes")
+        );
+    }
+
+    #[test]
+    fn map_by_name() {
+        let params = params(&[("workspace_id", "w1"), ("change_set_id", "cs1")]);
+
+        assert_eq!(
+            HashMap::from([
+                ("workspace_id".to_owned(), "w1".to_owned()),
+                ("change_set_id".to_owned(), "cs1".to_owned()),
+            ]),
+            deserialize::<HashMap<String, String>>(&params).expect("deserializes")
+        );
+    }
+
+    #[test]
+    fn tuple_by_position() {
+        let params = params(&[("workspace_id", "w1"), ("attempt", "3"), ("ok", "true")]);
+
+        assert_eq!(
+            ("w1".to_owned(), 3, true),
+            deserialize::<(String, u8, bool)>(&params).expect("deserializes")
+        );
+    }
+
+    #[test]
+    fn single_value() {
+        let params = params(&[("attempt", "42")]);
+
+        assert_eq!(42, deserialize::<u64>(&params).expect("deserializes"));
+        assert_eq!("42", deserialize::<String>(&params).expect("deserializes"));
+        assert_eq!(
+            Some(42),
+            deserialize::<Option<i32>>(&params).expect("deserializes")
+        );
+    }
+
+    #[test]
+    fn unit_enum_variants() {
+        assert_eq!(
+            Kind::ActionRun,
+            deserialize::<Kind>(&params(&[("kind", "actionRun")])).expect("deserializes")
+        );
+        assert_eq!(
+            (Kind::Validation, "w1".to_owned()),
+            deserialize::<(Kind, String)>(&params(&[("kind", "validation"), ("id", "w1")]))
+                .expect("deserializes")
+        );
+        assert!(deserialize::<Kind>(&params(&[("kind", "management")])).is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = deserialize::<u32>(&params(&[("attempt", "many")])).expect_err("fails to parse");
+        assert_eq!("cannot parse `many` as u32", err.to_string());
+
+        let err = deserialize::<JobParams>(&params(&[("workspace_id", "w1"), ("attempt", "-1")]))
+            .expect_err("fails to parse");
+        assert_eq!("cannot parse `-1` as u32", err.to_string());
+
+        let err = deserialize::<JobParams>(&params(&[("workspace_id", "w1")]))
+            .expect_err("missing field");
+        assert_eq!("missing field `attempt`", err.to_string());
+    }
+
+    #[test]
+    fn count_mismatches() {
+        let two = params(&[("workspace_id", "w1"), ("change_set_id", "cs1")]);
+
+        let err = deserialize::<String>(&two).expect_err("too many tokens");
+        assert_eq!("expected 1 captured token but found 2", err.to_string());
+
+        let err = deserialize::<(String, String, String)>(&two).expect_err("too few tokens");
+        assert_eq!("expected 3 captured tokens but found 2", err.to_string());
+    }
+}
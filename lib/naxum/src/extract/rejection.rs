@@ -128,3 +128,33 @@ composite_rejection! {
         MatchedSubjectMissing,
     }
 }
+
+define_rejection! {
+    #[status_code = 500]
+    #[body = "No path params found for matched subject"]
+    /// Rejection type for [`Path`](super::Path).
+    ///
+    /// This rejection is used if the message was not dispatched by a
+    /// [`Router`](crate::routing::Router), and so no subject tokens were captured.
+    pub struct MissingPathParams;
+}
+
+define_rejection! {
+    #[status_code = 400]
+    #[body = "Failed to deserialize the captured subject tokens into the target type"]
+    /// Rejection type for [`Path`](super::Path).
+    ///
+    /// This rejection is used if the captured subject tokens couldn't be deserialized into the
+    /// target type.
+    pub struct FailedToDeserializePathParams(Error);
+}
+
+composite_rejection! {
+    /// Rejection type for [`Path`](super::Path).
+    ///
+    /// Contains one variant for each way the [`Path`](super::Path) extractor can fail.
+    pub enum PathRejection {
+        FailedToDeserializePathParams,
+        MissingPathParams,
+    }
+}
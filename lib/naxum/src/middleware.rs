@@ -2,6 +2,7 @@ pub mod ack;
 pub mod delay;
 pub mod matched_subject;
 pub mod post_process;
+pub mod retry;
 pub mod trace;
 
 #[non_exhaustive]
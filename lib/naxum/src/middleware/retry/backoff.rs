@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_MULTIPLIER: u32 = 2;
+
+/// An exponential backoff used to delay the redelivery of a failed message.
+///
+/// The first failed attempt is delayed by the initial delay, and each attempt after that is
+/// delayed by the previous delay times the multiplier, up to the maximum delay.
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    multiplier: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: DEFAULT_INITIAL_DELAY,
+            max: DEFAULT_MAX_DELAY,
+            multiplier: DEFAULT_MULTIPLIER,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            multiplier: DEFAULT_MULTIPLIER,
+        }
+    }
+
+    pub fn multiplier(self, multiplier: u32) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    /// Returns the delay before redelivering a message which failed on the given attempt, where
+    /// the first attempt is `1`.
+    pub fn delay_for(&self, attempt: u64) -> Duration {
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+
+        self.multiplier
+            .checked_pow(exponent)
+            .and_then(|factor| self.initial.checked_mul(factor))
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_exponentially_up_to_max() {
+        let backoff = Backoff::exponential(Duration::from_secs(2), Duration::from_secs(60));
+
+        assert_eq!(Duration::from_secs(2), backoff.delay_for(0));
+        assert_eq!(Duration::from_secs(2), backoff.delay_for(1));
+        assert_eq!(Duration::from_secs(4), backoff.delay_for(2));
+        assert_eq!(Duration::from_secs(32), backoff.delay_for(5));
+        assert_eq!(Duration::from_secs(60), backoff.delay_for(6));
+        assert_eq!(Duration::from_secs(60), backoff.delay_for(u64::MAX));
+    }
+}
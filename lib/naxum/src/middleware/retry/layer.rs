@@ -0,0 +1,79 @@
+use std::{sync::Arc, time::Duration};
+
+use tower::Layer;
+
+use super::{backoff::Backoff, service::Retry};
+
+// Default `ack_wait` period when unset is 30 seconds (a NATS server default)
+const DEFAULT_PROGRESS_PERIOD: Duration = Duration::from_secs(20);
+const DEFAULT_MAX_ATTEMPTS: u64 = 5;
+
+#[derive(Clone, Debug)]
+pub struct RetryLayer {
+    pub(crate) backoff: Backoff,
+    pub(crate) max_attempts: u64,
+    pub(crate) dead_letter_subject_prefix: Option<Arc<str>>,
+    pub(crate) progress_period: Duration,
+}
+
+impl Default for RetryLayer {
+    fn default() -> Self {
+        Self {
+            backoff: Backoff::default(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            dead_letter_subject_prefix: None,
+            progress_period: DEFAULT_PROGRESS_PERIOD,
+        }
+    }
+}
+
+impl RetryLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn backoff(self, backoff: Backoff) -> Self {
+        Self { backoff, ..self }
+    }
+
+    /// Sets the number of deliveries after which a failed message is dead lettered rather than
+    /// retried.
+    pub fn max_attempts(self, max_attempts: u64) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the subject prefix that dead lettered messages are published under, as
+    /// `<prefix>.<stream>.<consumer>`.
+    ///
+    /// When unset, messages which fail on their final attempt are terminated instead.
+    pub fn dead_letter_subject_prefix(self, prefix: impl Into<String>) -> Self {
+        Self {
+            dead_letter_subject_prefix: Some(prefix.into().into()),
+            ..self
+        }
+    }
+
+    pub fn progress_period(self, progress_period: Duration) -> Self {
+        Self {
+            progress_period,
+            ..self
+        }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Retry {
+            inner,
+            backoff: self.backoff,
+            max_attempts: self.max_attempts,
+            dead_letter_subject_prefix: self.dead_letter_subject_prefix.clone(),
+            progress_period: self.progress_period,
+        }
+    }
+}
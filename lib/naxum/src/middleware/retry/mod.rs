@@ -0,0 +1,21 @@
+//! Retries failed JetStream messages with an exponential backoff, and moves them to a dead letter
+//! subject once they have been delivered too many times.
+//!
+//! [`RetryLayer`] is used in place of an [`AckLayer`](super::ack::AckLayer): successful messages
+//! are acked, failed messages are nacked with a delay computed from the delivery count in their
+//! JetStream metadata, and messages which fail on their final attempt are republished with
+//! [`headers`] describing the failure before being acked. Messages whose JetStream metadata
+//! cannot be parsed are terminated when they fail, since they can neither be counted nor dead
+//! lettered.
+//!
+//! Note that the consumer's `max_deliver` must be greater than the layer's maximum attempts (or
+//! unlimited), otherwise the server will stop redelivering a message before it can be dead
+//! lettered.
+
+mod backoff;
+mod layer;
+mod service;
+
+pub use nats_dead_letter_queue_core::headers;
+
+pub use self::{backoff::Backoff, layer::RetryLayer, service::Retry};
@@ -0,0 +1,460 @@
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_nats::{
+    jetstream::{self, message::Acker, AckKind},
+    HeaderMap, StatusCode,
+};
+use futures::future::BoxFuture;
+use tokio_util::sync::CancellationToken;
+use tower::Service;
+use tracing::{debug, trace, warn};
+
+use crate::{
+    message::Message,
+    middleware::{ack::maintain_progress::MaintainProgressTask, post_process::Info},
+    response::Response,
+};
+
+use super::{backoff::Backoff, headers, layer::RetryLayer};
+
+#[derive(Clone, Debug)]
+pub struct Retry<S> {
+    pub(crate) inner: S,
+    pub(crate) backoff: Backoff,
+    pub(crate) max_attempts: u64,
+    pub(crate) dead_letter_subject_prefix: Option<Arc<str>>,
+    pub(crate) progress_period: Duration,
+}
+
+impl<S> Retry<S> {
+    pub fn layer() -> RetryLayer {
+        RetryLayer::new()
+    }
+}
+
+impl<S> Service<Message<jetstream::Message>> for Retry<S>
+where
+    S: Service<Message<async_nats::Message>, Response = Response>,
+    S::Error: fmt::Display + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Message<jetstream::Message>) -> Self::Future {
+        // Split into jetstream message & extensions
+        let (jetstream_message, extensions) = req.split();
+
+        let info = match jetstream_message.info() {
+            Ok(info) => Some(Info::from(info)),
+            Err(err) => {
+                warn!(
+                    error = ?err,
+                    subject = jetstream_message.subject.as_str(),
+                    "failed to parse jetstream message info; message will be terminated on failure",
+                );
+                None
+            }
+        };
+        let jetstream_context = jetstream_message.context.clone();
+
+        // Split off acker from jetstream message which is now a core message, keeping a copy of
+        // the original message in case it needs to be dead lettered
+        let (core_message, acker) = jetstream_message.split();
+        let acker = Arc::new(acker);
+        let original = core_message.clone();
+
+        let message = Message::new_with_extensions(core_message, extensions);
+
+        let task_shutdown = CancellationToken::new();
+        let task =
+            MaintainProgressTask::new(acker.clone(), self.progress_period, task_shutdown.clone());
+        tokio::spawn(task.run());
+        // The drop guard will trigger a `cancel` on the token to ensure the task is shutdown even
+        // if the response future has issues
+        let shutdown_guard = task_shutdown.drop_guard();
+
+        let response = self.inner.call(message);
+
+        let on_failure = OnFailure {
+            backoff: self.backoff,
+            max_attempts: self.max_attempts,
+            dead_letter_subject_prefix: self.dead_letter_subject_prefix.clone(),
+            info,
+            jetstream_context,
+            original,
+            acker: acker.clone(),
+        };
+
+        Box::pin(async move {
+            let result = response.await;
+
+            // Progress acking is only maintained for the duration of the inner service
+            drop(shutdown_guard);
+
+            match &result {
+                Ok(response) if response.status().is_success() => {
+                    trace!("double acking message");
+                    if let Err(err) = acker.double_ack().await {
+                        warn!(
+                            error = ?err,
+                            subject = on_failure.original.subject.as_str(),
+                            "failed to double ack the message",
+                        );
+                    }
+                }
+                Ok(response) => on_failure.call(Failure::from_response(response)).await,
+                Err(err) => on_failure.call(Failure::from_error(err)).await,
+            }
+
+            result
+        })
+    }
+}
+
+struct Failure {
+    status: StatusCode,
+    description: Option<String>,
+}
+
+impl Failure {
+    fn from_response(response: &Response) -> Self {
+        let description = std::str::from_utf8(response.body().as_bytes())
+            .ok()
+            .filter(|body| !body.is_empty())
+            .map(header_safe);
+
+        Self {
+            status: response.status(),
+            description,
+        }
+    }
+
+    fn from_error<E>(err: &E) -> Self
+    where
+        E: fmt::Display,
+    {
+        Self {
+            status: StatusCode::from_u16(500).expect("status code is in valid range"),
+            description: Some(header_safe(&err.to_string())),
+        }
+    }
+}
+
+struct OnFailure {
+    backoff: Backoff,
+    max_attempts: u64,
+    dead_letter_subject_prefix: Option<Arc<str>>,
+    info: Option<Info>,
+    jetstream_context: jetstream::Context,
+    original: async_nats::Message,
+    acker: Arc<Acker>,
+}
+
+impl OnFailure {
+    async fn call(self, failure: Failure) {
+        let outcome = Outcome::for_failed_delivery(
+            &self.backoff,
+            self.max_attempts,
+            self.dead_letter_subject_prefix.as_deref(),
+            self.info.as_ref(),
+        );
+
+        match outcome {
+            Outcome::Retry { attempt, delay } => {
+                debug!(
+                    subject = self.original.subject.as_str(),
+                    attempt,
+                    max_attempts = self.max_attempts,
+                    "message failed; retrying after backoff",
+                );
+                self.nack_with_delay(delay).await;
+            }
+            Outcome::Terminate => {
+                warn!(
+                    subject = self.original.subject.as_str(),
+                    "message failed and cannot be retried or dead lettered; terminating",
+                );
+                if let Err(err) = self.acker.ack_with(AckKind::Term).await {
+                    warn!(
+                        error = ?err,
+                        subject = self.original.subject.as_str(),
+                        "failed to terminate the message",
+                    );
+                }
+            }
+            Outcome::DeadLetter {
+                attempt,
+                subject: dead_letter_subject,
+                info,
+            } => {
+                warn!(
+                    subject = self.original.subject.as_str(),
+                    dead_letter_subject = dead_letter_subject.as_str(),
+                    attempt,
+                    "message failed on its final attempt; publishing to dead letter subject",
+                );
+
+                let mut dead_letter_headers = self.original.headers.clone().unwrap_or_default();
+                add_dead_letter_headers(&mut dead_letter_headers, &self.original, info, &failure);
+
+                let published = match self
+                    .jetstream_context
+                    .publish_with_headers(
+                        dead_letter_subject,
+                        dead_letter_headers,
+                        self.original.payload.clone(),
+                    )
+                    .await
+                {
+                    Ok(ack_future) => ack_future.await.map(|_| ()),
+                    Err(err) => Err(err),
+                };
+
+                match published {
+                    Ok(()) => {
+                        trace!("double acking dead lettered message");
+                        if let Err(err) = self.acker.double_ack().await {
+                            warn!(
+                                error = ?err,
+                                subject = self.original.subject.as_str(),
+                                "failed to double ack the dead lettered message",
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        // Leave the message on its stream to try dead lettering again on the next
+                        // delivery rather than risk losing it
+                        warn!(
+                            error = ?err,
+                            subject = self.original.subject.as_str(),
+                            "failed to publish message to dead letter subject",
+                        );
+                        self.nack_with_delay(self.backoff.delay_for(attempt)).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn nack_with_delay(&self, delay: Duration) {
+        trace!(delay = ?delay, "nacking message with delay");
+        if let Err(err) = self.acker.ack_with(AckKind::Nak(Some(delay))).await {
+            warn!(
+                error = ?err,
+                subject = self.original.subject.as_str(),
+                "failed to nack the message",
+            );
+        }
+    }
+}
+
+/// What to do with a message whose delivery failed.
+#[derive(Debug)]
+enum Outcome<'a> {
+    /// Nack the message so that it is redelivered after a delay.
+    Retry { attempt: u64, delay: Duration },
+    /// Republish the message to the dead letter subject and ack it.
+    DeadLetter {
+        attempt: u64,
+        subject: String,
+        info: &'a Info,
+    },
+    /// Terminate the message so that it is never redelivered.
+    Terminate,
+}
+
+impl<'a> Outcome<'a> {
+    fn for_failed_delivery(
+        backoff: &Backoff,
+        max_attempts: u64,
+        dead_letter_subject_prefix: Option<&str>,
+        info: Option<&'a Info>,
+    ) -> Self {
+        // Without its info there is no delivery count to bound the retries with, nor a stream and
+        // consumer to dead letter it under, so retrying would redeliver the message forever
+        let Some(info) = info else {
+            return Self::Terminate;
+        };
+
+        let attempt = u64::try_from(info.delivered).unwrap_or(1).max(1);
+        if attempt < max_attempts {
+            return Self::Retry {
+                attempt,
+                delay: backoff.delay_for(attempt),
+            };
+        }
+
+        match dead_letter_subject_prefix {
+            Some(prefix) => Self::DeadLetter {
+                attempt,
+                subject: format!("{prefix}.{}.{}", info.stream, info.consumer),
+                info,
+            },
+            None => Self::Terminate,
+        }
+    }
+}
+
+fn add_dead_letter_headers(
+    dead_letter_headers: &mut HeaderMap,
+    original: &async_nats::Message,
+    info: &Info,
+    failure: &Failure,
+) {
+    dead_letter_headers.insert(headers::ORIGINAL_SUBJECT, original.subject.as_str());
+    dead_letter_headers.insert(headers::STREAM, info.stream.as_str());
+    dead_letter_headers.insert(headers::CONSUMER, info.consumer.as_str());
+    dead_letter_headers.insert(headers::STREAM_SEQUENCE, info.stream_sequence.to_string());
+    dead_letter_headers.insert(headers::DELIVERED, info.delivered.to_string());
+    dead_letter_headers.insert(headers::ERROR_STATUS, failure.status.as_u16().to_string());
+    if let Some(description) = failure.description.as_deref() {
+        dead_letter_headers.insert(headers::ERROR_DESCRIPTION, description);
+    }
+}
+
+/// Header values cannot contain line breaks, so collapse any into spaces.
+fn header_safe(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::response::IntoResponse;
+
+    fn info(delivered: i64) -> Info {
+        Info {
+            domain: None,
+            acc_hash: None,
+            stream: "PINGA_JOBS".to_owned(),
+            consumer: "pinga".to_owned(),
+            stream_sequence: 42,
+            consumer_sequence: 7,
+            delivered,
+            pending: 0,
+            published: time::OffsetDateTime::UNIX_EPOCH,
+            token: None,
+        }
+    }
+
+    fn backoff() -> Backoff {
+        Backoff::exponential(Duration::from_secs(1), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn retries_with_backoff_before_final_attempt() {
+        let info = info(3);
+
+        match Outcome::for_failed_delivery(&backoff(), 5, Some("dead_letter"), Some(&info)) {
+            Outcome::Retry { attempt, delay } => {
+                assert_eq!(3, attempt);
+                assert_eq!(Duration::from_secs(4), delay);
+            }
+            outcome => panic!("expected a retry, found {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn dead_letters_on_final_attempt() {
+        for delivered in [5, 6] {
+            let info = info(delivered);
+
+            match Outcome::for_failed_delivery(&backoff(), 5, Some("dead_letter"), Some(&info)) {
+                Outcome::DeadLetter {
+                    attempt, subject, ..
+                } => {
+                    assert_eq!(delivered as u64, attempt);
+                    assert_eq!("dead_letter.PINGA_JOBS.pinga", subject);
+                }
+                outcome => panic!("expected a dead letter, found {outcome:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn terminates_on_final_attempt_without_dead_letter_subject() {
+        let info = info(5);
+
+        assert!(matches!(
+            Outcome::for_failed_delivery(&backoff(), 5, None, Some(&info)),
+            Outcome::Terminate
+        ));
+    }
+
+    #[test]
+    fn terminates_without_info() {
+        assert!(matches!(
+            Outcome::for_failed_delivery(&backoff(), 5, Some("dead_letter"), None),
+            Outcome::Terminate
+        ));
+    }
+
+    #[test]
+    fn dead_letter_headers_describe_failure() {
+        let mut original_headers = HeaderMap::new();
+        original_headers.insert("X-Request-Id", "abc");
+        let original = async_nats::Message {
+            subject: "pinga.w1.cs1.job".into(),
+            reply: None,
+            payload: Bytes::new(),
+            headers: Some(original_headers.clone()),
+            status: None,
+            description: None,
+            length: 0,
+        };
+        let response = (
+            StatusCode::from_u16(503).expect("status code is in valid range"),
+            "service\r\nunavailable",
+        )
+            .into_response();
+
+        let mut dead_letter_headers = original_headers;
+        add_dead_letter_headers(
+            &mut dead_letter_headers,
+            &original,
+            &info(5),
+            &Failure::from_response(&response),
+        );
+
+        let header = |name: &str| {
+            dead_letter_headers
+                .get(name)
+                .map(|value| value.as_str().to_owned())
+        };
+        assert_eq!(Some("abc".to_owned()), header("X-Request-Id"));
+        assert_eq!(
+            Some("pinga.w1.cs1.job".to_owned()),
+            header(headers::ORIGINAL_SUBJECT)
+        );
+        assert_eq!(Some("PINGA_JOBS".to_owned()), header(headers::STREAM));
+        assert_eq!(Some("pinga".to_owned()), header(headers::CONSUMER));
+        assert_eq!(Some("42".to_owned()), header(headers::STREAM_SEQUENCE));
+        assert_eq!(Some("5".to_owned()), header(headers::DELIVERED));
+        assert_eq!(Some("503".to_owned()), header(headers::ERROR_STATUS));
+        assert_eq!(
+            Some("service  unavailable".to_owned()),
+            header(headers::ERROR_DESCRIPTION)
+        );
+    }
+
+    #[test]
+    fn errors_are_described_as_internal_failures() {
+        let failure = Failure::from_error(&"connection\nreset");
+
+        assert_eq!(500, failure.status.as_u16());
+        assert_eq!(Some("connection reset"), failure.description.as_deref());
+    }
+}
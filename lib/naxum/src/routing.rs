@@ -0,0 +1,609 @@
+//! Dispatching messages to handlers based on their subject.
+//!
+//! A [`Router`] holds a list of subject patterns, each with a handler or service. Patterns use
+//! the same `.` separated tokens as NATS subscriptions (including the `*` and `>` wildcards), and
+//! may also capture tokens by name with `:name`, which can then be extracted with
+//! [`Path`](crate::extract::Path):
+//!
+//! ```ignore
+//! let app = Router::new()
+//!     .route("pinga.:workspace_id.:change_set_id.job", process_job)
+//!     .route("pinga.:workspace_id.*.ping", ping)
+//!     .nest("pinga.admin", admin_router)
+//!     .fallback(unknown_subject)
+//!     .with_state(state);
+//! ```
+//!
+//! Routes are tried in the order they were added and the first matching route wins. Middleware
+//! can be applied to a single route with [`Handler::layer`] or [`Router::route_service`], to every
+//! route with [`Router::route_layer`], or to every route and the fallback with [`Router::layer`].
+
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tower::{Layer, Service, ServiceExt};
+use tracing::warn;
+
+use crate::{
+    extract::MatchedSubject,
+    handler::Handler,
+    message::{Message, MessageHead},
+    response::{IntoResponse, Response},
+    StatusCode,
+};
+
+mod boxed;
+mod pattern;
+mod route;
+
+pub use self::route::Route;
+
+use self::{
+    boxed::BoxedIntoRoute,
+    pattern::{Params, SubjectPattern},
+};
+
+/// The subject tokens captured by the route that matched a message, stored in its extensions.
+#[derive(Clone, Debug)]
+pub(crate) struct PathParams(pub(crate) Arc<Params>);
+
+/// A router that dispatches messages to handlers based on their subject.
+///
+/// See the [module docs](self) for more details.
+pub struct Router<S = (), R = async_nats::Message> {
+    routes: Vec<(SubjectPattern, Endpoint<S, R>)>,
+    fallback: Endpoint<S, R>,
+    has_custom_fallback: bool,
+}
+
+impl<S, R> Clone for Router<S, R> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            fallback: self.fallback.clone(),
+            has_custom_fallback: self.has_custom_fallback,
+        }
+    }
+}
+
+impl<S, R> fmt::Debug for Router<S, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(pattern, _)| pattern.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .field("has_custom_fallback", &self.has_custom_fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, R> Default for Router<S, R>
+where
+    S: Clone + Send + Sync + 'static,
+    R: MessageHead + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, R> Router<S, R>
+where
+    S: Clone + Send + Sync + 'static,
+    R: MessageHead + Send + 'static,
+{
+    /// Creates a new router with no routes. Messages which match no route are handled by the
+    /// default fallback, which responds with a "not found" status.
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback: Endpoint::Route(Route::new(tower::service_fn(
+                |msg: Message<R>| async move {
+                    warn!(
+                        subject = msg.subject().as_str(),
+                        "no route found for message subject",
+                    );
+                    Ok::<_, Infallible>(
+                        StatusCode::from_u16(404).expect("status code is in valid range"),
+                    )
+                },
+            ))),
+            has_custom_fallback: false,
+        }
+    }
+
+    /// Adds a handler for messages whose subject matches the pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is invalid or if a route with the same pattern already exists.
+    #[track_caller]
+    pub fn route<H, T>(self, pattern: &str, handler: H) -> Self
+    where
+        H: Handler<T, S, R>,
+        T: 'static,
+    {
+        self.push_route(
+            pattern,
+            Endpoint::Handler(BoxedIntoRoute::from_handler(handler)),
+        )
+    }
+
+    /// Adds a service for messages whose subject matches the pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is invalid or if a route with the same pattern already exists.
+    #[track_caller]
+    pub fn route_service<T>(self, pattern: &str, service: T) -> Self
+    where
+        T: Service<Message<R>, Error = Infallible> + Clone + Send + 'static,
+        T::Response: IntoResponse + 'static,
+        T::Future: Send + 'static,
+    {
+        self.push_route(pattern, Endpoint::Route(Route::new(service)))
+    }
+
+    /// Adds all the routes of another router beneath a subject prefix.
+    ///
+    /// If the nested router has a custom fallback, it handles every message under the prefix that
+    /// none of its routes match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the prefix is invalid or ends in a `>` wildcard, or if any of the resulting
+    /// patterns is invalid or already exists.
+    #[track_caller]
+    pub fn nest(mut self, prefix: &str, router: Router<S, R>) -> Self {
+        let parsed_prefix = parse_or_panic(prefix);
+        if parsed_prefix.has_tail_wildcard() {
+            panic!("cannot nest a router under `{prefix}`: prefixes cannot end in `>`");
+        }
+
+        let Router {
+            routes,
+            fallback,
+            has_custom_fallback,
+        } = router;
+
+        for (pattern, endpoint) in routes {
+            self = self.push_route(&format!("{prefix}.{pattern}"), endpoint);
+        }
+        if has_custom_fallback {
+            self = self.push_route(&format!("{prefix}.>"), fallback);
+        }
+
+        self
+    }
+
+    /// Applies a [`Layer`] to every route added so far, along with the fallback.
+    pub fn layer<L>(self, layer: L) -> Self
+    where
+        L: Layer<Route<R>> + Clone + Send + 'static,
+        L::Service: Service<Message<R>> + Clone + Send + 'static,
+        <L::Service as Service<Message<R>>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Message<R>>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Message<R>>>::Future: Send + 'static,
+    {
+        let Self {
+            routes,
+            fallback,
+            has_custom_fallback,
+        } = self;
+
+        Self {
+            routes: routes
+                .into_iter()
+                .map(|(pattern, endpoint)| (pattern, endpoint.layer(layer.clone())))
+                .collect(),
+            fallback: fallback.layer(layer),
+            has_custom_fallback,
+        }
+    }
+
+    /// Applies a [`Layer`] to every route added so far, but not to the fallback. This is useful
+    /// for middleware that should only see messages that matched a route.
+    pub fn route_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route<R>> + Clone + Send + 'static,
+        L::Service: Service<Message<R>> + Clone + Send + 'static,
+        <L::Service as Service<Message<R>>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Message<R>>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Message<R>>>::Future: Send + 'static,
+    {
+        self.routes = self
+            .routes
+            .into_iter()
+            .map(|(pattern, endpoint)| (pattern, endpoint.layer(layer.clone())))
+            .collect();
+
+        self
+    }
+
+    /// Sets the handler for messages which match no route.
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S, R>,
+        T: 'static,
+    {
+        self.fallback = Endpoint::Handler(BoxedIntoRoute::from_handler(handler));
+        self.has_custom_fallback = true;
+        self
+    }
+
+    /// Sets the service for messages which match no route.
+    pub fn fallback_service<T>(mut self, service: T) -> Self
+    where
+        T: Service<Message<R>, Error = Infallible> + Clone + Send + 'static,
+        T::Response: IntoResponse + 'static,
+        T::Future: Send + 'static,
+    {
+        self.fallback = Endpoint::Route(Route::new(service));
+        self.has_custom_fallback = true;
+        self
+    }
+
+    /// Provides the state for all handlers, returning a router that no longer needs any.
+    pub fn with_state<S2>(self, state: S) -> Router<S2, R> {
+        let Self {
+            routes,
+            fallback,
+            has_custom_fallback,
+        } = self;
+
+        Router {
+            routes: routes
+                .into_iter()
+                .map(|(pattern, endpoint)| {
+                    (pattern, Endpoint::Route(endpoint.into_route(state.clone())))
+                })
+                .collect(),
+            fallback: Endpoint::Route(fallback.into_route(state)),
+            has_custom_fallback,
+        }
+    }
+
+    #[track_caller]
+    fn push_route(mut self, pattern: &str, endpoint: Endpoint<S, R>) -> Self {
+        let pattern = parse_or_panic(pattern);
+        if self.routes.iter().any(|(existing, _)| existing == &pattern) {
+            panic!("overlapping route: a route for `{pattern}` already exists");
+        }
+
+        self.routes.push((pattern, endpoint));
+        self
+    }
+
+    fn find(&self, subject: &str) -> Option<(&SubjectPattern, &Endpoint<S, R>, Params)> {
+        self.routes.iter().find_map(|(pattern, endpoint)| {
+            pattern
+                .matches(subject)
+                .map(|params| (pattern, endpoint, params))
+        })
+    }
+}
+
+impl<R> Service<Message<R>> for Router<(), R>
+where
+    R: MessageHead + Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Every route is called with `oneshot`, which drives its readiness
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Message<R>) -> Self::Future {
+        let route = match self.find(req.subject().as_str()) {
+            Some((pattern, endpoint, params)) => {
+                let matched_subject = MatchedSubject::from(pattern.as_str());
+                let route = endpoint.clone().into_route(());
+                req.extensions_mut().insert(matched_subject);
+                req.extensions_mut().insert(PathParams(Arc::new(params)));
+                route
+            }
+            None => self.fallback.clone().into_route(()),
+        };
+
+        Box::pin(route.oneshot(req))
+    }
+}
+
+enum Endpoint<S, R> {
+    Route(Route<R>),
+    Handler(BoxedIntoRoute<S, R>),
+}
+
+impl<S, R> Clone for Endpoint<S, R> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Route(route) => Self::Route(route.clone()),
+            Self::Handler(handler) => Self::Handler(handler.clone()),
+        }
+    }
+}
+
+impl<S, R> Endpoint<S, R>
+where
+    S: Clone + Send + Sync + 'static,
+    R: MessageHead + Send + 'static,
+{
+    fn layer<L>(self, layer: L) -> Self
+    where
+        L: Layer<Route<R>> + Clone + Send + 'static,
+        L::Service: Service<Message<R>> + Clone + Send + 'static,
+        <L::Service as Service<Message<R>>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Message<R>>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Message<R>>>::Future: Send + 'static,
+    {
+        match self {
+            Self::Route(route) => Self::Route(route.layer(layer)),
+            Self::Handler(handler) => Self::Handler(handler.map(move |route| route.layer(layer))),
+        }
+    }
+
+    fn into_route(self, state: S) -> Route<R> {
+        match self {
+            Self::Route(route) => route,
+            Self::Handler(handler) => handler.into_route(state),
+        }
+    }
+}
+
+#[track_caller]
+fn parse_or_panic(pattern: &str) -> SubjectPattern {
+    match SubjectPattern::parse(pattern) {
+        Ok(parsed) => parsed,
+        Err(err) => panic!("invalid subject pattern `{pattern}`: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_nats::Subject;
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::extract::{Path, State};
+
+    fn message(subject: &str) -> Message<async_nats::Message> {
+        Message::new(async_nats::Message {
+            subject: Subject::from(subject),
+            reply: None,
+            payload: Bytes::new(),
+            headers: None,
+            status: None,
+            description: None,
+            length: 0,
+        })
+    }
+
+    async fn call(router: &Router, subject: &str) -> (u16, String) {
+        let response = router
+            .clone()
+            .oneshot(message(subject))
+            .await
+            .expect("router is infallible");
+
+        (
+            response.status().as_u16(),
+            String::from_utf8(response.body().as_bytes().to_vec()).expect("body is utf-8"),
+        )
+    }
+
+    async fn job(Path((workspace_id, change_set_id)): Path<(String, String)>) -> String {
+        format!("job {workspace_id} {change_set_id}")
+    }
+
+    async fn matched(subject: MatchedSubject) -> String {
+        subject.as_str().to_owned()
+    }
+
+    #[derive(Clone)]
+    struct CountLayer(Arc<AtomicUsize>);
+
+    impl<T> Layer<T> for CountLayer {
+        type Service = Count<T>;
+
+        fn layer(&self, inner: T) -> Self::Service {
+            Count {
+                inner,
+                count: self.0.clone(),
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct Count<T> {
+        inner: T,
+        count: Arc<AtomicUsize>,
+    }
+
+    impl<T, M> Service<M> for Count<T>
+    where
+        T: Service<M>,
+    {
+        type Response = T::Response;
+        type Error = T::Error;
+        type Future = T::Future;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: M) -> Self::Future {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            self.inner.call(req)
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatches_to_first_matching_route() {
+        let router = Router::new()
+            .route("pinga.:workspace_id.:change_set_id.job", job)
+            .route("pinga.>", matched)
+            .route("pinga.:workspace_id.*.job", || async { "unreachable" });
+
+        assert_eq!(
+            (200, "job w1 cs1".to_owned()),
+            call(&router, "pinga.w1.cs1.job").await
+        );
+        assert_eq!(
+            (200, "pinga.>".to_owned()),
+            call(&router, "pinga.w1.other").await
+        );
+    }
+
+    #[tokio::test]
+    async fn default_fallback_responds_not_found() {
+        let router: Router = Router::new().route("pinga.jobs", || async { "jobs" });
+
+        assert_eq!(404, call(&router, "rebaser.jobs").await.0);
+        assert_eq!(404, call(&router, "pinga.jobs.more").await.0);
+    }
+
+    #[tokio::test]
+    async fn custom_fallback_handles_unmatched_subjects() {
+        let router: Router = Router::new()
+            .route("pinga.jobs", || async { "jobs" })
+            .fallback(|| async { "fallback" });
+
+        assert_eq!((200, "jobs".to_owned()), call(&router, "pinga.jobs").await);
+        assert_eq!(
+            (200, "fallback".to_owned()),
+            call(&router, "rebaser.jobs").await
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_routes_match_beneath_prefix() {
+        let admin = Router::new()
+            .route("reset.:workspace_id", matched)
+            .fallback(|| async { "admin fallback" });
+        let router = Router::new()
+            .nest("pinga.admin", admin)
+            .route("pinga.:workspace_id", || async { "workspace" })
+            .fallback(|| async { "fallback" });
+
+        assert_eq!(
+            (200, "pinga.admin.reset.:workspace_id".to_owned()),
+            call(&router, "pinga.admin.reset.w1").await
+        );
+        assert_eq!(
+            (200, "admin fallback".to_owned()),
+            call(&router, "pinga.admin.unknown.thing").await
+        );
+        assert_eq!(
+            (200, "workspace".to_owned()),
+            call(&router, "pinga.w1").await
+        );
+        assert_eq!(
+            (200, "fallback".to_owned()),
+            call(&router, "rebaser.w1").await
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_router_without_fallback_uses_outer_fallback() {
+        let admin = Router::new().route("reset", || async { "reset" });
+        let router: Router = Router::new()
+            .nest("pinga.admin", admin)
+            .fallback(|| async { "fallback" });
+
+        assert_eq!(
+            (200, "reset".to_owned()),
+            call(&router, "pinga.admin.reset").await
+        );
+        assert_eq!(
+            (200, "fallback".to_owned()),
+            call(&router, "pinga.admin.other").await
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overlapping route")]
+    fn overlapping_routes_panic() {
+        let _: Router = Router::new()
+            .route("pinga.:workspace_id", || async {})
+            .route("pinga.:workspace_id", || async {});
+    }
+
+    #[test]
+    #[should_panic(expected = "prefixes cannot end in `>`")]
+    fn nesting_under_tail_wildcard_panics() {
+        let _: Router = Router::new().nest("pinga.>", Router::new());
+    }
+
+    #[tokio::test]
+    async fn layer_applies_to_routes_and_fallback() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let router: Router = Router::new()
+            .route("pinga.jobs", || async { "jobs" })
+            .layer(CountLayer(count.clone()));
+
+        call(&router, "pinga.jobs").await;
+        call(&router, "rebaser.jobs").await;
+
+        assert_eq!(2, count.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn route_layer_skips_fallback_and_later_routes() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let router: Router = Router::new()
+            .route("pinga.jobs", || async { "jobs" })
+            .route_layer(CountLayer(count.clone()))
+            .route("pinga.other", || async { "other" });
+
+        call(&router, "pinga.jobs").await;
+        call(&router, "pinga.other").await;
+        call(&router, "rebaser.jobs").await;
+
+        assert_eq!(1, count.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn handlers_receive_state() {
+        let router: Router = Router::new()
+            .route(
+                "pinga.:name",
+                |State(greeting): State<&'static str>, Path(name): Path<String>| async move {
+                    format!("{greeting} {name}")
+                },
+            )
+            .with_state("hello");
+
+        assert_eq!(
+            (200, "hello w1".to_owned()),
+            call(&router, "pinga.w1").await
+        );
+    }
+
+    #[tokio::test]
+    async fn path_rejection_responds_bad_request() {
+        let router: Router = Router::new()
+            .route("pinga.:count", |Path(count): Path<u32>| async move {
+                count.to_string()
+            });
+
+        assert_eq!((200, "42".to_owned()), call(&router, "pinga.42").await);
+        assert_eq!(400, call(&router, "pinga.many").await.0);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{handler::Handler, message::MessageHead};
+
+use super::Route;
+
+/// A handler which has not been given its state yet, and so cannot become a [`Route`] until the
+/// router's state is provided.
+pub(crate) struct BoxedIntoRoute<S, R>(Box<dyn ErasedIntoRoute<S, R>>);
+
+impl<S, R> BoxedIntoRoute<S, R>
+where
+    S: Clone + Send + Sync + 'static,
+    R: MessageHead + Send + 'static,
+{
+    pub(crate) fn from_handler<H, T>(handler: H) -> Self
+    where
+        H: Handler<T, S, R>,
+        T: 'static,
+    {
+        Self(Box::new(MakeErasedHandler {
+            handler,
+            into_route: |handler, state| Route::new(Handler::<T, S, R>::with_state(handler, state)),
+        }))
+    }
+
+    /// Applies `f` to the route once it has been created.
+    pub(crate) fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Route<R>) -> Route<R> + Clone + Send + 'static,
+    {
+        Self(Box::new(Map {
+            inner: self.0,
+            layer: Box::new(f),
+        }))
+    }
+
+    pub(crate) fn into_route(self, state: S) -> Route<R> {
+        self.0.into_route(state)
+    }
+}
+
+impl<S, R> Clone for BoxedIntoRoute<S, R> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+trait ErasedIntoRoute<S, R>: Send {
+    fn clone_box(&self) -> Box<dyn ErasedIntoRoute<S, R>>;
+
+    fn into_route(self: Box<Self>, state: S) -> Route<R>;
+}
+
+struct MakeErasedHandler<H, S, R> {
+    handler: H,
+    into_route: fn(H, S) -> Route<R>,
+}
+
+impl<H, S, R> Clone for MakeErasedHandler<H, S, R>
+where
+    H: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            handler: self.handler.clone(),
+            into_route: self.into_route,
+        }
+    }
+}
+
+impl<H, S, R> ErasedIntoRoute<S, R> for MakeErasedHandler<H, S, R>
+where
+    H: Clone + Send + 'static,
+    S: 'static,
+    R: 'static,
+{
+    fn clone_box(&self) -> Box<dyn ErasedIntoRoute<S, R>> {
+        Box::new(self.clone())
+    }
+
+    fn into_route(self: Box<Self>, state: S) -> Route<R> {
+        (self.into_route)(self.handler, state)
+    }
+}
+
+struct Map<S, R> {
+    inner: Box<dyn ErasedIntoRoute<S, R>>,
+    layer: Box<dyn LayerFn<R>>,
+}
+
+impl<S, R> ErasedIntoRoute<S, R> for Map<S, R>
+where
+    S: 'static,
+    R: 'static,
+{
+    fn clone_box(&self) -> Box<dyn ErasedIntoRoute<S, R>> {
+        Box::new(Self {
+            inner: self.inner.clone_box(),
+            layer: self.layer.clone_box(),
+        })
+    }
+
+    fn into_route(self: Box<Self>, state: S) -> Route<R> {
+        (self.layer)(self.inner.into_route(state))
+    }
+}
+
+trait LayerFn<R>: FnOnce(Route<R>) -> Route<R> + Send {
+    fn clone_box(&self) -> Box<dyn LayerFn<R>>;
+}
+
+impl<F, R> LayerFn<R> for F
+where
+    F: FnOnce(Route<R>) -> Route<R> + Clone + Send + 'static,
+{
+    fn clone_box(&self) -> Box<dyn LayerFn<R>> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,210 @@
+use std::{error, fmt, sync::Arc};
+
+const TOKEN_SEPARATOR: char = '.';
+const PARAM_PREFIX: char = ':';
+const WILDCARD: &str = "*";
+const TAIL_WILDCARD: &str = ">";
+
+/// Names and values of the subject tokens captured by a [`SubjectPattern`].
+pub(crate) type Params = Vec<(Arc<str>, String)>;
+
+/// A pattern that NATS subjects are matched against.
+///
+/// Patterns are made of `.` separated tokens, mirroring NATS subscriptions:
+///
+/// * `literal` matches the same token exactly
+/// * `*` matches any single token
+/// * `:name` matches any single token and captures it as `name`
+/// * `>` matches one or more remaining tokens and may only be the last token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SubjectPattern {
+    raw: Arc<str>,
+    tokens: Arc<[Token]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Literal(Box<str>),
+    Param(Arc<str>),
+    Wildcard,
+    TailWildcard,
+}
+
+impl SubjectPattern {
+    pub(crate) fn parse(raw: &str) -> Result<Self, InvalidSubjectPattern> {
+        if raw.is_empty() {
+            return Err(InvalidSubjectPattern::Empty);
+        }
+
+        let raw_tokens: Vec<&str> = raw.split(TOKEN_SEPARATOR).collect();
+        let mut tokens = Vec::with_capacity(raw_tokens.len());
+        for (index, raw_token) in raw_tokens.iter().enumerate() {
+            let token = match *raw_token {
+                "" => return Err(InvalidSubjectPattern::EmptyToken),
+                WILDCARD => Token::Wildcard,
+                TAIL_WILDCARD if index == raw_tokens.len() - 1 => Token::TailWildcard,
+                TAIL_WILDCARD => return Err(InvalidSubjectPattern::TailWildcardNotLast),
+                token if token.contains(WILDCARD) || token.contains(TAIL_WILDCARD) => {
+                    return Err(InvalidSubjectPattern::PartialWildcard(token.to_owned()));
+                }
+                token => match token.strip_prefix(PARAM_PREFIX) {
+                    Some("") => return Err(InvalidSubjectPattern::EmptyParamName),
+                    Some(name) => {
+                        if tokens
+                            .iter()
+                            .any(|token| matches!(token, Token::Param(existing) if existing.as_ref() == name))
+                        {
+                            return Err(InvalidSubjectPattern::DuplicateParam(name.to_owned()));
+                        }
+                        Token::Param(name.into())
+                    }
+                    None => Token::Literal(token.into()),
+                },
+            };
+            tokens.push(token);
+        }
+
+        Ok(Self {
+            raw: raw.into(),
+            tokens: tokens.into(),
+        })
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub(crate) fn has_tail_wildcard(&self) -> bool {
+        matches!(self.tokens.last(), Some(Token::TailWildcard))
+    }
+
+    /// Returns the captured params if the subject matches this pattern.
+    pub(crate) fn matches(&self, subject: &str) -> Option<Params> {
+        let mut subject_tokens = subject.split(TOKEN_SEPARATOR);
+        let mut params = Vec::new();
+
+        for token in self.tokens.iter() {
+            if let Token::TailWildcard = token {
+                return subject_tokens.next().map(|_| params);
+            }
+
+            let subject_token = subject_tokens.next()?;
+            match token {
+                Token::Literal(literal) if literal.as_ref() != subject_token => return None,
+                Token::Param(name) => params.push((name.clone(), subject_token.to_owned())),
+                Token::Literal(_) | Token::Wildcard | Token::TailWildcard => {}
+            }
+        }
+
+        subject_tokens.next().is_none().then_some(params)
+    }
+}
+
+impl fmt::Display for SubjectPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum InvalidSubjectPattern {
+    DuplicateParam(String),
+    Empty,
+    EmptyParamName,
+    EmptyToken,
+    PartialWildcard(String),
+    TailWildcardNotLast,
+}
+
+impl fmt::Display for InvalidSubjectPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateParam(name) => write!(f, "param `{name}` is captured more than once"),
+            Self::Empty => f.write_str("pattern is empty"),
+            Self::EmptyParamName => f.write_str("params must have a name"),
+            Self::EmptyToken => f.write_str("pattern contains an empty token"),
+            Self::PartialWildcard(token) => {
+                write!(f, "wildcards must be a whole token, found `{token}`")
+            }
+            Self::TailWildcardNotLast => f.write_str("`>` may only be the last token"),
+        }
+    }
+}
+
+impl error::Error for InvalidSubjectPattern {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Params {
+        pairs
+            .iter()
+            .map(|(name, value)| (Arc::from(*name), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn literal_patterns_match_exactly() {
+        let pattern = SubjectPattern::parse("pinga.jobs").expect("valid pattern");
+
+        assert_eq!(Some(params(&[])), pattern.matches("pinga.jobs"));
+        assert_eq!(None, pattern.matches("pinga.jobs.more"));
+        assert_eq!(None, pattern.matches("pinga"));
+        assert_eq!(None, pattern.matches("pinga.other"));
+    }
+
+    #[test]
+    fn wildcards_and_params_match_single_tokens() {
+        let pattern =
+            SubjectPattern::parse("rebaser.:workspace_id.*.process").expect("valid pattern");
+
+        assert_eq!(
+            Some(params(&[("workspace_id", "w1")])),
+            pattern.matches("rebaser.w1.cs1.process")
+        );
+        assert_eq!(None, pattern.matches("rebaser.w1.process"));
+        assert_eq!(None, pattern.matches("rebaser.w1.cs1.extra.process"));
+    }
+
+    #[test]
+    fn tail_wildcard_matches_one_or_more_tokens() {
+        let pattern = SubjectPattern::parse("veritech.:kind.>").expect("valid pattern");
+
+        assert!(pattern.has_tail_wildcard());
+        assert_eq!(
+            Some(params(&[("kind", "action")])),
+            pattern.matches("veritech.action.a.b.c")
+        );
+        assert_eq!(
+            Some(params(&[("kind", "action")])),
+            pattern.matches("veritech.action.a")
+        );
+        assert_eq!(None, pattern.matches("veritech.action"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert_eq!(Err(InvalidSubjectPattern::Empty), SubjectPattern::parse(""));
+        assert_eq!(
+            Err(InvalidSubjectPattern::EmptyToken),
+            SubjectPattern::parse("a..b")
+        );
+        assert_eq!(
+            Err(InvalidSubjectPattern::TailWildcardNotLast),
+            SubjectPattern::parse("a.>.b")
+        );
+        assert_eq!(
+            Err(InvalidSubjectPattern::PartialWildcard("a*".to_owned())),
+            SubjectPattern::parse("a*.b")
+        );
+        assert_eq!(
+            Err(InvalidSubjectPattern::EmptyParamName),
+            SubjectPattern::parse("a.:")
+        );
+        assert_eq!(
+            Err(InvalidSubjectPattern::DuplicateParam("id".to_owned())),
+            SubjectPattern::parse("a.:id.:id")
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use dyn_clone::DynClone;
+use si_data_spicedb::{Permission, Relationship, Relationships, SpiceDbClient, ZedToken};
+
+use crate::{Error, Result};
+
+/// A store of relationships which can check permissions against them, either a SpiceDB server or
+/// an [`InMemoryPermissions`](crate::InMemoryPermissions).
+///
+/// Services which don't know which store they use until runtime can hold a
+/// [`BoxPermissionsClient`].
+#[async_trait]
+pub trait PermissionsClient: DynClone + Send + Sync {
+    async fn create_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>>;
+
+    async fn delete_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>>;
+
+    /// Reads the relationships for the given relationship's object and relation, ignoring its
+    /// subject.
+    async fn read_relationship(&mut self, relationship: Relationship) -> Result<Relationships>;
+
+    async fn check_permissions(&mut self, permission: Permission) -> Result<bool>;
+
+    /// Lists the ids of the subjects of the given type which have the permission on the resource.
+    async fn lookup_subjects(
+        &mut self,
+        resource_type: String,
+        resource_id: String,
+        permission: String,
+        subject_type: String,
+    ) -> Result<Vec<String>>;
+}
+
+dyn_clone::clone_trait_object!(PermissionsClient);
+
+/// A [`PermissionsClient`] whose store is chosen at runtime.
+pub type BoxPermissionsClient = Box<dyn PermissionsClient>;
+
+#[async_trait]
+impl PermissionsClient for BoxPermissionsClient {
+    async fn create_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        (**self).create_relationships(relationships).await
+    }
+
+    async fn delete_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        (**self).delete_relationships(relationships).await
+    }
+
+    async fn read_relationship(&mut self, relationship: Relationship) -> Result<Relationships> {
+        (**self).read_relationship(relationship).await
+    }
+
+    async fn check_permissions(&mut self, permission: Permission) -> Result<bool> {
+        (**self).check_permissions(permission).await
+    }
+
+    async fn lookup_subjects(
+        &mut self,
+        resource_type: String,
+        resource_id: String,
+        permission: String,
+        subject_type: String,
+    ) -> Result<Vec<String>> {
+        (**self)
+            .lookup_subjects(resource_type, resource_id, permission, subject_type)
+            .await
+    }
+}
+
+#[async_trait]
+impl PermissionsClient for SpiceDbClient {
+    async fn create_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        SpiceDbClient::create_relationships(self, relationships)
+            .await
+            .map_err(Error::SpiceDb)
+    }
+
+    async fn delete_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        SpiceDbClient::delete_relationships(self, relationships)
+            .await
+            .map_err(Error::SpiceDb)
+    }
+
+    async fn read_relationship(&mut self, relationship: Relationship) -> Result<Relationships> {
+        SpiceDbClient::read_relationship(self, relationship)
+            .await
+            .map_err(Error::SpiceDb)
+    }
+
+    async fn check_permissions(&mut self, permission: Permission) -> Result<bool> {
+        SpiceDbClient::check_permissions(self, permission)
+            .await
+            .map_err(Error::SpiceDb)
+    }
+
+    async fn lookup_subjects(
+        &mut self,
+        resource_type: String,
+        resource_id: String,
+        permission: String,
+        subject_type: String,
+    ) -> Result<Vec<String>> {
+        SpiceDbClient::lookup_subjects(self, resource_type, resource_id, permission, subject_type)
+            .await
+            .map_err(Error::SpiceDb)
+    }
+}
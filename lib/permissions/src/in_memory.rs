@@ -0,0 +1,321 @@
+use std::{
+    collections::BTreeSet,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use si_data_spicedb::{Relationship, Relationships, SpiceDBObject, ZedToken};
+
+use crate::{Error, ObjectType, Permission, PermissionsClient, Relation, Result};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Tuple {
+    object_type: String,
+    object_id: String,
+    relation: String,
+    subject_type: String,
+    subject_id: String,
+}
+
+impl From<&Relationship> for Tuple {
+    fn from(relationship: &Relationship) -> Self {
+        Self {
+            object_type: relationship.object().r#type().to_owned(),
+            object_id: relationship.object().id().to_owned(),
+            relation: relationship.relation().to_owned(),
+            subject_type: relationship.subject().r#type().to_owned(),
+            subject_id: relationship.subject().id().to_owned(),
+        }
+    }
+}
+
+/// An in-process [`PermissionsClient`] which implements the same schema as SpiceDB, so that
+/// permission checks can be exercised without a SpiceDB server.
+///
+/// Clones share the same relationships. No [`ZedTokens`](ZedToken) are issued, as every write is
+/// visible immediately.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryPermissions {
+    relationships: Arc<Mutex<BTreeSet<Tuple>>>,
+}
+
+impl InMemoryPermissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn stored(&self) -> MutexGuard<'_, BTreeSet<Tuple>> {
+        // The set is never left partially updated, so a poisoned lock can still be used.
+        self.relationships
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn relations_granting(object_type: &str, permission: &str) -> Result<Vec<String>> {
+        let unknown = || Error::UnknownPermission {
+            object_type: object_type.to_owned(),
+            permission: permission.to_owned(),
+        };
+        if object_type != ObjectType::Workspace.to_string() {
+            return Err(unknown());
+        }
+
+        // As in SpiceDB, a relation can be checked directly as well as a permission.
+        if let Ok(permission) = Permission::from_str(permission) {
+            Ok(permission
+                .granted_by()
+                .iter()
+                .map(ToString::to_string)
+                .collect())
+        } else if let Ok(relation) = Relation::from_str(permission) {
+            Ok(vec![relation.to_string()])
+        } else {
+            Err(unknown())
+        }
+    }
+}
+
+#[async_trait]
+impl PermissionsClient for InMemoryPermissions {
+    async fn create_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        let mut stored = self.stored();
+        stored.extend(relationships.iter().map(Tuple::from));
+        Ok(None)
+    }
+
+    async fn delete_relationships(
+        &mut self,
+        relationships: Relationships,
+    ) -> Result<Option<ZedToken>> {
+        let mut stored = self.stored();
+        for relationship in &relationships {
+            stored.remove(&Tuple::from(relationship));
+        }
+        Ok(None)
+    }
+
+    async fn read_relationship(&mut self, relationship: Relationship) -> Result<Relationships> {
+        let filter = Tuple::from(&relationship);
+        let stored = self.stored();
+        Ok(stored
+            .iter()
+            .filter(|tuple| {
+                tuple.object_type == filter.object_type
+                    && tuple.object_id == filter.object_id
+                    && tuple.relation == filter.relation
+            })
+            .map(|tuple| {
+                Relationship::new(
+                    SpiceDBObject::new(&tuple.object_type, &tuple.object_id),
+                    &tuple.relation,
+                    SpiceDBObject::new(&tuple.subject_type, &tuple.subject_id),
+                    None,
+                )
+            })
+            .collect())
+    }
+
+    async fn check_permissions(&mut self, permission: si_data_spicedb::Permission) -> Result<bool> {
+        let resource = permission.resource();
+        let subject = permission.subject();
+        let relations = Self::relations_granting(resource.r#type(), permission.permission())?;
+
+        let stored = self.stored();
+        Ok(relations.into_iter().any(|relation| {
+            stored.contains(&Tuple {
+                object_type: resource.r#type().to_owned(),
+                object_id: resource.id().to_owned(),
+                relation,
+                subject_type: subject.r#type().to_owned(),
+                subject_id: subject.id().to_owned(),
+            })
+        }))
+    }
+
+    async fn lookup_subjects(
+        &mut self,
+        resource_type: String,
+        resource_id: String,
+        permission: String,
+        subject_type: String,
+    ) -> Result<Vec<String>> {
+        let relations = Self::relations_granting(&resource_type, &permission)?;
+
+        let stored = self.stored();
+        let subjects: BTreeSet<String> = stored
+            .iter()
+            .filter(|tuple| {
+                tuple.object_type == resource_type
+                    && tuple.object_id == resource_id
+                    && tuple.subject_type == subject_type
+                    && relations.contains(&tuple.relation)
+            })
+            .map(|tuple| tuple.subject_id.clone())
+            .collect();
+        Ok(subjects.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::{PermissionBuilder, RelationBuilder};
+
+    async fn grant(client: &mut InMemoryPermissions, relation: Relation, user: &str) {
+        RelationBuilder::new()
+            .object(ObjectType::Workspace, "workspace")
+            .relation(relation)
+            .subject(ObjectType::User, user)
+            .create(client)
+            .await
+            .expect("could not create relationship");
+    }
+
+    async fn has_permission(
+        client: &mut InMemoryPermissions,
+        permission: Permission,
+        user: &str,
+    ) -> bool {
+        PermissionBuilder::new()
+            .object(ObjectType::Workspace, "workspace")
+            .permission(permission)
+            .subject(ObjectType::User, user)
+            .has_permission(client)
+            .await
+            .expect("could not check permission")
+    }
+
+    #[tokio::test]
+    async fn roles_grant_expected_permissions() {
+        let expected = [
+            (Relation::Viewer, vec![Permission::View]),
+            (
+                Relation::Editor,
+                vec![
+                    Permission::View,
+                    Permission::Edit,
+                    Permission::Operate,
+                    Permission::AuthorFuncs,
+                ],
+            ),
+            (
+                Relation::Operator,
+                vec![Permission::View, Permission::Edit, Permission::Operate],
+            ),
+            (
+                Relation::FuncAuthor,
+                vec![Permission::View, Permission::Edit, Permission::AuthorFuncs],
+            ),
+            (
+                Relation::Approver,
+                vec![
+                    Permission::View,
+                    Permission::Edit,
+                    Permission::Operate,
+                    Permission::AuthorFuncs,
+                    Permission::Approve,
+                ],
+            ),
+            (Relation::Owner, Permission::iter().collect()),
+        ];
+
+        for (relation, permissions) in expected {
+            let mut client = InMemoryPermissions::new();
+            grant(&mut client, relation, "user").await;
+
+            for permission in Permission::iter() {
+                assert_eq!(
+                    permissions.contains(&permission),
+                    has_permission(&mut client, permission, "user").await,
+                    "{relation} and {permission}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn permissions_are_per_user_and_workspace() {
+        let mut client = InMemoryPermissions::new();
+        grant(&mut client, Relation::Editor, "editor").await;
+
+        assert!(!has_permission(&mut client, Permission::View, "someone-else").await);
+        assert!(!PermissionBuilder::new()
+            .object(ObjectType::Workspace, "other-workspace")
+            .permission(Permission::View)
+            .subject(ObjectType::User, "editor")
+            .has_permission(&mut client)
+            .await
+            .expect("could not check permission"));
+    }
+
+    #[tokio::test]
+    async fn read_and_delete_relationships() {
+        let mut client = InMemoryPermissions::new();
+        grant(&mut client, Relation::Operator, "scott").await;
+        grant(&mut client, Relation::Operator, "fletcher").await;
+        grant(&mut client, Relation::Viewer, "paul").await;
+
+        let operators = RelationBuilder::new()
+            .object(ObjectType::Workspace, "workspace")
+            .relation(Relation::Operator);
+        let mut subjects: Vec<String> = operators
+            .read(&mut client)
+            .await
+            .expect("could not read relationships")
+            .iter()
+            .map(|relationship| relationship.subject().id().to_owned())
+            .collect();
+        subjects.sort();
+        assert_eq!(vec!["fletcher", "scott"], subjects);
+
+        operators
+            .subject(ObjectType::User, "scott")
+            .delete(&mut client)
+            .await
+            .expect("could not delete relationship");
+        assert!(!has_permission(&mut client, Permission::Operate, "scott").await);
+        assert!(has_permission(&mut client, Permission::Operate, "fletcher").await);
+    }
+
+    #[tokio::test]
+    async fn lookup_subjects() {
+        let mut client = InMemoryPermissions::new();
+        grant(&mut client, Relation::Approver, "scott").await;
+        grant(&mut client, Relation::Owner, "fletcher").await;
+        grant(&mut client, Relation::Editor, "paul").await;
+
+        let approvers = client
+            .lookup_subjects(
+                ObjectType::Workspace.to_string(),
+                "workspace".to_owned(),
+                Permission::Approve.to_string(),
+                ObjectType::User.to_string(),
+            )
+            .await
+            .expect("could not look up subjects");
+
+        assert_eq!(vec!["fletcher", "scott"], approvers);
+    }
+
+    #[tokio::test]
+    async fn unknown_permission() {
+        let mut client = InMemoryPermissions::new();
+        let err = client
+            .check_permissions(si_data_spicedb::Permission::new(
+                SpiceDBObject::new(ObjectType::Workspace, "workspace"),
+                "delete_everything",
+                SpiceDBObject::new(ObjectType::User, "user"),
+                None,
+            ))
+            .await
+            .expect_err("checked an unknown permission");
+
+        assert!(matches!(err, Error::UnknownPermission { .. }));
+    }
+}
@@ -1,10 +1,14 @@
-use si_data_spicedb::{
-    Relationship, Relationships, SpiceDBObject, SpiceDbClient, SpiceDbError, ZedToken,
-};
+use si_data_spicedb::{Relationship, Relationships, SpiceDBObject, SpiceDbError, ZedToken};
 use si_events::{UserPk, WorkspacePk};
 use std::result;
 use thiserror::Error;
 
+mod client;
+mod in_memory;
+
+pub use client::{BoxPermissionsClient, PermissionsClient};
+pub use in_memory::InMemoryPermissions;
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum Error {
@@ -17,6 +21,11 @@ pub enum Error {
     RelationBuilder { required_fields: Vec<String> },
     #[error("spicedb client error: {0}")]
     SpiceDb(#[from] SpiceDbError),
+    #[error("unknown permission {permission} on object type {object_type}")]
+    UnknownPermission {
+        object_type: String,
+        permission: String,
+    },
 }
 
 type Result<T> = result::Result<T, Error>;
@@ -28,18 +37,71 @@ pub enum ObjectType {
     Workspace,
 }
 
-#[derive(Clone, Copy, strum::Display)]
+/// Permissions on a workspace. Each one is granted by the [`Relations`](Relation) returned by
+/// [`Permission::granted_by`], which must match `component/spicedb/schema.zed`.
+#[derive(Clone, Copy, strum::Display, strum::EnumIter, strum::EnumString, Debug, PartialEq, Eq)]
 #[strum(serialize_all = "snake_case")]
 pub enum Permission {
+    /// Approve change sets for applying.
     Approve,
+    /// Create and change functions, schema variants and modules.
+    AuthorFuncs,
+    /// Change components and views.
+    Edit,
+    /// Manage the workspace itself, such as its members and integrations.
     Manage,
+    /// Run actions and functions.
+    Operate,
+    /// Read anything in the workspace.
+    View,
 }
 
-#[derive(Clone, Copy, strum::Display, Debug)]
+impl Permission {
+    /// The relations on a workspace which grant this permission.
+    pub fn granted_by(&self) -> &'static [Relation] {
+        match self {
+            Self::Approve => &[Relation::Approver, Relation::Owner],
+            Self::AuthorFuncs => &[
+                Relation::Editor,
+                Relation::FuncAuthor,
+                Relation::Approver,
+                Relation::Owner,
+            ],
+            Self::Edit => &[
+                Relation::Editor,
+                Relation::Operator,
+                Relation::FuncAuthor,
+                Relation::Approver,
+                Relation::Owner,
+            ],
+            Self::Manage => &[Relation::Owner],
+            Self::Operate => &[
+                Relation::Editor,
+                Relation::Operator,
+                Relation::Approver,
+                Relation::Owner,
+            ],
+            Self::View => &[
+                Relation::Viewer,
+                Relation::Editor,
+                Relation::Operator,
+                Relation::FuncAuthor,
+                Relation::Approver,
+                Relation::Owner,
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, strum::Display, strum::EnumIter, strum::EnumString, Debug, PartialEq, Eq)]
 #[strum(serialize_all = "snake_case")]
 pub enum Relation {
     Approver,
+    Editor,
+    FuncAuthor,
+    Operator,
     Owner,
+    Viewer,
 }
 
 /// RelationBuilder allows defining a relationship in SpiceDb.
@@ -102,39 +164,34 @@ impl RelationBuilder {
     }
 
     /// Creates a new relationship in SpiceDb
-    pub async fn create(&self, client: &mut SpiceDbClient) -> Result<Option<ZedToken>> {
+    pub async fn create(&self, client: &mut impl PermissionsClient) -> Result<Option<ZedToken>> {
         match self.check() {
-            Ok(relationship) => client
-                .create_relationships(vec![relationship])
-                .await
-                .map_err(Error::SpiceDb),
+            Ok(relationship) => client.create_relationships(vec![relationship]).await,
             Err(err) => Err(err),
         }
     }
 
     /// Deletes an existing relationship in SpiceDb
-    pub async fn delete(&self, client: &mut SpiceDbClient) -> Result<Option<ZedToken>> {
+    pub async fn delete(&self, client: &mut impl PermissionsClient) -> Result<Option<ZedToken>> {
         match self.check() {
-            Ok(relationship) => client
-                .delete_relationships(vec![relationship])
-                .await
-                .map_err(Error::SpiceDb),
+            Ok(relationship) => client.delete_relationships(vec![relationship]).await,
             Err(err) => Err(err),
         }
     }
 
     /// Reads existing relations in SpiceDb for a given object and relation
-    pub async fn read(&self, client: &mut SpiceDbClient) -> Result<Relationships> {
+    pub async fn read(&self, client: &mut impl PermissionsClient) -> Result<Relationships> {
         match (self.object.clone(), self.relation) {
-            (Some(object), Some(relation)) => client
-                .read_relationship(Relationship::new(
-                    object,
-                    relation,
-                    SpiceDBObject::empty(),
-                    self.zed_token.clone(),
-                ))
-                .await
-                .map_err(Error::SpiceDb),
+            (Some(object), Some(relation)) => {
+                client
+                    .read_relationship(Relationship::new(
+                        object,
+                        relation,
+                        SpiceDBObject::empty(),
+                        self.zed_token.clone(),
+                    ))
+                    .await
+            }
             _ => Err(Error::RelationBuilder {
                 required_fields: vec!["object".to_string(), "relation".to_string()],
             }),
@@ -226,12 +283,9 @@ impl PermissionBuilder {
     }
 
     /// Checks if the given subject has the given permission in the given object
-    pub async fn has_permission(&self, client: &mut SpiceDbClient) -> Result<bool> {
+    pub async fn has_permission(&self, client: &mut impl PermissionsClient) -> Result<bool> {
         match self.check_has_permission() {
-            Ok(perms) => Ok(client
-                .check_permissions(perms)
-                .await
-                .map_err(Error::SpiceDb)?),
+            Ok(perms) => client.check_permissions(perms).await,
             Err(err) => Err(err),
         }
     }
@@ -0,0 +1,58 @@
+use std::{collections::BTreeSet, env, fs, path::PathBuf};
+
+use buck2_resources::Buck2Resources;
+use permissions::Permission;
+use strum::IntoEnumIterator;
+
+#[allow(clippy::disallowed_methods)] // Used only in tests to locate the schema
+fn schema_path() -> PathBuf {
+    if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
+        Buck2Resources::read()
+            .expect("should be able to read buck2 resources")
+            .get_ends_with("schema.zed")
+            .expect("should be able to get schema")
+    } else {
+        let dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR should be set");
+        PathBuf::from(dir).join("../../component/spicedb/schema.zed")
+    }
+}
+
+/// Parses each `permission <name> = <relation>+<relation>...` line of the workspace definition.
+fn schema_permissions() -> Vec<(String, BTreeSet<String>)> {
+    let schema = fs::read_to_string(schema_path()).expect("should be able to read schema");
+    schema
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("permission "))
+        .map(|definition| {
+            let (name, relations) = definition
+                .split_once('=')
+                .expect("permission should have a definition");
+            (
+                name.trim().to_owned(),
+                relations.split('+').map(|r| r.trim().to_owned()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn granted_by_matches_schema() {
+    let schema = schema_permissions();
+
+    let names: BTreeSet<String> = schema.iter().map(|(name, _)| name.clone()).collect();
+    let expected_names: BTreeSet<String> = Permission::iter().map(|p| p.to_string()).collect();
+    assert_eq!(
+        expected_names, names,
+        "schema and Permission disagree on permissions"
+    );
+
+    for (name, relations) in schema {
+        let permission: Permission = name.parse().expect("permission should parse");
+        let granted_by: BTreeSet<String> = permission
+            .granted_by()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(granted_by, relations, "relations granting {name} disagree");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, path::Path};
+use std::{env, path::Path, time::Duration};
 
 use buck2_resources::Buck2Resources;
 use derive_builder::Builder;
@@ -17,6 +17,7 @@ use ulid::Ulid;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
 const DEFAULT_CONCURRENCY_LIMIT: usize = 64;
+const DEFAULT_SCHEDULER_INTERVAL_SECS: u64 = 60;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -33,6 +34,17 @@ pub enum ConfigError {
     Settings(#[from] si_settings::SettingsError),
 }
 
+impl ConfigBuilder {
+    fn validate(&self) -> std::result::Result<(), String> {
+        // A zero period would panic when the scheduler creates its interval
+        if self.scheduler_interval_secs == Some(0) {
+            return Err("scheduler_interval_secs must be at least 1".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
 impl ConfigError {
     fn development(err: impl std::error::Error + 'static + Sync + Send) -> Self {
         Self::Development(Box::new(err))
@@ -42,6 +54,7 @@ impl ConfigError {
 type Result<T> = std::result::Result<T, ConfigError>;
 
 #[derive(Debug, Builder)]
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct Config {
     #[builder(default = "PgPoolConfig::default()")]
     pg_pool: PgPoolConfig,
@@ -63,6 +76,12 @@ pub struct Config {
 
     #[builder(default = "default_layer_db_config()")]
     layer_db_config: LayerDbConfig,
+
+    #[builder(default = "true")]
+    scheduler_enabled: bool,
+
+    #[builder(default = "default_scheduler_interval_secs()")]
+    scheduler_interval_secs: u64,
 }
 
 impl StandardConfig for Config {
@@ -111,6 +130,16 @@ impl Config {
     pub fn layer_db_config(&self) -> &LayerDbConfig {
         &self.layer_db_config
     }
+
+    /// Gets whether the scheduler for workspace schedules should run.
+    pub fn scheduler_enabled(&self) -> bool {
+        self.scheduler_enabled
+    }
+
+    /// Gets how often the scheduler checks for due workspace schedules.
+    pub fn scheduler_interval(&self) -> Duration {
+        Duration::from_secs(self.scheduler_interval_secs)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -129,6 +158,10 @@ pub struct ConfigFile {
     layer_db_config: LayerDbConfig,
     #[serde(default = "default_symmetric_crypto_config")]
     symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
+    #[serde(default = "default_scheduler_enabled")]
+    scheduler_enabled: bool,
+    #[serde(default = "default_scheduler_interval_secs")]
+    scheduler_interval_secs: u64,
 }
 
 impl Default for ConfigFile {
@@ -141,6 +174,8 @@ impl Default for ConfigFile {
             instance_id: random_instance_id(),
             layer_db_config: default_layer_db_config(),
             symmetric_crypto_service: default_symmetric_crypto_config(),
+            scheduler_enabled: default_scheduler_enabled(),
+            scheduler_interval_secs: default_scheduler_interval_secs(),
         }
     }
 }
@@ -163,6 +198,8 @@ impl TryFrom<ConfigFile> for Config {
         config.instance_id(value.instance_id);
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
         config.layer_db_config(value.layer_db_config);
+        config.scheduler_enabled(value.scheduler_enabled);
+        config.scheduler_interval_secs(value.scheduler_interval_secs);
         config.build().map_err(Into::into)
     }
 }
@@ -187,6 +224,14 @@ fn default_layer_db_config() -> LayerDbConfig {
     LayerDbConfig::default()
 }
 
+fn default_scheduler_enabled() -> bool {
+    true
+}
+
+fn default_scheduler_interval_secs() -> u64 {
+    DEFAULT_SCHEDULER_INTERVAL_SECS
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
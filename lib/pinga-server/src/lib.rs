@@ -1,6 +1,7 @@
 mod app_state;
 mod config;
 mod handlers;
+mod scheduler;
 pub mod server;
 
 use std::io;
@@ -43,6 +44,8 @@ pub enum ServerError {
     PgPool(#[from] Box<PgPoolError>),
     #[error("rebaser client error: {0}")]
     Rebaser(#[from] rebaser_client::ClientError),
+    #[error("schedule error: {0}")]
+    Schedule(#[from] dal::schedule::ScheduleError),
     #[error("symmetric crypto error: {0}")]
     SymmetricCryptoService(#[from] si_crypto::SymmetricCryptoError),
     #[error("transactions error: {0}")]
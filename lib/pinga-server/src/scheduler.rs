@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use dal::{schedule::ScheduledJob, DalContextBuilder};
+use telemetry::prelude::*;
+use tokio::time::{self, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+use crate::ServerResult;
+
+/// Periodically enqueues the jobs for every workspace schedule that is due.
+///
+/// Due schedules are locked while their jobs are enqueued, so it is safe for every pinga instance
+/// to run a scheduler.
+pub(crate) struct Scheduler {
+    ctx_builder: DalContextBuilder,
+    interval: Duration,
+    shutdown_token: CancellationToken,
+}
+
+impl Scheduler {
+    pub(crate) fn new(
+        ctx_builder: DalContextBuilder,
+        interval: Duration,
+        shutdown_token: CancellationToken,
+    ) -> Self {
+        Self {
+            ctx_builder,
+            interval,
+            shutdown_token,
+        }
+    }
+
+    pub(crate) async fn run(self) {
+        let mut interval = time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(err) = self.enqueue_due().await {
+                        error!(si.error.message = ?err, "failed to enqueue scheduled jobs");
+                    }
+                }
+                _ = self.shutdown_token.cancelled() => break,
+            }
+        }
+
+        info!("pinga scheduler shutdown complete");
+    }
+
+    #[instrument(name = "pinga.scheduler.enqueue_due", level = "debug", skip_all)]
+    async fn enqueue_due(&self) -> ServerResult<()> {
+        let ctx = self.ctx_builder.build_default().await?;
+
+        let enqueued = ScheduledJob::enqueue_due(&ctx, Utc::now()).await?;
+        ctx.commit_no_rebase().await?;
+
+        if enqueued > 0 {
+            info!(enqueued, "enqueued scheduled jobs");
+        }
+
+        Ok(())
+    }
+}
@@ -34,7 +34,9 @@ use telemetry_utils::metric;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use veritech_client::Client as VeritechClient;
 
-use crate::{app_state::AppState, handlers, Config, ServerError, ServerResult};
+use crate::{
+    app_state::AppState, handlers, scheduler::Scheduler, Config, ServerError, ServerResult,
+};
 
 const CONSUMER_NAME: &str = "pinga-server";
 
@@ -60,6 +62,7 @@ impl ServerMetadata {
 pub struct Server {
     metadata: Arc<ServerMetadata>,
     inner: Box<dyn Future<Output = io::Result<()>> + Unpin + Send>,
+    scheduler: Option<Scheduler>,
     shutdown_token: CancellationToken,
 }
 
@@ -67,6 +70,7 @@ impl fmt::Debug for Server {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Server")
             .field("metadata", &self.metadata)
+            .field("scheduler", &self.scheduler.is_some())
             .field("shutdown_token", &self.shutdown_token)
             .finish()
     }
@@ -117,13 +121,24 @@ impl Server {
             compute_executor,
         );
 
-        Self::from_services(
+        let scheduler = config.scheduler_enabled().then(|| {
+            Scheduler::new(
+                DalContext::builder(services_context.clone(), false),
+                config.scheduler_interval(),
+                token.clone(),
+            )
+        });
+
+        let mut server = Self::from_services(
             config.instance_id().to_string(),
             config.concurrency_limit(),
             services_context,
             token,
         )
-        .await
+        .await?;
+        server.scheduler = scheduler;
+
+        Ok(server)
     }
 
     #[instrument(name = "pinga.init.from_services", level = "info", skip_all)]
@@ -184,6 +199,7 @@ impl Server {
         Ok(Self {
             metadata,
             inner: Box::new(inner.into_future()),
+            scheduler: None,
             shutdown_token,
         })
     }
@@ -196,8 +212,20 @@ impl Server {
     }
 
     pub async fn try_run(self) -> ServerResult<()> {
+        let scheduler = self
+            .scheduler
+            .map(|scheduler| tokio::spawn(scheduler.run()));
+
         self.inner.await.map_err(ServerError::Naxum)?;
         info!("pinga main loop shutdown complete");
+
+        if let Some(scheduler) = scheduler {
+            // The scheduler stops on the same shutdown token as the main loop
+            if let Err(err) = scheduler.await {
+                error!(si.error.message = ?err, "pinga scheduler task failed");
+            }
+        }
+
         Ok(())
     }
 
@@ -1,6 +1,6 @@
 use naxum_api_types::RequestId;
 use serde::{Deserialize, Serialize};
-use si_events::{rebase_batch_address::RebaseBatchAddress, ChangeSetId, WorkspacePk};
+use si_events::{rebase_batch_address::RebaseBatchAddress, ChangeSetId, Conflict, WorkspacePk};
 
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -20,4 +20,9 @@ pub enum RebaseStatus {
     Error {
         message: String,
     },
+    /// The updates were not performed because they conflict with changes made to the change set
+    /// being rebased onto.
+    Conflicts {
+        conflicts: Vec<Conflict>,
+    },
 }
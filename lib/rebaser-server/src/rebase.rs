@@ -1,6 +1,6 @@
 use audit_logs_stream::AuditLogsStreamError;
 use dal::{
-    change_set::{ChangeSet, ChangeSetError, ChangeSetId},
+    change_set::{conflict, ChangeSet, ChangeSetError, ChangeSetId},
     workspace_snapshot::WorkspaceSnapshotError,
     DalContext, TransactionsError, Workspace, WorkspaceError, WorkspacePk, WorkspaceSnapshot,
     WsEvent, WsEventError,
@@ -95,14 +95,47 @@ pub async fn perform_rebase(
     );
     debug!("after snapshot fetch and parse: {:?}", start.elapsed());
 
+    let from_other_change_set = request
+        .from_change_set_id
+        .filter(|from_id| *from_id != to_rebase_change_set.id);
+    let mut updates = rebase_batch.updates().to_vec();
+    let replaying_from_base = !updating_head && from_other_change_set.is_some();
+
+    if let Some(from_change_set_id) = from_other_change_set {
+        if updating_head {
+            // A change set is being applied, which must not overwrite changes made to HEAD since
+            // it was forked
+            let from_change_set = ChangeSet::find(ctx, from_change_set_id)
+                .await?
+                .ok_or(RebaseError::MissingChangeSet(from_change_set_id))?;
+            let conflicts = from_change_set.conflicts_with_base(ctx).await?;
+            if !conflicts.is_empty() {
+                span.record("si.conflicts", format!("{conflicts:?}"));
+                span.record("si.conflicts.count", conflicts.len().to_string());
+                info!(
+                    "rebase not performed, found conflicts: {:?}",
+                    start.elapsed()
+                );
+                return Ok(RebaseStatus::Conflicts { conflicts });
+            }
+        } else {
+            // HEAD's changes are being replayed onto the change set, which must not overwrite the
+            // change set's own changes. Those updates are held back, and the conflicts they
+            // would have overwritten are reported when the change set is applied.
+            let conflicts = to_rebase_change_set.conflicts_with_base(ctx).await?;
+            if !conflicts.is_empty() {
+                span.record("si.conflicts", format!("{conflicts:?}"));
+                span.record("si.conflicts.count", conflicts.len().to_string());
+                updates = conflict::without_conflicting_updates(updates, &conflicts);
+            }
+        }
+    }
+
+    // Both sides have the replayed updates in common from now on
+    let merge_base_updates = replaying_from_base.then(|| updates.clone());
+
     let corrected_updates = to_rebase_workspace_snapshot
-        .correct_transforms(
-            rebase_batch.updates().to_vec(),
-            !updating_head
-                && request
-                    .from_change_set_id
-                    .is_some_and(|from_id| from_id != to_rebase_change_set.id),
-        )
+        .correct_transforms(updates, replaying_from_base)
         .await?;
     debug!("corrected transforms: {:?}", start.elapsed());
 
@@ -112,6 +145,13 @@ pub async fn perform_rebase(
 
     debug!("updates complete: {:?}", start.elapsed());
 
+    if let Some(merge_base_updates) = merge_base_updates {
+        to_rebase_change_set
+            .advance_merge_base(ctx, merge_base_updates)
+            .await?;
+        debug!("merge base advanced: {:?}", start.elapsed());
+    }
+
     if !corrected_updates.is_empty() {
         // Once all updates have been performed, we can write out, mark everything as recently seen
         // and update the pointer.
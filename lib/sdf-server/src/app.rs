@@ -5,7 +5,7 @@ use audit_database::AuditDatabaseContext;
 use axum::Router;
 use dal::ServicesContext;
 use nats_multiplexer_client::MultiplexerClient;
-use si_data_spicedb::SpiceDbClient;
+use permissions::{BoxPermissionsClient, PermissionsClient};
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 use si_posthog::PosthogClient;
 use telemetry::prelude::*;
@@ -36,7 +36,7 @@ impl AxumApp {
         create_workspace_allowlist: Vec<WorkspacePermissions>,
         application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
         shutdown_token: CancellationToken,
-        spicedb_client: Option<SpiceDbClient>,
+        permissions_client: Option<BoxPermissionsClient>,
         audit_database_context: AuditDatabaseContext,
     ) -> Self {
         Self::inner_from_services(
@@ -52,7 +52,7 @@ impl AxumApp {
             create_workspace_allowlist,
             application_runtime_mode,
             shutdown_token,
-            spicedb_client,
+            permissions_client,
             audit_database_context,
         )
     }
@@ -76,7 +76,7 @@ impl AxumApp {
         create_workspace_allowlist: Vec<WorkspacePermissions>,
         application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
         shutdown_token: CancellationToken,
-        spicedb_client: SpiceDbClient,
+        permissions_client: impl PermissionsClient + 'static,
         audit_database_context: AuditDatabaseContext,
     ) -> Self {
         Self::inner_from_services(
@@ -92,7 +92,7 @@ impl AxumApp {
             create_workspace_allowlist,
             application_runtime_mode,
             shutdown_token,
-            Some(spicedb_client),
+            Some(Box::new(permissions_client)),
             audit_database_context,
         )
     }
@@ -115,7 +115,7 @@ impl AxumApp {
         create_workspace_allowlist: Vec<WorkspacePermissions>,
         application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
         shutdown_token: CancellationToken,
-        spicedb_client: Option<SpiceDbClient>,
+        permissions_client: Option<BoxPermissionsClient>,
         audit_database_context: AuditDatabaseContext,
     ) -> Self {
         let state = AppState::new(
@@ -131,7 +131,7 @@ impl AxumApp {
             create_workspace_allowlist,
             application_runtime_mode,
             shutdown_token,
-            spicedb_client,
+            permissions_client,
             audit_database_context,
         );
 
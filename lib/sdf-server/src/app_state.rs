@@ -4,7 +4,7 @@ use asset_sprayer::AssetSprayer;
 use audit_database::AuditDatabaseContext;
 use axum::extract::FromRef;
 use nats_multiplexer_client::MultiplexerClient;
-use si_data_spicedb::SpiceDbClient;
+use permissions::BoxPermissionsClient;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 use std::fmt;
 use tokio::sync::{Mutex, RwLock};
@@ -36,7 +36,7 @@ pub struct AppState {
     create_workspace_allowlist: Vec<WorkspacePermissions>,
     pub application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
     shutdown_token: CancellationToken,
-    spicedb_client: Option<SpiceDbClient>,
+    permissions_client: Option<BoxPermissionsClient>,
     audit_database_context: AuditDatabaseContext,
 }
 
@@ -55,7 +55,7 @@ impl AppState {
         create_workspace_allowlist: Vec<WorkspacePermissions>,
         application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
         shutdown_token: CancellationToken,
-        spicedb_client: Option<SpiceDbClient>,
+        permissions_client: Option<BoxPermissionsClient>,
         audit_database_context: AuditDatabaseContext,
     ) -> Self {
         let nats_multiplexer_clients = NatsMultiplexerClients {
@@ -76,7 +76,7 @@ impl AppState {
             create_workspace_allowlist,
             application_runtime_mode,
             shutdown_token,
-            spicedb_client,
+            permissions_client,
             audit_database_context,
         }
     }
@@ -117,12 +117,12 @@ impl AppState {
         &self.shutdown_token
     }
 
-    pub fn spicedb_client(&mut self) -> Option<&mut SpiceDbClient> {
-        self.spicedb_client.as_mut()
+    pub fn permissions_client(&mut self) -> Option<&mut BoxPermissionsClient> {
+        self.permissions_client.as_mut()
     }
 
-    pub fn spicedb_client_clone(&self) -> Option<SpiceDbClient> {
-        self.spicedb_client.clone()
+    pub fn permissions_client_clone(&self) -> Option<BoxPermissionsClient> {
+        self.permissions_client.clone()
     }
 
     pub fn audit_database_context(&self) -> &AuditDatabaseContext {
@@ -2,8 +2,8 @@ use std::{collections::HashMap, fmt};
 
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Query},
-    http::request::Parts,
+    extract::{FromRequestParts, Path, Query},
+    http::{request::Parts, Method},
     Json,
 };
 use dal::{
@@ -12,7 +12,7 @@ use dal::{
 };
 use derive_more::Deref;
 use hyper::StatusCode;
-use si_jwt_public_key::{SiJwtClaimRole, SiJwtClaims};
+use si_jwt_public_key::{SiJwtClaimRole, SiJwtClaims, SiJwtScope};
 
 use crate::app_state::AppState;
 
@@ -28,9 +28,14 @@ impl FromRequestParts<AppState> for AccessBuilder {
     ) -> Result<Self, Self::Rejection> {
         let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
 
+        let history_actor = match claim.role() {
+            SiJwtClaimRole::Automation => dal::HistoryActor::Automation(claim.user_id()),
+            SiJwtClaimRole::Web => dal::HistoryActor::User(claim.user_id()),
+        };
+
         Ok(Self(context::AccessBuilder::new(
             dal::Tenancy::new(claim.workspace_id()),
-            dal::HistoryActor::from(claim.user_id()),
+            history_actor,
         )))
     }
 }
@@ -189,7 +194,7 @@ impl FromRequestParts<AppState> for Nats {
     }
 }
 
-/** Represents a user who is authorized for the web */
+/** Represents a user authorized for the web, or an automation token authorized for the route */
 #[derive(Clone, Debug)]
 pub struct Authorization(pub SiJwtClaims);
 
@@ -201,7 +206,7 @@ impl FromRequestParts<AppState> for Authorization {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        // If we already authorized this request for the web, don't do it again
+        // If we already authorized this request, don't do it again
         if let Some(authorization) = parts.extensions.get::<Authorization>() {
             return Ok(authorization.clone());
         }
@@ -222,32 +227,124 @@ impl FromRequestParts<AppState> for Authorization {
             .map_err(unauthorized_error)?;
         ctx.update_tenancy(dal::Tenancy::new(claim.workspace_id()));
 
-        if !is_authorized_for(&ctx, &claim, SiJwtClaimRole::Web)
+        if claim.authorized_for(SiJwtClaimRole::Automation) {
+            authorize_automation(parts, state, &claim).await?;
+        }
+        if !is_workspace_member(&ctx, &claim)
             .await
             .map_err(internal_error)?
         {
-            return Err(unauthorized_error("not authorized for web role"));
+            return Err(unauthorized_error("not a member of the workspace"));
         }
 
-        parts.extensions.insert(Self);
+        let authorization = Self(claim);
+        parts.extensions.insert(authorization.clone());
+
+        Ok(authorization)
+    }
+}
+
+/// The [scope](SiJwtScope) an automation token needs to use a route, added to the route with an
+/// [`Extension`](axum::Extension) layer outside of any other authorization layers. Without one,
+/// automation tokens can only use the route for read-only requests.
+#[derive(Clone, Copy, Debug)]
+pub struct RequiredScope(pub SiJwtScope);
+
+/** Represents an automation token which is authorized for the route */
+#[derive(Clone, Debug)]
+pub struct AutomationAuthorization(pub SiJwtClaims);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AutomationAuthorization {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
+        if !claim.authorized_for(SiJwtClaimRole::Automation) {
+            return Err(unauthorized_error("not authorized for automation role"));
+        }
 
         Ok(Self(claim))
     }
 }
 
+/// Ensures an automation token has the scope the route requires, and that the route is for the
+/// change set and view the token is restricted to, if any.
+///
+/// Tokens restricted to a change set or a view can only be used with routes which name it in their
+/// path; routes which don't name a view are denied to view-restricted tokens, since they could
+/// reach data outside of the view.
+async fn authorize_automation(
+    parts: &mut Parts,
+    state: &AppState,
+    claim: &SiJwtClaims,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let path_params = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+        .await
+        .map(|Path(path_params)| path_params)
+        .unwrap_or_default();
+
+    check_automation_access(
+        claim,
+        &parts.method,
+        parts.extensions.get::<RequiredScope>().copied(),
+        &path_params,
+    )
+    .map_err(forbidden_error)
+}
+
+fn check_automation_access(
+    claim: &SiJwtClaims,
+    method: &Method,
+    required_scope: Option<RequiredScope>,
+    path_params: &HashMap<String, String>,
+) -> Result<(), String> {
+    let scope = match required_scope {
+        Some(RequiredScope(scope)) => scope,
+        None if *method == Method::GET || *method == Method::HEAD => SiJwtScope::Read,
+        None => return Err("route is not available to automation tokens".to_string()),
+    };
+    if !claim.has_scope(scope) {
+        return Err(format!("automation token is missing the {scope:?} scope"));
+    }
+
+    if let Some(change_set_id) = claim.restricted_to_change_set() {
+        if path_params.get("change_set_id") != Some(&change_set_id.to_string()) {
+            return Err(format!(
+                "automation token is restricted to change set {change_set_id}"
+            ));
+        }
+    }
+    if let Some(view_id) = claim.restricted_to_view() {
+        let requested_view_id = path_params
+            .get("view_id")
+            .or_else(|| path_params.get("viewId"));
+        if requested_view_id != Some(&view_id.to_string()) {
+            return Err(format!("automation token is restricted to view {view_id}"));
+        }
+    }
+
+    Ok(())
+}
+
 async fn is_authorized_for(
     ctx: &dal::DalContext,
     claim: &SiJwtClaims,
     role: SiJwtClaimRole,
 ) -> dal::UserResult<bool> {
+    Ok(claim.authorized_for(role) && is_workspace_member(ctx, claim).await?)
+}
+
+async fn is_workspace_member(ctx: &dal::DalContext, claim: &SiJwtClaims) -> dal::UserResult<bool> {
     let workspace_members =
         User::list_members_for_workspace(ctx, claim.workspace_id().to_string()).await?;
 
-    let is_member = workspace_members
+    Ok(workspace_members
         .into_iter()
-        .any(|m| m.pk() == claim.user_id());
-
-    Ok(is_member && claim.authorized_for(role))
+        .any(|m| m.pk() == claim.user_id()))
 }
 
 pub struct WsAuthorization(pub SiJwtClaims);
@@ -315,6 +412,20 @@ pub fn unauthorized_error(message: impl fmt::Display) -> (StatusCode, Json<serde
     )
 }
 
+fn forbidden_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::Value>) {
+    let status_code = StatusCode::FORBIDDEN;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.to_string(),
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
+
 fn not_found_error(message: &str) -> (StatusCode, Json<serde_json::Value>) {
     let status_code = StatusCode::NOT_FOUND;
     (
@@ -328,3 +439,98 @@ fn not_found_error(message: &str) -> (StatusCode, Json<serde_json::Value>) {
         })),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use si_events::{ChangeSetId, UserPk, ViewId, WorkspacePk};
+
+    use super::*;
+
+    fn automation(
+        scopes: Vec<SiJwtScope>,
+        change_set_id: Option<ChangeSetId>,
+        view_id: Option<ViewId>,
+    ) -> SiJwtClaims {
+        SiJwtClaims::for_automation(
+            UserPk::generate(),
+            WorkspacePk::generate(),
+            scopes,
+            change_set_id,
+            view_id,
+        )
+    }
+
+    fn path_params<'a>(
+        params: impl IntoIterator<Item = (&'a str, String)>,
+    ) -> HashMap<String, String> {
+        params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn read_only_requests_need_the_read_scope() {
+        let reader = automation(vec![SiJwtScope::Read], None, None);
+        let no_scopes = automation(vec![], None, None);
+
+        assert!(check_automation_access(&reader, &Method::GET, None, &HashMap::new()).is_ok());
+        assert!(check_automation_access(&reader, &Method::HEAD, None, &HashMap::new()).is_ok());
+        assert!(check_automation_access(&no_scopes, &Method::GET, None, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn writes_need_a_required_scope_on_the_route() {
+        let applier = automation(vec![SiJwtScope::ChangeSetApply], None, None);
+        let apply = Some(RequiredScope(SiJwtScope::ChangeSetApply));
+        let create = Some(RequiredScope(SiJwtScope::ChangeSetCreate));
+
+        assert!(check_automation_access(&applier, &Method::POST, apply, &HashMap::new()).is_ok());
+        assert!(check_automation_access(&applier, &Method::POST, create, &HashMap::new()).is_err());
+        // Routes without a required scope are read-only for automation tokens
+        assert!(check_automation_access(&applier, &Method::POST, None, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn change_set_restricted_tokens_need_the_change_set_in_the_path() {
+        let change_set_id = ChangeSetId::new();
+        let claim = automation(vec![SiJwtScope::Read], Some(change_set_id), None);
+
+        let params = path_params([("change_set_id", change_set_id.to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_ok());
+
+        let params = path_params([("change_set_id", ChangeSetId::new().to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_err());
+        assert!(check_automation_access(&claim, &Method::GET, None, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn view_restricted_tokens_need_the_view_in_the_path() {
+        let view_id = ViewId::new();
+        let claim = automation(vec![SiJwtScope::Read], None, Some(view_id));
+
+        let params = path_params([("view_id", view_id.to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_ok());
+        let params = path_params([("viewId", view_id.to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_ok());
+
+        let params = path_params([("view_id", ViewId::new().to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_err());
+        // Routes which don't name a view are denied
+        let params = path_params([("change_set_id", ChangeSetId::new().to_string())]);
+        assert!(check_automation_access(&claim, &Method::GET, None, &params).is_err());
+    }
+
+    #[test]
+    fn web_tokens_are_not_restricted() {
+        let claim = SiJwtClaims::for_web(UserPk::generate(), WorkspacePk::generate());
+
+        assert!(check_automation_access(
+            &claim,
+            &Method::POST,
+            Some(RequiredScope(SiJwtScope::ChangeSetApply)),
+            &HashMap::new()
+        )
+        .is_ok());
+    }
+}
@@ -3,12 +3,12 @@ use std::task::{Context, Poll};
 use axum::{
     body::Body,
     extract::FromRequestParts,
-    http::Request,
+    http::{Method, Request},
     response::{IntoResponse, Response},
 };
+use dal::{UserPk, WorkspacePk};
 use futures::future::BoxFuture;
-use permissions::{Permission, PermissionBuilder};
-use si_jwt_public_key::SiJwtClaimRole;
+use permissions::{Permission, PermissionBuilder, PermissionsClient, Relation, RelationBuilder};
 use tower::{Layer, Service};
 
 use crate::{
@@ -16,15 +16,40 @@ use crate::{
     AppState,
 };
 
+/// The relations synced from the auth api's workspace roles by `refresh_workspace_members`. Before
+/// a workspace's members are first synced it has none of them, only its owner and approvers.
+const ROLE_RELATIONS: &[Relation] = &[
+    Relation::Viewer,
+    Relation::Editor,
+    Relation::FuncAuthor,
+    Relation::Operator,
+];
+
 #[derive(Clone)]
 pub struct WorkspacePermissionLayer {
     state: AppState,
     permission: Permission,
+    reads_only: bool,
 }
 
 impl WorkspacePermissionLayer {
     pub fn new(state: AppState, permission: Permission) -> Self {
-        Self { state, permission }
+        Self {
+            state,
+            permission,
+            reads_only: false,
+        }
+    }
+
+    /// Only checks `GET` and `HEAD` requests, passing anything else through. This is meant to be
+    /// applied to a whole router with [`route_layer`](axum::Router::route_layer), requiring
+    /// [`Permission::View`] to read while writes are checked by their own layers.
+    pub fn for_reads(state: AppState, permission: Permission) -> Self {
+        Self {
+            state,
+            permission,
+            reads_only: true,
+        }
     }
 }
 
@@ -36,6 +61,7 @@ impl<S> Layer<S> for WorkspacePermissionLayer {
             inner,
             state: self.state.clone(),
             permission: self.permission,
+            reads_only: self.reads_only,
         }
     }
 }
@@ -45,6 +71,7 @@ pub struct WorkspacePermission<S> {
     inner: S,
     state: AppState,
     permission: Permission,
+    reads_only: bool,
 }
 
 impl<S> Service<Request<Body>> for WorkspacePermission<S>
@@ -64,6 +91,10 @@ where
         let mut me = self.clone();
 
         Box::pin(async move {
+            if me.reads_only && !matches!(*req.method(), Method::GET | Method::HEAD) {
+                return me.inner.call(req).await;
+            }
+
             let (mut parts, body) = req.into_parts();
 
             let Authorization(claim) =
@@ -72,21 +103,22 @@ where
                     Err(err) => return Ok(err.into_response()),
                 };
 
-            if let Some(client) = me.state.spicedb_client() {
-                let is_allowed = match PermissionBuilder::new()
-                    .workspace_object(claim.workspace_id())
-                    .permission(me.permission)
-                    .user_subject(claim.user_id())
-                    .has_permission(client)
-                    .await
-                {
-                    Ok(is_allowed) => is_allowed,
-                    Err(e) => return Ok(extract::unauthorized_error(e).into_response()),
-                };
-                if !is_allowed || !claim.authorized_for(SiJwtClaimRole::Web) {
-                    return Ok(
-                        extract::unauthorized_error("not authorized for web role").into_response()
-                    );
+            if let Some(client) = me.state.permissions_client() {
+                let is_allowed =
+                    match is_allowed(client, claim.workspace_id(), claim.user_id(), me.permission)
+                        .await
+                    {
+                        Ok(is_allowed) => is_allowed,
+                        Err(e) => return Ok(extract::unauthorized_error(e).into_response()),
+                    };
+                // Automation tokens act on behalf of their user, and were already checked for the
+                // route's scope when authorized
+                if !is_allowed {
+                    return Ok(extract::unauthorized_error(format!(
+                        "not authorized for {} permission",
+                        me.permission
+                    ))
+                    .into_response());
                 }
             }
 
@@ -97,3 +129,123 @@ where
         })
     }
 }
+
+/// Checks whether the user has the permission on the workspace.
+///
+/// Workspaces whose members haven't been synced yet have no [`ROLE_RELATIONS`], so every member
+/// is allowed anything but approving and managing, which were enforced before roles existed. The
+/// caller has already checked that the user is a member of the workspace.
+async fn is_allowed(
+    client: &mut impl PermissionsClient,
+    workspace_id: WorkspacePk,
+    user_id: UserPk,
+    permission: Permission,
+) -> Result<bool, permissions::Error> {
+    if PermissionBuilder::new()
+        .workspace_object(workspace_id)
+        .permission(permission)
+        .user_subject(user_id)
+        .has_permission(client)
+        .await?
+    {
+        return Ok(true);
+    }
+
+    if matches!(permission, Permission::Approve | Permission::Manage) {
+        return Ok(false);
+    }
+    for relation in ROLE_RELATIONS {
+        let members = RelationBuilder::new()
+            .workspace_object(workspace_id)
+            .relation(*relation)
+            .read(client)
+            .await?;
+        if !members.is_empty() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use permissions::InMemoryPermissions;
+
+    use super::*;
+
+    async fn grant(
+        client: &mut InMemoryPermissions,
+        workspace_id: WorkspacePk,
+        relation: Relation,
+        user_id: UserPk,
+    ) {
+        RelationBuilder::new()
+            .workspace_object(workspace_id)
+            .relation(relation)
+            .user_subject(user_id)
+            .create(client)
+            .await
+            .expect("could not create relation");
+    }
+
+    #[tokio::test]
+    async fn members_are_allowed_until_roles_are_synced() {
+        let mut client = InMemoryPermissions::new();
+        let workspace_id = WorkspacePk::generate();
+        let owner = UserPk::generate();
+        let member = UserPk::generate();
+        grant(&mut client, workspace_id, Relation::Owner, owner).await;
+
+        for permission in [
+            Permission::View,
+            Permission::Edit,
+            Permission::Operate,
+            Permission::AuthorFuncs,
+        ] {
+            assert!(is_allowed(&mut client, workspace_id, member, permission)
+                .await
+                .expect("could not check permission"));
+        }
+        for permission in [Permission::Approve, Permission::Manage] {
+            assert!(!is_allowed(&mut client, workspace_id, member, permission)
+                .await
+                .expect("could not check permission"));
+            assert!(is_allowed(&mut client, workspace_id, owner, permission)
+                .await
+                .expect("could not check permission"));
+        }
+    }
+
+    #[tokio::test]
+    async fn roles_are_enforced_once_synced() {
+        let mut client = InMemoryPermissions::new();
+        let workspace_id = WorkspacePk::generate();
+        let viewer = UserPk::generate();
+        let editor = UserPk::generate();
+        let operator = UserPk::generate();
+        let stranger = UserPk::generate();
+        grant(&mut client, workspace_id, Relation::Viewer, viewer).await;
+        grant(&mut client, workspace_id, Relation::Editor, editor).await;
+        grant(&mut client, workspace_id, Relation::Operator, operator).await;
+
+        let check = |user_id, permission| {
+            let mut client = client.clone();
+            async move {
+                is_allowed(&mut client, workspace_id, user_id, permission)
+                    .await
+                    .expect("could not check permission")
+            }
+        };
+
+        assert!(check(viewer, Permission::View).await);
+        assert!(!check(viewer, Permission::Edit).await);
+        assert!(check(editor, Permission::Edit).await);
+        assert!(check(editor, Permission::Operate).await);
+        assert!(check(editor, Permission::AuthorFuncs).await);
+        assert!(!check(editor, Permission::Approve).await);
+        assert!(check(operator, Permission::Operate).await);
+        assert!(!check(operator, Permission::AuthorFuncs).await);
+        assert!(!check(stranger, Permission::View).await);
+    }
+}
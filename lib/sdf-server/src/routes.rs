@@ -20,6 +20,7 @@ use tower_http::{
 
 use crate::{
     app_state::{AppState, ApplicationRuntimeMode},
+    middleware::WorkspacePermissionLayer,
     ServerError,
 };
 
@@ -46,24 +47,45 @@ async fn app_state_middeware<B>(
 
 #[allow(clippy::too_many_arguments)]
 pub fn routes(state: AppState) -> Router {
-    let mut router: Router<AppState> = Router::new();
-    router = router
-        .nest("/api/action", crate::service::action::routes())
+    // Everything within a workspace needs the view permission to be read, writes are checked by
+    // each route
+    let workspace_routes: Router<AppState> = Router::new()
+        .nest("/api/action", crate::service::action::routes(state.clone()))
         .nest("/api/node_debug", crate::service::node_debug::routes())
         .nest("/api/attribute", crate::service::attribute::routes())
-        .nest("/api/change_set", crate::service::change_set::routes())
-        .nest("/api/component", crate::service::component::routes())
-        .nest("/api/diagram", crate::service::diagram::routes())
+        .nest(
+            "/api/change_set",
+            crate::service::change_set::routes(state.clone()),
+        )
+        .nest(
+            "/api/component",
+            crate::service::component::routes(state.clone()),
+        )
+        .nest(
+            "/api/diagram",
+            crate::service::diagram::routes(state.clone()),
+        )
         .nest("/api/graphviz", crate::service::graphviz::routes())
         .nest(
             "/api/qualification",
             crate::service::qualification::routes(),
         )
-        .nest("/api/secret", crate::service::secret::routes())
+        .nest("/api/secret", crate::service::secret::routes(state.clone()))
+        .nest("/api/module", crate::service::module::routes(state.clone()))
+        .nest(
+            "/api/variant",
+            crate::service::variant::routes(state.clone()),
+        )
+        .route_layer(WorkspacePermissionLayer::for_reads(
+            state.clone(),
+            permissions::Permission::View,
+        ));
+
+    let mut router: Router<AppState> = Router::new();
+    router = router
+        .merge(workspace_routes)
         .nest("/api/session", crate::service::session::routes())
         .nest("/api/ws", crate::service::ws::routes())
-        .nest("/api/module", crate::service::module::routes())
-        .nest("/api/variant", crate::service::variant::routes())
         .nest("/api/v2", crate::service::v2::routes(state.clone()))
         .layer(CompressionLayer::new())
         // allows us to be permissive about cors from our owned subdomains
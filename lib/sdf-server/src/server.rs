@@ -7,6 +7,7 @@ use dal::ServicesContext;
 use hyper::server::accept::Accept;
 use nats_multiplexer::Multiplexer;
 use nats_multiplexer_client::MultiplexerClient;
+use permissions::BoxPermissionsClient;
 use si_data_spicedb::SpiceDbClient;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 use si_posthog::PosthogClient;
@@ -118,9 +119,9 @@ impl Server {
 
         let application_runtime_mode = Arc::new(RwLock::new(ApplicationRuntimeMode::Running));
 
-        let mut spicedb_client = None;
+        let mut permissions_client: Option<BoxPermissionsClient> = None;
         if config.spicedb().enabled {
-            spicedb_client = Some(SpiceDbClient::new(config.spicedb()).await?);
+            permissions_client = Some(Box::new(SpiceDbClient::new(config.spicedb()).await?));
         }
 
         prepare_maintenance_mode_watcher(application_runtime_mode.clone(), token.clone())?;
@@ -147,7 +148,7 @@ impl Server {
             config.create_workspace_allowlist().clone(),
             application_runtime_mode,
             token,
-            spicedb_client,
+            permissions_client,
             audit_database_context,
         )
         .await
@@ -169,7 +170,7 @@ impl Server {
         create_workspace_allowlist: Vec<WorkspacePermissions>,
         application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
         token: CancellationToken,
-        spicedb_client: Option<SpiceDbClient>,
+        permissions_client: Option<BoxPermissionsClient>,
         audit_database_context: AuditDatabaseContext,
     ) -> ServerResult<Self> {
         let app = AxumApp::from_services(
@@ -184,7 +185,7 @@ impl Server {
             create_workspace_allowlist,
             application_runtime_mode,
             token.clone(),
-            spicedb_client,
+            permissions_client,
             // TODO(nick): split the migrator context and the reader-only context (should be read-only pg pool).
             audit_database_context.clone(),
         )
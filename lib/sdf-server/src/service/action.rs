@@ -13,7 +13,7 @@ use dal::{
 use dal::{ComponentError, ComponentId, StandardModelError, TransactionsError, UserError, UserPk};
 
 use super::ApiError;
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 mod cancel;
 mod history;
@@ -75,11 +75,16 @@ impl IntoResponse for ActionError {
     }
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let operate = WorkspacePermissionLayer::new(state, permissions::Permission::Operate);
+
     Router::new()
         .route("/list", get(list_actions::list_actions))
-        .route("/put_on_hold", post(put_on_hold::put_on_hold))
-        .route("/cancel", post(cancel::cancel))
-        .route("/retry", post(retry::retry))
+        .route(
+            "/put_on_hold",
+            post(put_on_hold::put_on_hold).layer(operate.clone()),
+        )
+        .route("/cancel", post(cancel::cancel).layer(operate.clone()))
+        .route("/retry", post(retry::retry).layer(operate))
         .route("/history", get(history::history))
 }
@@ -2,7 +2,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
-    Router,
+    Extension, Router,
 };
 use dal::{
     action::{prototype::ActionPrototypeError, ActionError},
@@ -11,11 +11,11 @@ use dal::{
     SchemaVariantError, StandardModelError, TransactionsError, WorkspaceError,
     WorkspaceSnapshotError, WsEventError,
 };
-
+use si_jwt_public_key::SiJwtScope;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::AppState;
+use crate::{extract::RequiredScope, middleware::WorkspacePermissionLayer, AppState};
 
 use super::ApiError;
 
@@ -94,46 +94,74 @@ impl IntoResponse for ChangeSetError {
     }
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Edit);
+    let approve = WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Approve);
+
     Router::new()
         .route(
             "/list_open_change_sets",
             get(list_open_change_sets::list_open_change_sets),
         )
-        .route("/add_action", post(add_action::add_action))
+        .route(
+            "/add_action",
+            post(add_action::add_action).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Operate,
+            )),
+        )
         .route(
             "/create_change_set",
-            post(create_change_set::create_change_set),
+            post(create_change_set::create_change_set)
+                .layer(edit.clone())
+                .layer(Extension(RequiredScope(SiJwtScope::ChangeSetCreate))),
         )
         .route(
             "/apply_change_set",
-            post(apply_change_set::apply_change_set),
+            post(apply_change_set::apply_change_set)
+                .layer(edit.clone())
+                .layer(Extension(RequiredScope(SiJwtScope::ChangeSetApply))),
         )
         .route(
             "/abandon_change_set",
-            post(abandon_change_set::abandon_change_set),
+            post(abandon_change_set::abandon_change_set).layer(edit.clone()),
         )
         .route(
             "/begin_approval_process",
-            post(begin_approval_process::begin_approval_process),
+            post(begin_approval_process::begin_approval_process).layer(edit.clone()),
         )
         .route(
             "/cancel_approval_process",
-            post(begin_approval_process::cancel_approval_process),
+            post(begin_approval_process::cancel_approval_process).layer(edit.clone()),
+        )
+        .route(
+            "/merge_vote",
+            post(merge_vote::merge_vote).layer(approve.clone()),
         )
-        .route("/merge_vote", post(merge_vote::merge_vote))
         .route(
             "/begin_abandon_approval_process",
-            post(begin_abandon_approval_process::begin_abandon_approval_process),
+            post(begin_abandon_approval_process::begin_abandon_approval_process)
+                .layer(edit.clone()),
         )
         .route(
             "/cancel_abandon_approval_process",
-            post(begin_abandon_approval_process::cancel_abandon_approval_process),
+            post(begin_abandon_approval_process::cancel_abandon_approval_process)
+                .layer(edit.clone()),
+        )
+        .route(
+            "/abandon_vote",
+            post(abandon_vote::abandon_vote).layer(approve),
+        )
+        .route(
+            "/rebase_on_base",
+            post(rebase_on_base::rebase_on_base).layer(edit),
         )
-        .route("/abandon_vote", post(abandon_vote::abandon_vote))
-        .route("/rebase_on_base", post(rebase_on_base::rebase_on_base))
+        // Only reads, but is a post
         .route(
             "/status_with_base",
-            post(status_with_base::status_with_base),
+            post(status_with_base::status_with_base).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::View,
+            )),
         )
 }
@@ -1,7 +1,10 @@
 use std::num::ParseIntError;
 
 use super::ApiError;
-use crate::{service::component::conflicts_for_component::conflicts_for_component, AppState};
+use crate::{
+    middleware::WorkspacePermissionLayer,
+    service::component::conflicts_for_component::conflicts_for_component, AppState,
+};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -21,7 +24,10 @@ use dal::{
     attribute::value::debug::AttributeDebugViewError, component::ComponentId, PropId,
     SchemaVariantError, SecretError as DalSecretError, WsEventError,
 };
-use dal::{attribute::value::AttributeValueError, component::debug::ComponentDebugViewError};
+use dal::{
+    attribute::value::AttributeValueError, component::debug::ComponentDebugViewError,
+    AttributeValueId,
+};
 use dal::{ChangeSetError, TransactionsError};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -41,6 +47,7 @@ pub mod json;
 pub mod list_qualifications;
 mod manage;
 pub mod refresh;
+pub mod resolve_conflict;
 pub mod restore_default_function;
 pub mod set_name;
 pub mod set_resource_id;
@@ -60,10 +67,14 @@ pub enum ComponentError {
     AttributeDebugViewError(#[from] AttributeDebugViewError),
     #[error("attribute value error: {0}")]
     AttributeValue(#[from] AttributeValueError),
+    #[error("audit logging error: {0}")]
+    AuditLogging(#[from] dal::audit_logging::AuditLoggingError),
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
     #[error("component debug view error: {0}")]
     ComponentDebugView(#[from] ComponentDebugViewError),
+    #[error("no conflict found for attribute value: {0}")]
+    ConflictNotFound(AttributeValueId),
     #[error("dal component error: {0}")]
     DalComponent(#[from] DalComponentError),
     #[error("diagram error: {0}")]
@@ -128,6 +139,7 @@ impl IntoResponse for ComponentError {
             ComponentError::SchemaNotFound
             | ComponentError::InvalidVisibility
             | ComponentError::PropNotFound(_)
+            | ComponentError::ConflictNotFound(_)
             | ComponentError::SchemaVariantNotFound
             | ComponentError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             ComponentError::PropertyEditor(err) => match err {
@@ -155,7 +167,9 @@ impl IntoResponse for ComponentError {
     }
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Edit);
+
     Router::new()
         .route("/get_actions", get(get_actions::get_actions))
         .route(
@@ -175,28 +189,44 @@ pub fn routes() -> Router<AppState> {
         .route("/get_resource", get(get_resource::get_resource))
         .route(
             "/update_property_editor_value",
-            post(update_property_editor_value::update_property_editor_value),
+            post(update_property_editor_value::update_property_editor_value).layer(edit.clone()),
         )
         .route(
             "/insert_property_editor_value",
-            post(insert_property_editor_value::insert_property_editor_value),
+            post(insert_property_editor_value::insert_property_editor_value).layer(edit.clone()),
         )
         .route(
             "/delete_property_editor_value",
-            post(delete_property_editor_value::delete_property_editor_value),
+            post(delete_property_editor_value::delete_property_editor_value).layer(edit.clone()),
         )
         .route(
             "/restore_default_function",
-            post(restore_default_function::restore_default_function),
+            post(restore_default_function::restore_default_function).layer(edit.clone()),
+        )
+        .route("/set_type", post(set_type::set_type).layer(edit.clone()))
+        .route("/set_name", post(set_name::set_name).layer(edit.clone()))
+        .route(
+            "/set_resource_id",
+            post(set_resource_id::set_resource_id).layer(edit.clone()),
+        )
+        .route(
+            "/refresh",
+            post(refresh::refresh).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Operate,
+            )),
         )
-        .route("/set_type", post(set_type::set_type))
-        .route("/set_name", post(set_name::set_name))
-        .route("/set_resource_id", post(set_resource_id::set_resource_id))
-        .route("/refresh", post(refresh::refresh))
         .route("/debug", get(debug::debug_component))
         .route("/json", get(json::json))
-        .route("/upgrade_component", post(upgrade::upgrade))
+        .route(
+            "/upgrade_component",
+            post(upgrade::upgrade).layer(edit.clone()),
+        )
         .route("/conflicts", get(conflicts_for_component))
-        .route("/manage", post(manage::manage))
-        .route("/unmanage", post(unmanage::unmanage))
+        .route(
+            "/resolve_conflict",
+            post(resolve_conflict::resolve_conflict).layer(edit.clone()),
+        )
+        .route("/manage", post(manage::manage).layer(edit.clone()))
+        .route("/unmanage", post(unmanage::unmanage).layer(edit))
 }
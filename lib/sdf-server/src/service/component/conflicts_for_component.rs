@@ -1,12 +1,20 @@
 use std::collections::HashMap;
 
-use axum::Json;
-use dal::{AttributeValueId, ComponentId, Visibility};
+use audit_database::{AuditLogMetadataPredicate, AuditLogQuery};
+use axum::{extract::State, Json};
+use dal::{
+    audit_logging, change_set::conflict::Conflict, workspace_snapshot::node_weight::NodeWeight,
+    AttributeValue, AttributeValueId, ComponentId, DalContext, Visibility,
+};
 use serde::{Deserialize, Serialize};
+use si_frontend_types::{
+    AttributeValueConflict, ConflictLastWriter, ConflictSide, ConflictWithHead,
+};
 
 use crate::{
     extract::{AccessBuilder, HandlerContext},
     service::component::ComponentResult,
+    AppState,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -17,18 +25,152 @@ pub struct ConflictsForComponentRequest {
     pub visibility: Visibility,
 }
 
-pub type ConflictsForComponentResponse =
-    HashMap<AttributeValueId, si_frontend_types::ConflictWithHead>;
+pub type ConflictsForComponentResponse = HashMap<AttributeValueId, AttributeValueConflict>;
 
+/// Lists the conflicts between the change set and its base change set over the component's
+/// attribute values, with the value each side holds and who last changed it.
 pub async fn conflicts_for_component(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    State(state): State<AppState>,
     Json(ConflictsForComponentRequest {
-        component_id: _,
+        component_id,
         visibility,
     }): Json<ConflictsForComponentRequest>,
 ) -> ComponentResult<Json<ConflictsForComponentResponse>> {
-    let _ctx = builder.build(request_ctx.build(visibility)).await?;
+    let ctx = builder.build(request_ctx.build(visibility)).await?;
+    let base_ctx = ctx.clone_with_base().await?;
+
+    let mut response = HashMap::new();
+    for conflict in ctx.change_set()?.conflicts_with_base(&ctx).await? {
+        let Some((attribute_value_id, conflict_component_id, conflict_with_head)) =
+            attribute_value_conflict(&ctx, &conflict).await?
+        else {
+            continue;
+        };
+        if conflict_component_id != component_id {
+            continue;
+        }
+
+        // The last writer is looked up by the node the conflict is over, which is not the
+        // attribute value it is shown on when the base change set modified what was removed
+        let written_av_id = conflict.node_id().into();
+        response.insert(
+            attribute_value_id,
+            AttributeValueConflict {
+                conflict: conflict_with_head,
+                change_set: conflict_side(&state, &ctx, attribute_value_id, written_av_id).await?,
+                head: conflict_side(&state, &base_ctx, attribute_value_id, written_av_id).await?,
+            },
+        );
+    }
+
+    Ok(Json(response))
+}
+
+/// Describes one side of a conflict: the value of the attribute value the conflict is shown on,
+/// and the newest audit log about the attribute value written in the context's change set (or, on
+/// HEAD, in the change sets applied to it).
+async fn conflict_side(
+    state: &AppState,
+    ctx: &DalContext,
+    shown_av_id: AttributeValueId,
+    written_av_id: AttributeValueId,
+) -> ComponentResult<ConflictSide> {
+    let value = if ctx
+        .workspace_snapshot()?
+        .get_node_index_by_id_opt(shown_av_id)
+        .await
+        .is_some()
+    {
+        AttributeValue::get_by_id(ctx, shown_av_id)
+            .await?
+            .view(ctx)
+            .await?
+    } else {
+        None
+    };
+
+    let query = AuditLogQuery::new(1).metadata(AuditLogMetadataPredicate::new(
+        ["attributeValueId"],
+        written_av_id.to_string(),
+    ));
+    let page = audit_logging::query(ctx, state.audit_database_context(), &query).await?;
+    let last_writer = page.rows.into_iter().next().map(|row| ConflictLastWriter {
+        user_id: row.user_id,
+        change_set_id: row.change_set_id,
+        timestamp: row.timestamp,
+    });
+
+    Ok(ConflictSide { value, last_writer })
+}
+
+/// Describes a conflict over an attribute value, or an edge from one, returning the id of the
+/// attribute value the conflict is shown on and the component it belongs to. Conflicts over
+/// anything else are not about a component's attributes, so are skipped.
+pub(crate) async fn attribute_value_conflict(
+    ctx: &DalContext,
+    conflict: &Conflict,
+) -> ComponentResult<Option<(AttributeValueId, ComponentId, ConflictWithHead)>> {
+    Ok(match conflict {
+        Conflict::ExclusiveEdge { source_id: id, .. }
+        | Conflict::ModifiedOnBoth { node_id: id } => attribute_value_with_component(ctx, *id)
+            .await?
+            .map(|(modified_av_id, component_id)| {
+                (
+                    modified_av_id,
+                    component_id,
+                    ConflictWithHead::ModifiedOnBoth { modified_av_id },
+                )
+            }),
+        Conflict::ModifiedWhatBaseRemoved { node_id } => {
+            attribute_value_with_component(ctx, *node_id).await?.map(
+                |(modified_av_id, component_id)| {
+                    (
+                        modified_av_id,
+                        component_id,
+                        ConflictWithHead::ModifiedWhatHeadRemoved { modified_av_id },
+                    )
+                },
+            )
+        }
+        // The attribute value is only in the base change set, so the conflict is shown on the
+        // attribute value containing it
+        Conflict::RemovedWhatBaseModified { node_id } => {
+            let base_ctx = ctx.clone_with_base().await?;
+            match attribute_value_with_component(&base_ctx, *node_id).await? {
+                Some((attribute_value_id, component_id)) => {
+                    let container_av_id =
+                        AttributeValue::parent_attribute_value_id(&base_ctx, attribute_value_id)
+                            .await?
+                            .unwrap_or(attribute_value_id);
+                    Some((
+                        container_av_id,
+                        component_id,
+                        ConflictWithHead::RemovedWhatHeadModified { container_av_id },
+                    ))
+                }
+                None => None,
+            }
+        }
+    })
+}
+
+async fn attribute_value_with_component(
+    ctx: &DalContext,
+    node_id: dal::Ulid,
+) -> ComponentResult<Option<(AttributeValueId, ComponentId)>> {
+    let workspace_snapshot = ctx.workspace_snapshot()?;
+    let Some(node_index) = workspace_snapshot.get_node_index_by_id_opt(node_id).await else {
+        return Ok(None);
+    };
+    let Ok(NodeWeight::AttributeValue(_)) = workspace_snapshot.get_node_weight(node_index).await
+    else {
+        return Ok(None);
+    };
+
+    let attribute_value_id = node_id.into();
+    let component_id = AttributeValue::component_id(ctx, attribute_value_id).await?;
 
-    Ok(Json(HashMap::new()))
+    Ok(Some((attribute_value_id, component_id)))
 }
@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{change_set::conflict::ConflictResolution, AttributeValueId, ChangeSet, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::{conflicts_for_component::attribute_value_conflict, ComponentError, ComponentResult};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveConflictRequest {
+    /// The attribute value the conflict is shown on, as returned by the conflicts route.
+    pub attribute_value_id: AttributeValueId,
+    pub resolution: ConflictResolution,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Resolves every conflict shown on the attribute value.
+pub async fn resolve_conflict(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(ResolveConflictRequest {
+        attribute_value_id,
+        resolution,
+        visibility,
+    }): Json<ResolveConflictRequest>,
+) -> ComponentResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(visibility)).await?;
+
+    let mut resolved = 0;
+    for conflict in ctx.change_set()?.conflicts_with_base(&ctx).await? {
+        let Some((conflict_av_id, _, _)) = attribute_value_conflict(&ctx, &conflict).await? else {
+            continue;
+        };
+        if conflict_av_id == attribute_value_id {
+            ChangeSet::resolve_conflict(&ctx, &conflict, resolution).await?;
+            resolved += 1;
+        }
+    }
+    if resolved == 0 {
+        return Err(ComponentError::ConflictNotFound(attribute_value_id));
+    }
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "resolve_conflict",
+        serde_json::json!({
+            "how": "/component/resolve_conflict",
+            "attribute_value_id": attribute_value_id,
+            "resolution": resolution,
+            "resolved_count": resolved,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
@@ -24,7 +24,7 @@ use thiserror::Error;
 use tokio::task::JoinError;
 
 use super::ApiError;
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 pub mod create_component;
 pub mod create_connection;
@@ -157,35 +157,37 @@ impl IntoResponse for DiagramError {
     }
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state, permissions::Permission::Edit);
+
     Router::new()
         .route(
             "/add_components_to_view",
-            post(add_components_to_view::add_components_to_view),
+            post(add_components_to_view::add_components_to_view).layer(edit.clone()),
         )
         .route(
             "/delete_connection",
-            post(delete_connection::delete_connection),
+            post(delete_connection::delete_connection).layer(edit.clone()),
         )
         .route(
             "/delete_components",
-            post(delete_component::delete_components),
+            post(delete_component::delete_components).layer(edit.clone()),
         )
         .route(
             "/remove_delete_intent",
-            post(remove_delete_intent::remove_delete_intent),
+            post(remove_delete_intent::remove_delete_intent).layer(edit.clone()),
         )
         .route(
             "/create_connection",
-            post(create_connection::create_connection),
+            post(create_connection::create_connection).layer(edit.clone()),
         )
         .route(
             "/create_component",
-            post(create_component::create_component),
+            post(create_component::create_component).layer(edit.clone()),
         )
         .route(
             "/set_component_position",
-            post(set_component_position::set_component_position),
+            post(set_component_position::set_component_position).layer(edit),
         )
         // Gets diagram for default view TODO: Delete this
         .route("/get_diagram", get(get_diagram::get_diagram))
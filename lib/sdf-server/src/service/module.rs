@@ -23,7 +23,7 @@ use thiserror::Error;
 use tokio::fs::read_dir;
 use ulid::Ulid;
 
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 use super::ApiError;
 
@@ -237,24 +237,33 @@ pub async fn pkg_open(builder: &DalContextBuilder, file_name: &str) -> ModuleRes
     Ok(SiPkg::load_from_file(&real_pkg_path).await?)
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    // Approvals here are for importing a whole workspace, as with installing one in v2
+    let manage = WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Manage);
+
     Router::new()
         .route("/get_module_by_hash", get(get_module::get_module_by_hash))
-        .route("/install_module", post(install_module::install_module))
+        .route(
+            "/install_module",
+            post(install_module::install_module).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::AuthorFuncs,
+            )),
+        )
         .route(
             "/remote_module_spec",
             get(remote_module_spec::remote_module_spec),
         )
         .route(
             "/begin_approval_process",
-            post(approval_process::begin_approval_process),
+            post(approval_process::begin_approval_process).layer(manage.clone()),
         )
         .route(
             "/cancel_approval_process",
-            post(approval_process::cancel_approval_process),
+            post(approval_process::cancel_approval_process).layer(manage.clone()),
         )
         .route(
             "/import_workspace_vote",
-            post(import_workspace_vote::import_workspace_vote),
+            post(import_workspace_vote::import_workspace_vote).layer(manage),
         )
 }
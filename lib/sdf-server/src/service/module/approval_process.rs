@@ -42,9 +42,11 @@ pub async fn begin_approval_process(
     let metadata = pkg_data.into_latest().metadata;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(ModuleError::InvalidUser(*user_pk))?,
+        HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(ModuleError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => {
             return Err(ModuleError::InvalidUserSystemInit);
@@ -100,7 +102,7 @@ pub async fn cancel_approval_process(
     let ctx = builder.build_head(request_ctx).await?;
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::Automation(user_pk) | HistoryActor::User(user_pk) => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(ModuleError::InvalidUser(*user_pk))?;
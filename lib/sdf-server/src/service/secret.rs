@@ -10,7 +10,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use super::impl_default_error_into_response;
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 pub mod create_secret;
 pub mod delete_secret;
@@ -59,11 +59,13 @@ pub type SecretResult<T> = Result<T, SecretError>;
 
 impl_default_error_into_response!(SecretError);
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state, permissions::Permission::Edit);
+
     Router::new()
         .route("/get_public_key", get(get_public_key::get_public_key))
-        .route("/", post(create_secret::create_secret))
+        .route("/", post(create_secret::create_secret).layer(edit.clone()))
         .route("/", get(list_secrets::list_secrets))
-        .route("/", patch(update_secret::update_secret))
-        .route("/", delete(delete_secret::delete_secret))
+        .route("/", patch(update_secret::update_secret).layer(edit.clone()))
+        .route("/", delete(delete_secret::delete_secret).layer(edit))
 }
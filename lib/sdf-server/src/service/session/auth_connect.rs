@@ -7,10 +7,9 @@ use dal::{
     UserPk, Workspace, WorkspacePk, WorkspaceSnapshotGraph,
 };
 use hyper::Uri;
-use permissions::{Relation, RelationBuilder};
+use permissions::{BoxPermissionsClient, PermissionsClient, Relation, RelationBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use si_data_spicedb::SpiceDbClient;
 use si_events::audit_log::AuditLogKind;
 use telemetry::tracing::warn;
 
@@ -96,7 +95,7 @@ async fn find_or_create_user_and_workspace(
     create_workspace_permissions: WorkspacePermissionsMode,
     create_workspace_allowlist: &[String],
     on_demand_assets: bool,
-    spicedb_client: Option<&mut SpiceDbClient>,
+    permissions_client: Option<&mut BoxPermissionsClient>,
 ) -> SessionResult<(User, Workspace)> {
     // lookup user or create if we've never seen it before
     let maybe_user = User::get_by_pk(&ctx, auth_api_user.id).await?;
@@ -189,7 +188,7 @@ async fn find_or_create_user_and_workspace(
         }
     };
 
-    if let Some(client) = spicedb_client {
+    if let Some(client) = permissions_client {
         // the creator is the owner. Currently, owners cannot be changed so this should always be
         // true. Once we map the auth-api roles to spicedb we can rely on that to tell us this
         // information.
@@ -260,7 +259,7 @@ pub async fn auth_connect(
         state.create_workspace_permissions(),
         state.create_workspace_allowlist(),
         request.on_demand_assets.unwrap_or(false),
-        state.spicedb_client_clone().as_mut(),
+        state.permissions_client_clone().as_mut(),
     )
     .await?;
 
@@ -309,7 +308,7 @@ pub async fn auth_reconnect(
         state.create_workspace_permissions(),
         state.create_workspace_allowlist(),
         auth_response_body.on_demand_assets.unwrap_or(false),
-        state.spicedb_client_clone().as_mut(),
+        state.permissions_client_clone().as_mut(),
     )
     .await?;
 
@@ -348,7 +347,7 @@ pub async fn user_has_permission_to_create_workspace(
 }
 
 async fn ensure_workspace_creator_is_owner_of_workspace(
-    client: &mut SpiceDbClient,
+    client: &mut impl PermissionsClient,
     user_id: UserPk,
     workspace_id: WorkspacePk,
 ) -> SessionResult<()> {
@@ -4,9 +4,8 @@ use axum::{
     Json,
 };
 use dal::{DalContext, User};
-use permissions::{ObjectType, Relation, RelationBuilder};
+use permissions::{ObjectType, PermissionsClient, Relation, RelationBuilder};
 use serde::{Deserialize, Serialize};
-use si_data_spicedb::SpiceDbClient;
 use strum::{Display, EnumString};
 
 use super::{SessionError, SessionResult};
@@ -32,14 +31,40 @@ pub struct WorkspaceMember {
 }
 
 #[derive(Clone, Display, Debug, Deserialize, EnumString, Serialize)]
-#[strum(serialize_all = "UPPERCASE")]
-#[serde(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WorkspaceRole {
     Approver,
     Editor,
+    FuncAuthor,
+    Operator,
     Owner,
+    Viewer,
 }
 
+impl WorkspaceRole {
+    /// The workspace relation that is kept in sync with the role. Owners are not synced here,
+    /// since they are added when they first log in to the workspace.
+    fn synced_relation(&self) -> Option<Relation> {
+        match self {
+            Self::Approver => Some(Relation::Approver),
+            Self::Editor => Some(Relation::Editor),
+            Self::FuncAuthor => Some(Relation::FuncAuthor),
+            Self::Operator => Some(Relation::Operator),
+            Self::Owner => None,
+            Self::Viewer => Some(Relation::Viewer),
+        }
+    }
+}
+
+const SYNCED_RELATIONS: [Relation; 5] = [
+    Relation::Approver,
+    Relation::Editor,
+    Relation::FuncAuthor,
+    Relation::Operator,
+    Relation::Viewer,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshWorkspaceMembersResponse {
@@ -82,23 +107,25 @@ pub async fn refresh_workspace_members(
     let ctx = builder.build_head(access_builder).await?;
     let posthog_client = PosthogClient(posthog_client.clone());
 
-    if let Some(client) = state.spicedb_client() {
-        let approvers: Vec<_> = workspace_members
-            .clone()
-            .into_iter()
-            .filter(|u| matches!(u.role, WorkspaceRole::Approver))
-            .map(|u| u.user_id)
-            .collect();
-        sync_workspace_approvers(
-            &ctx,
-            client,
-            request.workspace_id.clone(),
-            approvers,
-            &original_uri,
-            &host_name,
-            &posthog_client,
-        )
-        .await?;
+    if let Some(client) = state.permissions_client() {
+        for relation in SYNCED_RELATIONS {
+            let user_ids: Vec<_> = workspace_members
+                .iter()
+                .filter(|u| u.role.synced_relation() == Some(relation))
+                .map(|u| u.user_id.clone())
+                .collect();
+            sync_workspace_relation(
+                &ctx,
+                client,
+                request.workspace_id.clone(),
+                relation,
+                user_ids,
+                &original_uri,
+                &host_name,
+                &posthog_client,
+            )
+            .await?;
+        }
     }
 
     let members = User::list_members_for_workspace(&ctx, request.workspace_id.clone()).await?;
@@ -116,36 +143,38 @@ pub async fn refresh_workspace_members(
     Ok(Json(RefreshWorkspaceMembersResponse { success: true }))
 }
 
-async fn sync_workspace_approvers(
+#[allow(clippy::too_many_arguments)]
+async fn sync_workspace_relation(
     ctx: &DalContext,
-    client: &mut SpiceDbClient,
+    client: &mut impl PermissionsClient,
     workspace_id: String,
-    new_approver_ids: Vec<String>,
+    relation: Relation,
+    new_user_ids: Vec<String>,
     original_uri: &Uri,
     host_name: &String,
     PosthogClient(posthog_client): &PosthogClient,
 ) -> SessionResult<()> {
-    let existing_approvers = RelationBuilder::new()
+    let existing = RelationBuilder::new()
         .object(ObjectType::Workspace, workspace_id.clone())
-        .relation(Relation::Approver)
+        .relation(relation)
         .read(client)
         .await?;
 
-    let existing_approver_ids: Vec<_> = existing_approvers
+    let existing_ids: Vec<_> = existing
         .into_iter()
         .map(|w| w.subject().id().to_string())
         .collect();
 
-    let to_add: Vec<_> = new_approver_ids
+    let to_add: Vec<_> = new_user_ids
         .clone()
         .into_iter()
-        .filter(|u| !existing_approver_ids.contains(u))
+        .filter(|u| !existing_ids.contains(u))
         .collect();
 
-    let to_remove: Vec<_> = existing_approver_ids
+    let to_remove: Vec<_> = existing_ids
         .clone()
         .into_iter()
-        .filter(|r| !new_approver_ids.contains(r))
+        .filter(|r| !new_user_ids.contains(r))
         .collect();
 
     track(
@@ -153,19 +182,20 @@ async fn sync_workspace_approvers(
         ctx,
         original_uri,
         host_name,
-        "sync_workspace_approvers",
+        format!("sync_workspace_{relation}s"),
         serde_json::json!({
             "how": "/session/refresh_workspace_members",
+            "relation": relation.to_string(),
             "to_add": to_add.clone(),
             "to_remove": to_remove.clone(),
-            "existing_approver_ids": existing_approver_ids,
+            "existing_ids": existing_ids,
         }),
     );
 
     for user_pk_str in to_add {
         RelationBuilder::new()
             .object(ObjectType::Workspace, workspace_id.clone())
-            .relation(Relation::Approver)
+            .relation(relation)
             .subject(ObjectType::User, user_pk_str.clone())
             .create(client)
             .await?;
@@ -175,7 +205,7 @@ async fn sync_workspace_approvers(
             ctx,
             original_uri,
             host_name,
-            "add_approver",
+            format!("add_{relation}"),
             serde_json::json!({
                 "how": "/session/refresh_workspace_member",
                 "user_pk": user_pk_str,
@@ -186,7 +216,7 @@ async fn sync_workspace_approvers(
     for user_pk_str in to_remove {
         RelationBuilder::new()
             .object(ObjectType::Workspace, workspace_id.clone())
-            .relation(Relation::Approver)
+            .relation(relation)
             .subject(ObjectType::User, user_pk_str.clone())
             .delete(client)
             .await?;
@@ -196,7 +226,7 @@ async fn sync_workspace_approvers(
             ctx,
             original_uri,
             host_name,
-            "remove_approver",
+            format!("remove_{relation}"),
             serde_json::json!({
                 "how": "/session/refresh_workspace_member",
                 "user_pk": user_pk_str,
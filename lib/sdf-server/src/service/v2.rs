@@ -1,14 +1,17 @@
 use axum::Router;
 
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 pub mod admin;
+pub mod approval_policy;
 pub mod audit_log;
 pub mod change_set;
+pub mod component;
 pub mod func;
 pub mod integrations;
 pub mod management;
 pub mod module;
+pub mod schedule;
 pub mod variant;
 pub mod view;
 pub mod workspace;
@@ -23,16 +26,48 @@ const WORKSPACES_PREFIX: &str = "/workspaces/:workspace_id";
 pub fn routes(state: AppState) -> Router<AppState> {
     Router::new()
         .nest("/admin", admin::v2_routes(state.clone()))
+        .merge(workspace_routes(state))
+}
+
+/// Everything within a workspace needs the view permission to be read, writes are checked by each
+/// route.
+fn workspace_routes(state: AppState) -> Router<AppState> {
+    Router::new()
         .nest(&format!("{PREFIX}/audit-logs"), audit_log::v2_routes())
         .nest(CHANGE_SET_PREFIX, change_set::v2_routes(state.clone()))
-        .nest(&format!("{PREFIX}/funcs"), func::v2_routes())
-        .nest(&format!("{PREFIX}/modules"), module::v2_routes())
-        .nest(&format!("{PREFIX}/schema-variants"), variant::v2_routes())
-        .nest(&format!("{PREFIX}/management"), management::v2_routes())
-        .nest(&format!("{PREFIX}/views"), view::v2_routes())
-        .nest(WORKSPACES_PREFIX, workspace::v2_routes())
+        .nest(
+            &format!("{PREFIX}/components"),
+            component::v2_routes(state.clone()),
+        )
+        .nest(&format!("{PREFIX}/funcs"), func::v2_routes(state.clone()))
+        .nest(
+            &format!("{PREFIX}/modules"),
+            module::v2_routes(state.clone()),
+        )
+        .nest(
+            &format!("{PREFIX}/schema-variants"),
+            variant::v2_routes(state.clone()),
+        )
+        .nest(
+            &format!("{PREFIX}/management"),
+            management::v2_routes(state.clone()),
+        )
+        .nest(&format!("{PREFIX}/views"), view::v2_routes(state.clone()))
+        .nest(WORKSPACES_PREFIX, workspace::v2_routes(state.clone()))
         .nest(
             &format!("{WORKSPACES_PREFIX}/integrations"),
-            integrations::v2_routes(),
+            integrations::v2_routes(state.clone()),
+        )
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/approval-policies"),
+            approval_policy::v2_routes(state.clone()),
+        )
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/schedules"),
+            schedule::v2_routes(state.clone()),
         )
+        .route_layer(WorkspacePermissionLayer::for_reads(
+            state,
+            permissions::Permission::View,
+        ))
 }
@@ -17,11 +17,14 @@ use thiserror::Error;
 
 use crate::{extract::AdminAccessBuilder, service::ApiError, AppState};
 
+mod check_integrity;
+mod garbage_collect;
 mod get_snapshot;
 mod kill_execution;
 mod list_change_sets;
 mod list_workspace_users;
 mod prompts;
+mod repair_integrity;
 mod search_workspaces;
 mod set_concurrency_limit;
 mod set_snapshot;
@@ -43,6 +46,10 @@ pub enum AdminAPIError {
     ChangeSetNotFound(ChangeSetId),
     #[error("func runner error: {0}")]
     FuncRunner(#[from] FuncRunnerError),
+    #[error("garbage collection error: {0}")]
+    GarbageCollection(#[from] dal::garbage_collection::GarbageCollectionError),
+    #[error("grace period of {0} seconds is shorter than the minimum of {1} seconds for a sweep")]
+    GracePeriodTooShort(u64, u64),
     #[error("layer db error: {0}")]
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("multipart error: {0}")]
@@ -148,6 +155,7 @@ impl IntoResponse for AdminAPIError {
             AdminAPIError::FuncRunner(FuncRunnerError::DoNotHavePermissionToKillExecution) => {
                 StatusCode::UNAUTHORIZED
             }
+            AdminAPIError::GracePeriodTooShort(_, _) => StatusCode::BAD_REQUEST,
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
@@ -167,6 +175,7 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/func/runs/:func_run_id/kill_execution",
             put(kill_execution::kill_execution),
         )
+        .route("/garbage_collect", post(garbage_collect::garbage_collect))
         .route("/workspaces", get(search_workspaces::search_workspaces))
         .route(
             "/workspaces/:workspace_pk/users",
@@ -188,6 +197,14 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/workspaces/:workspace_pk/change_sets/:change_set_id/set_snapshot",
             post(set_snapshot::set_snapshot),
         )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/check_integrity",
+            get(check_integrity::check_integrity),
+        )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/repair_integrity",
+            post(repair_integrity::repair_integrity),
+        )
         .nest("/prompts", prompts::routes())
         .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
         .route_layer(axum::middleware::from_extractor_with_state::<
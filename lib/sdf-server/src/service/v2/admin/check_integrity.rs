@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    response::Json,
+};
+use dal::{workspace_snapshot::integrity::IntegrityViolation, ChangeSetId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::AdminAPIResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track_no_ctx,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckIntegrityResponse {
+    violations: Vec<IntegrityViolation>,
+}
+
+#[instrument(
+    name = "admin.check_integrity",
+    level = "info",
+    skip_all,
+    fields(
+        si.change_set.id = %change_set_id,
+        si.workspace.id = %workspace_pk,
+    ),
+)]
+pub async fn check_integrity(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<CheckIntegrityResponse>> {
+    let mut ctx = builder.build_head(access_builder).await?;
+    ctx.update_visibility_and_snapshot_to_visibility(change_set_id)
+        .await?;
+
+    let violations = ctx.workspace_snapshot()?.integrity_violations(&ctx).await?;
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_pk.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.check_integrity",
+        serde_json::json!({
+            "violation_count": violations.len(),
+        }),
+    );
+
+    Ok(Json(CheckIntegrityResponse { violations }))
+}
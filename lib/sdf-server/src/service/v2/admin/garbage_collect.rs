@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Host, OriginalUri},
+    response::Json,
+};
+use dal::garbage_collection;
+use serde::{Deserialize, Serialize};
+use si_layer_cache::gc::{GarbageCollectionConfig, GarbageCollectionReport, DEFAULT_GRACE_PERIOD};
+use telemetry::prelude::*;
+
+use super::{AdminAPIError, AdminAPIResult};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track,
+};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GarbageCollectRequest {
+    /// Defaults to true; sweeping must be asked for explicitly.
+    pub dry_run: Option<bool>,
+    /// Defaults to a day. Dry runs may use a shorter grace period, but a sweep may not, since
+    /// anything written within the grace period could still be in use without being reachable.
+    pub grace_period_seconds: Option<u64>,
+}
+
+#[instrument(
+    name = "admin.garbage_collect",
+    level = "info",
+    skip_all,
+    fields(
+        si.layer_cache.gc.dry_run = Empty,
+    ),
+)]
+pub async fn garbage_collect(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<GarbageCollectRequest>,
+) -> AdminAPIResult<Json<GarbageCollectionReport>> {
+    let span = current_span_for_instrument_at!("info");
+
+    let mut config = GarbageCollectionConfig::default();
+    if let Some(dry_run) = request.dry_run {
+        config.dry_run = dry_run;
+    }
+    if let Some(grace_period_seconds) = request.grace_period_seconds {
+        config.grace_period = Duration::from_secs(grace_period_seconds);
+    }
+    if !config.dry_run && config.grace_period < DEFAULT_GRACE_PERIOD {
+        return Err(AdminAPIError::GracePeriodTooShort(
+            config.grace_period.as_secs(),
+            DEFAULT_GRACE_PERIOD.as_secs(),
+        ));
+    }
+    span.record("si.layer_cache.gc.dry_run", config.dry_run);
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    let report = garbage_collection::collect_garbage(&ctx, config).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "admin.garbage_collect",
+        serde_json::json!({
+            "dry_run": report.dry_run,
+            "cutoff": report.cutoff,
+            "swept_snapshots": report.workspace_snapshots.swept_keys.len(),
+            "swept_content": report.cas.swept_keys.len(),
+            "swept_rebase_batches": report.rebase_batches.swept_keys.len(),
+            "swept_func_results": report.func_results.swept_keys.len(),
+        }),
+    );
+
+    Ok(Json(report))
+}
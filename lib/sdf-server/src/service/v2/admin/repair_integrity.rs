@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    response::Json,
+};
+use dal::{workspace_snapshot::integrity::IntegrityViolation, ChangeSetId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::AdminAPIResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track_no_ctx,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairIntegrityResponse {
+    repaired: Vec<IntegrityViolation>,
+    /// The violations left after repairing, which have to be fixed by hand.
+    remaining: Vec<IntegrityViolation>,
+}
+
+#[instrument(
+    name = "admin.repair_integrity",
+    level = "info",
+    skip_all,
+    fields(
+        si.change_set.id = %change_set_id,
+        si.workspace.id = %workspace_pk,
+    ),
+)]
+pub async fn repair_integrity(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<RepairIntegrityResponse>> {
+    let mut ctx = builder.build_head(access_builder).await?;
+    ctx.update_visibility_and_snapshot_to_visibility(change_set_id)
+        .await?;
+
+    let workspace_snapshot = ctx.workspace_snapshot()?;
+    let repaired = workspace_snapshot.repair_integrity_violations(&ctx).await?;
+    let remaining = workspace_snapshot.integrity_violations(&ctx).await?;
+
+    if !repaired.is_empty() {
+        ctx.commit().await?;
+    }
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_pk.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.repair_integrity",
+        serde_json::json!({
+            "repaired_count": repaired.len(),
+            "remaining_count": remaining.len(),
+        }),
+    );
+
+    Ok(Json(RepairIntegrityResponse {
+        repaired,
+        remaining,
+    }))
+}
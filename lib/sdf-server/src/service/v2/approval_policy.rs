@@ -0,0 +1,60 @@
+use axum::{
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use dal::change_set::approval::{ApprovalPolicyError, ApprovalPolicyId};
+use hyper::StatusCode;
+use thiserror::Error;
+
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+
+pub mod create_policy;
+pub mod delete_policy;
+pub mod list_policies;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApprovalPolicyAPIError {
+    #[error("approval policy error: {0}")]
+    ApprovalPolicy(#[from] ApprovalPolicyError),
+    #[error("approval policy not found: {0}")]
+    ApprovalPolicyNotFound(ApprovalPolicyId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+}
+
+pub type ApprovalPolicyAPIResult<T> = Result<T, ApprovalPolicyAPIError>;
+
+impl IntoResponse for ApprovalPolicyAPIError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::ApprovalPolicy(ApprovalPolicyError::RequiredApprovalsTooLow) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::ApprovalPolicyNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:approval_policy_id",
+            delete(delete_policy::delete_policy).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/",
+            post(create_policy::create_policy).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
+        .route("/", get(list_policies::list_policies))
+}
@@ -0,0 +1,43 @@
+use axum::{extract::Path, Json};
+use dal::{
+    change_set::approval::{ApprovalPolicy, ApprovalPolicyScope},
+    UserPk, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ApprovalPolicyAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub scope: ApprovalPolicyScope,
+    pub required_approvals: u32,
+    #[serde(default)]
+    pub approvers: Vec<UserPk>,
+    #[serde(default)]
+    pub allow_self_approval: bool,
+}
+
+pub async fn create_policy(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreatePolicyRequest>,
+) -> ApprovalPolicyAPIResult<Json<ApprovalPolicy>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let policy = ApprovalPolicy::new(
+        &ctx,
+        request.name,
+        request.scope,
+        request.required_approvals,
+        request.approvers,
+        request.allow_self_approval,
+    )
+    .await?;
+    ctx.commit().await?;
+
+    Ok(Json(policy))
+}
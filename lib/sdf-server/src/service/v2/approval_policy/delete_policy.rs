@@ -0,0 +1,27 @@
+use axum::extract::Path;
+use dal::{
+    change_set::approval::{ApprovalPolicy, ApprovalPolicyId},
+    WorkspacePk,
+};
+
+use super::{ApprovalPolicyAPIError, ApprovalPolicyAPIResult};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+pub async fn delete_policy(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, approval_policy_id)): Path<(WorkspacePk, ApprovalPolicyId)>,
+) -> ApprovalPolicyAPIResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ApprovalPolicy::get_by_id(&ctx, approval_policy_id)
+        .await?
+        .ok_or(ApprovalPolicyAPIError::ApprovalPolicyNotFound(
+            approval_policy_id,
+        ))?
+        .delete(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(())
+}
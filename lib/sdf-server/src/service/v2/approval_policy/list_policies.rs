@@ -0,0 +1,15 @@
+use axum::{extract::Path, Json};
+use dal::{change_set::approval::ApprovalPolicy, WorkspacePk};
+
+use super::ApprovalPolicyAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+pub async fn list_policies(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> ApprovalPolicyAPIResult<Json<Vec<ApprovalPolicy>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Ok(Json(ApprovalPolicy::list_for_workspace(&ctx).await?))
+}
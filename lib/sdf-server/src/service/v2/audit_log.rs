@@ -1,4 +1,5 @@
 use axum::{
+    http::StatusCode,
     response::{IntoResponse, Response},
     routing::get,
     Router,
@@ -23,6 +24,11 @@ pub enum AuditLogError {
     DalTransactions(#[from] dal::TransactionsError),
     #[error("dal user error: {0}")]
     DalUser(#[from] dal::UserError),
+    #[error(
+        "page size must be between 1 and {}: {0}",
+        audit_database::MAX_PAGE_SIZE
+    )]
+    InvalidPageSize(usize),
     #[error("user not found for id: {0}")]
     UserNotFound(UserPk),
 }
@@ -33,8 +39,8 @@ impl IntoResponse for AuditLogError {
     fn into_response(self) -> Response {
         let err_string = self.to_string();
 
-        #[allow(clippy::match_single_binding)]
         let (status_code, maybe_message) = match self {
+            AuditLogError::InvalidPageSize(_) => (StatusCode::BAD_REQUEST, None),
             _ => (ApiError::DEFAULT_ERROR_STATUS_CODE, None),
         };
 
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
-use audit_database::AuditLogRow;
+use audit_database::{
+    AuditLogCursor, AuditLogMetadataPredicate, AuditLogQuery, AuditLogRow, DEFAULT_PAGE_SIZE,
+    MAX_PAGE_SIZE,
+};
 use axum::{
     extract::{Path, Query, State},
     Json,
 };
+use chrono::{DateTime, Utc};
 use dal::{audit_logging, ChangeSet, DalContext, User};
 use serde::{Deserialize, Serialize};
 use si_events::{ChangeSetId, UserPk};
@@ -21,6 +25,62 @@ use crate::{
 pub struct ListAuditLogsRequest {
     size: Option<usize>,
     sort_ascending: Option<bool>,
+    /// An opaque cursor returned by a previous request, used to load the next page.
+    cursor: Option<AuditLogCursor>,
+    user_id: Option<UserPk>,
+    /// A comma-separated list of audit log kinds.
+    kinds: Option<String>,
+    entity_type: Option<String>,
+    entity_name: Option<String>,
+    start_timestamp: Option<DateTime<Utc>>,
+    end_timestamp: Option<DateTime<Utc>>,
+    /// A dot-separated path into the audit log metadata, compared against `metadata_value`.
+    metadata_path: Option<String>,
+    metadata_value: Option<String>,
+}
+
+impl ListAuditLogsRequest {
+    fn into_query(self) -> AuditLogResult<AuditLogQuery> {
+        let size = self.size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&size) {
+            return Err(AuditLogError::InvalidPageSize(size));
+        }
+
+        let mut query =
+            AuditLogQuery::new(size).sort_ascending(self.sort_ascending.unwrap_or(false));
+
+        if let Some(cursor) = self.cursor {
+            query = query.after(cursor);
+        }
+        if let Some(user_id) = self.user_id {
+            query = query.user_id(user_id);
+        }
+        if let Some(kinds) = self.kinds {
+            query = query.kinds(
+                kinds
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|kind| !kind.is_empty()),
+            );
+        }
+        if let Some(entity_type) = self.entity_type {
+            query = query.entity_type(entity_type);
+        }
+        if let Some(entity_name) = self.entity_name {
+            query = query.entity_name(entity_name);
+        }
+        if let Some(start_timestamp) = self.start_timestamp {
+            query = query.start_timestamp(start_timestamp);
+        }
+        if let Some(end_timestamp) = self.end_timestamp {
+            query = query.end_timestamp(end_timestamp);
+        }
+        if let Some((path, value)) = self.metadata_path.zip(self.metadata_value) {
+            query = query.metadata(AuditLogMetadataPredicate::new(path.split('.'), value));
+        }
+
+        Ok(query)
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -28,6 +88,7 @@ pub struct ListAuditLogsRequest {
 pub struct ListAuditLogsResponse {
     logs: Vec<frontend_types::AuditLog>,
     can_load_more: bool,
+    next_cursor: Option<AuditLogCursor>,
 }
 
 pub async fn list_audit_logs(
@@ -41,23 +102,19 @@ pub async fn list_audit_logs(
         .build(access_builder.build(change_set_id.into()))
         .await?;
 
-    let (database_logs, can_load_more) = audit_logging::list(
-        &ctx,
-        state.audit_database_context(),
-        request.size.unwrap_or(0),
-        request.sort_ascending.unwrap_or(false),
-    )
-    .await?;
+    let page =
+        audit_logging::query(&ctx, state.audit_database_context(), &request.into_query()?).await?;
 
     let mut assembler = Assembler::new();
-    let mut logs = Vec::with_capacity(database_logs.len());
-    for database_log in database_logs {
+    let mut logs = Vec::with_capacity(page.rows.len());
+    for database_log in page.rows {
         logs.push(assembler.assemble(&ctx, database_log).await?);
     }
 
     Ok(Json(ListAuditLogsResponse {
         logs,
-        can_load_more,
+        can_load_more: page.next_cursor.is_some(),
+        next_cursor: page.next_cursor,
     }))
 }
 
@@ -91,6 +148,7 @@ impl Assembler {
             user_id,
             user_email,
             user_name,
+            automation: audit_log.automation,
             kind: audit_log.kind,
             // TODO(nick): allow this to be optional in the frontend.
             entity_name: audit_log.entity_name.unwrap_or(" ".to_string()),
@@ -161,3 +219,26 @@ impl Assembler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(size: usize) -> ListAuditLogsRequest {
+        serde_json::from_value(serde_json::json!({ "size": size }))
+            .expect("could not deserialize request")
+    }
+
+    #[test]
+    fn page_sizes_out_of_range_are_rejected() {
+        for size in [0, MAX_PAGE_SIZE + 1, usize::MAX] {
+            assert!(matches!(
+                request(size).into_query(),
+                Err(AuditLogError::InvalidPageSize(rejected)) if rejected == size
+            ));
+        }
+        for size in [1, MAX_PAGE_SIZE] {
+            assert!(request(size).into_query().is_ok());
+        }
+    }
+}
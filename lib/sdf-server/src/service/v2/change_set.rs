@@ -4,7 +4,7 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
-    Router,
+    Extension, Json, Router,
 };
 use dal::{
     workspace_integrations::WorkspaceIntegration, ChangeSetId, ChangeSetStatus, DalContext,
@@ -13,11 +13,15 @@ use dal::{
 use reqwest::Client;
 use serde::Serialize;
 use si_data_spicedb::SpiceDbError;
+use si_jwt_public_key::SiJwtScope;
 use thiserror::Error;
 
-use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+use crate::{
+    extract::RequiredScope, middleware::WorkspacePermissionLayer, service::ApiError, AppState,
+};
 
 mod apply;
+mod approval_status;
 mod approve;
 mod cancel_approval_request;
 mod force_apply;
@@ -72,7 +76,28 @@ pub enum Error {
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
+        // Conflicts are returned alongside the error, so that they can be resolved
+        if let Self::ChangeSetApply(dal::ChangeSetApplyError::ChangeSet(
+            dal::ChangeSetError::ConflictsWithBase(conflicts),
+        )) = &self
+        {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": {
+                        "message": self.to_string(),
+                        "statusCode": StatusCode::CONFLICT.as_u16(),
+                    },
+                    "conflicts": conflicts,
+                })),
+            )
+                .into_response();
+        }
+
         let status_code = match &self {
+            Self::ChangeSet(dal::ChangeSetError::ApprovalRequirementsNotMet(_)) => {
+                StatusCode::PRECONDITION_FAILED
+            }
             Self::ChangeSetApply(_) => StatusCode::CONFLICT,
             Self::DvuRootsNotEmpty(_) => StatusCode::PRECONDITION_FAILED,
             Self::Transactions(dal::TransactionsError::BadWorkspaceAndChangeSet) => {
@@ -122,14 +147,22 @@ pub async fn post_to_webhook(
 }
 
 pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Edit);
+
     Router::new()
         .nest(
             "/:change_set_id",
             Router::new()
-                .route("/apply", post(apply::apply))
+                .route(
+                    "/apply",
+                    post(apply::apply)
+                        .layer(edit.clone())
+                        .layer(Extension(RequiredScope(SiJwtScope::ChangeSetApply))),
+                )
+                .route("/approval_status", get(approval_status::approval_status))
                 .route(
                     "/request_approval",
-                    post(request_approval::request_approval),
+                    post(request_approval::request_approval).layer(edit.clone()),
                 )
                 .route(
                     "/approve",
@@ -147,18 +180,20 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
                 )
                 .route(
                     "/cancel_approval_request",
-                    post(cancel_approval_request::cancel_approval_request),
+                    post(cancel_approval_request::cancel_approval_request).layer(edit.clone()),
                 )
                 // Consider how we make it editable again after it's been rejected
-                .route("/reopen", post(reopen::reopen))
+                .route("/reopen", post(reopen::reopen).layer(edit.clone()))
                 .route(
                     "/force_apply",
-                    post(force_apply::force_apply).layer(WorkspacePermissionLayer::new(
-                        state.clone(),
-                        permissions::Permission::Approve,
-                    )),
+                    post(force_apply::force_apply)
+                        .layer(WorkspacePermissionLayer::new(
+                            state.clone(),
+                            permissions::Permission::Approve,
+                        ))
+                        .layer(Extension(RequiredScope(SiJwtScope::ChangeSetApply))),
                 )
-                .route("/rename", post(rename::rename)),
+                .route("/rename", post(rename::rename).layer(edit)),
         )
         .route("/", get(list::list_actionable))
 }
@@ -0,0 +1,33 @@
+use axum::{extract::Path, Json};
+use dal::{change_set::approval::ApprovalRequirement, ChangeSet, ChangeSetId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalStatusResponse {
+    pub satisfied: bool,
+    pub requirements: Vec<ApprovalRequirement>,
+}
+
+pub async fn approval_status(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<ApprovalStatusResponse>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(Error::ChangeSetNotFound(change_set_id))?;
+    let requirements = change_set.approval_requirements(&ctx).await?;
+
+    Ok(Json(ApprovalStatusResponse {
+        satisfied: requirements.iter().all(ApprovalRequirement::is_satisfied),
+        requirements,
+    }))
+}
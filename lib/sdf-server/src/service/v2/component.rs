@@ -0,0 +1,61 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{post, put},
+    Router,
+};
+use dal::component::query::ComponentFilterParseError;
+use thiserror::Error;
+
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+
+pub mod search;
+pub mod set_tags;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentsError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] dal::ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] dal::ComponentError),
+    #[error("invalid query: {0}")]
+    ComponentFilterParse(#[from] ComponentFilterParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] dal::WsEventError),
+}
+
+pub type ComponentsResult<T> = Result<T, ComponentsError>;
+
+impl IntoResponse for ComponentsError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            ComponentsError::ComponentFilterParse(_) => StatusCode::BAD_REQUEST,
+            ComponentsError::Component(dal::ComponentError::NotFound(_)) => StatusCode::NOT_FOUND,
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        // Only reads, but is a post
+        .route(
+            "/search",
+            post(search::search).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::View,
+            )),
+        )
+        .route(
+            "/:component_id/tags",
+            put(set_tags::set_tags).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Edit,
+            )),
+        )
+}
@@ -0,0 +1,42 @@
+use axum::extract::{Json, Path};
+use dal::{component::query::ComponentFilter, ChangeSetId, Component, ComponentId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentsResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequest {
+    /// A query string, as parsed by [`ComponentFilter`].
+    query: Option<String>,
+    /// A structured filter, which must also match when given alongside a query string.
+    filter: Option<ComponentFilter>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    component_ids: Vec<ComponentId>,
+}
+
+pub async fn search(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(SearchRequest { query, filter }): Json<SearchRequest>,
+) -> ComponentsResult<Json<SearchResponse>> {
+    let mut filters = Vec::new();
+    if let Some(query) = query {
+        filters.push(query.parse()?);
+    }
+    filters.extend(filter);
+
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let component_ids = Component::query(&ctx, &ComponentFilter::And(filters)).await?;
+
+    Ok(Json(SearchResponse { component_ids }))
+}
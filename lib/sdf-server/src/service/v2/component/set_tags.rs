@@ -0,0 +1,85 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::extract::{Host, Json, OriginalUri, Path};
+use dal::{ChangeSet, ChangeSetId, Component, ComponentId, WorkspacePk, WsEvent};
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+use si_frontend_types::DiagramComponentView;
+
+use super::ComponentsResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    tracking::track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagsRequest {
+    tags: BTreeMap<String, String>,
+}
+
+pub async fn set_tags(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, component_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        ComponentId,
+    )>,
+    Json(SetTagsRequest { tags }): Json<SetTagsRequest>,
+) -> ComponentsResult<ForceChangeSetResponse<DiagramComponentView>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let component = Component::get_by_id(&ctx, component_id).await?;
+    let old_tags = component.tags().to_owned();
+    let component = component.set_tags(&ctx, tags).await?;
+    let component_name = component.name(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "set_component_tags",
+        serde_json::json!({
+            "how": "/component/set_tags",
+            "component_id": component_id,
+            "component_name": component_name,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+    ctx.write_audit_log(
+        AuditLogKind::UpdateComponentTags {
+            component_id,
+            old_tags,
+            new_tags: component.tags().to_owned(),
+        },
+        component_name,
+    )
+    .await?;
+
+    let payload = component
+        .into_frontend_type(
+            &ctx,
+            None,
+            component.change_status(&ctx).await?,
+            &mut HashMap::new(),
+        )
+        .await?;
+    WsEvent::component_updated(&ctx, payload.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(force_change_set_id, payload))
+}
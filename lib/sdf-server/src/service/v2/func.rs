@@ -3,7 +3,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
 use dal::{
     attribute::{prototype::argument::AttributePrototypeArgumentError, value::AttributeValueError},
@@ -16,12 +16,15 @@ use dal::{
     WorkspaceSnapshotError, WsEventError,
 };
 use si_frontend_types::FuncCode;
+use si_jwt_public_key::SiJwtScope;
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use veritech_client::FunctionResultFailureErrorKind;
 
-use crate::{service::ApiError, AppState};
+use crate::{
+    extract::RequiredScope, middleware::WorkspacePermissionLayer, service::ApiError, AppState,
+};
 
 pub mod argument;
 pub mod binding;
@@ -187,53 +190,82 @@ impl IntoResponse for FuncAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let author_funcs =
+        WorkspacePermissionLayer::new(state.clone(), permissions::Permission::AuthorFuncs);
+    let operate = WorkspacePermissionLayer::new(state, permissions::Permission::Operate);
+
     Router::new()
         // Func Stuff
         .route("/", get(list_funcs::list_funcs))
         .route("/including_pruned", get(list_all_funcs::list_all_funcs))
         .route("/code", get(get_code::get_code)) // accepts a list of func_ids
         .route("/runs/:func_run_id", get(get_func_run::get_func_run)) // accepts a list of func_ids
-        .route("/", post(create_func::create_func))
-        .route("/:func_id", put(update_func::update_func)) // only save the func's metadata
-        .route("/:func_id/code", put(save_code::save_code)) // only saves func code
-        .route("/:func_id/test_execute", post(test_execute::test_execute))
-        .route("/:func_id/execute", post(execute_func::execute_func))
+        .route(
+            "/",
+            post(create_func::create_func).layer(author_funcs.clone()),
+        )
+        // only save the func's metadata
+        .route(
+            "/:func_id",
+            put(update_func::update_func).layer(author_funcs.clone()),
+        )
+        // only saves func code
+        .route(
+            "/:func_id/code",
+            put(save_code::save_code).layer(author_funcs.clone()),
+        )
+        .route(
+            "/:func_id/test_execute",
+            post(test_execute::test_execute)
+                .layer(author_funcs.clone())
+                .layer(Extension(RequiredScope(SiJwtScope::FuncExecute))),
+        )
+        .route(
+            "/:func_id/execute",
+            post(execute_func::execute_func)
+                .layer(operate)
+                .layer(Extension(RequiredScope(SiJwtScope::FuncExecute))),
+        )
+        .route(
+            "/:func_id",
+            post(create_unlocked_copy::create_unlocked_copy).layer(author_funcs.clone()),
+        )
         .route(
             "/:func_id",
-            post(create_unlocked_copy::create_unlocked_copy),
+            delete(delete_func::delete_func).layer(author_funcs.clone()),
         )
-        .route("/:func_id", delete(delete_func::delete_func))
         // Func Bindings
         .route(
             "/:func_id/bindings",
-            post(binding::create_binding::create_binding),
+            post(binding::create_binding::create_binding).layer(author_funcs.clone()),
         )
         .route(
             "/:func_id/bindings",
-            delete(binding::delete_binding::delete_binding),
+            delete(binding::delete_binding::delete_binding).layer(author_funcs.clone()),
         )
         .route(
             "/:func_id/bindings",
-            put(binding::update_binding::update_binding),
+            put(binding::update_binding::update_binding).layer(author_funcs.clone()),
         )
         // Reset Attribute Bindings
         .route(
             "/:func_id/reset_attribute_binding",
-            post(binding::attribute::reset_attribute_binding::reset_attribute_binding),
+            post(binding::attribute::reset_attribute_binding::reset_attribute_binding)
+                .layer(author_funcs.clone()),
         )
         // Func Arguments
         .route(
             "/:func_id/arguments",
-            post(argument::create_argument::create_func_argument),
+            post(argument::create_argument::create_func_argument).layer(author_funcs.clone()),
         )
         .route(
             "/:func_id/arguments/:func_argument_id",
-            put(argument::update_argument::update_func_argument),
+            put(argument::update_argument::update_func_argument).layer(author_funcs.clone()),
         )
         .route(
             "/:func_id/arguments/:func_argument_id",
-            delete(argument::delete_argument::delete_func_argument),
+            delete(argument::delete_argument::delete_func_argument).layer(author_funcs),
         )
         .route(
             "/:func_id/generate_aws_function",
@@ -6,7 +6,7 @@ use axum::{
 use hyper::StatusCode;
 use thiserror::Error;
 
-use crate::{service::ApiError, AppState};
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
 
 pub mod get_integrations;
 pub mod update_integration;
@@ -32,11 +32,14 @@ impl IntoResponse for IntegrationsError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/:workspace_integration_id",
-            post(update_integration::update_integration),
+            post(update_integration::update_integration).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
         )
         .route("/", get(get_integrations::get_integration))
 }
@@ -7,7 +7,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use dal::{
     diagram::view::ViewId,
@@ -22,13 +22,15 @@ use dal::{
 };
 use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
+use si_jwt_public_key::SiJwtScope;
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use veritech_client::ManagementFuncStatus;
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, PosthogClient, RequiredScope},
+    middleware::WorkspacePermissionLayer,
     track,
 };
 
@@ -202,11 +204,16 @@ pub async fn run_prototype(
     ))
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/prototype/:prototypeId/:componentId/:viewId",
-            post(run_prototype),
+            post(run_prototype)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Operate,
+                ))
+                .layer(Extension(RequiredScope(SiJwtScope::FuncExecute))),
         )
         .route(
             "/prototype/:prototypeId/:componentId/latest",
@@ -215,6 +222,9 @@ pub fn v2_routes() -> Router<AppState> {
         .route("/history", get(history::history))
         .route(
             "/generate_template/:viewId",
-            post(generate_template::generate_template),
+            post(generate_template::generate_template).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::AuthorFuncs,
+            )),
         )
 }
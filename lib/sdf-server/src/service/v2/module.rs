@@ -9,7 +9,7 @@ use si_frontend_types as frontend_types;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::{service::ApiError, AppState};
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
 
 mod builtins;
 mod contribute;
@@ -60,11 +60,22 @@ impl IntoResponse for ModulesAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let author_funcs = WorkspacePermissionLayer::new(state, permissions::Permission::AuthorFuncs);
+
     Router::new()
-        .route("/contribute", post(contribute::contribute))
+        .route(
+            "/contribute",
+            post(contribute::contribute).layer(author_funcs.clone()),
+        )
         .route("/sync", get(sync::sync))
         .route("/", get(list::list))
-        .route("/:module_id/builtins/reject", post(builtins::reject))
-        .route("/:module_id/builtins/promote", post(builtins::promote))
+        .route(
+            "/:module_id/builtins/reject",
+            post(builtins::reject).layer(author_funcs.clone()),
+        )
+        .route(
+            "/:module_id/builtins/promote",
+            post(builtins::promote).layer(author_funcs),
+        )
 }
@@ -2,7 +2,7 @@ use axum::{
     extract::{Host, OriginalUri, Path},
     Json,
 };
-use dal::{module::ModuleId, ChangeSetId, HistoryActor, User, WorkspacePk};
+use dal::{module::ModuleId, ChangeSetId, User, WorkspacePk};
 use module_index_client::ModuleIndexClient;
 use serde::{Deserialize, Serialize};
 
@@ -37,9 +37,9 @@ pub async fn promote(
         None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
     };
 
-    let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
-        _ => None,
+    let user = match ctx.history_actor().user_pk() {
+        Some(user_pk) => User::get_by_pk(&ctx, user_pk).await?,
+        None => None,
     };
 
     let (_, created_by_email) = user
@@ -97,9 +97,9 @@ pub async fn reject(
         None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
     };
 
-    let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
-        _ => None,
+    let user = match ctx.history_actor().user_pk() {
+        Some(user_pk) => User::get_by_pk(&ctx, user_pk).await?,
+        None => None,
     };
 
     let (_, created_by_email) = user
@@ -0,0 +1,60 @@
+use axum::{
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Router,
+};
+use dal::schedule::{ScheduleError as DalScheduleError, ScheduledJobKind};
+use hyper::StatusCode;
+use thiserror::Error;
+
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+
+pub mod delete_schedule;
+pub mod list_schedules;
+pub mod set_schedule_enabled;
+pub mod upsert_schedule;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ScheduleError {
+    #[error("schedule error: {0}")]
+    Schedule(#[from] DalScheduleError),
+    #[error("no {0} schedule found")]
+    ScheduledJobNotFound(ScheduledJobKind),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+}
+
+pub type ScheduleResult<T> = Result<T, ScheduleError>;
+
+impl IntoResponse for ScheduleError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::Schedule(
+                DalScheduleError::InvalidSchedule(_, _) | DalScheduleError::ScheduleNeverFires(_),
+            ) => StatusCode::BAD_REQUEST,
+            Self::Schedule(DalScheduleError::ScheduledJobNotFound(_))
+            | Self::ScheduledJobNotFound(_) => StatusCode::NOT_FOUND,
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self).into_response()
+    }
+}
+
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let manage = WorkspacePermissionLayer::new(state, permissions::Permission::Manage);
+
+    Router::new()
+        .route("/", get(list_schedules::list_schedules))
+        .route(
+            "/:kind",
+            put(upsert_schedule::upsert_schedule)
+                .delete(delete_schedule::delete_schedule)
+                .layer(manage.clone()),
+        )
+        .route(
+            "/:kind/enabled",
+            post(set_schedule_enabled::set_schedule_enabled).layer(manage),
+        )
+}
@@ -0,0 +1,25 @@
+use axum::extract::Path;
+use dal::{
+    schedule::{ScheduledJob, ScheduledJobKind},
+    WorkspacePk,
+};
+
+use super::{ScheduleError, ScheduleResult};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+pub async fn delete_schedule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, kind)): Path<(WorkspacePk, ScheduledJobKind)>,
+) -> ScheduleResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ScheduledJob::find_by_kind(&ctx, kind)
+        .await?
+        .ok_or(ScheduleError::ScheduledJobNotFound(kind))?
+        .delete(&ctx)
+        .await?;
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
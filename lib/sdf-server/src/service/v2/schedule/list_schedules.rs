@@ -0,0 +1,23 @@
+use axum::Json;
+use dal::schedule::ScheduledJob;
+use serde::{Deserialize, Serialize};
+
+use super::ScheduleResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSchedulesResponse {
+    pub schedules: Vec<ScheduledJob>,
+}
+
+pub async fn list_schedules(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> ScheduleResult<Json<ListSchedulesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let schedules = ScheduledJob::list_for_workspace(&ctx).await?;
+
+    Ok(Json(ListSchedulesResponse { schedules }))
+}
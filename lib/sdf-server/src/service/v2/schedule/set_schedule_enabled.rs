@@ -0,0 +1,38 @@
+use axum::{extract::Path, Json};
+use dal::{
+    schedule::{ScheduledJob, ScheduledJobKind},
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{ScheduleError, ScheduleResult};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetScheduleEnabledRequest {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetScheduleEnabledResponse {
+    pub schedule: ScheduledJob,
+}
+
+pub async fn set_schedule_enabled(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, kind)): Path<(WorkspacePk, ScheduledJobKind)>,
+    Json(request): Json<SetScheduleEnabledRequest>,
+) -> ScheduleResult<Json<SetScheduleEnabledResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut schedule = ScheduledJob::find_by_kind(&ctx, kind)
+        .await?
+        .ok_or(ScheduleError::ScheduledJobNotFound(kind))?;
+    schedule.set_enabled(&ctx, request.enabled).await?;
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(SetScheduleEnabledResponse { schedule }))
+}
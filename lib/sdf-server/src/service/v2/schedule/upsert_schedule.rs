@@ -0,0 +1,36 @@
+use axum::{extract::Path, Json};
+use dal::{
+    schedule::{ScheduledJob, ScheduledJobKind},
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ScheduleResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertScheduleRequest {
+    /// A cron expression, such as `0 */6 * * *`.
+    pub schedule: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertScheduleResponse {
+    pub schedule: ScheduledJob,
+}
+
+pub async fn upsert_schedule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, kind)): Path<(WorkspacePk, ScheduledJobKind)>,
+    Json(request): Json<UpsertScheduleRequest>,
+) -> ScheduleResult<Json<UpsertScheduleResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let schedule = ScheduledJob::upsert(&ctx, kind, request.schedule).await?;
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(UpsertScheduleResponse { schedule }))
+}
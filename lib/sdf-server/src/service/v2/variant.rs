@@ -11,7 +11,7 @@ use dal::{
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::{service::ApiError, AppState};
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
 
 pub mod create_unlocked_copy;
 mod delete_unlocked_variant;
@@ -65,16 +65,18 @@ impl IntoResponse for SchemaVariantsAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let author_funcs = WorkspacePermissionLayer::new(state, permissions::Permission::AuthorFuncs);
+
     Router::new()
         .route("/", get(list_variants::list_variants))
         .route("/:schema_variant_id", get(get_variant::get_variant))
         .route(
             "/:schema_variant_id",
-            post(create_unlocked_copy::create_unlocked_copy),
+            post(create_unlocked_copy::create_unlocked_copy).layer(author_funcs.clone()),
         )
         .route(
             "/:schema_variant_id",
-            delete(delete_unlocked_variant::delete_unlocked_variant),
+            delete(delete_unlocked_variant::delete_unlocked_variant).layer(author_funcs),
         )
 }
@@ -1,6 +1,6 @@
 use std::num::ParseIntError;
 
-use crate::{app_state::AppState, service::ApiError};
+use crate::{app_state::AppState, middleware::WorkspacePermissionLayer, service::ApiError};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -31,6 +31,7 @@ mod paste_component;
 mod remove_view;
 mod set_component_parent;
 mod set_geometry;
+mod set_tags;
 pub mod update_view;
 
 #[remain::sorted]
@@ -109,18 +110,26 @@ impl IntoResponse for ViewError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let edit = WorkspacePermissionLayer::new(state, permissions::Permission::Edit);
+
     Router::new()
         // Func Stuff
         .route("/", get(list_views::list_views))
-        .route("/", post(create_view::create_view))
+        .route("/", post(create_view::create_view).layer(edit.clone()))
         .route(
             "/create_and_move",
-            post(create_view_and_move::create_view_and_move),
+            post(create_view_and_move::create_view_and_move).layer(edit.clone()),
         )
         .route(
             "/:view_id",
-            put(update_view::update_view).delete(remove_view::remove_view),
+            put(update_view::update_view)
+                .delete(remove_view::remove_view)
+                .layer(edit.clone()),
+        )
+        .route(
+            "/:view_id/tags",
+            put(set_tags::set_tags).layer(edit.clone()),
         )
         .route("/:view_id/get_diagram", get(get_diagram::get_diagram))
         .route("/:view_id/get_geometry", get(get_diagram::get_geometry))
@@ -130,34 +139,34 @@ pub fn v2_routes() -> Router<AppState> {
         )
         .route(
             "/:view_id/component",
-            post(create_component::create_component),
+            post(create_component::create_component).layer(edit.clone()),
         )
         .route(
             "/:view_id/paste_components",
-            post(paste_component::paste_component),
+            post(paste_component::paste_component).layer(edit.clone()),
         )
         .route(
             "/:view_id/erase_components",
-            delete(erase_components::erase_components),
+            delete(erase_components::erase_components).layer(edit.clone()),
         )
         .route(
             "/:view_id/component/set_geometry",
-            put(set_geometry::set_component_geometry),
+            put(set_geometry::set_component_geometry).layer(edit.clone()),
         )
         .route(
             "/:view_id/component/set_parent",
-            put(set_component_parent::set_component_parent),
+            put(set_component_parent::set_component_parent).layer(edit.clone()),
         )
         .route(
             "/:view_id/view_object",
-            post(create_view_object::create_view_object),
+            post(create_view_object::create_view_object).layer(edit.clone()),
         )
         .route(
             "/:view_id/view_object",
-            delete(erase_view_object::erase_view_object),
+            delete(erase_view_object::erase_view_object).layer(edit.clone()),
         )
         .route(
             "/:view_id/view_object/set_geometry",
-            put(set_geometry::set_view_object_geometry),
+            put(set_geometry::set_view_object_geometry).layer(edit),
         )
 }
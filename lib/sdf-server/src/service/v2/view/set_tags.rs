@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use crate::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::service::force_change_set_response::ForceChangeSetResponse;
+use crate::service::v2::view::ViewResult;
+use crate::tracking::track;
+use axum::extract::{Host, OriginalUri, Path};
+use axum::Json;
+use dal::diagram::view::{View, ViewId, ViewView};
+use dal::{ChangeSet, ChangeSetId, WorkspacePk, WsEvent};
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub tags: BTreeMap<String, String>,
+}
+
+pub async fn set_tags(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, view_id)): Path<(WorkspacePk, ChangeSetId, ViewId)>,
+    Json(Request { tags }): Json<Request>,
+) -> ViewResult<ForceChangeSetResponse<ViewView>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let mut view = View::get_by_id(&ctx, view_id).await?;
+    let old_tags = view.tags().to_owned();
+    view.set_tags(&ctx, tags).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "set_view_tags",
+        serde_json::json!({
+            "how": "/diagram/set_view_tags",
+            "view_id": view.id(),
+            "view_name": view.name(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+    ctx.write_audit_log(
+        AuditLogKind::UpdateViewTags {
+            view_id,
+            old_tags,
+            new_tags: view.tags().to_owned(),
+        },
+        view.name().to_owned(),
+    )
+    .await?;
+    let view_view = ViewView::from_view(&ctx, view).await?;
+
+    WsEvent::view_updated(&ctx, view_view.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(force_change_set_id, view_view))
+}
@@ -1,4 +1,4 @@
-use crate::{app_state::AppState, service::ApiError};
+use crate::{app_state::AppState, middleware::WorkspacePermissionLayer, service::ApiError};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -47,8 +47,20 @@ impl IntoResponse for WorkspaceAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/install", post(install_workspace::install_workspace))
-        .route("/export", post(export_workspace::export_workspace))
+        .route(
+            "/install",
+            post(install_workspace::install_workspace).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/export",
+            post(export_workspace::export_workspace).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
 }
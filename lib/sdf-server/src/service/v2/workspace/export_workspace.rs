@@ -4,7 +4,7 @@ use axum::{
     Json,
 };
 use chrono::Utc;
-use dal::{DalContext, HistoryActor, User, Workspace, WorkspacePk, WsEvent};
+use dal::{DalContext, User, Workspace, WorkspacePk, WsEvent};
 use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 use telemetry::prelude::info;
@@ -121,10 +121,10 @@ pub async fn export_workspace_inner(
 
     // Track
     {
-        let created_by = if let HistoryActor::User(user_pk) = ctx.history_actor() {
-            let user = User::get_by_pk(ctx, *user_pk)
+        let created_by = if let Some(user_pk) = ctx.history_actor().user_pk() {
+            let user = User::get_by_pk(ctx, user_pk)
                 .await?
-                .ok_or(WorkspaceAPIError::InvalidUser(*user_pk))?;
+                .ok_or(WorkspaceAPIError::InvalidUser(user_pk))?;
 
             user.email().clone()
         } else {
@@ -12,7 +12,7 @@ use si_pkg::{SiPkgError, SpecError};
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::AppState;
+use crate::{middleware::WorkspacePermissionLayer, AppState};
 
 use super::ApiError;
 
@@ -104,13 +104,24 @@ impl IntoResponse for SchemaVariantError {
     }
 }
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
+    let author_funcs = WorkspacePermissionLayer::new(state, permissions::Permission::AuthorFuncs);
+
     Router::new()
-        .route("/create_variant", post(create_variant::create_variant))
+        .route(
+            "/create_variant",
+            post(create_variant::create_variant).layer(author_funcs.clone()),
+        )
         .route(
             "/regenerate_variant",
-            post(regenerate_variant::regenerate_variant),
+            post(regenerate_variant::regenerate_variant).layer(author_funcs.clone()),
+        )
+        .route(
+            "/clone_variant",
+            post(clone_variant::clone_variant).layer(author_funcs.clone()),
+        )
+        .route(
+            "/save_variant",
+            post(save_variant::save_variant).layer(author_funcs),
         )
-        .route("/clone_variant", post(clone_variant::clone_variant))
-        .route("/save_variant", post(save_variant::save_variant))
 }
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use axum::{http::Method, Router};
+use dal::{
+    AttributeValue, AttributeValueId, ChangeSetId, Component, ComponentId, DalContext, Visibility,
+};
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    sdf_test, AuthTokenRef, DalContextHead,
+};
+use sdf_server::service::component::conflicts_for_component::{
+    ConflictsForComponentRequest, ConflictsForComponentResponse,
+};
+use si_events::audit_log::AuditLogKind;
+use si_frontend_types::{ConflictSide, ConflictWithHead};
+use tokio::time::Instant;
+
+use crate::service_tests::api_request_auth_json_body;
+
+const AUDIT_LOG_TIMEOUT_SECONDS: u64 = 5;
+const AUDIT_LOG_INTERVAL_MILLISECONDS: u64 = 100;
+
+#[sdf_test]
+async fn conflicts_show_both_sides_and_their_last_writers(
+    DalContextHead(mut ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    ChangeSetTestHelpers::fork_from_head_change_set(&mut ctx)
+        .await
+        .expect("could not fork change set");
+    let component_id =
+        create_component_for_default_schema_name_in_default_view(&ctx, "swifty", "conflicted")
+            .await
+            .expect("could not create component")
+            .id();
+    ChangeSetTestHelpers::apply_change_set_to_base(&mut ctx)
+        .await
+        .expect("could not apply change set");
+
+    // Both change sets set the same attribute value, and only one of them is applied
+    let open_change_set =
+        ChangeSetTestHelpers::fork_from_head_change_set_with_name(&mut ctx, "Open change set")
+            .await
+            .expect("could not fork change set");
+    let attribute_value_id = set_name(&mut ctx, component_id, "from the change set").await;
+
+    let applied_change_set = ChangeSetTestHelpers::fork_from_head_change_set(&mut ctx)
+        .await
+        .expect("could not fork change set");
+    set_name(&mut ctx, component_id, "from head").await;
+    ChangeSetTestHelpers::apply_change_set_to_base(&mut ctx)
+        .await
+        .expect("could not apply change set");
+
+    // Audit logs are written to the database in the background, so wait for both last writers
+    let request = ConflictsForComponentRequest {
+        component_id,
+        visibility: Visibility::new(open_change_set.id),
+    };
+    let start = Instant::now();
+    let conflict = loop {
+        let mut response: ConflictsForComponentResponse = api_request_auth_json_body(
+            app.clone(),
+            Method::GET,
+            "/api/component/conflicts",
+            auth_token,
+            &request,
+        )
+        .await;
+        let conflict = response
+            .remove(&attribute_value_id)
+            .expect("no conflict for the attribute value");
+        if conflict.change_set.last_writer.is_some() && conflict.head.last_writer.is_some() {
+            break conflict;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(AUDIT_LOG_TIMEOUT_SECONDS),
+            "hit timeout before both last writers were audit logged"
+        );
+        tokio::time::sleep(Duration::from_millis(AUDIT_LOG_INTERVAL_MILLISECONDS)).await;
+    };
+
+    assert_eq!(
+        ConflictWithHead::ModifiedOnBoth {
+            modified_av_id: attribute_value_id
+        },
+        conflict.conflict
+    );
+    assert_eq!(
+        Some(serde_json::json!("from the change set")),
+        conflict.change_set.value
+    );
+    assert_eq!(Some(serde_json::json!("from head")), conflict.head.value);
+    assert_eq!(
+        Some(open_change_set.id),
+        last_writer_change_set_id(&conflict.change_set)
+    );
+    assert_eq!(
+        Some(applied_change_set.id),
+        last_writer_change_set_id(&conflict.head)
+    );
+}
+
+fn last_writer_change_set_id(side: &ConflictSide) -> Option<ChangeSetId> {
+    side.last_writer
+        .as_ref()
+        .and_then(|last_writer| last_writer.change_set_id)
+}
+
+/// Sets "/root/domain/name" and commits, mimicking sdf by audit logging the update.
+async fn set_name(ctx: &mut DalContext, component_id: ComponentId, name: &str) -> AttributeValueId {
+    let component = Component::get_by_id(ctx, component_id)
+        .await
+        .expect("could not get component");
+    let schema_variant = component
+        .schema_variant(ctx)
+        .await
+        .expect("could not get schema variant");
+    let attribute_value_id = component
+        .attribute_values_for_prop(ctx, &["root", "domain", "name"])
+        .await
+        .expect("could not get attribute values for prop")
+        .pop()
+        .expect("no attribute value for prop");
+    let prop_id = AttributeValue::prop_id(ctx, attribute_value_id)
+        .await
+        .expect("could not get prop id");
+
+    let before_value = AttributeValue::get_by_id(ctx, attribute_value_id)
+        .await
+        .expect("could not get attribute value")
+        .view(ctx)
+        .await
+        .expect("could not view attribute value");
+    AttributeValue::update(ctx, attribute_value_id, Some(serde_json::json!(name)))
+        .await
+        .expect("could not update attribute value");
+    let component_name = component.name(ctx).await.expect("could not get name");
+    ctx.write_audit_log(
+        AuditLogKind::UpdatePropertyEditorValue {
+            component_id,
+            component_name: component_name.clone(),
+            schema_variant_id: schema_variant.id(),
+            schema_variant_display_name: schema_variant.display_name().to_string(),
+            prop_id,
+            prop_name: "name".to_string(),
+            attribute_value_id,
+            before_value,
+            after_value: Some(serde_json::json!(name)),
+        },
+        component_name,
+    )
+    .await
+    .expect("could not write audit log");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    attribute_value_id
+}
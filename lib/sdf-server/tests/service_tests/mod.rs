@@ -6,7 +6,9 @@ use axum::{
 use serde::{de::DeserializeOwned, Serialize};
 use tower::ServiceExt;
 
+mod conflicts;
 mod crdt;
+mod permissions;
 mod session;
 
 pub async fn api_request_auth_empty<Res: DeserializeOwned>(
@@ -38,6 +40,40 @@ pub async fn api_request_auth_empty<Res: DeserializeOwned>(
     serde_json::from_value(body_json).expect("response is not a valid rust struct")
 }
 
+pub async fn api_request_auth_json_body<Req: Serialize, Res: DeserializeOwned>(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: impl AsRef<str>,
+    request: &Req,
+) -> Res {
+    let auth_token = auth_token.as_ref();
+    let uri = uri.as_ref();
+    let api_request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"));
+
+    let api_request = api_request
+        .body(Body::from(
+            serde_json::to_vec(&serde_json::json!(&request)).expect("cannot turn request to json"),
+        ))
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    let body_json: serde_json::Value =
+        serde_json::from_slice(&body).expect("response is not valid json");
+    if status != StatusCode::OK {
+        dbg!(&body_json);
+        assert_eq!(status, StatusCode::OK);
+    }
+    serde_json::from_value(body_json).expect("response is not a valid rust struct")
+}
+
 #[allow(dead_code)]
 pub async fn api_request_auth_no_response<Req: Serialize>(
     app: Router,
@@ -0,0 +1,137 @@
+use axum::{
+    body::Body,
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::UserPk;
+use dal_test::{sdf_test, AuthTokenRef, DalContextHead, WorkspaceSignup};
+use permissions::{InMemoryPermissions, Relation, RelationBuilder};
+use sdf_server::service::change_set::create_change_set::CreateChangeSetRequest;
+use tower::ServiceExt;
+
+/// Sends a request and returns only its status, as denied requests don't have the route's response.
+async fn request_status(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: impl AsRef<str>,
+    body: Option<serde_json::Value>,
+) -> StatusCode {
+    let body = match body {
+        Some(body) => Body::from(serde_json::to_vec(&body).expect("cannot serialize body")),
+        None => Body::empty(),
+    };
+    let request = Request::builder()
+        .method(method)
+        .uri(uri.as_ref())
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(
+            http::header::AUTHORIZATION,
+            format!("Bearer {}", auth_token.as_ref()),
+        )
+        .body(body)
+        .expect("cannot create api request");
+
+    app.oneshot(request)
+        .await
+        .expect("cannot send request")
+        .status()
+}
+
+async fn list_change_sets(app: Router, auth_token: &str, nw: &WorkspaceSignup) -> StatusCode {
+    request_status(
+        app,
+        Method::GET,
+        format!("/api/v2/workspaces/{}/change-sets", nw.workspace.pk()),
+        auth_token,
+        None,
+    )
+    .await
+}
+
+async fn create_change_set(app: Router, auth_token: &str) -> StatusCode {
+    let request = CreateChangeSetRequest {
+        change_set_name: "mastodon".to_string(),
+    };
+    request_status(
+        app,
+        Method::POST,
+        "/api/change_set/create_change_set",
+        auth_token,
+        Some(serde_json::to_value(request).expect("cannot serialize request")),
+    )
+    .await
+}
+
+async fn grant(
+    permissions: &mut InMemoryPermissions,
+    nw: &WorkspaceSignup,
+    relation: Relation,
+    user_id: UserPk,
+) {
+    RelationBuilder::new()
+        .workspace_object(*nw.workspace.pk())
+        .relation(relation)
+        .user_subject(user_id)
+        .create(permissions)
+        .await
+        .expect("could not create relation");
+}
+
+#[sdf_test]
+async fn members_are_allowed_until_roles_are_synced(
+    DalContextHead(ctx): DalContextHead,
+    _permissions: InMemoryPermissions,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: &WorkspaceSignup,
+) {
+    ctx.commit().await.expect("failed to commit");
+
+    assert_eq!(
+        StatusCode::OK,
+        list_change_sets(app.clone(), auth_token, nw).await
+    );
+    assert_eq!(StatusCode::OK, create_change_set(app, auth_token).await);
+}
+
+#[sdf_test]
+async fn viewers_can_read_but_not_write(
+    DalContextHead(ctx): DalContextHead,
+    mut permissions: InMemoryPermissions,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: &WorkspaceSignup,
+) {
+    ctx.commit().await.expect("failed to commit");
+    grant(&mut permissions, nw, Relation::Viewer, nw.user.pk()).await;
+
+    assert_eq!(
+        StatusCode::OK,
+        list_change_sets(app.clone(), auth_token, nw).await
+    );
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        create_change_set(app.clone(), auth_token).await
+    );
+
+    grant(&mut permissions, nw, Relation::Editor, nw.user.pk()).await;
+    assert_eq!(StatusCode::OK, create_change_set(app, auth_token).await);
+}
+
+#[sdf_test]
+async fn members_without_a_role_cannot_read(
+    DalContextHead(ctx): DalContextHead,
+    mut permissions: InMemoryPermissions,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: &WorkspaceSignup,
+) {
+    ctx.commit().await.expect("failed to commit");
+    grant(&mut permissions, nw, Relation::Editor, UserPk::generate()).await;
+
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        list_change_sets(app, auth_token, nw).await
+    );
+}
@@ -248,6 +248,18 @@ impl Permission {
         i32::from(v1::check_permission_response::Permissionship::HasPermission) == permissionship
     }
 
+    pub fn resource(&self) -> &SpiceDBObject {
+        &self.resource
+    }
+
+    pub fn permission(&self) -> &str {
+        &self.permission
+    }
+
+    pub fn subject(&self) -> &SpiceDBObject {
+        &self.subject
+    }
+
     pub fn set_zed_token(&mut self, zed_token: Option<ZedToken>) {
         self.zed_token = zed_token;
     }
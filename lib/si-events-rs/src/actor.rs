@@ -7,4 +7,6 @@ pub use si_id::UserPk;
 pub enum Actor {
     System,
     User(UserPk),
+    /// A user acting through an automation token, rather than through the web app
+    Automation(UserPk),
 }
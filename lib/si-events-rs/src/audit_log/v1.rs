@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use si_id::ManagementPrototypeId;
 use strum::{Display, EnumDiscriminants};
@@ -277,6 +279,11 @@ pub enum AuditLogKindV1 {
         new_parent_id: ComponentId,
         new_parent_name: String,
     },
+    UpdateComponentTags {
+        component_id: ComponentId,
+        old_tags: BTreeMap<String, String>,
+        new_tags: BTreeMap<String, String>,
+    },
     UpdateDependentInputSocket {
         input_socket_id: InputSocketId,
         input_socket_name: String,
@@ -376,6 +383,11 @@ pub enum AuditLogKindV1 {
         view_id: ViewId,
         old_name: String,
     },
+    UpdateViewTags {
+        view_id: ViewId,
+        old_tags: BTreeMap<String, String>,
+        new_tags: BTreeMap<String, String>,
+    },
     UpgradeComponent {
         name: String,
         component_id: ComponentId,
@@ -676,6 +688,12 @@ pub enum AuditLogMetadataV1 {
         new_parent_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    UpdateComponentTags {
+        component_id: ComponentId,
+        old_tags: BTreeMap<String, String>,
+        new_tags: BTreeMap<String, String>,
+    },
+    #[serde(rename_all = "camelCase")]
     UpdateDependentInputSocket {
         input_socket_id: InputSocketId,
         input_socket_name: String,
@@ -778,6 +796,12 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     UpdateView { view_id: ViewId, old_name: String },
     #[serde(rename_all = "camelCase")]
+    UpdateViewTags {
+        view_id: ViewId,
+        old_tags: BTreeMap<String, String>,
+        new_tags: BTreeMap<String, String>,
+    },
+    #[serde(rename_all = "camelCase")]
     UpgradeComponent {
         name: String,
         component_id: ComponentId,
@@ -850,6 +874,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::UnlockFunc => ("Unlocked", Some("Function")),
             MetadataDiscrim::UnlockSchemaVariant => ("Unlocked", Some("Schema Variant")),
             MetadataDiscrim::UpdateComponentParent => ("Updated Parent", Some("Component")),
+            MetadataDiscrim::UpdateComponentTags => ("Updated Tags", Some("Component")),
             MetadataDiscrim::UpdateDependentInputSocket => ("Set Dependent", Some("Input Socket")),
             MetadataDiscrim::UpdateDependentOutputSocket => {
                 ("Set Dependent", Some("Output Socket"))
@@ -863,6 +888,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::UpdateSecret => ("Updated", Some("Secret")),
             MetadataDiscrim::UpdateSchemaVariant => ("Updated", Some("Schema Variant")),
             MetadataDiscrim::UpdateView => ("Updated", Some("View")),
+            MetadataDiscrim::UpdateViewTags => ("Updated Tags", Some("View")),
             MetadataDiscrim::UpgradeComponent => ("Upgraded", Some("Component")),
             MetadataDiscrim::WithdrawRequestForChangeSetApply => {
                 ("Withdrew Request to Apply", Some("Change Set"))
@@ -1274,6 +1300,15 @@ impl From<Kind> for Metadata {
                 new_parent_id,
                 new_parent_name,
             },
+            Kind::UpdateComponentTags {
+                component_id,
+                old_tags,
+                new_tags,
+            } => Self::UpdateComponentTags {
+                component_id,
+                old_tags,
+                new_tags,
+            },
             Kind::UpdateDependentInputSocket {
                 input_socket_id,
                 input_socket_name,
@@ -1449,6 +1484,15 @@ impl From<Kind> for Metadata {
             },
             Kind::UpdateSecret { name, secret_id } => Self::UpdateSecret { name, secret_id },
             Kind::UpdateView { view_id, old_name } => Self::UpdateView { view_id, old_name },
+            Kind::UpdateViewTags {
+                view_id,
+                old_tags,
+                new_tags,
+            } => Self::UpdateViewTags {
+                view_id,
+                old_tags,
+                new_tags,
+            },
             Kind::UpgradeComponent {
                 name,
                 component_id,
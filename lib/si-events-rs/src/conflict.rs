@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ulid::Ulid;
+
+/// A change made to both a change set and its base change set since the change set's merge base
+/// (the snapshot both last had in common). Applying either change over the other would lose
+/// work, so the conflict has to be resolved before the change set can be applied.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Conflict {
+    /// An edge of a kind that a node can only have one of was pointed at different nodes.
+    #[serde(rename_all = "camelCase")]
+    ExclusiveEdge {
+        source_id: Ulid,
+        edge_kind: String,
+        base_target_id: Ulid,
+        change_set_target_id: Ulid,
+    },
+    /// The node was modified differently in the change set and in the base change set.
+    #[serde(rename_all = "camelCase")]
+    ModifiedOnBoth { node_id: Ulid },
+    /// The node was modified in the change set, but removed from the base change set.
+    #[serde(rename_all = "camelCase")]
+    ModifiedWhatBaseRemoved { node_id: Ulid },
+    /// The node was removed from the change set, but modified in the base change set.
+    #[serde(rename_all = "camelCase")]
+    RemovedWhatBaseModified { node_id: Ulid },
+}
+
+impl Conflict {
+    /// The id of the node the conflict is about, which for edges is the node they leave from.
+    pub fn node_id(&self) -> Ulid {
+        match self {
+            Self::ExclusiveEdge { source_id, .. } => *source_id,
+            Self::ModifiedOnBoth { node_id }
+            | Self::ModifiedWhatBaseRemoved { node_id }
+            | Self::RemovedWhatBaseModified { node_id } => *node_id,
+        }
+    }
+}
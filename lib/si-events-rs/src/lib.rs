@@ -11,6 +11,7 @@ pub use si_id::ulid;
 mod actor;
 mod cas;
 mod change_set_status;
+mod conflict;
 mod event_session;
 mod func;
 mod func_execution;
@@ -31,6 +32,7 @@ pub use crate::{
     actor::UserPk,
     cas::CasValue,
     change_set_status::ChangeSetStatus,
+    conflict::Conflict,
     content_hash::ContentHash,
     encrypted_secret::EncryptedSecretKey,
     event_session::EventSessionId,
@@ -8,6 +8,7 @@ pub struct AuditLog {
     pub user_id: Option<UserPk>,
     pub user_email: Option<String>,
     pub user_name: Option<String>,
+    pub automation: bool,
     pub kind: String,
     pub entity_type: String,
     pub entity_name: String,
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use si_events::{ComponentId, SchemaId, SchemaVariantId, ViewId};
-use std::num::ParseIntError;
+use std::{collections::BTreeMap, num::ParseIntError};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 
 #[remain::sorted]
@@ -161,4 +161,6 @@ pub struct DiagramComponentView {
     pub can_be_upgraded: bool,
     pub from_base_change_set: bool,
     pub view_data: Option<GeometryAndView>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
@@ -1,5 +1,6 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use si_events::AttributeValueId;
+use si_events::{AttributeValueId, ChangeSetId, UserPk};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 
 #[remain::sorted]
@@ -18,6 +19,8 @@ use strum::{AsRefStr, Display, EnumIter, EnumString};
 )]
 #[serde(rename_all = "camelCase", tag = "bindingKind")]
 pub enum ConflictWithHead {
+    #[serde(rename_all = "camelCase")]
+    ModifiedOnBoth { modified_av_id: AttributeValueId },
     #[serde(rename_all = "camelCase")]
     ModifiedWhatHeadRemoved { modified_av_id: AttributeValueId },
     #[serde(rename_all = "camelCase")]
@@ -25,3 +28,32 @@ pub enum ConflictWithHead {
     #[serde(rename_all = "camelCase")]
     Untreated { raw: String },
 }
+
+/// A [`ConflictWithHead`] shown on an attribute value, along with what each side holds so that
+/// one of them can be picked.
+#[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueConflict {
+    pub conflict: ConflictWithHead,
+    pub change_set: ConflictSide,
+    pub head: ConflictSide,
+}
+
+/// One side of an [`AttributeValueConflict`].
+#[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictSide {
+    /// The value of the attribute value on this side, or none if this side removed it.
+    pub value: Option<serde_json::Value>,
+    /// The last change to the attribute value on this side, if one was audit logged.
+    pub last_writer: Option<ConflictLastWriter>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictLastWriter {
+    /// The user who made the change, or none for the system user.
+    pub user_id: Option<UserPk>,
+    pub change_set_id: Option<ChangeSetId>,
+    pub timestamp: DateTime<Utc>,
+}
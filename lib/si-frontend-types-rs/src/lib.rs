@@ -14,7 +14,9 @@ pub use crate::component::{
     DiagramSocketDirection, DiagramSocketNodeSide, GeometryAndView, GridPoint, RawGeometry, Size2D,
     StringGeometry,
 };
-pub use crate::conflict::ConflictWithHead;
+pub use crate::conflict::{
+    AttributeValueConflict, ConflictLastWriter, ConflictSide, ConflictWithHead,
+};
 pub use crate::func::{
     AttributeArgumentBinding, FuncArgument, FuncArgumentKind, FuncBinding, FuncBindings, FuncCode,
     FuncSummary, LeafInputLocation,
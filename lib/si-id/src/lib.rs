@@ -64,11 +64,13 @@ id!(WorkspaceSnapshotNodeId);
 
 // Please keep these alphabetically sorted!
 id_with_pg_types!(ActionId);
+id_with_pg_types!(ApprovalPolicyId);
 id_with_pg_types!(CachedModuleId);
 id_with_pg_types!(ChangeSetId);
 id_with_pg_types!(ComponentId);
 id_with_pg_types!(FuncId);
 id_with_pg_types!(FuncRunId);
+id_with_pg_types!(ScheduledJobId);
 id_with_pg_types!(UserPk);
 id_with_pg_types!(WorkspaceIntegrationId);
 
@@ -1,5 +1,5 @@
 use core::str;
-use si_events::{UserPk, WorkspacePk};
+use si_events::{ChangeSetId, UserPk, ViewId, WorkspacePk};
 use si_std::CanonicalFile;
 use std::sync::Arc;
 
@@ -86,6 +86,18 @@ pub enum SiJwtClaimRole {
     Automation,
 }
 
+/** What an automation token may be used for. Web tokens can be used for anything */
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SiJwtScope {
+    ChangeSetApply,
+    ChangeSetCreate,
+    FuncExecute,
+    /// Requests which don't change anything
+    Read,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum SiJwtClaims {
@@ -101,6 +113,12 @@ pub struct SiJwtClaimsV2 {
     user_id: UserPk,
     workspace_id: WorkspacePk,
     role: SiJwtClaimRole,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    scopes: Vec<SiJwtScope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    change_set_id: Option<ChangeSetId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    view_id: Option<ViewId>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -125,12 +143,43 @@ impl SiJwtClaims {
         }
     }
 
-    pub fn authorized_for(&self, required_role: SiJwtClaimRole) -> bool {
-        let role = match self {
+    pub fn role(&self) -> SiJwtClaimRole {
+        match self {
             Self::V2(SiJwtClaimsV2 { role, .. }) => *role,
             Self::V1(SiJwtClaimsV1 { .. }) => SiJwtClaimRole::Web,
-        };
-        role == required_role
+        }
+    }
+
+    pub fn authorized_for(&self, required_role: SiJwtClaimRole) -> bool {
+        self.role() == required_role
+    }
+
+    /// Whether the token may be used for the given scope. Only automation tokens are scoped.
+    pub fn has_scope(&self, scope: SiJwtScope) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 {
+                role: SiJwtClaimRole::Automation,
+                scopes,
+                ..
+            }) => scopes.contains(&scope),
+            _ => true,
+        }
+    }
+
+    /// The only change set the token may be used with, if it is restricted to one.
+    pub fn restricted_to_change_set(&self) -> Option<ChangeSetId> {
+        match self {
+            Self::V2(SiJwtClaimsV2 { change_set_id, .. }) => *change_set_id,
+            Self::V1(SiJwtClaimsV1 { .. }) => None,
+        }
+    }
+
+    /// The only view the token may be used with, if it is restricted to one.
+    pub fn restricted_to_view(&self) -> Option<ViewId> {
+        match self {
+            Self::V2(SiJwtClaimsV2 { view_id, .. }) => *view_id,
+            Self::V1(SiJwtClaimsV1 { .. }) => None,
+        }
     }
 
     pub fn for_web(user_id: UserPk, workspace_id: WorkspacePk) -> Self {
@@ -139,6 +188,27 @@ impl SiJwtClaims {
             user_id,
             workspace_id,
             role: SiJwtClaimRole::Web,
+            scopes: vec![],
+            change_set_id: None,
+            view_id: None,
+        })
+    }
+
+    pub fn for_automation(
+        user_id: UserPk,
+        workspace_id: WorkspacePk,
+        scopes: Vec<SiJwtScope>,
+        change_set_id: Option<ChangeSetId>,
+        view_id: Option<ViewId>,
+    ) -> Self {
+        Self::V2(SiJwtClaimsV2 {
+            version: MustBe!("2"),
+            user_id,
+            workspace_id,
+            role: SiJwtClaimRole::Automation,
+            scopes,
+            change_set_id,
+            view_id,
         })
     }
 
@@ -269,15 +339,51 @@ mod tests {
                 user_pk: UserPk::generate(),
                 workspace_pk: WorkspacePk::generate(),
             }),
-            SiJwtClaims::V2(SiJwtClaimsV2 {
-                version: MustBe!("2"),
-                user_id: UserPk::generate(),
-                workspace_id: WorkspacePk::generate(),
-                role: SiJwtClaimRole::Web,
-            }),
+            SiJwtClaims::for_web(UserPk::generate(), WorkspacePk::generate()),
+            SiJwtClaims::for_automation(
+                UserPk::generate(),
+                WorkspacePk::generate(),
+                vec![SiJwtScope::Read, SiJwtScope::ChangeSetApply],
+                Some(ChangeSetId::new()),
+                None,
+            ),
         ]
     }
 
+    #[test]
+    fn automation_scopes() {
+        let web = SiJwtClaims::for_web(UserPk::generate(), WorkspacePk::generate());
+        let automation = SiJwtClaims::for_automation(
+            UserPk::generate(),
+            WorkspacePk::generate(),
+            vec![SiJwtScope::Read],
+            None,
+            None,
+        );
+
+        assert!(web.has_scope(SiJwtScope::FuncExecute));
+        assert!(automation.has_scope(SiJwtScope::Read));
+        assert!(!automation.has_scope(SiJwtScope::FuncExecute));
+        assert!(automation.authorized_for(SiJwtClaimRole::Automation));
+        assert!(!automation.authorized_for(SiJwtClaimRole::Web));
+    }
+
+    #[test]
+    fn v2_claims_without_scopes_deserialize() {
+        let user_id = UserPk::generate();
+        let workspace_id = WorkspacePk::generate();
+        let claims: SiJwtClaims = serde_json::from_value(serde_json::json!({
+            "version": "2",
+            "userId": user_id,
+            "workspaceId": workspace_id,
+            "role": "web",
+        }))
+        .expect("deserialize claims");
+
+        assert_eq!(SiJwtClaims::for_web(user_id, workspace_id), claims);
+        assert_eq!(None, claims.restricted_to_change_set());
+    }
+
     #[tokio::test]
     async fn validate_with_primary_rs256() {
         for si_claim in v1_and_v2_claims() {
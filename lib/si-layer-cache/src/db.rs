@@ -14,8 +14,10 @@ use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use ulid::Ulid;
 
 use crate::db::encrypted_secret::EncryptedSecretDb;
+use crate::db::func_result::FuncResultDb;
 use crate::db::func_run::FuncRunDb;
 use crate::db::func_run_log::FuncRunLogDb;
+use crate::gc::{self, GarbageCollectionConfig, GarbageCollectionMarks, GarbageCollectionReport};
 use crate::hybrid_cache::CacheConfig;
 use crate::{
     activity_client::ActivityClient,
@@ -32,6 +34,7 @@ use self::{
 mod cache_updates;
 pub mod cas;
 pub mod encrypted_secret;
+pub mod func_result;
 pub mod func_run;
 pub mod func_run_log;
 pub mod rebase_batch;
@@ -48,6 +51,7 @@ where
 {
     cas: CasDb<CasValue>,
     encrypted_secret: EncryptedSecretDb<EncryptedSecretValue>,
+    func_result: FuncResultDb,
     func_run: FuncRunDb,
     func_run_log: FuncRunLogDb,
     rebase_batch: RebaseBatchDb<RebaseBatchValue>,
@@ -104,6 +108,7 @@ where
         let (
             cas_cache,
             encrypted_secret_cache,
+            func_result_cache,
             func_run_cache,
             func_run_log_cache,
             rebase_batch_cache,
@@ -129,6 +134,18 @@ where
                 5,
                 5
             ),
+            // Memoized func results are small and share the func run logs' portion, which are
+            // rarely read back once written
+            create_layer_cache(
+                func_result::CACHE_NAME,
+                pg_pool.clone(),
+                cache_config.clone(),
+                compute_executor.clone(),
+                tracker.clone(),
+                token.clone(),
+                2,
+                2
+            ),
             create_layer_cache(
                 func_run::CACHE_NAME,
                 pg_pool.clone(),
@@ -146,8 +163,8 @@ where
                 compute_executor.clone(),
                 tracker.clone(),
                 token.clone(),
-                5,
-                5
+                3,
+                3
             ),
             create_layer_cache(
                 rebase_batch::CACHE_NAME,
@@ -176,6 +193,7 @@ where
             &nats_client,
             cas_cache.clone(),
             encrypted_secret_cache.clone(),
+            func_result_cache.clone(),
             func_run_cache.clone(),
             func_run_log_cache.clone(),
             rebase_batch_cache.clone(),
@@ -198,6 +216,7 @@ where
         let cas = CasDb::new(cas_cache, persister_client.clone());
         let encrypted_secret =
             EncryptedSecretDb::new(encrypted_secret_cache, persister_client.clone());
+        let func_result = FuncResultDb::new(func_result_cache, persister_client.clone());
         let func_run = FuncRunDb::new(func_run_cache, persister_client.clone());
         let func_run_log = FuncRunLogDb::new(func_run_log_cache, persister_client.clone());
         let workspace_snapshot = WorkspaceSnapshotDb::new(snapshot_cache, persister_client.clone());
@@ -210,6 +229,7 @@ where
             activity,
             cas,
             encrypted_secret,
+            func_result,
             func_run,
             func_run_log,
             workspace_snapshot,
@@ -243,6 +263,10 @@ where
         &self.encrypted_secret
     }
 
+    pub fn func_result(&self) -> &FuncResultDb {
+        &self.func_result
+    }
+
     pub fn func_run(&self) -> &FuncRunDb {
         &self.func_run
     }
@@ -267,6 +291,64 @@ where
         &self.activity
     }
 
+    /// Sweeps every workspace snapshot and CAS row that is not in the marks, along with every
+    /// rebase batch and memoized func result, as long as it was created before the grace period. Content referenced by
+    /// func runs is marked here, since the layer db can read those itself.
+    ///
+    /// See the [`gc`](crate::gc) module for the division of labor with the caller.
+    #[instrument(
+        name = "layer_db.collect_garbage",
+        level = "info",
+        skip_all,
+        fields(
+            si.layer_cache.gc.dry_run = config.dry_run,
+        )
+    )]
+    pub async fn collect_garbage(
+        &self,
+        mut marks: GarbageCollectionMarks,
+        config: GarbageCollectionConfig,
+    ) -> LayerDbResult<GarbageCollectionReport> {
+        let cutoff = marks.cutoff(config.grace_period)?;
+        marks.mark_content(
+            self.func_run
+                .content_hashes_in_use(config.batch_size)
+                .await?,
+        );
+
+        let mut report = GarbageCollectionReport::new(&config, cutoff);
+        report.marked_snapshots = marks.snapshot_count();
+        report.marked_content = marks.content_count();
+
+        report.workspace_snapshots = gc::sweep(
+            &self.workspace_snapshot.cache,
+            cutoff,
+            Some(&marks.snapshot_keys()),
+            &config,
+        )
+        .await?;
+        report.cas = gc::sweep(
+            &self.cas.cache,
+            cutoff,
+            Some(&marks.content_keys()),
+            &config,
+        )
+        .await?;
+        report.rebase_batches = gc::sweep(&self.rebase_batch.cache, cutoff, None, &config).await?;
+        report.func_results = gc::sweep(&self.func_result.cache, cutoff, None, &config).await?;
+
+        info!(
+            dry_run = config.dry_run,
+            swept_snapshots = report.workspace_snapshots.swept_keys.len(),
+            swept_content = report.cas.swept_keys.len(),
+            swept_rebase_batches = report.rebase_batches.swept_keys.len(),
+            swept_func_results = report.func_results.swept_keys.len(),
+            "layer db garbage collection complete",
+        );
+
+        Ok(report)
+    }
+
     /// Run all migrations
     pub async fn pg_migrate(&self) -> LayerDbResult<()> {
         // This will do all migrations, not just "cas" migrations. We might want
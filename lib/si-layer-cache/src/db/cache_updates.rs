@@ -10,6 +10,7 @@ use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use ulid::Ulid;
 
 use crate::{
+    db::func_result::FuncResult,
     error::LayerDbResult,
     event::{LayeredEvent, LayeredEventServer},
     layer_cache::LayerCache,
@@ -21,6 +22,7 @@ use crate::{
 enum CacheName {
     Cas,
     EncryptedSecret,
+    FuncResult,
     FuncRun,
     FuncRunLog,
     WorkspaceSnapshots,
@@ -39,6 +41,7 @@ pub struct CacheUpdatesTask<
 {
     cas_cache: Arc<LayerCache<Arc<CasValue>>>,
     encrypted_secret_cache: Arc<LayerCache<Arc<EncryptedSecretValue>>>,
+    func_result_cache: Arc<LayerCache<Arc<FuncResult>>>,
     func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
@@ -64,6 +67,7 @@ where
         nats_client: &NatsClient,
         cas_cache: Arc<LayerCache<Arc<CasValue>>>,
         encrypted_secret_cache: Arc<LayerCache<Arc<EncryptedSecretValue>>>,
+        func_result_cache: Arc<LayerCache<Arc<FuncResult>>>,
         func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
         rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
@@ -80,6 +84,7 @@ where
         Ok(Self {
             cas_cache,
             encrypted_secret_cache,
+            func_result_cache,
             func_run_cache,
             func_run_log_cache,
             rebase_batch_cache,
@@ -109,6 +114,7 @@ where
             let cache_update_task = CacheUpdateTask::new(
                 self.cas_cache.clone(),
                 self.encrypted_secret_cache.clone(),
+                self.func_result_cache.clone(),
                 self.func_run_cache.clone(),
                 self.func_run_log_cache.clone(),
                 self.snapshot_cache.clone(),
@@ -129,6 +135,7 @@ where
 {
     cas_cache: Arc<LayerCache<Arc<Q>>>,
     encrypted_secret_cache: Arc<LayerCache<Arc<R>>>,
+    func_result_cache: Arc<LayerCache<Arc<FuncResult>>>,
     func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     snapshot_cache: Arc<LayerCache<Arc<S>>>,
@@ -145,6 +152,7 @@ where
     fn new(
         cas_cache: Arc<LayerCache<Arc<Q>>>,
         encrypted_secret_cache: Arc<LayerCache<Arc<R>>>,
+        func_result_cache: Arc<LayerCache<Arc<FuncResult>>>,
         func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
         snapshot_cache: Arc<LayerCache<Arc<S>>>,
@@ -153,6 +161,7 @@ where
         CacheUpdateTask {
             cas_cache,
             encrypted_secret_cache,
+            func_result_cache,
             func_run_cache,
             func_run_log_cache,
             snapshot_cache,
@@ -178,6 +187,14 @@ where
                         .insert_from_cache_updates(event.key, serialized_value);
                 }
             }
+            crate::event::LayeredEventKind::FuncResultWrite => {
+                if !self.func_result_cache.contains(&event.key) {
+                    let serialized_value =
+                        Arc::try_unwrap(event.payload.value).unwrap_or_else(|arc| (*arc).clone());
+                    self.func_result_cache
+                        .insert_from_cache_updates(event.key, serialized_value);
+                }
+            }
             crate::event::LayeredEventKind::Raw => {
                 warn!("Recevied a 'raw' layered event kind - this is for testing only. Bug!");
             }
@@ -0,0 +1,126 @@
+//! Memoized results of deterministic func executions.
+//!
+//! A func's result depends only on its code, its arguments and the before funcs run ahead of it,
+//! so results are keyed by a [`FuncResultKey`] derived from the hashes of all three. The key also
+//! includes the workspace that ran the func, so a result is only ever reused within that
+//! workspace: whether a result was memoized must not tell one workspace what another has run.
+
+use std::{fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use si_events::{Actor, CasValue, ContentHash, Tenancy, WorkspacePk};
+use telemetry::prelude::*;
+
+use crate::{
+    error::LayerDbResult,
+    event::{LayeredEvent, LayeredEventKind},
+    layer_cache::LayerCache,
+    persister::{PersisterClient, PersisterStatusReader},
+};
+
+use super::serialize;
+
+const KEYWORD_SINGULAR: &str = "func_result";
+const KEYWORD_PLURAL: &str = "func_results";
+
+pub const PARTITION_KEY: &str = KEYWORD_PLURAL;
+pub const DBNAME: &str = KEYWORD_PLURAL;
+pub const CACHE_NAME: &str = KEYWORD_PLURAL;
+pub const SORT_KEY: &str = KEYWORD_SINGULAR;
+
+/// Identifies the result of running a func's code with a set of arguments and before funcs in a
+/// workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FuncResultKey(ContentHash);
+
+impl FuncResultKey {
+    pub fn new(
+        workspace_pk: WorkspacePk,
+        code_hash: ContentHash,
+        args_hash: ContentHash,
+        before_hash: ContentHash,
+    ) -> Self {
+        let workspace_bytes = workspace_pk.into_inner().to_bytes();
+
+        let mut input = Vec::with_capacity(
+            workspace_bytes.len()
+                + code_hash.as_bytes().len()
+                + args_hash.as_bytes().len()
+                + before_hash.as_bytes().len(),
+        );
+        input.extend_from_slice(&workspace_bytes);
+        input.extend_from_slice(code_hash.as_bytes());
+        input.extend_from_slice(args_hash.as_bytes());
+        input.extend_from_slice(before_hash.as_bytes());
+
+        Self(ContentHash::new(&input))
+    }
+}
+
+impl fmt::Display for FuncResultKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The values a func execution produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuncResult {
+    pub unprocessed_value: Option<CasValue>,
+    pub value: Option<CasValue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuncResultDb {
+    pub cache: Arc<LayerCache<Arc<FuncResult>>>,
+    persister_client: PersisterClient,
+}
+
+impl FuncResultDb {
+    pub fn new(cache: Arc<LayerCache<Arc<FuncResult>>>, persister_client: PersisterClient) -> Self {
+        Self {
+            cache,
+            persister_client,
+        }
+    }
+
+    pub fn write(
+        &self,
+        key: FuncResultKey,
+        value: Arc<FuncResult>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<PersisterStatusReader> {
+        let (postcard_value, size_hint) = serialize::to_vec(&value)?;
+
+        let cache_key: Arc<str> = key.to_string().into();
+
+        self.cache.insert(cache_key.clone(), value, size_hint);
+
+        let event = LayeredEvent::new(
+            LayeredEventKind::FuncResultWrite,
+            Arc::new(DBNAME.to_string()),
+            cache_key,
+            Arc::new(postcard_value),
+            Arc::new(SORT_KEY.to_string()),
+            None,
+            tenancy,
+            actor,
+        );
+        let reader = self.persister_client.write_event(event)?;
+
+        Ok(reader)
+    }
+
+    #[instrument(
+        name = "func_result.read",
+        level = "debug",
+        skip_all,
+        fields(
+            si.func_result.key = %key,
+        )
+    )]
+    pub async fn read(&self, key: &FuncResultKey) -> LayerDbResult<Option<Arc<FuncResult>>> {
+        self.cache.get(key.to_string().into()).await
+    }
+}
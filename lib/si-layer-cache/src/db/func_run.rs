@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -33,6 +34,7 @@ pub struct FuncRunDb {
     get_last_action_by_action_id: String,
     list_management_history: String,
     get_last_management_by_func_and_component_id: String,
+    list_page_by_key: String,
 }
 
 impl FuncRunDb {
@@ -76,9 +78,50 @@ impl FuncRunDb {
                 LIMIT 1
             "#
             ),
+            list_page_by_key: format!(
+                "SELECT key, value FROM {DBNAME} WHERE key > $1 ORDER BY key LIMIT $2"
+            ),
         }
     }
 
+    /// Returns every [`ContentHash`] referenced by a stored [`FuncRun`]. Func runs point at their
+    /// arguments, code and results in the CAS, so this content must survive garbage collection
+    /// even when no workspace snapshot refers to it.
+    #[instrument(name = "func_run.content_hashes_in_use", level = "info", skip_all)]
+    pub async fn content_hashes_in_use(
+        &self,
+        batch_size: usize,
+    ) -> LayerDbResult<HashSet<ContentHash>> {
+        let limit = i64::try_from(batch_size)?;
+        let mut hashes = HashSet::new();
+        let mut after_key = String::new();
+
+        loop {
+            let rows = self
+                .cache
+                .pg()
+                .query(&self.list_page_by_key, &[&after_key, &limit])
+                .await?
+                .unwrap_or_default();
+            let row_count = rows.len();
+
+            for row in rows {
+                let func_run: FuncRun = serialize::from_bytes(row.get("value"))?;
+                hashes.insert(func_run.function_args_cas_address());
+                hashes.insert(func_run.function_code_cas_address());
+                hashes.extend(func_run.result_value_cas_address());
+                hashes.extend(func_run.result_unprocessed_value_cas_address());
+                after_key = row.get("key");
+            }
+
+            if row_count < batch_size {
+                break;
+            }
+        }
+
+        Ok(hashes)
+    }
+
     pub async fn list_action_history(
         &self,
         workspace_id: WorkspacePk,
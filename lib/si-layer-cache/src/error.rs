@@ -1,4 +1,4 @@
-use std::{error, num::TryFromIntError};
+use std::{error, num::TryFromIntError, time::Duration};
 
 use si_data_nats::async_nats::jetstream;
 use si_data_pg::{PgError, PgPoolError};
@@ -46,6 +46,10 @@ pub enum LayerDbError {
     Decompress(String),
     #[error("Foyer error: {0}")]
     Foyer(#[source] Box<dyn error::Error + Sync + Send + 'static>),
+    #[error("garbage collection grace period out of range: {0}")]
+    GracePeriodOutOfRange(#[from] chrono::OutOfRangeError),
+    #[error("garbage collection grace period reaches back before the earliest time: {0:?}")]
+    GracePeriodTooLong(Duration),
     #[error("failed to parse content hash from str: {0}")]
     HashParse(#[from] ContentHashParseError),
     #[error("incomplete key: {0}")]
@@ -69,6 +69,7 @@ pub use si_id::LayeredEventId;
 pub enum LayeredEventKind {
     CasInsertion,
     EncryptedSecretInsertion,
+    FuncResultWrite,
     FuncRunLogWrite,
     FuncRunWrite,
     Raw,
@@ -0,0 +1,218 @@
+//! Mark-and-sweep garbage collection for the content addressed tables of the layer db.
+//!
+//! The layer db has no idea what lives inside of a workspace snapshot, so marking is up to the
+//! caller: it walks every snapshot still in use, recording the snapshot addresses and content
+//! hashes it can reach in a [`GarbageCollectionMarks`]. The layer db then sweeps every row that
+//! was not marked and that is older than the grace period, deleting it from postgres and evicting
+//! it from the local foyer cache.
+//!
+//! Content referenced by func runs is always kept, and rebase batches are swept once they are
+//! older than the grace period, since they are only needed while a rebase is in flight. Memoized
+//! func results are swept on the same schedule, as a swept result is recomputed when next needed.
+
+use std::{collections::HashSet, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use si_events::{ContentHash, WorkspaceSnapshotAddress};
+use telemetry::prelude::*;
+
+use crate::{
+    error::{LayerDbError, LayerDbResult},
+    layer_cache::LayerCache,
+};
+
+/// By default, nothing written within the last day is ever collected.
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60 * 24);
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Controls how a garbage collection run behaves.
+#[derive(Debug, Clone, Copy)]
+pub struct GarbageCollectionConfig {
+    /// Rows created less than this long before marking started are never swept. This protects
+    /// snapshots and content that have been written but are not referenced by a change set
+    /// pointer yet.
+    pub grace_period: Duration,
+    /// How many rows to examine (and delete) at a time.
+    pub batch_size: usize,
+    /// When true, report what would be swept without deleting anything.
+    pub dry_run: bool,
+}
+
+impl Default for GarbageCollectionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: DEFAULT_GRACE_PERIOD,
+            batch_size: DEFAULT_BATCH_SIZE,
+            dry_run: true,
+        }
+    }
+}
+
+/// The set of reachable snapshot addresses and content hashes, gathered by the caller.
+#[derive(Debug, Clone)]
+pub struct GarbageCollectionMarks {
+    started_at: DateTime<Utc>,
+    snapshot_addresses: HashSet<WorkspaceSnapshotAddress>,
+    content_hashes: HashSet<ContentHash>,
+}
+
+impl Default for GarbageCollectionMarks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GarbageCollectionMarks {
+    /// Starts marking. The time of creation is what the grace period is measured from, so the
+    /// marks must be created *before* the roots are read.
+    pub fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            snapshot_addresses: HashSet::new(),
+            content_hashes: HashSet::new(),
+        }
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Returns the creation time before which unmarked rows are considered garbage.
+    pub fn cutoff(&self, grace_period: Duration) -> LayerDbResult<DateTime<Utc>> {
+        self.started_at
+            .checked_sub_signed(chrono::Duration::from_std(grace_period)?)
+            .ok_or(LayerDbError::GracePeriodTooLong(grace_period))
+    }
+
+    /// Marks a snapshot address as reachable. Returns false if it had already been marked, in
+    /// which case there is no need to walk the snapshot again.
+    pub fn mark_snapshot(&mut self, address: WorkspaceSnapshotAddress) -> bool {
+        self.snapshot_addresses.insert(address)
+    }
+
+    pub fn mark_content(&mut self, hashes: impl IntoIterator<Item = ContentHash>) {
+        self.content_hashes.extend(hashes);
+    }
+
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshot_addresses.len()
+    }
+
+    pub fn content_count(&self) -> usize {
+        self.content_hashes.len()
+    }
+
+    pub(crate) fn snapshot_keys(&self) -> HashSet<String> {
+        self.snapshot_addresses
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub(crate) fn content_keys(&self) -> HashSet<String> {
+        self.content_hashes
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+/// What was (or, for a dry run, would have been) swept from a single table.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GarbageCollectionSweepReport {
+    pub examined: usize,
+    pub swept_keys: Vec<String>,
+}
+
+/// The result of a garbage collection run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GarbageCollectionReport {
+    pub dry_run: bool,
+    pub cutoff: DateTime<Utc>,
+    pub marked_snapshots: usize,
+    pub marked_content: usize,
+    pub workspace_snapshots: GarbageCollectionSweepReport,
+    pub cas: GarbageCollectionSweepReport,
+    pub rebase_batches: GarbageCollectionSweepReport,
+    pub func_results: GarbageCollectionSweepReport,
+}
+
+impl GarbageCollectionReport {
+    pub(crate) fn new(config: &GarbageCollectionConfig, cutoff: DateTime<Utc>) -> Self {
+        Self {
+            dry_run: config.dry_run,
+            cutoff,
+            marked_snapshots: 0,
+            marked_content: 0,
+            workspace_snapshots: Default::default(),
+            cas: Default::default(),
+            rebase_batches: Default::default(),
+            func_results: Default::default(),
+        }
+    }
+}
+
+/// Sweeps every row of the cache's table created before the cutoff whose key is not marked. When
+/// no marks are given, every row created before the cutoff is swept.
+#[instrument(
+    name = "layer_db.gc.sweep",
+    level = "info",
+    skip_all,
+    fields(
+        si.layer_cache.name = %cache.pg().table_name,
+        si.layer_cache.gc.dry_run = config.dry_run,
+    )
+)]
+pub(crate) async fn sweep<V>(
+    cache: &LayerCache<V>,
+    cutoff: DateTime<Utc>,
+    marked: Option<&HashSet<String>>,
+    config: &GarbageCollectionConfig,
+) -> LayerDbResult<GarbageCollectionSweepReport>
+where
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let pg = cache.pg();
+    let limit = i64::try_from(config.batch_size)?;
+    let mut report = GarbageCollectionSweepReport::default();
+    let mut after_key = String::new();
+
+    loop {
+        let keys = pg.keys_created_before(cutoff, &after_key, limit).await?;
+        let Some(last_key) = keys.last() else {
+            break;
+        };
+        after_key = last_key.clone();
+        let is_last_page = keys.len() < config.batch_size;
+        report.examined += keys.len();
+
+        let garbage: Vec<String> = keys
+            .into_iter()
+            .filter(|key| !marked.is_some_and(|marked| marked.contains(key)))
+            .collect();
+
+        if !garbage.is_empty() && !config.dry_run {
+            pg.delete_many(&garbage).await?;
+            for key in &garbage {
+                cache.remove_from_memory(key);
+            }
+        }
+        report.swept_keys.extend(garbage);
+
+        if is_last_page {
+            break;
+        }
+    }
+
+    debug!(
+        examined = report.examined,
+        swept = report.swept_keys.len(),
+        "swept {}",
+        pg.table_name
+    );
+
+    Ok(report)
+}
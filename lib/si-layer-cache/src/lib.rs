@@ -29,6 +29,7 @@ mod activity_client;
 pub mod db;
 pub mod error;
 pub mod event;
+pub mod gc;
 pub mod hybrid_cache;
 pub mod layer_cache;
 mod nats;
@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS cas_created_at ON cas (created_at);
+CREATE INDEX IF NOT EXISTS workspace_snapshots_created_at ON workspace_snapshots (created_at);
+CREATE INDEX IF NOT EXISTS rebase_batches_created_at ON rebase_batches (created_at);
@@ -0,0 +1,11 @@
+CREATE TABLE func_results
+(
+    key               text                     NOT NULL PRIMARY KEY,
+    sort_key          text                     NOT NULL,
+    created_at        timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    value             bytea                    NOT NULL,
+    serialization_lib text                     NOT NULL DEFAULT 'postcard'
+);
+
+CREATE INDEX IF NOT EXISTS func_results_sort_key ON func_results (sort_key);
+CREATE INDEX IF NOT EXISTS func_results_created_at ON func_results (created_at);
@@ -325,6 +325,7 @@ impl PersistEventTask {
         match event.event_kind {
             LayeredEventKind::CasInsertion
             | LayeredEventKind::EncryptedSecretInsertion
+            | LayeredEventKind::FuncResultWrite
             | LayeredEventKind::Raw
             | LayeredEventKind::RebaseBatchEvict
             | LayeredEventKind::RebaseBatchWrite
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use si_data_pg::{postgres_types::ToSql, PgPool, PgPoolConfig, PgRow};
 use telemetry::tracing::info;
 use telemetry_utils::metric;
@@ -36,6 +37,9 @@ pub struct PgLayer {
     insert_value_query: String,
     contains_key_query: String,
     search_query: String,
+    keys_created_before_query: String,
+    keys_created_since_query: String,
+    delete_many_query: String,
 }
 
 impl PgLayer {
@@ -51,6 +55,9 @@ impl PgLayer {
             insert_value_query: format!("INSERT INTO {table_name} (key, sort_key, value) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING"),
             contains_key_query: format!("SELECT key FROM {table_name} WHERE key = $1 LIMIT 1"),
             search_query: format!("SELECT value FROM {table_name} WHERE sort_key LIKE $1"),
+            keys_created_before_query: format!("SELECT key FROM {table_name} WHERE created_at < $1 AND key > $2 ORDER BY key LIMIT $3"),
+            keys_created_since_query: format!("SELECT key FROM {table_name} WHERE created_at >= $1"),
+            delete_many_query: format!("DELETE FROM {table_name} WHERE key = any($1)"),
             table_name,
         }
     }
@@ -214,6 +221,40 @@ impl PgLayer {
         Ok(())
     }
 
+    pub async fn delete_many(&self, keys: &[String]) -> LayerDbResult<()> {
+        let client = self.pool.get().await?;
+        client.query(&self.delete_many_query, &[&keys]).await?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` keys, in key order, for rows created before the cutoff. Pass the
+    /// last key of the previous page as `after_key` to fetch the next page.
+    pub async fn keys_created_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        after_key: &str,
+        limit: i64,
+    ) -> LayerDbResult<Vec<String>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                &self.keys_created_before_query,
+                &[&cutoff, &after_key, &limit],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|r| r.get("key")).collect())
+    }
+
+    pub async fn keys_created_since(&self, cutoff: DateTime<Utc>) -> LayerDbResult<Vec<String>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(&self.keys_created_since_query, &[&cutoff])
+            .await?;
+
+        Ok(rows.into_iter().map(|r| r.get("key")).collect())
+    }
+
     pub async fn contains_key(&self, key: &str) -> LayerDbResult<bool> {
         let client = self.pool.get().await?;
         let maybe_row = client.query_opt(&self.contains_key_query, &[&key]).await?;
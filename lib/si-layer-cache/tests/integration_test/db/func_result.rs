@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use si_events::{Actor, ChangeSetId, ContentHash, Tenancy, UserPk, WorkspacePk};
+use si_layer_cache::{
+    db::{
+        func_result::{FuncResult, FuncResultKey},
+        serialize,
+    },
+    hybrid_cache::CacheConfig,
+    persister::PersistStatus,
+    LayerDb,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::integration_test::{setup_compute_executor, setup_nats_client, setup_pg_db};
+
+type TestLayerDb = LayerDb<String, String, String, String>;
+
+fn func_result(value: serde_json::Value) -> Arc<FuncResult> {
+    Arc::new(FuncResult {
+        unprocessed_value: Some(value.clone().into()),
+        value: Some(value.into()),
+    })
+}
+
+#[test]
+fn key_depends_on_every_hash() {
+    let workspace_pk = WorkspacePk::new();
+    let code = ContentHash::new(b"function main() { return 'poop'; }");
+    let args = ContentHash::new(b"{}");
+    let before = ContentHash::new(b"");
+
+    let key = FuncResultKey::new(workspace_pk, code, args, before);
+    assert_eq!(key, FuncResultKey::new(workspace_pk, code, args, before));
+    assert_ne!(key, FuncResultKey::new(workspace_pk, args, code, before));
+    assert_ne!(
+        key,
+        FuncResultKey::new(workspace_pk, code, ContentHash::new(b"{\"a\":1}"), before)
+    );
+    assert_ne!(
+        key,
+        FuncResultKey::new(workspace_pk, code, args, ContentHash::new(b"secret"))
+    );
+}
+
+#[test]
+fn key_depends_on_workspace() {
+    let code = ContentHash::new(b"function main() { return 'poop'; }");
+    let args = ContentHash::new(b"{}");
+    let before = ContentHash::new(b"");
+
+    assert_ne!(
+        FuncResultKey::new(WorkspacePk::new(), code, args, before),
+        FuncResultKey::new(WorkspacePk::new(), code, args, before)
+    );
+}
+
+#[tokio::test]
+async fn write_to_db() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        setup_pg_db("func_result_write_to_db").await,
+        setup_nats_client(Some("func_result_write_to_db".to_string())).await,
+        setup_compute_executor(),
+        CacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate layer db");
+
+    let workspace_pk = WorkspacePk::new();
+    let key = FuncResultKey::new(
+        workspace_pk,
+        ContentHash::new(b"the code"),
+        ContentHash::new(b"the args"),
+        ContentHash::new(b""),
+    );
+    let value = func_result(serde_json::json!({ "name": "courage the cowardly dog" }));
+
+    let status = ldb
+        .func_result()
+        .write(
+            key,
+            value.clone(),
+            Tenancy::new(workspace_pk, ChangeSetId::new()),
+            Actor::User(UserPk::new()),
+        )
+        .expect("failed to write to layerdb");
+    match status.get_status().await.expect("failed to get status") {
+        PersistStatus::Finished => {}
+        PersistStatus::Error(e) => panic!("Write failed; {e}"),
+    }
+
+    let key_str: Arc<str> = key.to_string().into();
+
+    // Are we in memory?
+    let in_memory = ldb.func_result().cache.cache().get(key_str.clone()).await;
+    assert_eq!(Some(value.clone()), in_memory);
+
+    // Are we in pg?
+    let in_pg_postcard = ldb
+        .func_result()
+        .cache
+        .pg()
+        .get(&key_str)
+        .await
+        .expect("error getting data from pg")
+        .expect("no func result in pg");
+    let in_pg: FuncResult =
+        serialize::from_bytes(&in_pg_postcard[..]).expect("cannot deserialize data");
+    assert_eq!(value.as_ref(), &in_pg);
+}
+
+#[tokio::test]
+async fn cold_read_from_db() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        setup_pg_db("func_result_cold_read_from_db").await,
+        setup_nats_client(Some("func_result_cold_read_from_db".to_string())).await,
+        setup_compute_executor(),
+        CacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate layer db");
+
+    let workspace_pk = WorkspacePk::new();
+    let key = FuncResultKey::new(
+        workspace_pk,
+        ContentHash::new(b"the code"),
+        ContentHash::new(b"the args"),
+        ContentHash::new(b""),
+    );
+    let value = func_result(serde_json::json!(["eustace", "muriel"]));
+
+    let status = ldb
+        .func_result()
+        .write(
+            key,
+            value.clone(),
+            Tenancy::new(workspace_pk, ChangeSetId::new()),
+            Actor::User(UserPk::new()),
+        )
+        .expect("failed to write to layerdb");
+    match status.get_status().await.expect("failed to get status") {
+        PersistStatus::Finished => {}
+        PersistStatus::Error(e) => panic!("Write failed; {e}"),
+    }
+
+    // Delete from cache
+    let key_str: Arc<str> = key.to_string().into();
+    ldb.func_result().cache.cache().remove(&key_str);
+    let not_in_cache = ldb.func_result().cache.cache().get(key_str.clone()).await;
+    assert_eq!(not_in_cache, None);
+
+    // Read the data from pg
+    let data = ldb
+        .func_result()
+        .read(&key)
+        .await
+        .expect("cannot read from layerdb")
+        .expect("data not in layerdb");
+    assert_eq!(value, data);
+
+    // A key nobody has written is a miss
+    let missing = ldb
+        .func_result()
+        .read(&FuncResultKey::new(
+            workspace_pk,
+            ContentHash::new(b"other code"),
+            ContentHash::new(b"the args"),
+            ContentHash::new(b""),
+        ))
+        .await
+        .expect("cannot read from layerdb");
+    assert_eq!(None, missing);
+}
@@ -0,0 +1,156 @@
+use std::{sync::Arc, time::Duration};
+
+use si_events::{Actor, ChangeSetId, Tenancy, UserPk, WorkspacePk};
+use si_layer_cache::{
+    gc::{GarbageCollectionConfig, GarbageCollectionMarks},
+    hybrid_cache::CacheConfig,
+    persister::{PersistStatus, PersisterStatusReader},
+    LayerDb,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::integration_test::{setup_compute_executor, setup_nats_client, setup_pg_db};
+
+type TestLayerDb = LayerDb<String, String, String, String>;
+
+async fn assert_persisted(status: PersisterStatusReader) {
+    match status.get_status().await.expect("failed to get status") {
+        PersistStatus::Finished => {}
+        PersistStatus::Error(e) => panic!("Write failed; {e}"),
+    }
+}
+
+#[test]
+fn grace_periods_reaching_past_the_earliest_time_are_rejected() {
+    let marks = GarbageCollectionMarks::new();
+
+    assert!(marks.cutoff(Duration::from_secs(60 * 60 * 24)).is_ok());
+    // Within chrono's duration range, but far beyond what a date can go back to
+    assert!(marks
+        .cutoff(Duration::from_secs(i64::MAX as u64 / 1000))
+        .is_err());
+    assert!(marks.cutoff(Duration::MAX).is_err());
+}
+
+#[tokio::test]
+async fn sweeps_unmarked_rows() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        setup_pg_db("gc_sweeps_unmarked_rows").await,
+        setup_nats_client(Some("gc_sweeps_unmarked_rows".to_string())).await,
+        setup_compute_executor(),
+        CacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate layer db");
+
+    let tenancy = Tenancy::new(WorkspacePk::new(), ChangeSetId::new());
+    let actor = Actor::User(UserPk::new());
+
+    let (kept_snapshot, status) = ldb
+        .workspace_snapshot()
+        .write(Arc::new("ozzy".into()), None, tenancy, actor)
+        .expect("failed to write snapshot");
+    assert_persisted(status).await;
+    let (garbage_snapshot, status) = ldb
+        .workspace_snapshot()
+        .write(Arc::new("ronnie".into()), None, tenancy, actor)
+        .expect("failed to write snapshot");
+    assert_persisted(status).await;
+    let (kept_content, status) = ldb
+        .cas()
+        .write(Arc::new("paranoid".into()), None, tenancy, actor)
+        .expect("failed to write cas");
+    assert_persisted(status).await;
+    let (garbage_content, status) = ldb
+        .cas()
+        .write(Arc::new("heaven and hell".into()), None, tenancy, actor)
+        .expect("failed to write cas");
+    assert_persisted(status).await;
+
+    let config = GarbageCollectionConfig {
+        grace_period: Duration::ZERO,
+        batch_size: 1,
+        dry_run: true,
+    };
+    let marks = || {
+        let mut marks = GarbageCollectionMarks::new();
+        marks.mark_snapshot(kept_snapshot);
+        marks.mark_content([kept_content]);
+        marks
+    };
+
+    // A dry run reports the garbage, but leaves it in place
+    let report = ldb
+        .collect_garbage(marks(), config)
+        .await
+        .expect("dry run failed");
+    assert!(report.dry_run);
+    assert_eq!(2, report.workspace_snapshots.examined);
+    assert_eq!(
+        vec![garbage_snapshot.to_string()],
+        report.workspace_snapshots.swept_keys
+    );
+    assert_eq!(vec![garbage_content.to_string()], report.cas.swept_keys);
+    assert!(ldb
+        .workspace_snapshot()
+        .cache
+        .pg()
+        .contains_key(&garbage_snapshot.to_string())
+        .await
+        .expect("error checking pg"));
+
+    // Nothing is swept when everything is within the grace period
+    let report = ldb
+        .collect_garbage(
+            marks(),
+            GarbageCollectionConfig {
+                grace_period: Duration::from_secs(60 * 60),
+                dry_run: false,
+                ..config
+            },
+        )
+        .await
+        .expect("gc failed");
+    assert_eq!(0, report.workspace_snapshots.examined);
+    assert!(report.cas.swept_keys.is_empty());
+
+    // A real run deletes only the garbage, from both pg and memory
+    ldb.collect_garbage(
+        marks(),
+        GarbageCollectionConfig {
+            dry_run: false,
+            ..config
+        },
+    )
+    .await
+    .expect("gc failed");
+
+    let snapshot_pg = ldb.workspace_snapshot().cache.pg();
+    assert!(snapshot_pg
+        .contains_key(&kept_snapshot.to_string())
+        .await
+        .expect("error checking pg"));
+    assert!(!snapshot_pg
+        .contains_key(&garbage_snapshot.to_string())
+        .await
+        .expect("error checking pg"));
+    assert!(!ldb
+        .workspace_snapshot()
+        .cache
+        .contains(&garbage_snapshot.to_string()));
+
+    let cas_pg = ldb.cas().cache.pg();
+    assert!(cas_pg
+        .contains_key(&kept_content.to_string())
+        .await
+        .expect("error checking pg"));
+    assert!(!cas_pg
+        .contains_key(&garbage_content.to_string())
+        .await
+        .expect("error checking pg"));
+    assert!(!ldb.cas().cache.contains(&garbage_content.to_string()));
+}
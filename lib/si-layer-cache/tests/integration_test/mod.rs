@@ -8,6 +8,7 @@ use crate::TEST_PG_DBNAME;
 
 mod activities;
 mod db;
+mod gc;
 mod layer_cache;
 
 const DEFAULT_TEST_PG_USER: &str = "si_test";
@@ -75,6 +75,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn func_runtime_round_trip() {
+        let mut spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        for (func, runtime) in spec
+            .funcs
+            .iter_mut()
+            .zip([FuncSpecRuntime::LangJs, FuncSpecRuntime::Shell])
+        {
+            func.data = Some(
+                FuncSpecData::builder()
+                    .name(&func.name)
+                    .handler("main")
+                    .code_plaintext("echo true")
+                    .backend_kind(FuncSpecBackendKind::JsAttribute)
+                    .response_type(FuncSpecBackendResponseType::Boolean)
+                    .runtime(runtime)
+                    .build()
+                    .expect("failed to build func data"),
+            );
+        }
+        // Funcs which run JavaScript are written as they were before funcs had a runtime, so
+        // they are also how funcs from older packages are read
+        let js_func_data =
+            serde_json::to_value(&spec.funcs[0].data).expect("failed to serialize func data");
+        assert!(js_func_data.get("runtime").is_none());
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(&pkg_data).expect("failed to load pkg from bytes");
+        let funcs = read_pkg.funcs().expect("failed to get funcs");
+
+        assert_eq!(Some(FuncSpecRuntime::LangJs), funcs[0].runtime());
+        assert_eq!(Some(FuncSpecRuntime::Shell), funcs[1].runtime());
+        let func_spec: FuncSpec = funcs[1].clone().try_into().expect("failed to convert func");
+        assert_eq!(
+            FuncSpecRuntime::Shell,
+            func_spec.data.expect("func has data").runtime
+        );
+    }
+
     #[tokio::test]
     async fn pkg_bytes_round_trip() {
         let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
@@ -9,7 +9,7 @@ use object_tree::{
     GraphError, NameStr, NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 
-use crate::spec::{FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType};
+use crate::spec::{FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecRuntime};
 
 use super::{read_common_fields, write_common_fields, PkgNode};
 
@@ -22,6 +22,7 @@ const KEY_BACKEND_KIND_STR: &str = "backend_kind";
 const KEY_RESPONSE_TYPE_STR: &str = "response_type";
 const KEY_HIDDEN_STR: &str = "hidden";
 const KEY_LINK_STR: &str = "link";
+const KEY_RUNTIME_STR: &str = "runtime";
 const KEY_IS_FROM_BUILTIN: &str = "is_from_builtin";
 
 #[derive(Clone, Debug)]
@@ -35,6 +36,7 @@ pub struct FuncData {
     pub response_type: FuncSpecBackendResponseType,
     pub hidden: bool,
     pub link: Option<Url>,
+    pub runtime: FuncSpecRuntime,
 }
 
 #[derive(Clone, Debug)]
@@ -76,6 +78,13 @@ impl WriteBytes for FuncNode {
                 KEY_LINK_STR,
                 data.link.as_ref().map(|l| l.as_str()).unwrap_or(""),
             )?;
+            // Only written for other runtimes, so that the hashes of JavaScript funcs are the
+            // same as before funcs had a runtime
+            write_key_value_line_opt(
+                writer,
+                KEY_RUNTIME_STR,
+                (!data.runtime.is_default()).then_some(data.runtime),
+            )?;
         }
 
         write_common_fields(writer, Some(self.unique_id.as_str()), self.deleted)?;
@@ -121,6 +130,12 @@ impl ReadBytes for FuncNode {
                 } else {
                     Some(Url::parse(&link_str).map_err(GraphError::parse)?)
                 };
+                let runtime = match read_key_value_line_opt(reader, KEY_RUNTIME_STR)? {
+                    Some(runtime_str) => {
+                        FuncSpecRuntime::from_str(&runtime_str).map_err(GraphError::parse)?
+                    }
+                    None => FuncSpecRuntime::default(),
+                };
 
                 Some(FuncData {
                     name: name.clone(),
@@ -132,6 +147,7 @@ impl ReadBytes for FuncNode {
                     response_type,
                     hidden,
                     link,
+                    runtime,
                 })
             }
         };
@@ -178,6 +194,7 @@ impl NodeChild for FuncSpec {
                     response_type: data.response_type,
                     hidden: data.hidden,
                     link: data.link.as_ref().cloned(),
+                    runtime: data.runtime,
                 }),
                 unique_id: self.unique_id.to_owned(),
                 deleted: self.deleted,
@@ -7,7 +7,7 @@ use crate::{
     node::PkgNode,
     spec::{
         FuncArgumentKind, FuncArgumentSpec, FuncSpec, FuncSpecBackendKind,
-        FuncSpecBackendResponseType, FuncSpecData,
+        FuncSpecBackendResponseType, FuncSpecData, FuncSpecRuntime,
     },
 };
 
@@ -107,6 +107,7 @@ pub struct SiPkgFuncData {
     response_type: FuncSpecBackendResponseType,
     hidden: bool,
     link: Option<Url>,
+    runtime: FuncSpecRuntime,
 }
 
 impl SiPkgFuncData {
@@ -145,6 +146,10 @@ impl SiPkgFuncData {
     pub fn link(&self) -> Option<&Url> {
         self.link.as_ref()
     }
+
+    pub fn runtime(&self) -> FuncSpecRuntime {
+        self.runtime
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -187,6 +192,7 @@ impl<'a> SiPkgFunc<'a> {
                 response_type: data.response_type,
                 hidden: data.hidden,
                 link: data.link,
+                runtime: data.runtime,
             }),
             hash: func_hashed_node.hash(),
             unique_id: func_node.unique_id,
@@ -262,6 +268,10 @@ impl<'a> SiPkgFunc<'a> {
         }
     }
 
+    pub fn runtime(&self) -> Option<FuncSpecRuntime> {
+        self.data().map(|data| data.runtime)
+    }
+
     pub fn is_from_builtin(&self) -> Option<bool> {
         self.is_from_builtin
     }
@@ -299,7 +309,8 @@ impl<'a> TryFrom<SiPkgFunc<'a>> for FuncSpec {
                 .code_base64(&data.code_base64)
                 .backend_kind(data.backend_kind)
                 .response_type(data.response_type)
-                .hidden(data.hidden);
+                .hidden(data.hidden)
+                .runtime(data.runtime);
 
             if let Some(display_name) = &data.display_name {
                 data_builder.display_name(display_name);
@@ -108,6 +108,36 @@ pub enum FuncSpecBackendResponseType {
     Void,
 }
 
+/// The language runtime a func's code is written for. Packages from before funcs had a runtime
+/// only hold JavaScript funcs.
+#[remain::sorted]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    AsRefStr,
+    Display,
+    EnumIter,
+    EnumString,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum FuncSpecRuntime {
+    #[default]
+    LangJs,
+    Shell,
+}
+
+impl FuncSpecRuntime {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Builder, Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
@@ -130,6 +160,11 @@ pub struct FuncSpecData {
     pub hidden: bool,
     #[builder(setter(into, strip_option), default)]
     pub link: Option<Url>,
+    // Left out when it's the default, so that the specs (and unique ids) of JavaScript funcs are
+    // the same as before funcs had a runtime
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "FuncSpecRuntime::is_default")]
+    pub runtime: FuncSpecRuntime,
 }
 
 impl FuncSpecData {
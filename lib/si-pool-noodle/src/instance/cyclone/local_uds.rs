@@ -40,6 +40,12 @@ use tracing::{debug, trace};
 
 use crate::instance::{Instance, Spec, SpecBuilder};
 
+#[cfg(target_os = "linux")]
+use sandbox::LocalSandboxRuntime;
+
+#[cfg(target_os = "linux")]
+mod sandbox;
+
 /// Error type for [`LocalUdsInstance`].
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -87,6 +93,9 @@ pub enum LocalUdsInstanceError {
     /// Instance has exhausted its predefined request count.
     #[error("no remaining requests, cyclone server is considered unhealthy")]
     NoRemainingRequests,
+    /// Failed to create, limit or remove a sandbox cgroup.
+    #[error("failed to manage sandbox cgroup: {0}")]
+    SandboxCgroup(#[source] io::Error),
     /// Failed to setup the host correctly.
     #[error("failed to setup host")]
     SetupFailed,
@@ -243,6 +252,11 @@ pub struct LocalUdsInstanceSpec {
     #[builder(try_setter, setter(into), default)]
     lang_server_cmd_path: CanonicalCommand,
 
+    /// Canonical path to the language server program for shell functions, which a spawned Cyclone
+    /// server can only execute when given one.
+    #[builder(setter(into, strip_option), default)]
+    lang_shell_server_cmd_path: Option<CanonicalCommand>,
+
     /// Overrides the default function timeout for the language server program, in seconds.
     #[builder(default)]
     lang_server_function_timeout: Option<usize>,
@@ -255,6 +269,11 @@ pub struct LocalUdsInstanceSpec {
     #[builder(default)]
     runtime_strategy: LocalUdsRuntimeStrategy,
 
+    /// Limits and isolation for a Cyclone server spawned with the `LocalSandbox` runtime
+    /// strategy.
+    #[builder(default)]
+    sandbox: LocalUdsSandboxSpec,
+
     /// Sets the watch timeout value for a spawned Cyclone server.
     #[builder(setter(into, strip_option), default)]
     watch_timeout: Option<Duration>,
@@ -296,6 +315,8 @@ impl Spec for LocalUdsInstanceSpec {
             LocalUdsRuntimeStrategy::LocalProcess => Ok(()),
             #[cfg(target_os = "linux")]
             LocalUdsRuntimeStrategy::LocalFirecracker => LocalFirecrackerRuntime::clean(id).await,
+            #[cfg(target_os = "linux")]
+            LocalUdsRuntimeStrategy::LocalSandbox => LocalSandboxRuntime::clean(self, id).await,
         }
     }
 
@@ -306,6 +327,8 @@ impl Spec for LocalUdsInstanceSpec {
             LocalUdsRuntimeStrategy::LocalProcess => Ok(()),
             #[cfg(target_os = "linux")]
             LocalUdsRuntimeStrategy::LocalFirecracker => LocalFirecrackerRuntime::prepare(id).await,
+            #[cfg(target_os = "linux")]
+            LocalUdsRuntimeStrategy::LocalSandbox => LocalSandboxRuntime::prepare(self, id).await,
         }
     }
 
@@ -318,6 +341,8 @@ impl Spec for LocalUdsInstanceSpec {
             LocalUdsRuntimeStrategy::LocalFirecracker => {
                 LocalFirecrackerRuntime::setup_firecracker(self).await
             }
+            #[cfg(target_os = "linux")]
+            LocalUdsRuntimeStrategy::LocalSandbox => LocalSandboxRuntime::setup(self).await,
         }
     }
 
@@ -403,6 +428,18 @@ impl LocalUdsInstanceSpecBuilder {
         self.limit_requests(Some(1))
     }
 
+    /// Sets the language server program for shell functions from anything which converts to a
+    /// [`CanonicalCommand`].
+    pub fn try_lang_shell_server_cmd_path<P>(
+        &mut self,
+        path: P,
+    ) -> result::Result<&mut Self, P::Error>
+    where
+        P: TryInto<CanonicalCommand>,
+    {
+        Ok(self.lang_shell_server_cmd_path(path.try_into()?))
+    }
+
     /// Enables the `ping` execution endpoint for a spawned Cyclone server.
     pub fn ping(&mut self) -> &mut Self {
         self._ping(true)
@@ -485,6 +522,49 @@ fn temp_path_and_socket_from(
     }
 }
 
+/// Limits and isolation for Cyclone servers run with the
+/// [`LocalSandbox`](LocalUdsRuntimeStrategy::LocalSandbox) runtime strategy.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct LocalUdsSandboxSpec {
+    /// A cgroup v2 directory delegated to this process, under which each instance gets its own
+    /// cgroup. It must not contain any processes itself, including this one.
+    pub cgroup_root: PathBuf,
+    /// The most memory, in bytes, the processes of an instance may use together.
+    pub memory_max_bytes: Option<u64>,
+    /// The most CPU time the processes of an instance may use together, as a percentage of one
+    /// CPU, so `200` allows two full CPUs.
+    pub cpu_max_percent: Option<u32>,
+    /// The most processes (and threads) an instance may run at once.
+    pub pids_max: Option<u32>,
+    /// Whether the instance shares the host's network, rather than having only a loopback
+    /// interface.
+    pub allow_network: bool,
+    /// Whether the filesystem is read only within the instance, apart from the
+    /// [`writable_paths`](Self::writable_paths) and the directory holding the instance's socket.
+    pub read_only_root: bool,
+    /// Paths which stay writable when the root is read only.
+    pub writable_paths: Vec<PathBuf>,
+    /// Paths hidden from the instance, such as key and credential files. Files read as empty
+    /// and directories as empty and read only; paths which don't exist are skipped.
+    pub masked_paths: Vec<PathBuf>,
+}
+
+impl Default for LocalUdsSandboxSpec {
+    fn default() -> Self {
+        Self {
+            cgroup_root: PathBuf::from("/sys/fs/cgroup/si-cyclone"),
+            memory_max_bytes: Some(1024 * 1024 * 1024),
+            cpu_max_percent: Some(100),
+            pids_max: Some(256),
+            allow_network: false,
+            read_only_root: true,
+            writable_paths: Vec::new(),
+            masked_paths: Vec::new(),
+        }
+    }
+}
+
 #[remain::sorted]
 /// Runtime strategy when spawning [`Instance`]s.
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
@@ -496,6 +576,9 @@ pub enum LocalUdsRuntimeStrategy {
     LocalFirecracker,
     /// Run processes on the local machine
     LocalProcess,
+    #[cfg(target_os = "linux")]
+    /// Run processes on the local machine, in unprivileged namespaces and a cgroup
+    LocalSandbox,
 }
 
 impl Default for LocalUdsRuntimeStrategy {
@@ -505,6 +588,18 @@ impl Default for LocalUdsRuntimeStrategy {
     }
 }
 
+impl LocalUdsRuntimeStrategy {
+    /// Whether the strategy runs the cyclone command directly on the local machine.
+    pub fn runs_local_process(&self) -> bool {
+        match self {
+            Self::LocalProcess => true,
+            #[cfg(target_os = "linux")]
+            Self::LocalSandbox => true,
+            _ => false,
+        }
+    }
+}
+
 #[async_trait]
 pub trait LocalInstanceRuntime: Send + Sync {
     fn id(&self) -> u32;
@@ -525,40 +620,49 @@ impl LocalProcessRuntime {
         socket: &PathBuf,
         spec: LocalUdsInstanceSpec,
     ) -> Result<Box<dyn LocalInstanceRuntime>> {
-        let mut cmd = Command::new(&spec.cyclone_cmd_path);
-        cmd.arg("--bind-uds")
-            .arg(socket)
-            .arg("--lang-server")
-            .arg(&spec.lang_server_cmd_path)
-            .arg("--enable-watch");
-        if let Some(timeout) = spec.lang_server_function_timeout {
-            cmd.arg("--timeout").arg(timeout.to_string());
-        }
-        if let Some(limit_requests) = spec.limit_requests {
-            cmd.arg("--limit-requests").arg(limit_requests.to_string());
-        }
-        if let Some(timeout) = spec.watch_timeout {
-            cmd.arg("--watch-timeout")
-                .arg(timeout.as_secs().to_string());
-        }
-        if spec.ping {
-            cmd.arg("--enable-ping");
-        }
-        if spec.resolver {
-            cmd.arg("--enable-resolver");
-        }
-        if spec.action {
-            cmd.arg("--enable-action-run");
-        }
-
         Ok(Box::new(LocalProcessRuntime {
-            cmd,
+            cmd: local_cyclone_command(socket, &spec),
             child: None,
             socket: socket.to_path_buf(),
         }))
     }
 }
 
+/// Builds the command running Cyclone directly on the local machine.
+fn local_cyclone_command(socket: &Path, spec: &LocalUdsInstanceSpec) -> Command {
+    let mut cmd = Command::new(&spec.cyclone_cmd_path);
+    cmd.arg("--bind-uds")
+        .arg(socket)
+        .arg("--lang-server")
+        .arg(&spec.lang_server_cmd_path)
+        .arg("--enable-watch");
+    if let Some(lang_shell_server_cmd_path) = &spec.lang_shell_server_cmd_path {
+        cmd.arg("--lang-shell-server")
+            .arg(lang_shell_server_cmd_path);
+    }
+    if let Some(timeout) = spec.lang_server_function_timeout {
+        cmd.arg("--timeout").arg(timeout.to_string());
+    }
+    if let Some(limit_requests) = spec.limit_requests {
+        cmd.arg("--limit-requests").arg(limit_requests.to_string());
+    }
+    if let Some(timeout) = spec.watch_timeout {
+        cmd.arg("--watch-timeout")
+            .arg(timeout.as_secs().to_string());
+    }
+    if spec.ping {
+        cmd.arg("--enable-ping");
+    }
+    if spec.resolver {
+        cmd.arg("--enable-resolver");
+    }
+    if spec.action {
+        cmd.arg("--enable-action-run");
+    }
+
+    cmd
+}
+
 #[async_trait]
 impl LocalInstanceRuntime for LocalProcessRuntime {
     fn id(&self) -> u32 {
@@ -770,6 +874,10 @@ async fn runtime_instance_from_spec(
         LocalUdsRuntimeStrategy::LocalFirecracker => {
             LocalFirecrackerRuntime::build(spec.clone(), id).await
         }
+        #[cfg(target_os = "linux")]
+        LocalUdsRuntimeStrategy::LocalSandbox => {
+            LocalSandboxRuntime::build(local_cyclone_command(socket, spec), socket, spec, id).await
+        }
     }
 }
 
@@ -0,0 +1,590 @@
+//! A sandboxed local process runtime for [`LocalUdsInstance`](super::LocalUdsInstance)s.
+//!
+//! The Cyclone server (and so its language server) is spawned into fresh, unprivileged user,
+//! mount, PID, IPC, UTS and cgroup namespaces, plus a network namespace unless networking is
+//! allowed. Each instance gets its own cgroup v2 group under a delegated root, which caps its
+//! memory, CPU and process count and lets every process in the sandbox be killed at once. Paths
+//! holding veritech's keys and credentials are masked, so function code can't read them even
+//! when the root filesystem is otherwise visible.
+//!
+//! No privileges are needed beyond unprivileged user namespaces and write access to the
+//! delegated cgroup root, which makes this runtime usable on hosts without KVM or Docker.
+
+use std::{
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use cyclone_core::process;
+use nix::{
+    fcntl::{open, OFlag},
+    libc,
+    mount::{mount, MsFlags},
+    sched::{unshare, CloneFlags},
+    sys::{
+        stat::Mode,
+        wait::{waitpid, WaitStatus},
+    },
+    unistd::{close, fork, getgid, getuid, write, ForkResult},
+};
+use tokio::{
+    fs,
+    process::{Child, Command},
+    time,
+};
+use tracing::{debug, warn};
+
+use super::{
+    LocalInstanceRuntime, LocalUdsInstanceError, LocalUdsInstanceSpec, LocalUdsSandboxSpec, Result,
+};
+
+const CGROUP_CONTROLLERS: &str = "+cpu +memory +pids";
+/// The period over which the CPU limit is measured, in microseconds.
+const CPU_PERIOD_MICROS: u64 = 100_000;
+/// How many times to try removing a killed cgroup, whose processes may take a moment to exit.
+const CGROUP_REMOVE_ATTEMPTS: u32 = 100;
+
+// From `linux/mount.h`, which `libc` does not carry for `mount_setattr(2)` yet.
+const AT_RECURSIVE: libc::c_uint = 0x8000;
+const MOUNT_ATTR_RDONLY: u64 = 0x1;
+
+#[derive(Debug)]
+pub(super) struct LocalSandboxRuntime {
+    cmd: Command,
+    child: Option<Child>,
+    socket: PathBuf,
+    cgroup: PathBuf,
+    id: u32,
+}
+
+impl LocalSandboxRuntime {
+    pub(super) async fn build(
+        cmd: Command,
+        socket: &Path,
+        spec: &LocalUdsInstanceSpec,
+        id: u32,
+    ) -> Result<Box<dyn LocalInstanceRuntime>> {
+        Ok(Box::new(Self::new(cmd, socket, spec, id).await))
+    }
+
+    async fn new(mut cmd: Command, socket: &Path, spec: &LocalUdsInstanceSpec, id: u32) -> Self {
+        let sandbox = &spec.sandbox;
+        let cgroup = cgroup_for(sandbox, id);
+
+        let mut clone_flags = CloneFlags::CLONE_NEWUSER
+            | CloneFlags::CLONE_NEWNS
+            | CloneFlags::CLONE_NEWPID
+            | CloneFlags::CLONE_NEWIPC
+            | CloneFlags::CLONE_NEWUTS
+            | CloneFlags::CLONE_NEWCGROUP;
+        if !sandbox.allow_network {
+            clone_flags |= CloneFlags::CLONE_NEWNET;
+        }
+        let mut writable_paths = sandbox.writable_paths.clone();
+        if let Some(socket_dir) = socket.parent() {
+            writable_paths.push(socket_dir.to_path_buf());
+        }
+        // Whether each path is a directory decides how it is masked, which is looked up now as
+        // the child can't allocate for it
+        let mut masked_paths = Vec::with_capacity(sandbox.masked_paths.len());
+        for path in &sandbox.masked_paths {
+            match fs::metadata(path).await {
+                Ok(metadata) => masked_paths.push((path.clone(), metadata.is_dir())),
+                Err(err) => {
+                    debug!(error = ?err, path = %path.display(), "not masking missing path");
+                }
+            }
+        }
+        // The uid and gid stay the same inside the sandbox, so files keep their owners and the
+        // server holds no capabilities once it is running
+        let setup = SandboxSetup {
+            cgroup_procs: cgroup.join("cgroup.procs"),
+            clone_flags,
+            uid_map: format!("{uid} {uid} 1", uid = getuid()).into_bytes(),
+            gid_map: format!("{gid} {gid} 1", gid = getgid()).into_bytes(),
+            read_only_root: sandbox.read_only_root,
+            writable_paths,
+            masked_paths,
+        };
+
+        // SAFETY: the closure runs in the forked child before `exec`, where it only makes
+        // system calls and uses memory prepared before the fork.
+        unsafe {
+            cmd.pre_exec(move || enter_sandbox(&setup));
+        }
+
+        Self {
+            cmd,
+            child: None,
+            socket: socket.to_path_buf(),
+            cgroup,
+            id,
+        }
+    }
+
+    /// Creates the delegated cgroup root and enables the controllers the instances are limited
+    /// by.
+    pub(super) async fn setup(spec: &LocalUdsInstanceSpec) -> Result<()> {
+        let cgroup_root = &spec.sandbox.cgroup_root;
+        fs::create_dir_all(cgroup_root)
+            .await
+            .map_err(LocalUdsInstanceError::SandboxCgroup)?;
+        fs::write(
+            cgroup_root.join("cgroup.subtree_control"),
+            CGROUP_CONTROLLERS,
+        )
+        .await
+        .map_err(LocalUdsInstanceError::SandboxCgroup)?;
+
+        Ok(())
+    }
+
+    /// Creates the instance's cgroup with its limits.
+    pub(super) async fn prepare(spec: &LocalUdsInstanceSpec, id: u32) -> Result<()> {
+        let sandbox = &spec.sandbox;
+        let cgroup = cgroup_for(sandbox, id);
+        fs::create_dir(&cgroup)
+            .await
+            .map_err(LocalUdsInstanceError::SandboxCgroup)?;
+
+        let mut limits = Vec::new();
+        if let Some(memory_max_bytes) = sandbox.memory_max_bytes {
+            limits.push(("memory.max", memory_max_bytes.to_string()));
+        }
+        if let Some(cpu_max_percent) = sandbox.cpu_max_percent {
+            let quota = u64::from(cpu_max_percent) * CPU_PERIOD_MICROS / 100;
+            limits.push(("cpu.max", format!("{quota} {CPU_PERIOD_MICROS}")));
+        }
+        if let Some(pids_max) = sandbox.pids_max {
+            limits.push(("pids.max", pids_max.to_string()));
+        }
+        for (file, limit) in limits {
+            fs::write(cgroup.join(file), limit)
+                .await
+                .map_err(LocalUdsInstanceError::SandboxCgroup)?;
+        }
+
+        Ok(())
+    }
+
+    /// Kills anything left in the instance's cgroup and removes it.
+    pub(super) async fn clean(spec: &LocalUdsInstanceSpec, id: u32) -> Result<()> {
+        remove_cgroup(&cgroup_for(&spec.sandbox, id)).await
+    }
+}
+
+#[async_trait]
+impl LocalInstanceRuntime for LocalSandboxRuntime {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn socket(&mut self) -> PathBuf {
+        self.socket.to_path_buf()
+    }
+
+    async fn spawn(&mut self) -> Result<()> {
+        self.child = Some(
+            self.cmd
+                .spawn()
+                .map_err(LocalUdsInstanceError::ChildSpawn)?,
+        );
+        Ok(())
+    }
+
+    async fn terminate(&mut self) -> Result<()> {
+        // The spawned child is the sandbox's init shim; the server is sent `SIGTERM` when it
+        // exits, and anything still running afterwards is killed along with the cgroup
+        if let Some(child) = self.child.as_mut() {
+            process::child_shutdown(child, Some(process::Signal::SIGTERM), None).await?;
+        }
+        remove_cgroup(&self.cgroup).await
+    }
+}
+
+fn cgroup_for(sandbox: &LocalUdsSandboxSpec, id: u32) -> PathBuf {
+    sandbox.cgroup_root.join(format!("cyclone-{id}"))
+}
+
+async fn remove_cgroup(cgroup: &Path) -> Result<()> {
+    if !fs::try_exists(cgroup)
+        .await
+        .map_err(LocalUdsInstanceError::SandboxCgroup)?
+    {
+        return Ok(());
+    }
+
+    if let Err(err) = fs::write(cgroup.join("cgroup.kill"), "1").await {
+        // `cgroup.kill` is only in Linux 5.14 and later
+        warn!(error = ?err, cgroup = %cgroup.display(), "failed to kill sandbox cgroup");
+    }
+
+    // The cgroup can only be removed once its processes have exited
+    let mut attempts = 0;
+    loop {
+        match fs::remove_dir(cgroup).await {
+            Ok(()) => return Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) if attempts < CGROUP_REMOVE_ATTEMPTS => {
+                debug!(error = ?err, cgroup = %cgroup.display(), "sandbox cgroup still busy");
+                attempts += 1;
+                time::sleep(Duration::from_millis(10)).await;
+            }
+            Err(err) => return Err(LocalUdsInstanceError::SandboxCgroup(err)),
+        }
+    }
+}
+
+/// Everything [`enter_sandbox`] needs, prepared before forking.
+struct SandboxSetup {
+    cgroup_procs: PathBuf,
+    clone_flags: CloneFlags,
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+    read_only_root: bool,
+    writable_paths: Vec<PathBuf>,
+    /// Each masked path, along with whether it is a directory.
+    masked_paths: Vec<(PathBuf, bool)>,
+}
+
+/// Moves the forked child into the sandbox, just before it `exec`s the server.
+fn enter_sandbox(setup: &SandboxSetup) -> io::Result<()> {
+    // Joining the cgroup has to happen while it is still writable by our uid
+    write_file(&setup.cgroup_procs, b"0")?;
+
+    unshare(setup.clone_flags)?;
+    write_file(Path::new("/proc/self/setgroups"), b"deny")?;
+    write_file(Path::new("/proc/self/uid_map"), &setup.uid_map)?;
+    write_file(Path::new("/proc/self/gid_map"), &setup.gid_map)?;
+
+    // Keep the mounts made below from propagating back to the host
+    mount(
+        None::<&str>,
+        "/",
+        None::<&str>,
+        MsFlags::MS_REC | MsFlags::MS_PRIVATE,
+        None::<&str>,
+    )?;
+
+    // Only processes forked after joining a PID namespace are in it, so fork the server off and
+    // stay behind as a shim that exits with it
+    // SAFETY: the child is single threaded, having just been forked itself.
+    if let ForkResult::Parent { child } = unsafe { fork() }? {
+        // The shim must not hold on to the pipe the spawning process waits on for `exec`, or
+        // the spawn would not return until the server exits
+        // SAFETY: `close_range` only closes file descriptors, none of which the shim uses.
+        unsafe { libc::syscall(libc::SYS_close_range, 3, libc::c_uint::MAX, 0) };
+        let code = loop {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Exited(_, code)) => break code,
+                Ok(WaitStatus::Signaled(_, signal, _)) => break 128 + signal as i32,
+                Ok(_) | Err(nix::errno::Errno::EINTR) => continue,
+                Err(_) => break 1,
+            }
+        };
+        // SAFETY: exits without running anything that belongs to the parent process.
+        unsafe { libc::_exit(code) };
+    }
+
+    // Stop the server when the shim is stopped, which is what gets signalled on terminate
+    // SAFETY: `prctl` with `PR_SET_PDEATHSIG` only reads its integer arguments.
+    if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // A fresh `/proc` only shows the sandbox's own processes
+    mount(
+        Some("proc"),
+        "/proc",
+        Some("proc"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        None::<&str>,
+    )?;
+
+    if setup.read_only_root {
+        set_mount_attributes(Path::new("/"), MOUNT_ATTR_RDONLY, 0)?;
+        for path in &setup.writable_paths {
+            // Bind mounts inherit the read only attribute, so it is cleared afterwards
+            mount(
+                Some(path.as_path()),
+                path.as_path(),
+                None::<&str>,
+                MsFlags::MS_BIND | MsFlags::MS_REC,
+                None::<&str>,
+            )?;
+            set_mount_attributes(path, 0, MOUNT_ATTR_RDONLY)?;
+        }
+    }
+
+    // Masked last, so that they stay read only even beneath a writable path
+    for (path, is_dir) in &setup.masked_paths {
+        if *is_dir {
+            mount(
+                Some("tmpfs"),
+                path.as_path(),
+                Some("tmpfs"),
+                MsFlags::MS_RDONLY | MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+                None::<&str>,
+            )?;
+        } else {
+            mount(
+                Some("/dev/null"),
+                path.as_path(),
+                None::<&str>,
+                MsFlags::MS_BIND,
+                None::<&str>,
+            )?;
+            set_mount_attributes(path, MOUNT_ATTR_RDONLY, 0)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let fd = open(path, OFlag::O_WRONLY | OFlag::O_CLOEXEC, Mode::empty())?;
+    let result = write(fd, contents);
+    close(fd)?;
+    result?;
+    Ok(())
+}
+
+/// The `struct mount_attr` argument of `mount_setattr(2)`.
+#[repr(C)]
+struct MountAttr {
+    attr_set: u64,
+    attr_clr: u64,
+    propagation: u64,
+    userns_fd: u64,
+}
+
+/// Sets and clears attributes on the mount at the path and every mount beneath it.
+fn set_mount_attributes(path: &Path, attr_set: u64, attr_clr: u64) -> io::Result<()> {
+    // `mount_setattr` needs a NUL terminated path, built without allocating
+    let bytes = path.as_os_str().as_bytes();
+    let mut buffer = [0u8; libc::PATH_MAX as usize];
+    if bytes.len() >= buffer.len() {
+        return Err(io::Error::from_raw_os_error(libc::ENAMETOOLONG));
+    }
+    buffer[..bytes.len()].copy_from_slice(bytes);
+
+    let attr = MountAttr {
+        attr_set,
+        attr_clr,
+        propagation: 0,
+        userns_fd: 0,
+    };
+    // SAFETY: the path is NUL terminated and the attributes outlive the call.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_mount_setattr,
+            libc::AT_FDCWD,
+            buffer.as_ptr(),
+            AT_RECURSIVE,
+            &attr as *const MountAttr,
+            std::mem::size_of::<MountAttr>(),
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, os::unix::process::CommandExt as _};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A cgroup v2 directory delegated to the user running the tests, which must not contain the
+    /// tests' own process.
+    const ENV_VAR_CGROUP_ROOT: &str = "SI_TEST_SANDBOX_CGROUP_ROOT";
+
+    const MEMORY_MAX_BYTES: u64 = 32 * 1024 * 1024;
+    const PIDS_MAX: u32 = 16;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns the delegated cgroup root, or none when the host can't run sandboxes.
+    #[allow(clippy::disallowed_methods)]
+    fn sandbox_cgroup_root() -> Option<PathBuf> {
+        let Some(cgroup_root) = env::var_os(ENV_VAR_CGROUP_ROOT) else {
+            eprintln!("skipping sandbox test: {ENV_VAR_CGROUP_ROOT} is not set");
+            return None;
+        };
+
+        // Unprivileged user namespaces can be disabled by a sysctl or a seccomp filter
+        let mut probe = std::process::Command::new("true");
+        // SAFETY: the closure only makes a system call.
+        unsafe {
+            probe.pre_exec(|| Ok(unshare(CloneFlags::CLONE_NEWUSER)?));
+        }
+        if !probe.status().is_ok_and(|status| status.success()) {
+            eprintln!("skipping sandbox test: unprivileged user namespaces are not available");
+            return None;
+        }
+
+        Some(PathBuf::from(cgroup_root))
+    }
+
+    async fn sandbox_spec(sandbox: LocalUdsSandboxSpec) -> Option<LocalUdsInstanceSpec> {
+        let spec = LocalUdsInstanceSpec {
+            sandbox,
+            ..Default::default()
+        };
+        if let Err(err) = LocalSandboxRuntime::setup(&spec).await {
+            eprintln!("skipping sandbox test: the cgroup root is not delegated: {err}");
+            return None;
+        }
+
+        Some(spec)
+    }
+
+    /// Runs the script in a sandbox, as the Cyclone server would be.
+    async fn spawn_script(
+        spec: &LocalUdsInstanceSpec,
+        socket_dir: &TempDir,
+        script: &str,
+    ) -> LocalSandboxRuntime {
+        let id = rand::random();
+        LocalSandboxRuntime::prepare(spec, id)
+            .await
+            .expect("could not prepare cgroup");
+
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c").arg(script);
+        let socket = socket_dir.path().join("cyclone.sock");
+        let mut runtime = LocalSandboxRuntime::new(cmd, &socket, spec, id).await;
+        runtime.spawn().await.expect("could not spawn sandbox");
+
+        runtime
+    }
+
+    /// Reads a counter from one of a cgroup's `*.events` files.
+    async fn cgroup_event(cgroup: &Path, file: &str, event: &str) -> u64 {
+        fs::read_to_string(cgroup.join(file))
+            .await
+            .expect("could not read cgroup events")
+            .lines()
+            .find_map(|line| line.strip_prefix(event)?.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn sandbox_isolates_instances() {
+        let Some(cgroup_root) = sandbox_cgroup_root() else {
+            return;
+        };
+        let socket_dir = TempDir::new().expect("could not create temp dir");
+        let read_only = TempDir::new().expect("could not create temp dir");
+        let writable = TempDir::new().expect("could not create temp dir");
+        let secrets = TempDir::new().expect("could not create temp dir");
+        let key_file = secrets.path().join("decryption.key");
+        std::fs::write(&key_file, "secret").expect("could not write key file");
+        let creds_dir = secrets.path().join("creds");
+        std::fs::create_dir(&creds_dir).expect("could not create creds dir");
+        std::fs::write(creds_dir.join("nats.creds"), "secret").expect("could not write creds");
+
+        let Some(spec) = sandbox_spec(LocalUdsSandboxSpec {
+            cgroup_root,
+            allow_network: false,
+            read_only_root: true,
+            writable_paths: vec![writable.path().to_path_buf()],
+            masked_paths: vec![key_file.clone(), creds_dir.clone()],
+            ..Default::default()
+        })
+        .await
+        else {
+            return;
+        };
+
+        // Each check exits with its own code, so a failure says which one it was
+        let script = format!(
+            r#"
+            # Without a network there are no routes, not even through the loopback interface
+            test "$(wc -l < /proc/net/route)" -eq 1 || exit 10
+            if touch "{read_only}/file" 2>/dev/null; then exit 11; fi
+            touch "{writable}/file" || exit 12
+            test ! -s "{key_file}" || exit 13
+            test ! -e "{creds_dir}/nats.creds" || exit 14
+            "#,
+            read_only = read_only.path().display(),
+            writable = writable.path().display(),
+            key_file = key_file.display(),
+            creds_dir = creds_dir.display(),
+        );
+        let mut runtime = spawn_script(&spec, &socket_dir, &script).await;
+        let status = time::timeout(
+            TIMEOUT,
+            runtime.child.as_mut().expect("no sandbox child").wait(),
+        )
+        .await
+        .expect("hit timeout before the sandbox exited")
+        .expect("could not wait for the sandbox");
+        runtime.terminate().await.expect("could not terminate");
+
+        assert_eq!(Some(0), status.code());
+        assert!(writable.path().join("file").exists());
+        assert!(!read_only.path().join("file").exists());
+        assert_eq!(
+            "secret",
+            std::fs::read_to_string(&key_file).expect("could not read key file")
+        );
+    }
+
+    #[tokio::test]
+    async fn sandbox_limits_instances_and_terminate_removes_cgroup() {
+        let Some(cgroup_root) = sandbox_cgroup_root() else {
+            return;
+        };
+        let socket_dir = TempDir::new().expect("could not create temp dir");
+        let Some(spec) = sandbox_spec(LocalUdsSandboxSpec {
+            cgroup_root,
+            memory_max_bytes: Some(MEMORY_MAX_BYTES),
+            pids_max: Some(PIDS_MAX),
+            ..Default::default()
+        })
+        .await
+        else {
+            return;
+        };
+
+        // `tail` holds on to its whole input while looking for a line ending, and the loop
+        // forks until it can't
+        let script = r#"
+            head -c 268435456 /dev/zero | tail > /dev/null &
+            while :; do sleep 60 & done
+        "#;
+        let mut runtime = spawn_script(&spec, &socket_dir, script).await;
+        let cgroup = runtime.cgroup.clone();
+
+        let start = time::Instant::now();
+        while cgroup_event(&cgroup, "memory.events", "max").await == 0
+            || cgroup_event(&cgroup, "pids.events", "max").await == 0
+        {
+            assert!(
+                start.elapsed() < TIMEOUT,
+                "hit timeout before the sandbox reached its limits"
+            );
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(
+            fs::read_to_string(cgroup.join("memory.current"))
+                .await
+                .expect("could not read memory usage")
+                .trim()
+                .parse::<u64>()
+                .expect("could not parse memory usage")
+                <= MEMORY_MAX_BYTES
+        );
+
+        runtime.terminate().await.expect("could not terminate");
+        assert!(!cgroup.exists());
+    }
+}
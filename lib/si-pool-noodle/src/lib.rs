@@ -22,12 +22,12 @@ pub use cyclone_client::{ClientError, CycloneClient, ExecutionError};
 
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentView, CycloneRequest,
-    CycloneRequestable, FunctionResult, FunctionResultFailure, FunctionResultFailureError,
-    FunctionResultFailureErrorKind, KillExecutionRequest, ManagementRequest,
-    ManagementResultSuccess, OutputStream, ProgressMessage, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, SensitiveStrings, ValidationRequest,
-    ValidationResultSuccess,
+    CycloneRequestable, FuncRuntime, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, FunctionResultFailureErrorKind, KillExecutionRequest,
+    ManagementRequest, ManagementResultSuccess, OutputStream, ProgressMessage,
+    ResolverFunctionRequest, ResolverFunctionResultSuccess, ResourceStatus,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveStrings,
+    ValidationRequest, ValidationResultSuccess,
 };
 
 /// [`PoolNoodleError`] implementations.
@@ -37,6 +37,8 @@ pub mod instance;
 mod lifeguard;
 /// [`PoolNoodle`] implementations.
 pub mod pool_noodle;
+/// Demand-driven sizing for [`PoolNoodle`]s.
+pub mod scaling;
 mod task;
 
 #[cfg(test)]
@@ -3,18 +3,21 @@ use crate::task::{PoolNoodleTask, PoolNoodleTaskType};
 use crossbeam_queue::ArrayQueue;
 use std::fmt::Display;
 use std::result;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use telemetry_utils::metric;
 use tokio::sync::Mutex;
+use tokio::sync::Notify;
 use tokio::sync::Semaphore;
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 
 use tokio::sync::mpsc::{self, Receiver, Sender};
-use tokio::time::{timeout, Duration};
+use tokio::time::{timeout, Duration, Instant};
 use tracing::{debug, info, warn};
 
 use crate::errors::PoolNoodleError;
+use crate::scaling::{PoolNoodleScaling, PoolNoodleStats, ScalingDecision, ScalingPolicy};
 use crate::{Instance, Spec};
 
 /// [`pool_noodle`] implementations.
@@ -68,8 +71,11 @@ pub struct PoolNoodleConfig<S> {
     pub max_concurrency: u32,
     /// Maximum number of instances to manage at once
     pub pool_size: u32,
-    /// Number of attempts to get from the pool before giving up with 10 ms between attempts
+    /// Number of attempts to get from the pool before giving up, each waiting up to 100 ms for an
+    /// instance to become ready
     pub retry_limit: u32,
+    /// How the pool grows and shrinks with demand, up to `pool_size` instances
+    pub scaling: PoolNoodleScaling,
     /// Shuts down the pool management tasks
     pub shutdown_token: CancellationToken,
     /// The spec for the type of instance to manage
@@ -86,6 +92,7 @@ where
             max_concurrency: 1000,
             pool_size: 100,
             retry_limit: 120, // * 100ms between tries, we will try for 2 minutes before giving up
+            scaling: PoolNoodleScaling::default(),
             shutdown_token: CancellationToken::new(),
             spec: S::default(),
         }
@@ -156,17 +163,25 @@ where
             }
         });
 
-        // start by cleaning jails just to make sure
+        // start by cleaning jails just to make sure, then leave them idle until there is demand
         let inner = self.inner();
         tokio::spawn(async move {
             for id in 1..=inner.pool_size {
-                inner.push_clean_task_to_work_queue(id).await;
+                inner.push_park_task_to_work_queue(id).await;
             }
         });
 
+        let inner = self.inner();
+        tokio::spawn(async move { inner.scale().await });
+
         Ok(())
     }
 
+    /// Returns a snapshot of the pool's instances and the demand for them.
+    pub fn stats(&self) -> PoolNoodleStats {
+        self.0.stats()
+    }
+
     fn inner(&self) -> Arc<PoolNoodleInner<I, S>> {
         Arc::clone(&self.0)
     }
@@ -177,6 +192,8 @@ where
     pub async fn get(&self) -> Result<LifeGuard<I, E, S>, E> {
         metric!(counter.pool_noodle.get_requests = 1);
         let inner = self.inner();
+        let started_at = Instant::now();
+        let mut waiting = None;
 
         let max_retries = self.inner().retry_limit; // Set the maximum number of retries
         let mut retries = 0;
@@ -190,8 +207,13 @@ where
                 // Try to ensure the item is healthy
                 match &mut instance.ensure_healthy().await {
                     Ok(_) => {
+                        inner.in_use.fetch_add(1, Ordering::Relaxed);
                         metric!(counter.pool_noodle.get_requests = -1);
                         metric!(counter.pool_noodle.active = 1);
+                        metric!(
+                            histogram.pool_noodle.get_wait_ms =
+                                u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX)
+                        );
                         return Ok(LifeGuard::new(
                             Some(instance),
                             inner.queue_tx.clone(),
@@ -200,6 +222,7 @@ where
                     }
                     Err(_) => {
                         debug!("PoolNoodle: not healthy, cleaning up and getting a new one.");
+                        inner.live.fetch_sub(1, Ordering::Relaxed);
                         drop(instance);
                     }
                 }
@@ -209,7 +232,11 @@ where
                     "Failed to get from pool, retry ({} of {})",
                     retries, max_retries
                 );
-                sleep(Duration::from_millis(100)).await;
+                // Let the scaler know right away that a caller is waiting, rather than on its
+                // next check
+                waiting.get_or_insert_with(|| WaitingGuard::new(&inner.waiting));
+                inner.demand_notify.notify_one();
+                let _ = timeout(Duration::from_millis(100), inner.ready_notify.notified()).await;
             }
         }
     }
@@ -251,10 +278,19 @@ where
     pool_size: u32,
     ready_queue: ArrayQueue<I>,
     retry_limit: u32,
+    scaling: PoolNoodleScaling,
     shutdown_token: CancellationToken,
     spec: S,
     queue_rx: Mutex<Receiver<PoolNoodleTaskType<I, S>>>,
     queue_tx: Sender<PoolNoodleTaskType<I, S>>,
+    /// Ids of the instances which are not running, available to scale up with
+    idle_ids: ArrayQueue<u32>,
+    in_use: AtomicU32,
+    live: AtomicU32,
+    target: AtomicU32,
+    waiting: AtomicU32,
+    demand_notify: Notify,
+    ready_notify: Notify,
 }
 
 impl<I, E, S> PoolNoodleInner<I, S>
@@ -275,10 +311,78 @@ where
             pool_size: config.pool_size,
             ready_queue: ArrayQueue::new(config.pool_size as usize),
             retry_limit: config.retry_limit,
+            scaling: config.scaling,
             shutdown_token: config.shutdown_token,
             spec: config.spec,
             queue_rx: queue_rx.into(),
             queue_tx,
+            idle_ids: ArrayQueue::new(config.pool_size as usize),
+            in_use: AtomicU32::new(0),
+            live: AtomicU32::new(0),
+            target: AtomicU32::new(0),
+            waiting: AtomicU32::new(0),
+            demand_notify: Notify::new(),
+            ready_notify: Notify::new(),
+        }
+    }
+
+    fn stats(&self) -> PoolNoodleStats {
+        PoolNoodleStats {
+            in_use: self.in_use.load(Ordering::Relaxed),
+            ready: self.ready_queue.len() as u32,
+            live: self.live.load(Ordering::Relaxed),
+            waiting: self.waiting.load(Ordering::Relaxed),
+            target: self.target.load(Ordering::Relaxed),
+        }
+    }
+
+    // brings instances up or shuts idle ones down to meet demand, whenever a caller has to wait
+    // for an instance and otherwise at a fixed interval
+    async fn scale(self: Arc<Self>) {
+        let mut policy = ScalingPolicy::new(self.scaling, self.pool_size);
+        let mut last_stats = PoolNoodleStats::default();
+        loop {
+            tokio::select! {
+                _ = self.shutdown_token.cancelled() => {
+                    debug!("scaler received cancellation");
+                    break;
+                }
+                _ = self.demand_notify.notified() => {}
+                _ = sleep(self.scaling.interval) => {}
+            }
+
+            let mut stats = self.stats();
+            stats.target = policy.target(&stats);
+            self.target.store(stats.target, Ordering::Relaxed);
+
+            match policy.decide(&stats, Instant::now()) {
+                ScalingDecision::Grow(count) => {
+                    for _ in 0..count {
+                        let Some(id) = self.idle_ids.pop() else {
+                            break;
+                        };
+                        self.live.fetch_add(1, Ordering::Relaxed);
+                        self.push_clean_task_to_work_queue(id).await;
+                    }
+                }
+                ScalingDecision::Shrink(count) => {
+                    for _ in 0..count {
+                        let Some(instance) = self.ready_queue.pop() else {
+                            break;
+                        };
+                        metric!(counter.pool_noodle.ready = -1);
+                        self.live.fetch_sub(1, Ordering::Relaxed);
+                        self.push_retire_task_to_work_queue(instance).await;
+                    }
+                }
+                ScalingDecision::Hold => {}
+            }
+
+            let stats = self.stats();
+            metric!(counter.pool_noodle.live = delta(last_stats.live, stats.live));
+            metric!(counter.pool_noodle.target = delta(last_stats.target, stats.target));
+            metric!(counter.pool_noodle.waiting = delta(last_stats.waiting, stats.waiting));
+            last_stats = stats;
         }
     }
 
@@ -286,27 +390,43 @@ where
         match task_type {
             PoolNoodleTaskType::Clean(task) => self.handle_clean(task).await,
             PoolNoodleTaskType::Drop(task) => self.handle_drop(task).await,
+            PoolNoodleTaskType::Park(task) => self.handle_park(task).await,
             PoolNoodleTaskType::Prepare(task) => self.handle_prepare(task).await,
+            PoolNoodleTaskType::Retire(task) => self.handle_retire(task).await,
         }
     }
 
-    // clean instances with backoff to handle intermittent failures. If an instance fails enough it
-    // will be abandoned
     async fn handle_clean(&self, task: PoolNoodleTask<I, S>) {
         metric!(counter.pool_noodle.task.clean = -1);
+        if self.clean_with_backoff(&task).await {
+            self.push_prepare_task_to_work_queue(task.id()).await;
+        } else {
+            // parking gives cleaning another go, so that the id isn't lost to the pool
+            self.live.fetch_sub(1, Ordering::Relaxed);
+            self.push_park_task_to_work_queue(task.id()).await;
+        }
+    }
+
+    async fn handle_park(&self, task: PoolNoodleTask<I, S>) {
+        metric!(counter.pool_noodle.task.park = -1);
+        if self.clean_with_backoff(&task).await && self.idle_ids.push(task.id()).is_err() {
+            warn!("failed to park instance: {}", task.id());
+        }
+    }
+
+    // clean instances with backoff to handle intermittent failures. If an instance fails enough it
+    // will be abandoned
+    async fn clean_with_backoff(&self, task: &PoolNoodleTask<I, S>) -> bool {
         let id = task.id();
         let max_retries = 5;
         let mut attempts = 0;
         loop {
             match task.clean().await {
-                Ok(_) => {
-                    self.push_prepare_task_to_work_queue(id).await;
-                    break;
-                }
+                Ok(_) => return true,
                 Err(e) => {
                     if attempts >= max_retries {
                         warn!("Failed to clean instance {} after {} attempts. Abandoning this instance", id, max_retries);
-                        break;
+                        return false;
                     }
                     warn!("PoolNoodle: failed to clean instance: {}", id);
                     warn!("{}", e);
@@ -320,6 +440,7 @@ where
 
     async fn handle_drop(&self, task: PoolNoodleTask<I, S>) {
         metric!(counter.pool_noodle.task.drop = -1);
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
         let id = task.id();
         match task.terminate().await {
             Ok(_) => {
@@ -328,10 +449,26 @@ where
             Err(e) => {
                 warn!("PoolNoodle: failed to drop instance: {}", id);
                 warn!("{}", e);
+                // as when retiring, parking cleans up whatever the instance left behind and
+                // returns its id to the idle ids
+                self.live.fetch_sub(1, Ordering::Relaxed);
+                self.push_park_task_to_work_queue(id).await;
             }
         }
     }
 
+    async fn handle_retire(&self, task: PoolNoodleTask<I, S>) {
+        metric!(counter.pool_noodle.task.retire = -1);
+        let id = task.id();
+        if let Err(e) = task.terminate().await {
+            warn!("PoolNoodle: failed to retire instance: {}", id);
+            warn!("{}", e);
+        }
+        // parking cleans up whatever the instance left behind, so its id goes back to the idle
+        // ids even when it failed to terminate
+        self.push_park_task_to_work_queue(id).await;
+    }
+
     async fn handle_prepare(&self, task: PoolNoodleTask<I, S>) {
         metric!(counter.pool_noodle.task.prepare = -1);
         let id = task.id();
@@ -370,31 +507,80 @@ where
         metric!(counter.pool_noodle.task.prepare = 1);
     }
 
+    async fn push_park_task_to_work_queue(&self, id: u32) {
+        let task = PoolNoodleTaskType::Park(PoolNoodleTask::new(None, id, self.spec.clone()));
+        if self.queue_tx.send(task).await.is_err() {
+            warn!("failed to push instance to park: {}", id);
+        };
+        metric!(counter.pool_noodle.task.park = 1);
+    }
+
+    async fn push_retire_task_to_work_queue(&self, instance: I) {
+        let id = instance.id();
+        let task =
+            PoolNoodleTaskType::Retire(PoolNoodleTask::new(Some(instance), id, self.spec.clone()));
+        if self.queue_tx.send(task).await.is_err() {
+            warn!("failed to push instance to retire: {}", id);
+        };
+        metric!(counter.pool_noodle.task.retire = 1);
+    }
+
     async fn push_to_ready_queue(&self, instance: I) {
         let id = instance.id();
         if self.ready_queue.push(instance).is_err() {
             warn!("failed to push to ready queue: {}", id);
         }
         metric!(counter.pool_noodle.ready = 1);
+        self.ready_notify.notify_one();
+    }
+}
+
+/// Counts a caller of [`PoolNoodle::get`] as waiting for as long as it is held.
+struct WaitingGuard<'a>(&'a AtomicU32);
+
+impl<'a> WaitingGuard<'a> {
+    fn new(waiting: &'a AtomicU32) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
     }
 }
 
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn delta(last: u32, current: u32) -> i64 {
+    i64::from(current) - i64::from(last)
+}
+
 #[cfg(test)]
 mod tests {
 
     use std::fmt::{self, Formatter};
+    use std::sync::atomic::AtomicBool;
 
     use crate::instance::SpecBuilder;
     use async_trait::async_trait;
     use derive_builder::Builder;
+    use futures::future::join_all;
     use tokio::time::{sleep, Duration};
 
     use super::*;
 
-    pub struct DummyInstance {}
+    pub struct DummyInstance {
+        id: u32,
+        terminated: Arc<AtomicU32>,
+        fail_terminate: Arc<AtomicBool>,
+    }
 
-    #[derive(Clone)]
-    pub struct DummyInstanceSpec {}
+    #[derive(Clone, Default)]
+    pub struct DummyInstanceSpec {
+        spawned: Arc<AtomicU32>,
+        terminated: Arc<AtomicU32>,
+        fail_terminate: Arc<AtomicBool>,
+    }
     #[async_trait]
     impl Spec for DummyInstanceSpec {
         type Instance = DummyInstance;
@@ -410,8 +596,13 @@ mod tests {
             Ok(())
         }
 
-        async fn spawn(&self, _id: u32) -> result::Result<Self::Instance, Self::Error> {
-            Ok(DummyInstance {})
+        async fn spawn(&self, id: u32) -> result::Result<Self::Instance, Self::Error> {
+            self.spawned.fetch_add(1, Ordering::Relaxed);
+            Ok(DummyInstance {
+                id,
+                terminated: self.terminated.clone(),
+                fail_terminate: self.fail_terminate.clone(),
+            })
         }
     }
     #[derive(Builder, Default, Clone)]
@@ -421,7 +612,7 @@ mod tests {
         type Error = DummyInstanceError;
 
         fn build(&self) -> result::Result<Self::Spec, Self::Error> {
-            Ok(DummyInstanceSpec {})
+            Ok(DummyInstanceSpec::default())
         }
     }
     #[derive(Debug)]
@@ -438,6 +629,10 @@ mod tests {
         type Error = DummyInstanceError;
 
         async fn terminate(&mut self) -> result::Result<(), Self::Error> {
+            if self.fail_terminate.load(Ordering::Relaxed) {
+                return Err(DummyInstanceError {});
+            }
+            self.terminated.fetch_add(1, Ordering::Relaxed);
             Ok(())
         }
 
@@ -446,20 +641,32 @@ mod tests {
         }
 
         fn id(&self) -> u32 {
-            0
+            self.id
+        }
+    }
+
+    async fn eventually(description: &str, condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for {description}"
+            );
+            sleep(Duration::from_millis(10)).await;
         }
     }
     #[tokio::test]
     async fn pool_noodle_lifecycle() {
         let shutdown_token = CancellationToken::new();
 
-        let spec = DummyInstanceSpec {};
+        let spec = DummyInstanceSpec::default();
 
         let config = PoolNoodleConfig {
             check_health: false,
             max_concurrency: 10,
             pool_size: 3,
             retry_limit: 3,
+            scaling: PoolNoodleScaling::default(),
             shutdown_token: shutdown_token.clone(),
             spec,
         };
@@ -482,4 +689,170 @@ mod tests {
         shutdown_token.cancel();
         assert!(pool.get().await.is_err());
     }
+
+    #[tokio::test]
+    async fn pool_noodle_scales_with_demand() {
+        let shutdown_token = CancellationToken::new();
+
+        let spec = DummyInstanceSpec::default();
+        let spawned = spec.spawned.clone();
+        let terminated = spec.terminated.clone();
+
+        let config = PoolNoodleConfig {
+            check_health: false,
+            max_concurrency: 10,
+            pool_size: 8,
+            retry_limit: 50,
+            scaling: PoolNoodleScaling {
+                min_size: 2,
+                warm_buffer: 1,
+                cool_down: Duration::from_millis(200),
+                interval: Duration::from_millis(10),
+            },
+            shutdown_token: shutdown_token.clone(),
+            spec,
+        };
+        let mut pool = PoolNoodle::new(config).await;
+        pool.run().expect("failed to start");
+
+        // with no demand, only the minimum and the warm buffer are brought up
+        eventually("the pool to warm up", || pool.stats().ready == 3).await;
+        assert_eq!(3, pool.stats().live);
+        assert_eq!(3, spawned.load(Ordering::Relaxed));
+
+        // a burst larger than the warm pool grows it, up to the pool size
+        let instances: Vec<_> = join_all((0..6).map(|_| pool.get()))
+            .await
+            .into_iter()
+            .map(|instance| instance.expect("should be able to get an instance"))
+            .collect();
+        eventually("the pool to grow", || {
+            let stats = pool.stats();
+            stats.in_use == 6 && stats.live == 7 && stats.target == 7
+        })
+        .await;
+
+        let more: Vec<_> = join_all((0..2).map(|_| pool.get()))
+            .await
+            .into_iter()
+            .map(|instance| instance.expect("should be able to get an instance"))
+            .collect();
+        eventually("the pool to reach its size", || pool.stats().live == 8).await;
+        assert_eq!(8, pool.stats().target);
+
+        // once the burst is over, idle instances are shut down after the cool down
+        drop(instances);
+        drop(more);
+        eventually("the pool to shrink", || {
+            let stats = pool.stats();
+            stats.in_use == 0 && stats.live == 3 && stats.ready == 3
+        })
+        .await;
+        assert!(terminated.load(Ordering::Relaxed) >= 5);
+
+        shutdown_token.cancel();
+    }
+
+    #[tokio::test]
+    async fn pool_noodle_reuses_instances_that_failed_to_retire() {
+        let shutdown_token = CancellationToken::new();
+
+        let spec = DummyInstanceSpec::default();
+        let fail_terminate = spec.fail_terminate.clone();
+
+        let config = PoolNoodleConfig {
+            check_health: false,
+            max_concurrency: 10,
+            pool_size: 4,
+            retry_limit: 50,
+            scaling: PoolNoodleScaling {
+                min_size: 1,
+                warm_buffer: 0,
+                cool_down: Duration::from_millis(500),
+                interval: Duration::from_millis(10),
+            },
+            shutdown_token: shutdown_token.clone(),
+            spec,
+        };
+        let mut pool = PoolNoodle::new(config).await;
+        pool.run().expect("failed to start");
+
+        let instances: Vec<_> = join_all((0..4).map(|_| pool.get()))
+            .await
+            .into_iter()
+            .map(|instance| instance.expect("should be able to get an instance"))
+            .collect();
+        drop(instances);
+        eventually("the pool to fill up", || pool.stats().ready == 4).await;
+
+        // every instance fails to terminate as the pool shrinks
+        fail_terminate.store(true, Ordering::Relaxed);
+        eventually("the pool to shrink", || pool.stats().live == 1).await;
+
+        // none of their ids were lost, so the pool can still grow to its size
+        let instances: Vec<_> =
+            timeout(Duration::from_secs(5), join_all((0..4).map(|_| pool.get())))
+                .await
+                .expect("timed out waiting for the pool to grow")
+                .into_iter()
+                .map(|instance| instance.expect("should be able to get an instance"))
+                .collect();
+        assert_eq!(4, pool.stats().live);
+
+        drop(instances);
+        shutdown_token.cancel();
+    }
+
+    #[tokio::test]
+    async fn pool_noodle_reuses_used_instances_that_failed_to_terminate() {
+        let shutdown_token = CancellationToken::new();
+
+        let spec = DummyInstanceSpec::default();
+        let fail_terminate = spec.fail_terminate.clone();
+
+        let config = PoolNoodleConfig {
+            check_health: false,
+            max_concurrency: 10,
+            pool_size: 2,
+            retry_limit: 50,
+            scaling: PoolNoodleScaling {
+                min_size: 0,
+                warm_buffer: 0,
+                cool_down: Duration::from_secs(60),
+                interval: Duration::from_millis(10),
+            },
+            shutdown_token: shutdown_token.clone(),
+            spec,
+        };
+        let mut pool = PoolNoodle::new(config).await;
+        pool.run().expect("failed to start");
+
+        let instances: Vec<_> = join_all((0..2).map(|_| pool.get()))
+            .await
+            .into_iter()
+            .map(|instance| instance.expect("should be able to get an instance"))
+            .collect();
+
+        // every used instance fails to terminate as it is dropped
+        fail_terminate.store(true, Ordering::Relaxed);
+        drop(instances);
+        eventually("the instances to be dropped", || {
+            let stats = pool.stats();
+            stats.in_use == 0 && stats.live == 0
+        })
+        .await;
+
+        // none of their ids were lost, so the pool can still grow to its size
+        let instances: Vec<_> =
+            timeout(Duration::from_secs(5), join_all((0..2).map(|_| pool.get())))
+                .await
+                .expect("timed out waiting for the pool to grow")
+                .into_iter()
+                .map(|instance| instance.expect("should be able to get an instance"))
+                .collect();
+        assert_eq!(2, pool.stats().live);
+
+        drop(instances);
+        shutdown_token.cancel();
+    }
 }
@@ -0,0 +1,265 @@
+use std::cmp;
+
+use tokio::time::{Duration, Instant};
+
+/// How a [`PoolNoodle`](crate::PoolNoodle) grows and shrinks with demand.
+///
+/// The pool aims to run enough instances for every one in use, every caller waiting on
+/// [`get`](crate::PoolNoodle::get) and a warm buffer on top, between [`Self::min_size`] and the
+/// pool's size. It grows as soon as demand rises, but only shuts idle instances down once demand
+/// has stayed lower for the whole [`Self::cool_down`].
+#[derive(Clone, Copy, Debug)]
+pub struct PoolNoodleScaling {
+    /// Fewest instances to keep running, even with no demand at all
+    pub min_size: u32,
+    /// Ready instances to keep on top of those in use and those being waited on
+    pub warm_buffer: u32,
+    /// How long demand must stay below the running instances before idle ones are shut down
+    pub cool_down: Duration,
+    /// How often the pool checks its size against demand, besides whenever a caller has to wait
+    pub interval: Duration,
+}
+
+impl Default for PoolNoodleScaling {
+    fn default() -> Self {
+        Self {
+            min_size: 10,
+            warm_buffer: 5,
+            cool_down: Duration::from_secs(60),
+            interval: Duration::from_millis(250),
+        }
+    }
+}
+
+/// A snapshot of a [`PoolNoodle`](crate::PoolNoodle)'s instances and the demand for them.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PoolNoodleStats {
+    /// Instances handed out by [`get`](crate::PoolNoodle::get) and not yet reclaimed
+    pub in_use: u32,
+    /// Instances running and ready to be handed out
+    pub ready: u32,
+    /// Instances in use, ready or being brought up
+    pub live: u32,
+    /// Callers of [`get`](crate::PoolNoodle::get) waiting for a ready instance
+    pub waiting: u32,
+    /// The number of live instances the pool is scaling towards
+    pub target: u32,
+}
+
+/// What the pool needs to do to meet demand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ScalingDecision {
+    /// Bring up this many more instances
+    Grow(u32),
+    /// Shut down this many ready instances
+    Shrink(u32),
+    Hold,
+}
+
+/// Decides how many instances a pool should run, given its [`PoolNoodleStats`] over time.
+#[derive(Debug)]
+pub(crate) struct ScalingPolicy {
+    scaling: PoolNoodleScaling,
+    max_size: u32,
+    /// When the pool started running more instances than demand needs, while it still does
+    over_target_since: Option<Instant>,
+}
+
+impl ScalingPolicy {
+    pub(crate) fn new(scaling: PoolNoodleScaling, max_size: u32) -> Self {
+        Self {
+            scaling,
+            max_size,
+            over_target_since: None,
+        }
+    }
+
+    /// The number of live instances which meets the demand in the stats.
+    pub(crate) fn target(&self, stats: &PoolNoodleStats) -> u32 {
+        let min_size = cmp::min(self.scaling.min_size, self.max_size);
+        stats
+            .in_use
+            .saturating_add(stats.waiting)
+            .saturating_add(self.scaling.warm_buffer)
+            .clamp(min_size, self.max_size)
+    }
+
+    pub(crate) fn decide(&mut self, stats: &PoolNoodleStats, now: Instant) -> ScalingDecision {
+        let target = self.target(stats);
+
+        if stats.live < target {
+            self.over_target_since = None;
+            return ScalingDecision::Grow(target - stats.live);
+        }
+        if stats.live == target {
+            self.over_target_since = None;
+            return ScalingDecision::Hold;
+        }
+
+        let over_target_since = *self.over_target_since.get_or_insert(now);
+        if now.duration_since(over_target_since) < self.scaling.cool_down {
+            return ScalingDecision::Hold;
+        }
+        // Only ready instances can be shut down; the rest are in use or still coming up
+        match cmp::min(stats.live - target, stats.ready) {
+            0 => ScalingDecision::Hold,
+            count => {
+                self.over_target_since = None;
+                ScalingDecision::Shrink(count)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ScalingPolicy {
+        ScalingPolicy::new(
+            PoolNoodleScaling {
+                min_size: 2,
+                warm_buffer: 3,
+                cool_down: Duration::from_secs(60),
+                interval: Duration::from_millis(250),
+            },
+            10,
+        )
+    }
+
+    #[test]
+    fn target_stays_between_min_and_max() {
+        let policy = policy();
+
+        assert_eq!(3, policy.target(&PoolNoodleStats::default()));
+        assert_eq!(
+            7,
+            policy.target(&PoolNoodleStats {
+                in_use: 3,
+                waiting: 1,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            10,
+            policy.target(&PoolNoodleStats {
+                in_use: 9,
+                waiting: 4,
+                ..Default::default()
+            })
+        );
+
+        let policy = ScalingPolicy::new(
+            PoolNoodleScaling {
+                min_size: 20,
+                warm_buffer: 0,
+                ..Default::default()
+            },
+            4,
+        );
+        assert_eq!(4, policy.target(&PoolNoodleStats::default()));
+    }
+
+    #[test]
+    fn grows_immediately() {
+        let mut policy = policy();
+        let now = Instant::now();
+
+        let stats = PoolNoodleStats {
+            in_use: 3,
+            ready: 0,
+            live: 3,
+            waiting: 2,
+            target: 0,
+        };
+        assert_eq!(ScalingDecision::Grow(5), policy.decide(&stats, now));
+    }
+
+    #[test]
+    fn shrinks_ready_instances_after_cool_down() {
+        let mut policy = policy();
+        let start = Instant::now();
+
+        let stats = PoolNoodleStats {
+            in_use: 1,
+            ready: 6,
+            live: 7,
+            waiting: 0,
+            target: 0,
+        };
+        assert_eq!(ScalingDecision::Hold, policy.decide(&stats, start));
+        assert_eq!(
+            ScalingDecision::Hold,
+            policy.decide(&stats, start + Duration::from_secs(59))
+        );
+        assert_eq!(
+            ScalingDecision::Shrink(3),
+            policy.decide(&stats, start + Duration::from_secs(60))
+        );
+
+        // Further shrinking waits for another cool down
+        let stats = PoolNoodleStats {
+            in_use: 0,
+            ready: 4,
+            live: 4,
+            ..stats
+        };
+        let later = start + Duration::from_secs(61);
+        assert_eq!(ScalingDecision::Hold, policy.decide(&stats, later));
+        assert_eq!(
+            ScalingDecision::Shrink(1),
+            policy.decide(&stats, later + Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn demand_during_cool_down_restarts_it() {
+        let mut policy = policy();
+        let start = Instant::now();
+
+        let idle = PoolNoodleStats {
+            ready: 6,
+            live: 6,
+            ..Default::default()
+        };
+        assert_eq!(ScalingDecision::Hold, policy.decide(&idle, start));
+
+        let busy = PoolNoodleStats {
+            in_use: 3,
+            ready: 3,
+            live: 6,
+            ..Default::default()
+        };
+        assert_eq!(
+            ScalingDecision::Hold,
+            policy.decide(&busy, start + Duration::from_secs(30))
+        );
+
+        assert_eq!(
+            ScalingDecision::Hold,
+            policy.decide(&idle, start + Duration::from_secs(60))
+        );
+        assert_eq!(
+            ScalingDecision::Shrink(3),
+            policy.decide(&idle, start + Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn only_shrinks_ready_instances() {
+        let mut policy = policy();
+        let start = Instant::now();
+
+        let stats = PoolNoodleStats {
+            in_use: 0,
+            ready: 0,
+            live: 8,
+            ..Default::default()
+        };
+        assert_eq!(ScalingDecision::Hold, policy.decide(&stats, start));
+        assert_eq!(
+            ScalingDecision::Hold,
+            policy.decide(&stats, start + Duration::from_secs(60))
+        );
+    }
+}
@@ -13,7 +13,9 @@ type Result<T, E> = result::Result<T, PoolNoodleError<E>>;
 pub(crate) enum PoolNoodleTaskType<I, S> {
     Clean(PoolNoodleTask<I, S>),
     Drop(PoolNoodleTask<I, S>),
+    Park(PoolNoodleTask<I, S>),
     Prepare(PoolNoodleTask<I, S>),
+    Retire(PoolNoodleTask<I, S>),
 }
 
 #[derive(Clone, Debug)]
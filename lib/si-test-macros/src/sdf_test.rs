@@ -29,6 +29,22 @@ pub(crate) fn expand(item: ItemFn, args: Args) -> TokenStream {
 
 fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
     let mut expander = SdfTestFnSetupExpander::new();
+    let params: Vec<_> = params.collect();
+
+    // The router is given the in-memory permissions if the test asks for them, wherever they are
+    // in the arguments
+    if params.iter().any(|param| match param {
+        FnArg::Typed(pat_type) => match &*pat_type.ty {
+            Type::Path(type_path) => path_as_string(&type_path.path)
+                .split("::")
+                .last()
+                .is_some_and(|ty_str| ty_str == "InMemoryPermissions"),
+            _ => false,
+        },
+        FnArg::Receiver(_) => false,
+    }) {
+        expander.setup_in_memory_permissions();
+    }
 
     for param in params {
         match param {
@@ -85,6 +101,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "InMemoryPermissions" => {
+                                let var = expander.setup_in_memory_permissions();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var.clone()});
+                            }
                             "Router" => {
                                 let var = expander.setup_router();
                                 let var = var.as_ref();
@@ -238,6 +259,7 @@ struct SdfTestFnSetupExpander {
     auth_token: Option<Rc<Ident>>,
     auth_token_ref: Option<Rc<Ident>>,
     spicedb_client: Option<Rc<Ident>>,
+    in_memory_permissions: Option<Rc<Ident>>,
 }
 
 impl SdfTestFnSetupExpander {
@@ -273,6 +295,7 @@ impl SdfTestFnSetupExpander {
             auth_token: None,
             auth_token_ref: None,
             spicedb_client: None,
+            in_memory_permissions: None,
         }
     }
 
@@ -375,7 +398,17 @@ impl SdfTestFnSetupExpander {
         let ws_multiplexer_client = ws_multiplexer_client.as_ref();
         let crdt_multiplexer_client = self.setup_crdt_multiplexer_client();
         let crdt_multiplexer_client = crdt_multiplexer_client.as_ref();
-        let spicedb_client = self.setup_spicedb_client();
+        let permissions_client = match self.in_memory_permissions.clone() {
+            Some(in_memory_permissions) => {
+                let in_memory_permissions = in_memory_permissions.as_ref();
+                quote! { #in_memory_permissions.clone() }
+            }
+            None => {
+                let spicedb_client = self.setup_spicedb_client();
+                let spicedb_client = spicedb_client.as_ref();
+                quote! { #spicedb_client }
+            }
+        };
         let audit_database_context = self.setup_audit_database_context();
 
         let var = Ident::new("router", Span::call_site());
@@ -401,7 +434,7 @@ impl SdfTestFnSetupExpander {
                         ::tokio::sync::RwLock::new(::sdf_server::ApplicationRuntimeMode::Running)
                     ),
                     #cancellation_token.clone(),
-                    #spicedb_client,
+                    #permissions_client,
                     #audit_database_context,
                 ).into_inner()
             };
@@ -461,6 +494,20 @@ impl SdfTestFnSetupExpander {
         self.spicedb_client.as_ref().unwrap().clone()
     }
 
+    fn setup_in_memory_permissions(&mut self) -> Rc<Ident> {
+        if let Some(ref ident) = self.in_memory_permissions {
+            return ident.clone();
+        }
+
+        let var = Ident::new("in_memory_permissions", Span::call_site());
+        self.code_extend(quote! {
+            let #var = ::permissions::InMemoryPermissions::new();
+        });
+        self.in_memory_permissions = Some(Rc::new(var));
+
+        self.in_memory_permissions.as_ref().unwrap().clone()
+    }
+
     fn finish(self) -> SdfTestFnSetup {
         SdfTestFnSetup {
             code: self.code,
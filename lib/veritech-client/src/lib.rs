@@ -10,19 +10,22 @@ use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use veritech_core::{
     reply_mailbox_for_output, reply_mailbox_for_result, GetNatsSubjectFor,
-    FINAL_MESSAGE_HEADER_KEY, REPLY_INBOX_HEADER_NAME,
+    FINAL_MESSAGE_HEADER_KEY, FUNC_RUNTIME_HEADER_NAME, REPLY_INBOX_HEADER_NAME,
+    WORKSPACE_CONCURRENCY_LIMIT_HEADER_NAME,
 };
 
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentKind, ComponentView,
-    ComponentViewWithGeometry, FunctionResult, FunctionResultFailure,
+    ComponentViewWithGeometry, FuncRuntime, FunctionResult, FunctionResultFailure,
     FunctionResultFailureErrorKind, KillExecutionRequest, ManagementFuncStatus, ManagementRequest,
     ManagementResultSuccess, OutputStream, ResolverFunctionComponent, ResolverFunctionRequest,
     ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
     ValidationRequest, ValidationResultSuccess,
 };
-pub use veritech_core::{encrypt_value_tree, VeritechValueEncryptError};
+pub use veritech_core::{
+    contains_secret_reference, encrypt_value_tree, SecretReference, VeritechValueEncryptError,
+};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -57,12 +60,37 @@ enum RequestMode {
 pub struct Client {
     nats: NatsClient,
     context: jetstream::Context,
+    workspace_concurrency_limit: Option<u32>,
+    func_runtime: FuncRuntime,
 }
 
 impl Client {
     pub fn new(nats: NatsClient) -> Self {
         let context = jetstream::new(nats.clone());
-        Self { nats, context }
+        Self {
+            nats,
+            context,
+            workspace_concurrency_limit: None,
+            func_runtime: FuncRuntime::default(),
+        }
+    }
+
+    /// Returns a client whose executions ask veritech to run at most `limit` of the workspace's
+    /// executions at once.
+    pub fn with_workspace_concurrency_limit(&self, limit: u32) -> Self {
+        Self {
+            workspace_concurrency_limit: Some(limit),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a client whose executions ask veritech to run their functions with `runtime`'s
+    /// lang server.
+    pub fn with_func_runtime(&self, runtime: FuncRuntime) -> Self {
+        Self {
+            func_runtime: runtime,
+            ..self.clone()
+        }
     }
 
     fn nats_subject_prefix(&self) -> Option<&str> {
@@ -289,6 +317,16 @@ impl Client {
             RequestMode::Jetstream => {
                 let mut headers = propagation::empty_injected_headers();
                 headers.insert(REPLY_INBOX_HEADER_NAME, reply_mailbox_root.clone());
+                if let Some(limit) = self.workspace_concurrency_limit {
+                    headers.insert(
+                        WORKSPACE_CONCURRENCY_LIMIT_HEADER_NAME,
+                        limit.to_string().as_str(),
+                    );
+                }
+                headers.insert(
+                    FUNC_RUNTIME_HEADER_NAME,
+                    self.func_runtime.to_string().as_str(),
+                );
 
                 self.context
                     .publish_with_headers(subject, headers, msg.into())
@@ -18,10 +18,12 @@ use cyclone_core::{
 use si_data_nats::{async_nats, jetstream, Subject};
 
 mod crypto;
+mod secret_reference;
 
 pub use crypto::{
     decrypt_value_tree, encrypt_value_tree, VeritechValueDecryptError, VeritechValueEncryptError,
 };
+pub use secret_reference::{contains_secret_reference, is_secret_reference, SecretReference};
 
 const NATS_WORK_QUEUE_STREAM_NAME: &str = "VERITECH_REQUESTS";
 const NATS_WORK_QUEUE_STREAM_SUBJECTS: &[&str] = &["veritech.requests.>"];
@@ -39,6 +41,10 @@ const SUBJECT_PREFIX: &str = "veritech.requests";
 
 pub const REPLY_INBOX_HEADER_NAME: &str = "X-Reply-Inbox";
 pub const FINAL_MESSAGE_HEADER_KEY: &str = "X-Final-Message";
+/// Header carrying the most executions a workspace may have in flight at once.
+pub const WORKSPACE_CONCURRENCY_LIMIT_HEADER_NAME: &str = "X-Workspace-Concurrency-Limit";
+/// Header carrying the runtime whose lang server executes the request's function.
+pub const FUNC_RUNTIME_HEADER_NAME: &str = "X-Func-Runtime";
 
 // NOTE(nick,fletcher): we can probably take this type formalization a step further, but this is
 // essentially the "FuncRunId" from the "dal".
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+const MARKER_FIELD: &str = "siSecretReference";
+const PROVIDER_FIELD: &str = "provider";
+const PATH_FIELD: &str = "path";
+
+/// A reference to a secret value held by an external provider, stored in place of the value
+/// itself.
+///
+/// A reference is an object with a marker field, so it can be found anywhere in a secret's
+/// message. The marker is a bool, which [`encrypt_value_tree`](crate::encrypt_value_tree) leaves
+/// untouched, so references can be found both before and after a value tree is encrypted.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretReference {
+    /// The name of the provider which holds the value.
+    pub provider: String,
+    /// Where the value is held within the provider, relative to the prefix for the workspace
+    /// using it.
+    pub path: String,
+}
+
+impl SecretReference {
+    pub fn new(provider: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Returns the reference as an object that can be stored in a secret's message.
+    pub fn to_value(&self) -> Value {
+        json!({
+            MARKER_FIELD: true,
+            PROVIDER_FIELD: self.provider,
+            PATH_FIELD: self.path,
+        })
+    }
+
+    /// Parses a decrypted reference object, returning the reference and any other fields that
+    /// were added to the object alongside it.
+    ///
+    /// Returns `None` if the value is not a reference, or if its provider or path are missing.
+    pub fn from_value(value: &Value) -> Option<(Self, Map<String, Value>)> {
+        if !is_secret_reference(value) {
+            return None;
+        }
+
+        let mut object = value.as_object()?.clone();
+        object.remove(MARKER_FIELD);
+        let provider = object.remove(PROVIDER_FIELD)?.as_str()?.to_owned();
+        let path = object.remove(PATH_FIELD)?.as_str()?.to_owned();
+
+        Some((Self { provider, path }, object))
+    }
+}
+
+/// Returns whether the value is a [`SecretReference`] object.
+pub fn is_secret_reference(value: &Value) -> bool {
+    value
+        .as_object()
+        .and_then(|object| object.get(MARKER_FIELD))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Returns whether the value, or any value nested within it, is a [`SecretReference`] object.
+pub fn contains_secret_reference(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => {
+            is_secret_reference(value) || object.values().any(contains_secret_reference)
+        }
+        Value::Array(array) => array.iter().any(contains_secret_reference),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use si_crypto::VeritechKeyPair;
+
+    use super::*;
+    use crate::encrypt_value_tree;
+
+    #[test]
+    fn round_trip() {
+        let reference = SecretReference::new("vault", "aws/production");
+
+        let (parsed, extra) =
+            SecretReference::from_value(&reference.to_value()).expect("value is not a reference");
+
+        assert_eq!(reference, parsed);
+        assert!(extra.is_empty());
+    }
+
+    #[test]
+    fn from_value_keeps_extra_fields() {
+        let mut value = SecretReference::new("vault", "aws/production").to_value();
+        value
+            .as_object_mut()
+            .expect("value is not an object")
+            .insert("WorkspaceToken".to_owned(), json!("token"));
+
+        let (_, extra) = SecretReference::from_value(&value).expect("value is not a reference");
+
+        assert_eq!(Some(&json!("token")), extra.get("WorkspaceToken"));
+        assert_eq!(1, extra.len());
+    }
+
+    #[test]
+    fn from_value_rejects_non_references() {
+        assert!(SecretReference::from_value(&json!({"provider": "vault", "path": "a"})).is_none());
+        assert!(SecretReference::from_value(&json!({MARKER_FIELD: false})).is_none());
+        assert!(SecretReference::from_value(&json!({MARKER_FIELD: true, "path": "a"})).is_none());
+        assert!(SecretReference::from_value(&json!("vault")).is_none());
+    }
+
+    #[test]
+    fn found_after_encryption() {
+        let (encryption_key, _decryption_key) = VeritechKeyPair::create();
+        let mut value = json!({
+            "credentials": [SecretReference::new("vault", "aws/production").to_value()],
+            "region": "us-east-2",
+        });
+        assert!(contains_secret_reference(&value));
+
+        encrypt_value_tree(&mut value, &encryption_key).expect("failed to encrypt value tree");
+
+        assert!(contains_secret_reference(&value));
+        assert!(!contains_secret_reference(&json!({"region": "us-east-2"})));
+    }
+}
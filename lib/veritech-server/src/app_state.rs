@@ -10,7 +10,7 @@ use tokio::sync::oneshot;
 use tokio::sync::Mutex;
 use veritech_core::ExecutionId;
 
-use crate::server::ServerMetadata;
+use crate::{scheduler::FairScheduler, secret_provider::SecretProviders, server::ServerMetadata};
 
 /// Application state.
 #[derive(Clone, Debug)]
@@ -20,7 +20,9 @@ pub struct AppState {
     // NOTE(nick,fletcher,scott): this implements clone and the inner bits are wrapped in an Arc.
     // If that changes, then I hope you read this comment before that happens.
     pub cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
+    pub scheduler: FairScheduler,
     pub decryption_key: Arc<VeritechDecryptionKey>,
+    pub secret_providers: Arc<SecretProviders>,
     // TODO(nick,fletcher,scott): make this mutable at runtime.
     pub cyclone_client_execution_timeout: Duration,
     pub nats: NatsClient,
@@ -32,7 +34,9 @@ impl AppState {
     pub fn new(
         metadata: Arc<ServerMetadata>,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
+        scheduler: FairScheduler,
         decryption_key: Arc<VeritechDecryptionKey>,
+        secret_providers: Arc<SecretProviders>,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
@@ -40,7 +44,9 @@ impl AppState {
         Self {
             metadata,
             cyclone_pool,
+            scheduler,
             decryption_key,
+            secret_providers,
             cyclone_client_execution_timeout,
             nats,
             kill_senders,
@@ -1,6 +1,7 @@
 use si_crypto::VeritechCryptoConfig;
 use si_std::CanonicalFileError;
 use std::{
+    collections::HashMap,
     env,
     net::{SocketAddr, ToSocketAddrs},
     path::{Path, PathBuf},
@@ -15,16 +16,23 @@ use si_data_nats::NatsConfig;
 use si_pool_noodle::{
     instance::cyclone::{
         LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance,
-        LocalUdsInstanceSpec, LocalUdsRuntimeStrategy, LocalUdsSocketStrategy,
+        LocalUdsInstanceSpec, LocalUdsRuntimeStrategy, LocalUdsSandboxSpec, LocalUdsSocketStrategy,
     },
+    scaling::PoolNoodleScaling,
     Instance,
 };
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::secret_provider::SecretProviderConfig;
+
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
+/// The most requests taken off the work queue at once. Once the pool is busy, requests wait in
+/// the fair scheduler, which can only choose between the requests taken so far, so this has to
+/// stay well above the pool size for the scheduler to be fair.
 const DEFAULT_CONCURRENCY_LIMIT: usize = 1000;
+const DEFAULT_WORKSPACE_CONCURRENCY_LIMIT: usize = 256;
 
 const DEFAULT_CYCLONE_CLIENT_EXECUTION_TIMEOUT_SECS: u64 = 60 * 35;
 const DEFAULT_CYCLONE_CLIENT_EXECUTION_TIMEOUT: Duration =
@@ -68,14 +76,23 @@ pub struct Config {
     #[builder(default = "default_healthcheck_pool()")]
     healthcheck_pool: bool,
 
+    #[builder(default)]
+    pool_scaling: PoolNoodleScaling,
+
     #[builder(default = "default_cyclone_client_execution_timeout()")]
     cyclone_client_execution_timeout: Duration,
 
     #[builder(default = "default_concurrency_limit()")]
     concurrency_limit: usize,
 
+    #[builder(default = "default_workspace_concurrency_limit()")]
+    workspace_concurrency_limit: usize,
+
     #[builder(default = "random_instance_id()")]
     instance_id: String,
+
+    #[builder(default)]
+    secret_providers: HashMap<String, SecretProviderConfig>,
 }
 
 impl StandardConfig for Config {
@@ -109,6 +126,11 @@ impl Config {
         self.healthcheck_pool
     }
 
+    /// Gets the config's cyclone pool scaling.
+    pub fn pool_scaling(&self) -> PoolNoodleScaling {
+        self.pool_scaling
+    }
+
     /// Consumes into a [`CycloneSpec`].
     pub fn into_cyclone_spec(self) -> CycloneSpec {
         self.cyclone_spec
@@ -124,10 +146,20 @@ impl Config {
         self.concurrency_limit
     }
 
+    /// Gets the config's concurrency limit for workspaces which do not send their own.
+    pub fn workspace_concurrency_limit(&self) -> usize {
+        self.workspace_concurrency_limit
+    }
+
     /// Gets the config's instance ID.
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
     }
+
+    /// Gets a reference to the config's secret providers, by name.
+    pub fn secret_providers(&self) -> &HashMap<String, SecretProviderConfig> {
+        &self.secret_providers
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -139,12 +171,22 @@ pub struct ConfigFile {
     pub crypto: VeritechCryptoConfig,
     #[serde(default = "default_healthcheck_pool")]
     healthcheck_pool: bool,
+    #[serde(default = "default_pool_min_size")]
+    pool_min_size: u32,
+    #[serde(default = "default_pool_warm_buffer")]
+    pool_warm_buffer: u32,
+    #[serde(default = "default_pool_cool_down_secs")]
+    pool_cool_down_secs: u64,
     #[serde(default = "default_cyclone_client_execution_timeout_secs")]
     cyclone_client_execution_timeout_secs: u64,
     #[serde(default = "default_concurrency_limit")]
     concurrency_limit: usize,
+    #[serde(default = "default_workspace_concurrency_limit")]
+    workspace_concurrency_limit: usize,
     #[serde(default = "random_instance_id")]
     instance_id: String,
+    #[serde(default)]
+    secret_providers: HashMap<String, SecretProviderConfig>,
 }
 
 impl Default for ConfigFile {
@@ -160,9 +202,14 @@ impl ConfigFile {
             cyclone: CycloneConfig::default_local_http(),
             crypto: Default::default(),
             healthcheck_pool: default_healthcheck_pool(),
+            pool_min_size: default_pool_min_size(),
+            pool_warm_buffer: default_pool_warm_buffer(),
+            pool_cool_down_secs: default_pool_cool_down_secs(),
             cyclone_client_execution_timeout_secs: default_cyclone_client_execution_timeout_secs(),
             concurrency_limit: default_concurrency_limit(),
+            workspace_concurrency_limit: default_workspace_concurrency_limit(),
             instance_id: random_instance_id(),
+            secret_providers: Default::default(),
         }
     }
 
@@ -172,11 +219,39 @@ impl ConfigFile {
             cyclone: CycloneConfig::default_local_uds(),
             crypto: Default::default(),
             healthcheck_pool: default_healthcheck_pool(),
+            pool_min_size: default_pool_min_size(),
+            pool_warm_buffer: default_pool_warm_buffer(),
+            pool_cool_down_secs: default_pool_cool_down_secs(),
             cyclone_client_execution_timeout_secs: default_cyclone_client_execution_timeout_secs(),
             concurrency_limit: default_concurrency_limit(),
+            workspace_concurrency_limit: default_workspace_concurrency_limit(),
             instance_id: random_instance_id(),
+            secret_providers: Default::default(),
         }
     }
+
+    /// The key, credential and secret files veritech reads, which function code must not.
+    fn sensitive_paths(&self) -> Vec<PathBuf> {
+        let key_files = [
+            &self.crypto.encryption_key_file,
+            &self.crypto.decryption_key_file,
+        ];
+        let mut paths: Vec<PathBuf> = key_files
+            .into_iter()
+            .flatten()
+            .map(|file| file.as_path().to_path_buf())
+            .collect();
+        paths.extend(self.nats.creds_file.iter().map(PathBuf::from));
+        paths.extend(
+            self.secret_providers
+                .values()
+                .filter_map(|provider| match provider {
+                    SecretProviderConfig::File { root } => Some(root.clone()),
+                    SecretProviderConfig::Http { .. } => None,
+                }),
+        );
+        paths
+    }
 }
 
 impl StandardConfigFile for ConfigFile {
@@ -188,16 +263,26 @@ impl TryFrom<ConfigFile> for Config {
 
     fn try_from(mut value: ConfigFile) -> Result<Self> {
         detect_and_configure_development(&mut value)?;
+        let masked_paths = value.sensitive_paths();
+        value.cyclone.extend_sandbox_masked_paths(masked_paths);
 
         let mut config = Config::builder();
         config.nats(value.nats);
         config.cyclone_spec(value.cyclone.try_into()?);
         config.crypto(value.crypto);
+        config.pool_scaling(PoolNoodleScaling {
+            min_size: value.pool_min_size,
+            warm_buffer: value.pool_warm_buffer,
+            cool_down: Duration::from_secs(value.pool_cool_down_secs),
+            ..Default::default()
+        });
         config.cyclone_client_execution_timeout(Duration::from_secs(
             value.cyclone_client_execution_timeout_secs,
         ));
         config.concurrency_limit(value.concurrency_limit);
+        config.workspace_concurrency_limit(value.workspace_concurrency_limit);
         config.instance_id(value.instance_id);
+        config.secret_providers(value.secret_providers);
         config.build().map_err(Into::into)
     }
 }
@@ -267,6 +352,8 @@ pub enum CycloneConfig {
         #[serde(default = "default_lang_server_cmd_path")]
         lang_server_cmd_path: String,
         #[serde(default)]
+        lang_shell_server_cmd_path: Option<String>,
+        #[serde(default)]
         lang_server_function_timeout: Option<usize>,
         #[serde(default)]
         socket_strategy: LocalUdsSocketStrategy,
@@ -286,6 +373,8 @@ pub enum CycloneConfig {
         pool_size: u32,
         #[serde(default)]
         connect_timeout: u64,
+        #[serde(default)]
+        sandbox: LocalUdsSandboxSpec,
     },
 }
 
@@ -308,6 +397,7 @@ impl CycloneConfig {
         Self::LocalUds {
             cyclone_cmd_path: default_cyclone_cmd_path(),
             lang_server_cmd_path: default_lang_server_cmd_path(),
+            lang_shell_server_cmd_path: Default::default(),
             lang_server_function_timeout: Default::default(),
             socket_strategy: Default::default(),
             runtime_strategy: default_runtime_strategy(),
@@ -318,6 +408,7 @@ impl CycloneConfig {
             action: default_enable_endpoint(),
             pool_size: default_pool_size(),
             connect_timeout: default_connect_timeout(),
+            sandbox: Default::default(),
         }
     }
 
@@ -369,6 +460,18 @@ impl CycloneConfig {
         };
     }
 
+    /// Sets the lang server program for shell functions, which only Cyclone servers spawned over a
+    /// local Unix domain socket can run.
+    pub fn set_lang_shell_server_cmd_path(&mut self, value: String) {
+        if let CycloneConfig::LocalUds {
+            lang_shell_server_cmd_path,
+            ..
+        } = self
+        {
+            *lang_shell_server_cmd_path = Some(value);
+        }
+    }
+
     pub fn set_limit_requests(&mut self, value: impl Into<Option<u32>>) {
         match self {
             CycloneConfig::LocalUds { limit_requets, .. } => *limit_requets = value.into(),
@@ -402,6 +505,13 @@ impl CycloneConfig {
             *pool_size = value
         };
     }
+
+    /// Hides the paths from sandboxed Cyclone servers, along with any already configured.
+    pub fn extend_sandbox_masked_paths(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        if let CycloneConfig::LocalUds { sandbox, .. } = self {
+            sandbox.masked_paths.extend(paths)
+        };
+    }
 }
 
 impl Default for CycloneConfig {
@@ -418,6 +528,7 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
             CycloneConfig::LocalUds {
                 cyclone_cmd_path,
                 lang_server_cmd_path,
+                lang_shell_server_cmd_path,
                 lang_server_function_timeout,
                 socket_strategy,
                 runtime_strategy,
@@ -428,18 +539,24 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 action,
                 pool_size,
                 connect_timeout,
+                sandbox,
             } => {
                 let mut builder = LocalUdsInstance::spec();
 
                 //we only need these if running local process. Maybe the builder should handle
                 //this?
-                if matches!(runtime_strategy, LocalUdsRuntimeStrategy::LocalProcess) {
+                if runtime_strategy.runs_local_process() {
                     builder
                         .try_cyclone_cmd_path(cyclone_cmd_path)
                         .map_err(ConfigError::cyclone_spec_build)?;
                     builder
                         .try_lang_server_cmd_path(lang_server_cmd_path)
                         .map_err(ConfigError::cyclone_spec_build)?;
+                    if let Some(lang_shell_server_cmd_path) = lang_shell_server_cmd_path {
+                        builder
+                            .try_lang_shell_server_cmd_path(lang_shell_server_cmd_path)
+                            .map_err(ConfigError::cyclone_spec_build)?;
+                    }
                 }
                 builder.lang_server_function_timeout(lang_server_function_timeout);
 
@@ -460,6 +577,7 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 }
                 builder.pool_size(pool_size);
                 builder.connect_timeout(connect_timeout);
+                builder.sandbox(sandbox);
 
                 Ok(Self::LocalUds(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -533,7 +651,7 @@ fn default_runtime_strategy() -> LocalUdsRuntimeStrategy {
     LocalUdsRuntimeStrategy::default()
 }
 
-fn default_pool_size() -> u32 {
+pub(crate) fn default_pool_size() -> u32 {
     50
 }
 
@@ -545,6 +663,18 @@ fn default_healthcheck_pool() -> bool {
     true
 }
 
+fn default_pool_min_size() -> u32 {
+    PoolNoodleScaling::default().min_size
+}
+
+fn default_pool_warm_buffer() -> u32 {
+    PoolNoodleScaling::default().warm_buffer
+}
+
+fn default_pool_cool_down_secs() -> u64 {
+    PoolNoodleScaling::default().cool_down.as_secs()
+}
+
 fn default_cyclone_client_execution_timeout() -> Duration {
     DEFAULT_CYCLONE_CLIENT_EXECUTION_TIMEOUT
 }
@@ -557,6 +687,10 @@ fn default_concurrency_limit() -> usize {
     DEFAULT_CONCURRENCY_LIMIT
 }
 
+pub(crate) fn default_workspace_concurrency_limit() -> usize {
+    DEFAULT_WORKSPACE_CONCURRENCY_LIMIT
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
@@ -599,6 +733,12 @@ fn buck2_development(config: &mut ConfigFile) -> Result<()> {
     config
         .cyclone
         .set_lang_server_cmd_path(lang_server_cmd_path);
+    // Only binaries which bundle lang-shell as a resource can execute shell functions
+    if let Ok(lang_shell_server_cmd_path) = resources.get_ends_with("lang-shell") {
+        config.cyclone.set_lang_shell_server_cmd_path(
+            lang_shell_server_cmd_path.to_string_lossy().to_string(),
+        );
+    }
 
     Ok(())
 }
@@ -637,6 +777,15 @@ fn cargo_development(dir: String, config: &mut ConfigFile) -> Result<()> {
     config
         .cyclone
         .set_lang_server_cmd_path(lang_server_cmd_path);
+    // Shell functions can only be executed once lang-shell has been built
+    if let Ok(lang_shell_server_cmd_path) = Path::new(&dir)
+        .join("../../target/debug/lang-shell")
+        .canonicalize()
+    {
+        config.cyclone.set_lang_shell_server_cmd_path(
+            lang_shell_server_cmd_path.to_string_lossy().to_string(),
+        );
+    }
 
     Ok(())
 }
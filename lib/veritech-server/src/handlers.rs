@@ -10,7 +10,7 @@ use si_data_nats::{InnerMessage, Subject};
 // seems strange to get these cyclone_core types from si_pool_noodle?
 use si_pool_noodle::{
     ActionRunResultSuccess, CycloneClient, CycloneRequest, CycloneRequestable, ExecutionError,
-    ManagementResultSuccess, ProgressMessage, ResolverFunctionResultSuccess,
+    FuncRuntime, ManagementResultSuccess, ProgressMessage, ResolverFunctionResultSuccess,
     SchemaVariantDefinitionResultSuccess, SensitiveStrings, ValidationResultSuccess,
 };
 use std::{collections::HashMap, result, str::Utf8Error, sync::Arc, time::Duration};
@@ -20,10 +20,13 @@ use thiserror::Error;
 use tokio::sync::{oneshot, Mutex};
 use veritech_core::{
     ExecutionId, VeritechRequest, VeritechRequestError, VeritechValueDecryptError,
-    REPLY_INBOX_HEADER_NAME,
+    FUNC_RUNTIME_HEADER_NAME, REPLY_INBOX_HEADER_NAME, WORKSPACE_CONCURRENCY_LIMIT_HEADER_NAME,
 };
 
-use crate::{app_state::AppState, request::DecryptRequest, Publisher, PublisherError};
+use crate::{
+    app_state::AppState, request::DecryptRequest, scheduler::ExecutionPriority,
+    secret_provider::SecretProviderError, Publisher, PublisherError,
+};
 
 pub use kill::process_kill_request;
 
@@ -38,6 +41,8 @@ pub enum HandlerError {
     CyclonePool(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
     #[error("cyclone timed out: {0:?}")]
     CycloneTimeout(Duration),
+    #[error("invalid func runtime: {0}")]
+    InvalidFuncRuntime(String),
     #[error("invalid incoming subject: {0}")]
     InvalidIncomingSubject(Subject),
     #[error("function execution killed: {0}")]
@@ -64,6 +69,10 @@ pub enum HandlerError {
     PoolNoodleExecutionValidation(#[from] si_pool_noodle::ExecutionError<ValidationResultSuccess>),
     #[error("publisher error: {0}")]
     Publisher(#[from] PublisherError),
+    #[error("scheduler closed before the execution could run")]
+    SchedulerClosed,
+    #[error("secret provider error: {0}")]
+    SecretProvider(#[from] SecretProviderError),
     #[error("utf8 error when creating subject")]
     Utf8(#[from] Utf8Error),
     #[error("veritech request error: {0}")]
@@ -90,17 +99,34 @@ pub async fn process_request(
     let span = Span::current();
 
     let reply_subject = match maybe_headers
+        .as_ref()
         .and_then(|headers| headers.get(REPLY_INBOX_HEADER_NAME).map(|v| v.to_string()))
     {
         Some(header_value) => Subject::from_utf8(header_value)?,
         None => return Err(HandlerError::NoReplyInbox),
     };
+    let workspace_concurrency_limit = maybe_headers.as_ref().and_then(|headers| {
+        headers
+            .get(WORKSPACE_CONCURRENCY_LIMIT_HEADER_NAME)
+            .and_then(|value| value.as_str().parse::<usize>().ok())
+    });
+    // Requests from clients which predate runtimes are always for lang-js
+    let func_runtime = match maybe_headers
+        .as_ref()
+        .and_then(|headers| headers.get(FUNC_RUNTIME_HEADER_NAME))
+    {
+        Some(value) => value
+            .as_str()
+            .parse::<FuncRuntime>()
+            .map_err(|_| HandlerError::InvalidFuncRuntime(value.to_string()))?,
+        None => FuncRuntime::default(),
+    };
 
     let mut parts = subject.as_str().split('.');
 
     // Based on whether or not there is a prefix, we need to determine how many parts there are
     // before the exact subject part we are interested in.
-    if state.nats_subject_has_prefix() {
+    let workspace_id = if state.nats_subject_has_prefix() {
         match (
             parts.next(),
             parts.next(),
@@ -111,6 +137,7 @@ pub async fn process_request(
             (Some(_), Some(_), Some(_), Some(workspace_id), Some(change_set_id)) => {
                 span.record("si.workspace.id", workspace_id);
                 span.record("si.change_set.id", change_set_id);
+                workspace_id
             }
             _ => return Err(HandlerError::InvalidIncomingSubject(subject)),
         }
@@ -119,10 +146,11 @@ pub async fn process_request(
             (Some(_), Some(_), Some(workspace_id), Some(change_set_id)) => {
                 span.record("si.workspace.id", workspace_id);
                 span.record("si.change_set.id", change_set_id);
+                workspace_id
             }
             _ => return Err(HandlerError::InvalidIncomingSubject(subject)),
         }
-    }
+    };
 
     let (Some(request_subject), None) = (parts.next(), parts.next()) else {
         return Err(HandlerError::InvalidIncomingSubject(subject));
@@ -131,23 +159,34 @@ pub async fn process_request(
     let veritech_request =
         VeritechRequest::from_subject_and_payload(request_subject, &msg.payload)?;
 
+    // Hold the execution until it is this workspace's turn, releasing the turn when done
+    let _permit = state
+        .scheduler
+        .acquire(
+            workspace_id,
+            ExecutionPriority::for_request(&veritech_request),
+            workspace_concurrency_limit,
+        )
+        .await
+        .ok_or(HandlerError::SchedulerClosed)?;
+
     info!(execution_kind = %veritech_request.subject_suffix(), execution_id = %veritech_request.execution_id(), "validated request and about to execute");
 
     match veritech_request {
         VeritechRequest::ActionRun(request) => {
-            dispatch_request(state, request, reply_subject).await?
+            dispatch_request(state, workspace_id, request, func_runtime, reply_subject).await?
         }
         VeritechRequest::Management(request) => {
-            dispatch_request(state, request, reply_subject).await?
+            dispatch_request(state, workspace_id, request, func_runtime, reply_subject).await?
         }
         VeritechRequest::Resolver(request) => {
-            dispatch_request(state, request, reply_subject).await?
+            dispatch_request(state, workspace_id, request, func_runtime, reply_subject).await?
         }
         VeritechRequest::SchemaVariantDefinition(request) => {
-            dispatch_request(state, request, reply_subject).await?
+            dispatch_request(state, workspace_id, request, func_runtime, reply_subject).await?
         }
         VeritechRequest::Validation(request) => {
-            dispatch_request(state, request, reply_subject).await?
+            dispatch_request(state, workspace_id, request, func_runtime, reply_subject).await?
         }
         // Kill requests do not get handled here
         VeritechRequest::KillExecution(_) => {
@@ -160,7 +199,9 @@ pub async fn process_request(
 
 async fn dispatch_request<Request>(
     state: AppState,
+    workspace_id: &str,
     mut request: Request,
+    func_runtime: FuncRuntime,
     reply_mailbox: Subject,
 ) -> HandlerResult<()>
 where
@@ -181,13 +222,24 @@ where
     // Decrypt the relevant contents of the request and track any resulting sensitive strings
     // to be redacted
     request.decrypt(&mut sensitive_strings, &state.decryption_key)?;
+    // Resolve any references to secrets held by external providers for the workspace, also
+    // tracking the resolved values to be redacted
+    state
+        .secret_providers
+        .resolve_before_functions(
+            workspace_id,
+            request.before_functions_mut(),
+            &mut sensitive_strings,
+        )
+        .await?;
 
     // NOTE(nick,fletcher): we need to create a owned client here because publisher has its own lifetime. Yeehaw.
     let nats_for_publisher = state.nats.clone();
     let publisher = Publisher::new(&nats_for_publisher, &reply_mailbox);
     let execution_id = request.execution_id().to_owned();
 
-    let cyclone_request = CycloneRequest::from_parts(request.clone(), sensitive_strings);
+    let cyclone_request =
+        CycloneRequest::from_parts(request.clone(), sensitive_strings).with_runtime(func_runtime);
 
     let (kill_sender, kill_receiver) = oneshot::channel::<()>();
     {
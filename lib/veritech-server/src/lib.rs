@@ -3,6 +3,8 @@ mod config;
 mod handlers;
 mod publisher;
 mod request;
+mod scheduler;
+mod secret_provider;
 mod server;
 
 use std::io;
@@ -18,6 +20,10 @@ pub use crate::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
         CycloneSpec, CycloneStream, StandardConfig, StandardConfigFile,
     },
+    secret_provider::{
+        FileSecretProvider, HttpSecretProvider, SecretProvider, SecretProviderConfig,
+        SecretProviderError, SecretProviderResult, SecretProviders,
+    },
     server::Server,
 };
 
@@ -42,6 +48,8 @@ pub enum ServerError {
     NatsSubscribe(Subject, #[source] NatsError),
     #[error("naxum error: {0}")]
     Naxum(#[source] io::Error),
+    #[error("secret provider error: {0}")]
+    SecretProvider(#[from] SecretProviderError),
     #[error("veritech decryption key error: {0}")]
     VeritechDecryptionKey(#[from] si_crypto::VeritechDecryptionKeyError),
     #[error("wrong cyclone spec type for {0} spec: {1:?}")]
@@ -11,6 +11,9 @@ pub trait DecryptRequest {
         sensitive_strings: &mut SensitiveStrings,
         decryption_key: &VeritechDecryptionKey,
     ) -> Result<(), VeritechValueDecryptError>;
+
+    /// Returns the before functions whose arguments hold the request's secrets.
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction];
 }
 
 impl DecryptRequest for ResolverFunctionRequest {
@@ -21,6 +24,10 @@ impl DecryptRequest for ResolverFunctionRequest {
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
+
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction] {
+        &mut self.before
+    }
 }
 
 impl DecryptRequest for ActionRunRequest {
@@ -31,6 +38,10 @@ impl DecryptRequest for ActionRunRequest {
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
+
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction] {
+        &mut self.before
+    }
 }
 
 impl DecryptRequest for ValidationRequest {
@@ -41,6 +52,10 @@ impl DecryptRequest for ValidationRequest {
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
+
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction] {
+        &mut self.before
+    }
 }
 
 impl DecryptRequest for SchemaVariantDefinitionRequest {
@@ -52,6 +67,10 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
         // No before funcs defined!
         Ok(())
     }
+
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction] {
+        &mut []
+    }
 }
 
 impl DecryptRequest for ManagementRequest {
@@ -62,6 +81,10 @@ impl DecryptRequest for ManagementRequest {
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
+
+    fn before_functions_mut(&mut self) -> &mut [BeforeFunction] {
+        &mut self.before
+    }
 }
 
 fn decrypt_before_func_args(
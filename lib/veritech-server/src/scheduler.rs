@@ -0,0 +1,500 @@
+//! Fair scheduling of function executions between workspaces.
+//!
+//! Every execution waits on a [`SchedulerPermit`] before it takes an instance from the pool.
+//! Permits go round-robin to the workspaces with executions waiting, each getting a few turns in
+//! a row (more for larger quotas, up to a cap) and never having more executions in flight than its
+//! quota. While another workspace can run within its share, workspaces are held to a fraction of
+//! all executions in flight, so no workspace can take the whole pool from the others; otherwise
+//! they borrow the idle slots. Action and management functions, which users wait on, go before
+//! attribute, validation and schema variant definition functions.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use tokio::sync::oneshot;
+use veritech_core::VeritechRequest;
+
+/// The most executions a workspace starts in a row before the next workspace gets its turn. Below
+/// this, a workspace gets one turn for each execution its quota allows in flight.
+const MAX_TURNS_PER_ROUND: usize = 4;
+/// While other workspaces can use them, the most executions a workspace may have in flight is the
+/// scheduler's limit divided by this.
+const WORKSPACE_SHARE_DIVISOR: usize = 4;
+
+/// How urgently an execution should run, relative to the others waiting.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutionPriority {
+    /// Executions users are waiting on, such as actions and management functions
+    High,
+    /// Executions kept up to date in the background, such as attribute functions
+    Low,
+}
+
+impl ExecutionPriority {
+    const ALL: [Self; 2] = [Self::High, Self::Low];
+
+    pub fn for_request(request: &VeritechRequest) -> Self {
+        match request {
+            VeritechRequest::ActionRun(_) | VeritechRequest::Management(_) => Self::High,
+            VeritechRequest::KillExecution(_)
+            | VeritechRequest::Resolver(_)
+            | VeritechRequest::SchemaVariantDefinition(_)
+            | VeritechRequest::Validation(_) => Self::Low,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::High => 0,
+            Self::Low => 1,
+        }
+    }
+}
+
+/// Hands out [`SchedulerPermits`](SchedulerPermit) to run executions, fairly between
+/// workspaces.
+#[derive(Clone, Debug)]
+pub struct FairScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+#[derive(Debug)]
+struct SchedulerState {
+    max_in_flight: usize,
+    max_workspace_quota: usize,
+    default_workspace_quota: usize,
+    in_flight: usize,
+    workspaces: HashMap<String, WorkspaceState>,
+    /// Workspaces with executions waiting, in the order they take their turns
+    rotation: VecDeque<String>,
+}
+
+#[derive(Debug, Default)]
+struct WorkspaceState {
+    quota: usize,
+    in_flight: usize,
+    turns_left: usize,
+    waiting: [VecDeque<oneshot::Sender<SchedulerPermit>>; 2],
+}
+
+impl WorkspaceState {
+    fn has_waiting(&self) -> bool {
+        self.waiting.iter().any(|waiting| !waiting.is_empty())
+    }
+
+    fn turns_per_round(&self) -> usize {
+        self.quota.clamp(1, MAX_TURNS_PER_ROUND)
+    }
+}
+
+impl FairScheduler {
+    /// Creates a scheduler running at most `max_in_flight` executions at once, and at most
+    /// `default_workspace_quota` for any one workspace which does not send its own quota.
+    ///
+    /// Whatever they send, workspaces are held to a quarter of `max_in_flight` while another
+    /// workspace could use the rest.
+    pub fn new(max_in_flight: usize, default_workspace_quota: usize) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                max_in_flight,
+                max_workspace_quota: (max_in_flight / WORKSPACE_SHARE_DIVISOR).max(1),
+                default_workspace_quota: default_workspace_quota.max(1),
+                in_flight: 0,
+                workspaces: HashMap::new(),
+                rotation: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Waits for the workspace's turn to run an execution, which lasts until the returned
+    /// permit is dropped.
+    ///
+    /// Returns `None` if the scheduler drops the request, which only happens if it is shutting
+    /// down.
+    pub async fn acquire(
+        &self,
+        workspace_id: &str,
+        priority: ExecutionPriority,
+        quota: Option<usize>,
+    ) -> Option<SchedulerPermit> {
+        let (permit_tx, permit_rx) = oneshot::channel();
+        {
+            let mut state = self.lock();
+            let quota = quota.unwrap_or(state.default_workspace_quota).max(1);
+
+            let workspace = state.workspaces.entry(workspace_id.to_owned()).or_default();
+            workspace.quota = quota;
+            let joins_rotation = !workspace.has_waiting();
+            if joins_rotation {
+                workspace.turns_left = workspace.turns_per_round();
+            }
+            workspace.waiting[priority.index()].push_back(permit_tx);
+            if joins_rotation {
+                state.rotation.push_back(workspace_id.to_owned());
+            }
+            metric!(
+                counter.veritech.scheduler.queued = 1,
+                workspace_id = workspace_id
+            );
+
+            self.dispatch(&mut state);
+        }
+
+        permit_rx.await.ok()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn dispatch(&self, state: &mut SchedulerState) {
+        while state.in_flight < state.max_in_flight {
+            let Some((workspace_id, permit_tx)) = state.next_waiting() else {
+                break;
+            };
+            metric!(
+                counter.veritech.scheduler.queued = -1,
+                workspace_id = workspace_id.as_str()
+            );
+
+            let permit = SchedulerPermit {
+                scheduler: self.clone(),
+                workspace_id: workspace_id.clone(),
+                active: true,
+            };
+            if let Err(mut permit) = permit_tx.send(permit) {
+                // The execution stopped waiting, so its turn goes to the next one
+                permit.active = false;
+                state.release(&workspace_id);
+            } else {
+                metric!(
+                    counter.veritech.scheduler.in_flight = 1,
+                    workspace_id = workspace_id.as_str()
+                );
+            }
+        }
+    }
+}
+
+impl SchedulerState {
+    fn next_waiting(&mut self) -> Option<(String, oneshot::Sender<SchedulerPermit>)> {
+        // The first workspace in the rotation below its limit with an execution of the highest
+        // priority waiting goes next, while the ones before it keep their place. Workspaces are
+        // limited to their share while any workspace can go within its own, and only then borrow
+        // the idle slots up to their quota
+        let (index, priority) = [true, false].into_iter().find_map(|within_share| {
+            ExecutionPriority::ALL.into_iter().find_map(|priority| {
+                self.rotation
+                    .iter()
+                    .position(|workspace_id| {
+                        self.workspaces.get(workspace_id).is_some_and(|workspace| {
+                            let limit = if within_share {
+                                workspace.quota.min(self.max_workspace_quota)
+                            } else {
+                                workspace.quota
+                            };
+                            workspace.in_flight < limit
+                                && !workspace.waiting[priority.index()].is_empty()
+                        })
+                    })
+                    .map(|index| (index, priority))
+            })
+        })?;
+
+        let workspace_id = self.rotation[index].clone();
+        let workspace = self.workspaces.get_mut(&workspace_id)?;
+        let permit_tx = workspace.waiting[priority.index()].pop_front()?;
+        workspace.in_flight += 1;
+        self.in_flight += 1;
+
+        workspace.turns_left = workspace.turns_left.saturating_sub(1);
+        if workspace.turns_left == 0 || !workspace.has_waiting() {
+            workspace.turns_left = workspace.turns_per_round();
+            self.rotation.remove(index);
+            if workspace.has_waiting() {
+                self.rotation.push_back(workspace_id.clone());
+            }
+        }
+
+        Some((workspace_id, permit_tx))
+    }
+
+    fn release(&mut self, workspace_id: &str) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
+            workspace.in_flight = workspace.in_flight.saturating_sub(1);
+            if workspace.in_flight == 0 && !workspace.has_waiting() {
+                self.workspaces.remove(workspace_id);
+            }
+        }
+    }
+}
+
+/// A workspace's turn to run an execution, which ends when the permit is dropped.
+#[derive(Debug)]
+pub struct SchedulerPermit {
+    scheduler: FairScheduler,
+    workspace_id: String,
+    active: bool,
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        metric!(
+            counter.veritech.scheduler.in_flight = -1,
+            workspace_id = self.workspace_id.as_str()
+        );
+
+        let mut state = self.scheduler.lock();
+        state.release(&self.workspace_id);
+        self.scheduler.dispatch(&mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use tokio::{sync::mpsc, time};
+
+    use super::*;
+    use crate::config::{default_pool_size, default_workspace_concurrency_limit};
+
+    /// Queues executions and finishes them on demand, recording the order they start in.
+    struct Executions {
+        scheduler: FairScheduler,
+        started_tx: mpsc::UnboundedSender<&'static str>,
+        started_rx: mpsc::UnboundedReceiver<&'static str>,
+        finish_txs: HashMap<&'static str, oneshot::Sender<()>>,
+    }
+
+    impl Executions {
+        fn new(max_in_flight: usize) -> Self {
+            Self::with_scheduler(FairScheduler::new(max_in_flight, 10))
+        }
+
+        fn with_scheduler(scheduler: FairScheduler) -> Self {
+            let (started_tx, started_rx) = mpsc::unbounded_channel();
+            Self {
+                scheduler,
+                started_tx,
+                started_rx,
+                finish_txs: HashMap::new(),
+            }
+        }
+
+        async fn queue(
+            &mut self,
+            workspace_id: &'static str,
+            priority: ExecutionPriority,
+            quota: Option<usize>,
+            label: &'static str,
+        ) {
+            let scheduler = self.scheduler.clone();
+            let started_tx = self.started_tx.clone();
+            let (finish_tx, finish_rx) = oneshot::channel();
+            tokio::spawn(async move {
+                let _permit = scheduler
+                    .acquire(workspace_id, priority, quota)
+                    .await
+                    .expect("scheduler should hand out a permit");
+                started_tx.send(label).expect("failed to report start");
+                let _ = finish_rx.await;
+            });
+            self.finish_txs.insert(label, finish_tx);
+            settle().await;
+        }
+
+        async fn finish(&mut self, label: &'static str) {
+            let _ = self
+                .finish_txs
+                .remove(label)
+                .expect("execution was never queued")
+                .send(());
+            settle().await;
+        }
+
+        fn started(&mut self) -> Vec<&'static str> {
+            let mut started = Vec::new();
+            while let Ok(label) = self.started_rx.try_recv() {
+                started.push(label);
+            }
+            started
+        }
+
+        /// Finishes each execution as it starts, one at a time, returning the order they ran in.
+        async fn run_one_at_a_time(&mut self) -> Vec<&'static str> {
+            let mut order = Vec::new();
+            loop {
+                let started = self.started();
+                match started.as_slice() {
+                    [] => break,
+                    [label] => {
+                        order.push(*label);
+                        self.finish(label).await;
+                    }
+                    _ => panic!("only one execution should run at a time: {started:?}"),
+                }
+            }
+            order
+        }
+    }
+
+    async fn settle() {
+        time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn workspaces_take_turns_weighted_by_their_quota() {
+        let mut executions = Executions::new(1);
+
+        // Hold the only slot while the rest queue up behind it
+        executions
+            .queue("blocker", ExecutionPriority::Low, None, "blocker")
+            .await;
+        for label in ["heavy-1", "heavy-2", "heavy-3", "heavy-4", "heavy-5"] {
+            executions
+                .queue("heavy", ExecutionPriority::Low, Some(100), label)
+                .await;
+        }
+        for label in ["medium-1", "medium-2", "medium-3"] {
+            executions
+                .queue("medium", ExecutionPriority::Low, Some(2), label)
+                .await;
+        }
+        for label in ["light-1", "light-2"] {
+            executions
+                .queue("light", ExecutionPriority::Low, Some(1), label)
+                .await;
+        }
+
+        assert_eq!(vec!["blocker"], executions.started());
+        executions.finish("blocker").await;
+
+        // However large its quota, a workspace's turns are capped
+        assert_eq!(
+            vec![
+                "heavy-1", "heavy-2", "heavy-3", "heavy-4", "medium-1", "medium-2", "light-1",
+                "heavy-5", "medium-3", "light-2"
+            ],
+            executions.run_one_at_a_time().await
+        );
+    }
+
+    #[tokio::test]
+    async fn workspaces_stay_within_their_quota() {
+        let mut executions = Executions::new(10);
+
+        for label in ["busy-1", "busy-2", "busy-3"] {
+            executions
+                .queue("busy", ExecutionPriority::Low, Some(2), label)
+                .await;
+        }
+        executions
+            .queue("quiet", ExecutionPriority::Low, Some(2), "quiet-1")
+            .await;
+        assert_eq!(vec!["busy-1", "busy-2", "quiet-1"], executions.started());
+
+        executions.finish("busy-1").await;
+        assert_eq!(vec!["busy-3"], executions.started());
+    }
+
+    #[tokio::test]
+    async fn idle_slots_are_lent_until_other_workspaces_need_them() {
+        let pool_size = default_pool_size() as usize;
+        let mut executions = Executions::with_scheduler(FairScheduler::new(
+            pool_size,
+            default_workspace_concurrency_limit(),
+        ));
+        let busy_label =
+            |index: usize| -> &'static str { Box::leak(format!("busy-{index}").into_boxed_str()) };
+
+        // Whether it sends the default limit or none, a workspace with the pool to itself can use
+        // all of it
+        for index in 0..=pool_size {
+            let quota = (index % 2 == 0).then_some(256);
+            executions
+                .queue("busy", ExecutionPriority::Low, quota, busy_label(index))
+                .await;
+        }
+        assert_eq!(pool_size, executions.started().len());
+
+        // Once another workspace is waiting, it takes the slots that free up, ahead of the busy
+        // workspace which is over its share
+        for label in ["quiet-1", "quiet-2"] {
+            executions
+                .queue("quiet", ExecutionPriority::Low, None, label)
+                .await;
+        }
+        assert!(executions.started().is_empty());
+        executions.finish(busy_label(0)).await;
+        assert_eq!(vec!["quiet-1"], executions.started());
+        executions.finish(busy_label(1)).await;
+        assert_eq!(vec!["quiet-2"], executions.started());
+
+        // With nothing else waiting, the busy workspace borrows the idle slots again
+        executions.finish(busy_label(2)).await;
+        assert_eq!(vec![busy_label(pool_size)], executions.started());
+    }
+
+    #[tokio::test]
+    async fn actions_and_management_funcs_go_first() {
+        let mut executions = Executions::new(1);
+
+        executions
+            .queue("blocker", ExecutionPriority::Low, None, "blocker")
+            .await;
+        executions
+            .queue("dvu", ExecutionPriority::Low, Some(5), "attribute-1")
+            .await;
+        executions
+            .queue("dvu", ExecutionPriority::Low, Some(5), "attribute-2")
+            .await;
+        executions
+            .queue("dvu", ExecutionPriority::High, Some(5), "action-1")
+            .await;
+        executions
+            .queue("other", ExecutionPriority::High, Some(5), "management-1")
+            .await;
+
+        assert_eq!(vec!["blocker"], executions.started());
+        executions.finish("blocker").await;
+
+        assert_eq!(
+            vec!["action-1", "management-1", "attribute-1", "attribute-2"],
+            executions.run_one_at_a_time().await
+        );
+    }
+
+    #[tokio::test]
+    async fn abandoned_executions_give_up_their_turn() {
+        let scheduler = FairScheduler::new(1, 10);
+
+        let permit = scheduler
+            .acquire("workspace", ExecutionPriority::Low, None)
+            .await
+            .expect("scheduler should hand out a permit");
+        let abandoned = time::timeout(
+            Duration::from_millis(20),
+            scheduler.acquire("workspace", ExecutionPriority::Low, None),
+        )
+        .await;
+        assert!(abandoned.is_err());
+        drop(permit);
+
+        let permit = time::timeout(
+            Duration::from_millis(100),
+            scheduler.acquire("workspace", ExecutionPriority::Low, None),
+        )
+        .await;
+        assert!(matches!(permit, Ok(Some(_))));
+    }
+}
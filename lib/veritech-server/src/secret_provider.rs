@@ -0,0 +1,437 @@
+//! Providers which hold secret values outside of System Initiative.
+//!
+//! A secret's message can contain a [`SecretReference`] in place of a value. Once a request has
+//! been decrypted, each reference in its before functions' arguments is resolved by the
+//! [`SecretProvider`] it names, and every string in the resolved value is tracked in the
+//! request's [`SensitiveStrings`] so that it is redacted from function output.
+//!
+//! Every provider holds each workspace's values beneath a prefix named for the workspace, so a
+//! reference's path is always resolved as `<workspace id>/<path>` for the workspace that made the
+//! request. A workspace can't name a value held for another: paths may only contain ASCII
+//! letters, digits, `_`, `-` and (non-leading, non-repeated) `/` separators.
+
+use std::{collections::HashMap, fmt, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_pool_noodle::{BeforeFunction, SensitiveStrings};
+use si_std::SensitiveString;
+use telemetry::prelude::*;
+use thiserror::Error;
+use veritech_core::{is_secret_reference, SecretReference};
+
+mod file;
+mod http;
+
+pub use file::FileSecretProvider;
+pub use http::HttpSecretProvider;
+
+const DEFAULT_HTTP_CONNECT_TIMEOUT_SECS: u64 = 5;
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SecretProviderError {
+    #[error("invalid secret path: {0}")]
+    InvalidPath(String),
+    #[error("invalid secret reference at {0}")]
+    InvalidReference(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("secret not found in {0} provider: {1}")]
+    NotFound(String, String),
+    #[error("reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("unknown secret provider: {0}")]
+    UnknownProvider(String),
+}
+
+pub type SecretProviderResult<T> = Result<T, SecretProviderError>;
+
+/// Resolves [`SecretReference`] paths into the values they refer to.
+#[async_trait]
+pub trait SecretProvider: fmt::Debug + Send + Sync {
+    /// Fetches the value held at the path. A value which is not valid JSON is returned as a
+    /// string.
+    async fn resolve(&self, path: &str) -> SecretProviderResult<Value>;
+}
+
+/// Configuration for a single named [`SecretProvider`].
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecretProviderConfig {
+    /// Values are held in files beneath a root directory, one file per path.
+    File { root: PathBuf },
+    /// Values are fetched with a `GET` request for the path, relative to a base url.
+    Http {
+        base_url: String,
+        #[serde(default)]
+        token: Option<SensitiveString>,
+        /// How long to wait for a connection to the store before giving up.
+        #[serde(default = "default_http_connect_timeout_secs")]
+        connect_timeout_secs: u64,
+        /// How long to wait for a whole request, from connecting to reading the response body.
+        #[serde(default = "default_http_timeout_secs")]
+        timeout_secs: u64,
+    },
+}
+
+fn default_http_connect_timeout_secs() -> u64 {
+    DEFAULT_HTTP_CONNECT_TIMEOUT_SECS
+}
+
+fn default_http_timeout_secs() -> u64 {
+    DEFAULT_HTTP_TIMEOUT_SECS
+}
+
+/// The [`SecretProviders`](SecretProvider) available to resolve references, by name.
+#[derive(Clone, Debug, Default)]
+pub struct SecretProviders(HashMap<String, Arc<dyn SecretProvider>>);
+
+impl SecretProviders {
+    /// Builds the providers described by the configuration.
+    pub fn from_config(
+        config: &HashMap<String, SecretProviderConfig>,
+    ) -> SecretProviderResult<Self> {
+        let mut providers = Self::default();
+        for (name, provider_config) in config {
+            let provider: Arc<dyn SecretProvider> = match provider_config {
+                SecretProviderConfig::File { root } => Arc::new(FileSecretProvider::new(root)),
+                SecretProviderConfig::Http {
+                    base_url,
+                    token,
+                    connect_timeout_secs,
+                    timeout_secs,
+                } => Arc::new(HttpSecretProvider::new(
+                    base_url,
+                    token.clone(),
+                    Duration::from_secs(*connect_timeout_secs),
+                    Duration::from_secs(*timeout_secs),
+                )?),
+            };
+            providers.insert(name, provider);
+        }
+        Ok(providers)
+    }
+
+    /// Adds a provider, replacing any existing provider with the same name.
+    pub fn insert(&mut self, name: impl Into<String>, provider: Arc<dyn SecretProvider>) {
+        self.0.insert(name.into(), provider);
+    }
+
+    /// Resolves the references in each before function's (decrypted) argument, within the
+    /// workspace's prefix.
+    pub async fn resolve_before_functions(
+        &self,
+        workspace_id: &str,
+        before: &mut [BeforeFunction],
+        sensitive_strings: &mut SensitiveStrings,
+    ) -> SecretProviderResult<()> {
+        for func in before {
+            self.resolve_value_tree(workspace_id, &mut func.arg, sensitive_strings)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces every [`SecretReference`] in the value with the value it refers to, within the
+    /// workspace's prefix.
+    ///
+    /// Any other fields on a reference object are kept when the resolved value is an object
+    /// (without replacing its own fields), so that fields added to a secret's message after it
+    /// was decrypted still reach the before function.
+    pub async fn resolve_value_tree(
+        &self,
+        workspace_id: &str,
+        value: &mut Value,
+        sensitive_strings: &mut SensitiveStrings,
+    ) -> SecretProviderResult<()> {
+        let mut json_pointer_stack = vec!["".to_owned()];
+
+        while let Some(pointer) = json_pointer_stack.pop() {
+            let Some(value) = value.pointer_mut(&pointer) else {
+                continue;
+            };
+            match value {
+                Value::Object(_) if is_secret_reference(value) => {
+                    let (reference, extra) = SecretReference::from_value(value)
+                        .ok_or_else(|| SecretProviderError::InvalidReference(pointer.clone()))?;
+                    let mut resolved = self.resolve(workspace_id, &reference).await?;
+                    if let Value::Object(object) = &mut resolved {
+                        for (key, extra_value) in extra {
+                            object.entry(key).or_insert(extra_value);
+                        }
+                    }
+                    track_sensitive_strings(&resolved, sensitive_strings);
+                    *value = resolved;
+                }
+                Value::Object(object) => {
+                    json_pointer_stack.extend(object.keys().map(|key| {
+                        format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"))
+                    }));
+                }
+                Value::Array(array) => {
+                    json_pointer_stack
+                        .extend((0..array.len()).map(|index| format!("{pointer}/{index}")));
+                }
+                Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+                    // Nothing to do
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "veritech.secret_provider.resolve",
+        level = "debug",
+        skip_all,
+        fields(si.secret_provider.name = %reference.provider)
+    )]
+    async fn resolve(
+        &self,
+        workspace_id: &str,
+        reference: &SecretReference,
+    ) -> SecretProviderResult<Value> {
+        let provider = self
+            .0
+            .get(&reference.provider)
+            .ok_or_else(|| SecretProviderError::UnknownProvider(reference.provider.clone()))?;
+
+        validate_path(&reference.path)?;
+        if workspace_id.contains('/') {
+            return Err(SecretProviderError::InvalidPath(workspace_id.to_owned()));
+        }
+        validate_path(workspace_id)?;
+
+        provider
+            .resolve(&format!("{workspace_id}/{}", reference.path))
+            .await
+    }
+}
+
+/// Ensures the path is made up of non-empty segments of ASCII letters, digits, `_` and `-`,
+/// separated by `/`. Anything which could be interpreted by a provider (`.`, `%`, `?`, `#`,
+/// etc.) is rejected rather than escaped.
+fn validate_path(path: &str) -> SecretProviderResult<()> {
+    let valid = path.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    });
+    if !valid {
+        return Err(SecretProviderError::InvalidPath(path.to_owned()));
+    }
+
+    Ok(())
+}
+
+fn track_sensitive_strings(value: &Value, sensitive_strings: &mut SensitiveStrings) {
+    match value {
+        Value::String(s) => sensitive_strings.insert(s),
+        Value::Array(array) => array
+            .iter()
+            .for_each(|value| track_sensitive_strings(value, sensitive_strings)),
+        Value::Object(object) => object
+            .values()
+            .for_each(|value| track_sensitive_strings(value, sensitive_strings)),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Parses a provider's raw value as JSON, falling back to a string (without any trailing
+/// newline) when it isn't valid JSON.
+fn parse_value(raw: String) -> Value {
+    serde_json::from_str(&raw)
+        .unwrap_or_else(|_| Value::String(raw.trim_end_matches(['\r', '\n']).to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct StaticSecretProvider(HashMap<&'static str, Value>);
+
+    #[async_trait]
+    impl SecretProvider for StaticSecretProvider {
+        async fn resolve(&self, path: &str) -> SecretProviderResult<Value> {
+            self.0
+                .get(path)
+                .cloned()
+                .ok_or_else(|| SecretProviderError::NotFound("static".to_owned(), path.to_owned()))
+        }
+    }
+
+    const WORKSPACE_ID: &str = "01JAWORKSPACEA0000000000000";
+    const OTHER_WORKSPACE_ID: &str = "01JAWORKSPACEB0000000000000";
+
+    fn providers() -> SecretProviders {
+        let mut providers = SecretProviders::default();
+        providers.insert(
+            "static",
+            Arc::new(StaticSecretProvider(HashMap::from([
+                ("01JAWORKSPACEA0000000000000/token", json!("hunter2")),
+                (
+                    "01JAWORKSPACEA0000000000000/aws",
+                    json!({"AccessKeyId": "AKIA", "SecretAccessKey": "shh", "Region": "here"}),
+                ),
+                ("01JAWORKSPACEB0000000000000/token", json!("swordfish")),
+            ]))),
+        );
+        providers
+    }
+
+    #[tokio::test]
+    async fn resolves_nested_references() {
+        let mut value = json!({
+            "AccessKeyId": SecretReference::new("static", "aws").to_value(),
+            "tokens": [SecretReference::new("static", "token").to_value(), "plain"],
+        });
+        let mut sensitive_strings = SensitiveStrings::default();
+
+        providers()
+            .resolve_value_tree(WORKSPACE_ID, &mut value, &mut sensitive_strings)
+            .await
+            .expect("failed to resolve value tree");
+
+        assert_eq!(
+            json!({
+                "AccessKeyId": {"AccessKeyId": "AKIA", "SecretAccessKey": "shh", "Region": "here"},
+                "tokens": ["hunter2", "plain"],
+            }),
+            value
+        );
+        assert!(sensitive_strings.has_sensitive("hunter2"));
+        assert!(sensitive_strings.has_sensitive("shh"));
+        assert!(!sensitive_strings.has_sensitive("plain"));
+    }
+
+    #[tokio::test]
+    async fn keeps_extra_fields_on_reference() {
+        let mut value = SecretReference::new("static", "aws").to_value();
+        let object = value.as_object_mut().expect("value is not an object");
+        object.insert("WorkspaceToken".to_owned(), json!("workspace-token"));
+        object.insert("Region".to_owned(), json!("overridden"));
+
+        providers()
+            .resolve_value_tree(WORKSPACE_ID, &mut value, &mut SensitiveStrings::default())
+            .await
+            .expect("failed to resolve value tree");
+
+        assert_eq!(
+            json!({
+                "AccessKeyId": "AKIA",
+                "SecretAccessKey": "shh",
+                "Region": "here",
+                "WorkspaceToken": "workspace-token",
+            }),
+            value
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_provider() {
+        let mut value = SecretReference::new("vault", "aws").to_value();
+
+        let err = providers()
+            .resolve_value_tree(WORKSPACE_ID, &mut value, &mut SensitiveStrings::default())
+            .await
+            .expect_err("resolved a reference to an unknown provider");
+
+        assert!(matches!(err, SecretProviderError::UnknownProvider(name) if name == "vault"));
+    }
+
+    #[tokio::test]
+    async fn references_are_scoped_to_the_workspace() {
+        let providers = providers();
+
+        let mut value = SecretReference::new("static", "token").to_value();
+        providers
+            .resolve_value_tree(
+                OTHER_WORKSPACE_ID,
+                &mut value,
+                &mut SensitiveStrings::default(),
+            )
+            .await
+            .expect("failed to resolve value tree");
+        assert_eq!(json!("swordfish"), value);
+
+        // Workspace B can't reach workspace A's values, however the path is written
+        for path in [
+            "../01JAWORKSPACEA0000000000000/token",
+            "%2e%2e/01JAWORKSPACEA0000000000000/token",
+            "/01JAWORKSPACEA0000000000000/token",
+            "token?workspace=01JAWORKSPACEA0000000000000",
+            "token#01JAWORKSPACEA0000000000000",
+        ] {
+            let mut value = SecretReference::new("static", path).to_value();
+            let result = providers
+                .resolve_value_tree(
+                    OTHER_WORKSPACE_ID,
+                    &mut value,
+                    &mut SensitiveStrings::default(),
+                )
+                .await;
+            assert!(
+                matches!(result, Err(SecretProviderError::InvalidPath(_))),
+                "path was not rejected: {path}"
+            );
+        }
+
+        let mut value = SecretReference::new("static", "aws").to_value();
+        let result = providers
+            .resolve_value_tree(
+                OTHER_WORKSPACE_ID,
+                &mut value,
+                &mut SensitiveStrings::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(SecretProviderError::NotFound(_, _))));
+    }
+
+    #[test]
+    fn validate_path_allows_only_plain_segments() {
+        for path in ["token", "aws/production", "a-b_c/D9"] {
+            assert!(validate_path(path).is_ok(), "path was rejected: {path}");
+        }
+        for path in [
+            "", "/token", "token/", "a//b", "..", "a/../b", "%2e%2e", "a.json", "a?b", "a#b",
+            "a b", "a\\b",
+        ] {
+            assert!(
+                validate_path(path).is_err(),
+                "path was not rejected: {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn http_config_has_default_timeouts() {
+        let config: SecretProviderConfig =
+            serde_json::from_value(json!({"kind": "http", "base_url": "http://localhost:8200"}))
+                .expect("failed to deserialize config");
+
+        assert!(matches!(
+            config,
+            SecretProviderConfig::Http {
+                connect_timeout_secs: DEFAULT_HTTP_CONNECT_TIMEOUT_SECS,
+                timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_value_falls_back_to_string() {
+        assert_eq!(json!({"a": 1}), parse_value("{\"a\": 1}\n".to_owned()));
+        assert_eq!(json!("hunter2"), parse_value("hunter2\n".to_owned()));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use telemetry::prelude::*;
+use tokio::fs;
+
+use super::{parse_value, SecretProvider, SecretProviderError, SecretProviderResult};
+
+/// A provider which holds each value in a file beneath a root directory, at the reference's path.
+#[derive(Clone, Debug)]
+pub struct FileSecretProvider {
+    root: PathBuf,
+}
+
+impl FileSecretProvider {
+    /// Creates a new [provider for files beneath a directory](FileSecretProvider).
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the file for the path, which must be relative and stay beneath the root.
+    fn file_path(&self, path: &str) -> SecretProviderResult<PathBuf> {
+        let relative = Path::new(path);
+        if path.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(SecretProviderError::InvalidPath(path.to_owned()));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl SecretProvider for FileSecretProvider {
+    #[instrument(
+        name = "veritech.secret_provider.file.resolve",
+        level = "debug",
+        skip_all,
+        fields(root = %self.root.display())
+    )]
+    async fn resolve(&self, path: &str) -> SecretProviderResult<Value> {
+        let raw = match fs::read_to_string(self.file_path(path)?).await {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(SecretProviderError::NotFound(
+                    "file".to_owned(),
+                    path.to_owned(),
+                ));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(parse_value(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn resolves_files_beneath_root() {
+        let root = tempfile::tempdir().expect("failed to create temp dir");
+        fs::create_dir_all(root.path().join("aws"))
+            .await
+            .expect("failed to create dir");
+        fs::write(
+            root.path().join("aws/production"),
+            "{\"AccessKeyId\": \"AKIA\"}\n",
+        )
+        .await
+        .expect("failed to write secret");
+        fs::write(root.path().join("token"), "hunter2\n")
+            .await
+            .expect("failed to write secret");
+        let provider = FileSecretProvider::new(root.path());
+
+        assert_eq!(
+            json!({"AccessKeyId": "AKIA"}),
+            provider
+                .resolve("aws/production")
+                .await
+                .expect("failed to resolve")
+        );
+        assert_eq!(
+            json!("hunter2"),
+            provider.resolve("token").await.expect("failed to resolve")
+        );
+        assert!(matches!(
+            provider.resolve("missing").await,
+            Err(SecretProviderError::NotFound(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_root() {
+        let provider = FileSecretProvider::new("/secrets");
+
+        for path in [
+            "",
+            "../etc/passwd",
+            "/etc/passwd",
+            "aws/../../etc/passwd",
+            "./token",
+        ] {
+            assert!(
+                matches!(
+                    provider.resolve(path).await,
+                    Err(SecretProviderError::InvalidPath(_))
+                ),
+                "path was not rejected: {path}"
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde_json::Value;
+use si_std::SensitiveString;
+use telemetry::prelude::*;
+
+use super::{
+    parse_value, validate_path, SecretProvider, SecretProviderError, SecretProviderResult,
+};
+
+/// A provider which fetches each value from an HTTP key-value store with a `GET` request for the
+/// reference's path, relative to a base url.
+#[derive(Clone, Debug)]
+pub struct HttpSecretProvider {
+    base_url: String,
+    token: Option<SensitiveString>,
+    inner: reqwest::Client,
+}
+
+impl HttpSecretProvider {
+    /// Creates a new [provider for an HTTP key-value store](HttpSecretProvider). When a token is
+    /// given, it is sent as a bearer token with each request.
+    ///
+    /// A request gives up once connecting takes longer than the connect timeout, or the whole
+    /// request takes longer than the timeout, so an unresponsive store can't hold up execution.
+    pub fn new(
+        base_url: impl Into<String>,
+        token: Option<SensitiveString>,
+        connect_timeout: Duration,
+        timeout: Duration,
+    ) -> SecretProviderResult<Self> {
+        let inner = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(timeout)
+            .build()?;
+
+        Ok(Self {
+            base_url: base_url.into(),
+            token,
+            inner,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+}
+
+#[async_trait]
+impl SecretProvider for HttpSecretProvider {
+    #[instrument(
+        name = "veritech.secret_provider.http.resolve",
+        level = "debug",
+        skip_all,
+        fields(base_url = %self.base_url)
+    )]
+    async fn resolve(&self, path: &str) -> SecretProviderResult<Value> {
+        // The path becomes part of the url, so anything that could be percent-decoded or read as
+        // a query or fragment is rejected
+        validate_path(path)?;
+
+        let mut request = self.inner.get(self.url(path));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token.as_str());
+        }
+
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(SecretProviderError::NotFound(
+                "http".to_owned(),
+                path.to_owned(),
+            ));
+        }
+
+        Ok(parse_value(response.error_for_status()?.text().await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(base_url: &str, timeout: Duration) -> HttpSecretProvider {
+        HttpSecretProvider::new(base_url, None, timeout, timeout)
+            .expect("failed to create provider")
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_which_could_be_reinterpreted() {
+        let provider = provider("http://localhost:8200/v1/", Duration::from_secs(5));
+
+        for path in [
+            "../sys/raw",
+            "aws/%2e%2e/%2e%2e/sys/raw",
+            "aws/%2E%2E/sys/raw",
+            "aws?list=true",
+            "aws#fragment",
+        ] {
+            assert!(
+                matches!(
+                    provider.resolve(path).await,
+                    Err(SecretProviderError::InvalidPath(_))
+                ),
+                "path was not rejected: {path}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_unresponsive_stores() {
+        // Connections are accepted into the listener's backlog, but nothing ever responds
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let address = listener.local_addr().expect("failed to get local address");
+        let provider = provider(&format!("http://{address}"), Duration::from_millis(100));
+
+        let err = provider
+            .resolve("aws/production")
+            .await
+            .expect_err("resolved a value from an unresponsive store");
+
+        assert!(
+            matches!(&err, SecretProviderError::Reqwest(err) if err.is_timeout()),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn url() {
+        let provider = provider("http://localhost:8200/v1/", Duration::from_secs(5));
+
+        assert_eq!(
+            "http://localhost:8200/v1/aws/production",
+            provider.url("/aws/production")
+        );
+    }
+}
@@ -35,7 +35,10 @@ use veritech_core::{incoming_subject, veritech_work_queue, ExecutionId, GetNatsS
 use crate::{
     app_state::{AppState, KillAppState},
     config::CycloneSpec,
-    handlers, Config, ServerError, ServerResult,
+    handlers,
+    scheduler::FairScheduler,
+    secret_provider::SecretProviders,
+    Config, ServerError, ServerResult,
 };
 
 const CONSUMER_NAME: &str = "veritech-server";
@@ -82,6 +85,7 @@ impl Server {
         });
 
         let decryption_key = VeritechDecryptionKey::from_config(config.crypto().clone()).await?;
+        let secret_providers = SecretProviders::from_config(config.secret_providers())?;
 
         let kill_senders = Arc::new(Mutex::new(HashMap::new()));
 
@@ -120,6 +124,7 @@ impl Server {
                 let pool_config = PoolNoodleConfig {
                     check_health: config.healthcheck_pool(),
                     pool_size: spec.pool_size,
+                    scaling: config.pool_scaling(),
                     shutdown_token: token.clone(),
                     spec: spec.clone(),
                     ..Default::default()
@@ -136,11 +141,30 @@ impl Server {
                     .run()
                     .map_err(|e| ServerError::CyclonePool(Box::new(e)))?;
 
+                // Executions beyond the pool size would only queue for an instance, so the
+                // scheduler holds them back instead and picks whose turn it is. It can only pick
+                // between the requests naxum has taken off the work queue, which the concurrency
+                // limit caps, so one workspace filling that limit would starve the others
+                if config.concurrency_limit() <= spec.pool_size as usize {
+                    warn!(
+                        concurrency_limit = config.concurrency_limit(),
+                        pool_size = spec.pool_size,
+                        "concurrency limit is not above the pool size, so executions cannot be \
+                        scheduled fairly between workspaces",
+                    );
+                }
+                let scheduler = FairScheduler::new(
+                    spec.pool_size.max(1) as usize,
+                    config.workspace_concurrency_limit(),
+                );
+
                 let inner_future = Self::build_app(
                     metadata.clone(),
                     config.concurrency_limit(),
                     cyclone_pool,
+                    scheduler,
                     Arc::new(decryption_key),
+                    Arc::new(secret_providers),
                     config.cyclone_client_execution_timeout(),
                     nats.clone(),
                     kill_senders.clone(),
@@ -185,7 +209,9 @@ impl Server {
         metadata: Arc<ServerMetadata>,
         concurrency_limit: usize,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
+        scheduler: FairScheduler,
         decryption_key: Arc<VeritechDecryptionKey>,
+        secret_providers: Arc<SecretProviders>,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
@@ -209,7 +235,9 @@ impl Server {
         let state = AppState::new(
             metadata,
             cyclone_pool,
+            scheduler,
             decryption_key,
+            secret_providers,
             cyclone_client_execution_timeout,
             nats,
             kill_senders,